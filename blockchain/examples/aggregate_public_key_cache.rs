@@ -0,0 +1,85 @@
+use std::time::Instant;
+
+use nimiq_block::{Message, MultiSignature, SignedSkipBlockInfo, SkipBlockInfo};
+use nimiq_block_production::test_utils::TemporaryBlockProducer;
+use nimiq_blockchain::{AbstractBlockchain, AggregatePublicKeyCache};
+use nimiq_bls::AggregateSignature;
+use nimiq_collections::bitset::BitSet;
+use nimiq_primitives::policy;
+use nimiq_test_utils::blockchain::voting_key;
+use nimiq_vrf::VrfSeed;
+
+/// The number of multisignatures that are verified.
+const NUM_BLOCKS: u16 = 100;
+
+/// The number of distinct signer sets among the multisignatures.
+const NUM_SIGNER_SETS: u16 = 10;
+
+/// Compares the time needed to verify multisignatures with overlapping signer sets when the
+/// aggregated voting keys are computed for every signature with the time needed when they are
+/// taken from an `AggregatePublicKeyCache`.
+/// Run this example with `cargo run --release --example aggregate_public_key_cache`.
+fn main() {
+    let producer = TemporaryBlockProducer::new();
+    let blockchain = producer.blockchain.read();
+    let election_hash = blockchain.election_head_hash();
+    let validators = blockchain.current_validators().unwrap();
+
+    println!("====== Creating {} multisignatures ======", NUM_BLOCKS);
+
+    let key_pair = voting_key();
+    let mut multisigs = vec![];
+
+    for i in 0..NUM_BLOCKS {
+        let info = SkipBlockInfo {
+            block_number: i as u32 + 1,
+            vrf_entropy: VrfSeed::default().entropy(),
+        };
+        let signed_info = SignedSkipBlockInfo::from_message(info.clone(), &key_pair.secret_key, 0);
+
+        // Consecutive signatures share most of their signers and every signer set repeats.
+        let first_slot = i % NUM_SIGNER_SETS;
+        let mut signers = BitSet::new();
+        for slot in first_slot..first_slot + policy::TWO_F_PLUS_ONE {
+            signers.insert(slot as usize);
+        }
+
+        let multisig = MultiSignature::new(
+            AggregateSignature::from_signatures(&[signed_info
+                .signature
+                .multiply(signers.len() as u16)]),
+            signers,
+        );
+
+        multisigs.push((multisig, info.hash_with_prefix()));
+    }
+
+    println!("====== Verifying without cache ======");
+
+    let start = Instant::now();
+    for (multisig, message_hash) in &multisigs {
+        multisig
+            .verify_against_validators(&validators, message_hash)
+            .unwrap();
+    }
+    let uncached = start.elapsed();
+    println!("Took {:?}", uncached);
+
+    println!("====== Verifying with cache ======");
+
+    let mut cache = AggregatePublicKeyCache::default();
+    let start = Instant::now();
+    for (multisig, message_hash) in &multisigs {
+        let agg_pk = cache
+            .get_or_aggregate(0, &election_hash, multisig, &validators)
+            .unwrap();
+        multisig.verify_aggregate(&agg_pk, message_hash).unwrap();
+    }
+    let cached = start.elapsed();
+    println!(
+        "Took {:?} with {} cached keys, speedup {:.2}x",
+        cached,
+        cache.len(),
+        uncached.as_secs_f64() / cached.as_secs_f64()
+    );
+}
@@ -4,8 +4,9 @@ use nimiq_hash::Blake2bHash;
 use nimiq_primitives::networks::NetworkId;
 use nimiq_primitives::policy;
 use nimiq_primitives::slots::{Validator, Validators};
+use parking_lot::Mutex;
 
-use crate::{Blockchain, ChainInfo};
+use crate::{AggregatePublicKeyCache, Blockchain, ChainInfo};
 
 /// Defines several basic methods for blockchains.
 pub trait AbstractBlockchain {
@@ -109,6 +110,11 @@ pub trait AbstractBlockchain {
         offset: u32,
         txn_option: Option<&Transaction>,
     ) -> Option<(Validator, u16)>;
+
+    /// Returns the cache of aggregated voting keys, if this blockchain keeps one.
+    fn aggregate_public_key_cache(&self) -> Option<&Mutex<AggregatePublicKeyCache>> {
+        None
+    }
 }
 
 impl AbstractBlockchain for Blockchain {
@@ -132,6 +138,10 @@ impl AbstractBlockchain for Blockchain {
         self.state.election_head.clone()
     }
 
+    fn election_head_hash(&self) -> Blake2bHash {
+        self.state.election_head_hash.clone()
+    }
+
     fn block_number(&self) -> u32 {
         self.state.main_chain.head.block_number()
     }
@@ -197,4 +207,8 @@ impl AbstractBlockchain for Blockchain {
         self.get_proposer_at(block_number, offset, vrf_entropy, txn_option)
             .map(|slot| (slot.validator, slot.number))
     }
+
+    fn aggregate_public_key_cache(&self) -> Option<&Mutex<AggregatePublicKeyCache>> {
+        Some(&self.aggregate_public_keys)
+    }
 }
@@ -0,0 +1,117 @@
+use std::collections::{HashMap, VecDeque};
+
+use nimiq_block::{MultiSignature, MultisigError};
+use nimiq_bls::AggregatePublicKey;
+use nimiq_collections::bitset::BitSet;
+use nimiq_hash::Blake2bHash;
+use nimiq_primitives::policy;
+use nimiq_primitives::slots::Validators;
+
+/// A bounded cache of aggregated voting keys, indexed by the hash of the election block that
+/// selected the validators and by the signer bitmap. It avoids aggregating the same subsets of
+/// voting keys over and over when verifying justifications. Once the cache is full, the least
+/// recently used entry is evicted.
+pub struct AggregatePublicKeyCache {
+    capacity: usize,
+    retention_epochs: u32,
+    // The aggregated keys, together with the number of the election block they belong to.
+    keys: HashMap<(Blake2bHash, BitSet), (u32, AggregatePublicKey)>,
+    // The cached entries, ordered from the least to the most recently used one.
+    order: VecDeque<(Blake2bHash, BitSet)>,
+}
+
+impl AggregatePublicKeyCache {
+    /// The default number of aggregated keys kept in the cache.
+    pub const DEFAULT_CAPACITY: usize = 256;
+
+    /// The default number of past epochs for which aggregated keys are kept.
+    pub const DEFAULT_RETENTION_EPOCHS: u32 = 1;
+
+    pub fn new(capacity: usize, retention_epochs: u32) -> Self {
+        Self {
+            capacity,
+            retention_epochs,
+            keys: HashMap::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of cached aggregated keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns true if no aggregated keys are cached.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns true if the aggregated key of the given signers is cached.
+    pub fn contains(&self, election_hash: &Blake2bHash, signers: &BitSet) -> bool {
+        self.keys
+            .contains_key(&(election_hash.clone(), signers.clone()))
+    }
+
+    /// Returns the aggregated voting keys of the signers of the given multisignature. The
+    /// validators must be the ones selected by the given election block. If the key isn't cached
+    /// yet, it is aggregated and inserted into the cache.
+    pub fn get_or_aggregate(
+        &mut self,
+        election_block_number: u32,
+        election_hash: &Blake2bHash,
+        multisig: &MultiSignature,
+        validators: &Validators,
+    ) -> Result<AggregatePublicKey, MultisigError> {
+        let key = (election_hash.clone(), multisig.signers.clone());
+
+        if let Some((_, agg_pk)) = self.keys.get(&key) {
+            let agg_pk = *agg_pk;
+            self.order.retain(|known| known != &key);
+            self.order.push_back(key);
+            return Ok(agg_pk);
+        }
+
+        let agg_pk = multisig.aggregate_public_key(validators)?;
+
+        if self.capacity == 0 {
+            return Ok(agg_pk);
+        }
+
+        if self.order.len() >= self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.keys.remove(&evicted);
+            }
+        }
+
+        self.keys
+            .insert(key.clone(), (election_block_number, agg_pk));
+        self.order.push_back(key);
+
+        Ok(agg_pk)
+    }
+
+    /// Evicts the aggregated keys of all epochs that are more than the retention window behind
+    /// the given election block. This is called whenever the election head changes.
+    pub fn evict_outdated(&mut self, election_block_number: u32) {
+        let window = self
+            .retention_epochs
+            .saturating_mul(policy::BLOCKS_PER_EPOCH);
+        let oldest = election_block_number.saturating_sub(window);
+
+        let keys = &mut self.keys;
+        keys.retain(|_, (block_number, _)| *block_number >= oldest);
+        self.order.retain(|key| keys.contains_key(key));
+    }
+
+    /// Removes all aggregated keys from the cache.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.order.clear();
+    }
+}
+
+impl Default for AggregatePublicKeyCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY, Self::DEFAULT_RETENTION_EPOCHS)
+    }
+}
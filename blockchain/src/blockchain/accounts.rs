@@ -1,8 +1,9 @@
 use crate::blockchain_state::BlockchainState;
 use crate::history::ExtendedTransaction;
-use crate::{Blockchain, PushError};
+use crate::{Blockchain, PushError, SlashReason, SlashRecord};
 use nimiq_account::Accounts;
 use nimiq_account::BlockLog;
+use nimiq_account::{AccountError, Inherent};
 use nimiq_block::{Block, BlockError::TransactionExecutionMismatch, SkipBlockInfo};
 use nimiq_database::WriteTransaction;
 use nimiq_primitives::policy;
@@ -24,6 +25,7 @@ impl Blockchain {
             Block::Macro(ref macro_block) => {
                 // Initialize a vector to store the inherents
                 let inherents = self.create_macro_block_inherents(state, &macro_block.header);
+                Self::check_inherents(&inherents, true)?;
 
                 // Commit block to AccountsTree and create the receipts.
                 let batch_info = accounts.commit(
@@ -83,6 +85,7 @@ impl Blockchain {
                 // Create the inherents from any forks or skip block info.
                 let inherents =
                     self.create_slash_inherents(&body.fork_proofs, skip_block_info, Some(txn));
+                Self::check_inherents(&inherents, false)?;
 
                 // Commit block to AccountsTree and create the receipts.
                 let batch_info = accounts.commit(
@@ -112,6 +115,27 @@ impl Blockchain {
                 self.chain_store
                     .put_receipts(txn, micro_block.header.block_number, &receipts);
 
+                // Store the slashes. The slash inherents from fork proofs come first, followed by
+                // the one from the skip block, if any.
+                let slashes: Vec<SlashRecord> = inherents
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, inherent)| {
+                        let reason = if index < body.fork_proofs.len() {
+                            SlashReason::ForkProof
+                        } else {
+                            SlashReason::SkipBlock
+                        };
+                        SlashRecord::from_inherent(
+                            micro_block.header.block_number,
+                            reason,
+                            inherent,
+                        )
+                    })
+                    .collect();
+                self.chain_store
+                    .put_slashes(txn, micro_block.header.block_number, &slashes);
+
                 // Store the transactions and the inherents into the History tree.
                 let ext_txs = ExtendedTransaction::from(
                     self.network_id,
@@ -159,8 +183,8 @@ impl Blockchain {
                     "Reverting block"
                 );
 
-                // Get the body of the block.
-                let body = micro_block.body.as_ref().unwrap();
+                // Get the body of the block. It is missing if it was already pruned.
+                let body = micro_block.body.as_ref().ok_or(PushError::BodyPruned)?;
 
                 let skip_block_info = if micro_block.is_skip_block() {
                     Some(SkipBlockInfo {
@@ -197,6 +221,10 @@ impl Blockchain {
                     }
                 };
 
+                // Remove the slashes applied by this block.
+                self.chain_store
+                    .remove_slashes(txn, micro_block.header.block_number);
+
                 // Remove the transactions from the History tree. For this you only need to calculate the
                 // number of transactions that you want to remove.
                 let num_txs = body.transactions.len() + inherents.len();
@@ -217,4 +245,17 @@ impl Blockchain {
             Block::Macro(_) => unreachable!("Macro blocks are final and can't be reverted"),
         }
     }
+
+    /// Checks that all the given inherents may be applied in a macro block (`macro_block` is true)
+    /// or in a micro block (`macro_block` is false).
+    fn check_inherents(inherents: &[Inherent], macro_block: bool) -> Result<(), PushError> {
+        if inherents
+            .iter()
+            .all(|inherent| inherent.is_valid_for(macro_block))
+        {
+            Ok(())
+        } else {
+            Err(PushError::AccountsError(AccountError::InvalidInherent))
+        }
+    }
 }
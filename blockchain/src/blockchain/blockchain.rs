@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use nimiq_account::{Account, Accounts, BlockLog};
+use futures::{stream::BoxStream, StreamExt};
+use parking_lot::Mutex;
+
+use nimiq_account::{Account, Accounts, BlockLog, LogFilter};
 use nimiq_block::Block;
 use nimiq_database::{Environment, ReadTransaction, WriteTransaction};
 use nimiq_genesis::NetworkInfo;
@@ -17,9 +20,10 @@ use crate::chain_info::ChainInfo;
 #[cfg(feature = "metrics")]
 use crate::chain_metrics::BlockchainMetrics;
 use crate::chain_store::ChainStore;
+use crate::fork_proof_cache::ForkProofCache;
 use crate::history::HistoryStore;
 use crate::reward::genesis_parameters;
-use crate::{BlockchainError, BlockchainEvent, ForkEvent};
+use crate::{AggregatePublicKeyCache, BlockchainError, BlockchainEvent, ForkEvent};
 use nimiq_trie::key_nibbles::KeyNibbles;
 
 /// The Blockchain struct. It stores all information of the blockchain. It is the main data
@@ -52,6 +56,16 @@ pub struct Blockchain {
     pub(crate) genesis_supply: Coin,
     // The timestamp at the genesis block. This is needed to calculate the rewards.
     pub(crate) genesis_timestamp: u64,
+    // The maximum drift, in milliseconds, that a block's timestamp may be ahead of our time.
+    pub max_future_drift_ms: u64,
+    // Whether the bodies of micro blocks outside of the transaction validity window are pruned.
+    // This is disabled by default, since nodes serving the block history need all block bodies.
+    pub prune_bodies: bool,
+    // The fork proofs that were already reported or included in a block during this epoch.
+    pub(crate) known_fork_proofs: Mutex<ForkProofCache>,
+    // The aggregated voting keys of recently seen signer sets. It can be replaced to change its
+    // capacity or retention window.
+    pub aggregate_public_keys: Mutex<AggregatePublicKeyCache>,
 }
 
 /// Implements methods to start a Blockchain.
@@ -215,6 +229,10 @@ impl Blockchain {
             metrics: Arc::new(BlockchainMetrics::default()),
             genesis_supply,
             genesis_timestamp,
+            max_future_drift_ms: policy::TIMESTAMP_MAX_DRIFT,
+            prune_bodies: false,
+            known_fork_proofs: Mutex::new(ForkProofCache::new(ForkProofCache::DEFAULT_CAPACITY)),
+            aggregate_public_keys: Mutex::new(AggregatePublicKeyCache::default()),
         })
     }
 
@@ -272,6 +290,10 @@ impl Blockchain {
             metrics: Arc::new(BlockchainMetrics::default()),
             genesis_supply,
             genesis_timestamp,
+            max_future_drift_ms: policy::TIMESTAMP_MAX_DRIFT,
+            prune_bodies: false,
+            known_fork_proofs: Mutex::new(ForkProofCache::new(ForkProofCache::DEFAULT_CAPACITY)),
+            aggregate_public_keys: Mutex::new(AggregatePublicKeyCache::default()),
         })
     }
 
@@ -282,6 +304,15 @@ impl Blockchain {
     pub fn write_transaction(&self) -> WriteTransaction {
         WriteTransaction::new(&self.env)
     }
+
+    /// Subscribes to the logs of applied and reverted blocks that match the given filter. The
+    /// filter is applied once, when the block logs are notified, so blocks without any matching
+    /// log are never emitted.
+    pub fn subscribe_logs(&mut self, filter: LogFilter) -> BoxStream<'static, BlockLog> {
+        self.log_notifier
+            .as_filtered_stream(move |block_log: &BlockLog| filter.apply(block_log))
+            .boxed()
+    }
 }
 
 pub trait TransactionVerificationCache: Send + Sync {
@@ -219,6 +219,7 @@ impl Blockchain {
             main_chain_successor: None,
             head: block.clone(),
             cum_tx_fees,
+            body_pruned: false,
         };
 
         this.chain_store
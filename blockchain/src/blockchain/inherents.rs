@@ -1,11 +1,9 @@
-use beserial::Serialize;
-use nimiq_account::{Inherent, InherentType, StakingContract};
+use nimiq_account::{Inherent, StakingContract};
 use nimiq_block::{ForkProof, MacroHeader, SkipBlockInfo};
 use nimiq_database as db;
 use nimiq_keys::Address;
 use nimiq_primitives::coin::Coin;
 use nimiq_primitives::policy;
-use nimiq_primitives::slots::SlashedSlot;
 use nimiq_vrf::{AliasMethod, VrfUseCase};
 
 use crate::blockchain_state::BlockchainState;
@@ -74,20 +72,12 @@ impl Blockchain {
             )
             .expect("Couldn't calculate slot owner!");
 
-        // Create the SlashedSlot struct.
-        let slot = SlashedSlot {
-            slot: proposer_slot.number,
-            validator_address: proposer_slot.validator.address,
-            event_block: fork_proof.header1.block_number,
-        };
-
         // Create the corresponding slash inherent.
-        Inherent {
-            ty: InherentType::Slash,
-            target: self.staking_contract_address(),
-            value: Coin::ZERO,
-            data: slot.serialize_to_vec(),
-        }
+        Inherent::slash(
+            proposer_slot.number,
+            proposer_slot.validator.address,
+            fork_proof.header1.block_number,
+        )
     }
 
     /// It creates a slash inherent from a skip block. It expects a *verified* skip block!
@@ -111,20 +101,12 @@ impl Blockchain {
             "Slash inherent from skip block"
         );
 
-        // Create the SlashedSlot struct.
-        let slot = SlashedSlot {
-            slot: proposer_slot.number,
-            validator_address: proposer_slot.validator.address,
-            event_block: skip_block_info.block_number,
-        };
-
         // Create the corresponding slash inherent.
-        Inherent {
-            ty: InherentType::Slash,
-            target: self.staking_contract_address(),
-            value: Coin::ZERO,
-            data: slot.serialize_to_vec(),
-        }
+        Inherent::slash(
+            proposer_slot.number,
+            proposer_slot.validator.address,
+            skip_block_info.block_number,
+        )
     }
 
     /// Creates the inherents to finalize a batch. The inherents are for reward distribution and
@@ -240,12 +222,7 @@ impl Blockchain {
             )
             .expect("Couldn't find validator in the accounts trie when paying rewards!");
 
-            let inherent = Inherent {
-                ty: InherentType::Reward,
-                target: validator.reward_address.clone(),
-                value: reward,
-                data: vec![],
-            };
+            let inherent = Inherent::reward(validator.reward_address.clone(), reward);
 
             // Test whether account will accept inherent. If it can't then the reward will be
             // burned.
@@ -288,23 +265,11 @@ impl Blockchain {
 
         // Create the inherent for the burned reward.
         if burned_reward > Coin::ZERO {
-            let inherent = Inherent {
-                ty: InherentType::Reward,
-                target: Address::burn_address(),
-                value: burned_reward,
-                data: vec![],
-            };
-
-            inherents.push(inherent);
+            inherents.push(Inherent::reward(Address::burn_address(), burned_reward));
         }
 
         // Push FinalizeBatch inherent to update StakingContract.
-        inherents.push(Inherent {
-            ty: InherentType::FinalizeBatch,
-            target: self.staking_contract_address(),
-            value: Coin::ZERO,
-            data: Vec::new(),
-        });
+        inherents.push(Inherent::finalize_batch());
 
         inherents
     }
@@ -312,11 +277,6 @@ impl Blockchain {
     /// Creates the inherent to finalize an epoch. The inherent is for updating the StakingContract.
     pub fn finalize_previous_epoch(&self) -> Inherent {
         // Create the FinalizeEpoch inherent.
-        Inherent {
-            ty: InherentType::FinalizeEpoch,
-            target: self.staking_contract_address(),
-            value: Coin::ZERO,
-            data: Vec::new(),
-        }
+        Inherent::finalize_epoch()
     }
 }
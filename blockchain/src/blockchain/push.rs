@@ -1,20 +1,22 @@
 use std::error::Error;
+use std::iter;
 use std::ops::Deref;
 
 use nimiq_account::BlockLog;
 use parking_lot::{RwLockUpgradableReadGuard, RwLockWriteGuard};
 
-use nimiq_block::{Block, ForkProof};
-use nimiq_database::WriteTransaction;
+use nimiq_block::{Block, ForkProof, MicroBlock};
+use nimiq_database::{Transaction as DBtx, WriteTransaction};
 use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_primitives::policy;
 
+use crate::blockchain::slots::Slot;
 use crate::blockchain_state::BlockchainState;
 use crate::chain_info::ChainInfo;
 use crate::chain_store::MAX_EPOCHS_STORED;
 use crate::{
-    AbstractBlockchain, Blockchain, BlockchainEvent, ChainOrdering, ForkEvent, PushError,
-    PushResult,
+    AbstractBlockchain, Blockchain, BlockchainEvent, ChainOrdering, ForkEvent, IgnoreReason,
+    PushDetails, PushError, PushResult,
 };
 
 /// Implements methods to push blocks into the chain. This is used when the node has already synced
@@ -28,7 +30,7 @@ impl Blockchain {
         this: RwLockUpgradableReadGuard<Self>,
         block: Block,
         trusted: bool,
-    ) -> Result<PushResult, PushError> {
+    ) -> Result<PushDetails, PushError> {
         // Ignore all blocks that precede (or are at the same height) as the most recent accepted
         // macro block.
         let last_macro_block = policy::last_macro_block(this.block_number());
@@ -39,7 +41,10 @@ impl Blockchain {
                 last_macro_block_no = last_macro_block,
                 "Ignoring block",
             );
-            return Ok(PushResult::Ignored);
+            return Ok(PushDetails::ignored(
+                this.state.head_hash.clone(),
+                IgnoreReason::BeforeMacroBlock,
+            ));
         }
 
         // TODO: We might want to pass this as argument to this method.
@@ -51,7 +56,10 @@ impl Blockchain {
             .get_chain_info(&block.hash(), false, Some(&read_txn))
             .is_some()
         {
-            return Ok(PushResult::Known);
+            return Ok(PushDetails::new(
+                PushResult::Known,
+                this.state.head_hash.clone(),
+            ));
         }
 
         // Check if we have this block's parent.
@@ -68,20 +76,86 @@ impl Blockchain {
                 PushError::Orphan
             })?;
 
-        // Get the intended block proposer.
-        let offset = if let Block::Macro(macro_block) = &block {
+        // Verify the block.
+        this.verify_block(&block, &prev_info, &read_txn, trusted)?;
+
+        // Detect forks in micro blocks other than skip block
+        let forks = this.detect_forks(&block, &prev_info, &read_txn);
+
+        // Calculate chain ordering.
+        let chain_order =
+            ChainOrdering::order_chains(this.deref(), &block, &prev_info, Some(&read_txn));
+
+        read_txn.close();
+
+        let chain_info = ChainInfo::from_block(block, &prev_info);
+
+        // Extend, rebranch or just store the block depending on the chain ordering.
+        let result = match chain_order {
+            ChainOrdering::Extend => {
+                return Blockchain::extend(
+                    this,
+                    chain_info.head.hash(),
+                    chain_info,
+                    prev_info,
+                    forks,
+                );
+            }
+            ChainOrdering::Superior => {
+                return Blockchain::rebranch(this, chain_info.head.hash(), chain_info, forks);
+            }
+            ChainOrdering::Inferior => {
+                debug!(block = %chain_info.head, "Storing block - on inferior chain");
+                PushDetails::ignored(this.state.head_hash.clone(), IgnoreReason::InferiorChain)
+            }
+            ChainOrdering::Unknown => {
+                debug!(block = %chain_info.head, "Storing block - on fork");
+                PushDetails::new(PushResult::Forked, this.state.head_hash.clone())
+            }
+        };
+
+        let mut txn = this.write_transaction();
+        this.chain_store
+            .put_chain_info(&mut txn, &chain_info.head.hash(), &chain_info, true);
+        txn.commit();
+
+        this.report_forks(forks);
+
+        Ok(result)
+    }
+
+    /// Returns the slot of the intended proposer of `block`, which builds on `parent`.
+    fn get_block_proposer(
+        &self,
+        block: &Block,
+        parent: &Block,
+        txn: Option<&DBtx>,
+    ) -> Option<Slot> {
+        let offset = if let Block::Macro(macro_block) = block {
             macro_block.round()
         } else {
             // Skip and micro block offset is block number
             block.block_number()
         };
-        let proposer_slot = this
-            .get_proposer_at(
-                block.block_number(),
-                offset,
-                prev_info.head.seed().entropy(),
-                Some(&read_txn),
-            )
+
+        self.get_proposer_at(block.block_number(), offset, parent.seed().entropy(), txn)
+    }
+
+    /// Verifies the header, justification and body of a block that is about to be pushed on top
+    /// of the block described by `prev_info`.
+    fn verify_block(
+        &self,
+        block: &Block,
+        prev_info: &ChainInfo,
+        txn: &DBtx,
+        trusted: bool,
+    ) -> Result<(), PushError> {
+        // Check the block size before doing any expensive verification.
+        Blockchain::verify_block_size(block)?;
+
+        // Get the intended block proposer.
+        let proposer_slot = self
+            .get_block_proposer(block, &prev_info.head, Some(txn))
             .ok_or_else(|| {
                 warn!(%block, reason = "failed to determine block proposer", "Rejecting block");
                 PushError::Orphan
@@ -89,12 +163,13 @@ impl Blockchain {
 
         // Check the header.
         if let Err(e) = Blockchain::verify_block_header(
-            this.deref(),
+            self,
             &block.header(),
             &proposer_slot.validator.signing_key,
-            Some(&read_txn),
+            Some(txn),
             !trusted,
             block.is_skip(),
+            self.max_future_drift_ms,
         ) {
             warn!(%block, reason = "bad header", "Rejecting block");
             return Err(e);
@@ -102,8 +177,8 @@ impl Blockchain {
 
         // Check the justification.
         if let Err(e) = Blockchain::verify_block_justification(
-            &*this,
-            &block,
+            self,
+            block,
             &proposer_slot.validator.signing_key,
             !trusted,
         ) {
@@ -112,10 +187,10 @@ impl Blockchain {
         }
 
         // Check the body.
-        if let Err(e) = this.verify_block_body(
+        if let Err(e) = self.verify_block_body(
             &block.header(),
             &block.body(),
-            Some(&read_txn),
+            Some(txn),
             block.is_skip(),
             !trusted,
         ) {
@@ -123,95 +198,156 @@ impl Blockchain {
             return Err(e);
         }
 
-        // Detect forks in micro blocks other than skip block
-        if !block.is_skip() {
-            if let Block::Micro(micro_block) = &block {
-                // Check if there are two blocks in the same slot and with the same height. Since we already
-                // verified the validator for the current slot, this is enough to check for fork proofs.
-                // Note: We don't verify the justifications for the other blocks here, since they had to
-                // already be verified in order to be added to the blockchain.
-                // Count the micro blocks after the last macro block.
-                let mut micro_blocks: Vec<Block> =
-                    this.chain_store
-                        .get_blocks_at(block.block_number(), false, Some(&read_txn));
-
-                micro_blocks.retain(|block| block.is_micro() && !block.is_skip());
-
-                // Get the micro header from the block
-                let micro_header1 = &micro_block.header;
-
-                // Get the justification for the block. We assume that the
-                // validator's signature is valid.
-                let justification1 = match micro_block
-                    .justification
-                    .clone()
-                    .expect("Missing justification!")
-                {
-                    nimiq_block::MicroJustification::Micro(signature) => signature,
-                    nimiq_block::MicroJustification::Skip(_) => {
-                        unreachable!("Skip blocks are already filtered")
-                    }
-                };
+        Ok(())
+    }
+
+    /// Checks if there are other micro blocks produced in the same slot as the given block and
+    /// returns a fork event with the corresponding fork proof for each one of them that isn't
+    /// known yet. The events are reported by `report_forks` once the block is stored.
+    fn detect_forks(&self, block: &Block, prev_info: &ChainInfo, txn: &DBtx) -> Vec<ForkEvent> {
+        let mut forks = vec![];
+
+        // Skip blocks can't be used to create fork proofs.
+        if block.is_skip() {
+            return forks;
+        }
 
-                for micro_block in micro_blocks.drain(..).map(|block| block.unwrap_micro()) {
-                    // If there's another micro block set to this block height, which also has the same
-                    // VrfSeed entropy we notify the fork event.
-                    if block.seed().entropy() == micro_block.header.seed.entropy() {
-                        let micro_header2 = micro_block.header;
-                        let justification2 =
-                            match micro_block.justification.expect("Missing justification!") {
-                                nimiq_block::MicroJustification::Micro(signature) => signature,
-                                nimiq_block::MicroJustification::Skip(_) => {
-                                    unreachable!("Skip blocks are already filtered")
-                                }
-                            };
-
-                        let proof = ForkProof {
-                            header1: micro_header1.clone(),
-                            header2: micro_header2,
-                            justification1: justification1.clone(),
-                            justification2,
-                            prev_vrf_seed: prev_info.head.seed().clone(),
+        if let Block::Micro(micro_block) = block {
+            // Check if there are two blocks in the same slot and with the same height. Since we already
+            // verified the validator for the current slot, this is enough to check for fork proofs.
+            // Note: We don't verify the justifications for the other blocks here, since they had to
+            // already be verified in order to be added to the blockchain.
+            // Count the micro blocks after the last macro block.
+            let mut micro_blocks: Vec<Block> =
+                self.chain_store
+                    .get_blocks_at(block.block_number(), false, Some(txn));
+
+            micro_blocks.retain(|block| block.is_micro() && !block.is_skip());
+
+            // Get the micro header from the block
+            let micro_header1 = &micro_block.header;
+
+            // Get the justification for the block. We assume that the
+            // validator's signature is valid.
+            let justification1 = match micro_block
+                .justification
+                .clone()
+                .expect("Missing justification!")
+            {
+                nimiq_block::MicroJustification::Micro(signature) => signature,
+                nimiq_block::MicroJustification::Skip(_) => {
+                    unreachable!("Skip blocks are already filtered")
+                }
+            };
+
+            for micro_block in micro_blocks.drain(..).map(|block| block.unwrap_micro()) {
+                // If there's another micro block set to this block height, which also has the same
+                // VrfSeed entropy we notify the fork event.
+                if block.seed().entropy() == micro_block.header.seed.entropy() {
+                    let micro_header2 = micro_block.header;
+                    let justification2 =
+                        match micro_block.justification.expect("Missing justification!") {
+                            nimiq_block::MicroJustification::Micro(signature) => signature,
+                            nimiq_block::MicroJustification::Skip(_) => {
+                                unreachable!("Skip blocks are already filtered")
+                            }
                         };
 
-                        this.fork_notifier.notify(ForkEvent::Detected(proof));
+                    let proof = ForkProof {
+                        header1: micro_header1.clone(),
+                        header2: micro_header2,
+                        justification1: justification1.clone(),
+                        justification2,
+                        prev_vrf_seed: prev_info.head.seed().clone(),
+                    };
+
+                    // Each fork proof is only reported once.
+                    if self.known_fork_proofs.lock().contains(&proof) {
+                        continue;
                     }
+
+                    let block_number = block.block_number();
+                    let slot = match self.get_proposer_at(
+                        block_number,
+                        block_number,
+                        prev_info.head.seed().entropy(),
+                        Some(txn),
+                    ) {
+                        Some(slot) => slot,
+                        None => {
+                            warn!(%block, "Failed to determine the slot owner of a fork");
+                            continue;
+                        }
+                    };
+
+                    forks.push(ForkEvent::Detected {
+                        proof,
+                        block_number,
+                        slot: slot.number,
+                    });
                 }
             }
         }
 
-        // Calculate chain ordering.
-        let chain_order =
-            ChainOrdering::order_chains(this.deref(), &block, &prev_info, Some(&read_txn));
+        forks
+    }
 
-        read_txn.close();
+    /// Notifies the fork events detected by `detect_forks` whose fork proofs are still unknown and
+    /// remembers them, so that they aren't reported again.
+    fn report_forks(&self, forks: Vec<ForkEvent>) {
+        let forks: Vec<ForkEvent> = {
+            let mut known_fork_proofs = self.known_fork_proofs.lock();
+            forks
+                .into_iter()
+                .filter(|event| match event {
+                    ForkEvent::Detected { proof, .. } => known_fork_proofs.insert(proof.clone()),
+                })
+                .collect()
+        };
 
-        let chain_info = ChainInfo::from_block(block, &prev_info);
+        for event in forks {
+            self.fork_notifier.notify(event);
+        }
+    }
 
-        // Extend, rebranch or just store the block depending on the chain ordering.
-        let result = match chain_order {
-            ChainOrdering::Extend => {
-                return Blockchain::extend(this, chain_info.head.hash(), chain_info, prev_info);
-            }
-            ChainOrdering::Superior => {
-                return Blockchain::rebranch(this, chain_info.head.hash(), chain_info);
+    /// Notifies a `TransactionConfirmed` event for each transaction of a block that was added to
+    /// the main chain, in the order in which the transactions are included in the block.
+    fn notify_confirmed_transactions(&self, block_hash: &Blake2bHash, block: &Block) {
+        if let Some(transactions) = block.transactions() {
+            for (index, transaction) in transactions.iter().enumerate() {
+                self.notifier.notify(BlockchainEvent::TransactionConfirmed {
+                    tx_hash: transaction.get_raw_transaction().hash(),
+                    block_hash: block_hash.clone(),
+                    block_number: block.block_number(),
+                    index,
+                });
             }
-            ChainOrdering::Inferior => {
-                debug!(block = %chain_info.head, "Storing block - on inferior chain");
-                PushResult::Ignored
-            }
-            ChainOrdering::Unknown => {
-                debug!(block = %chain_info.head, "Storing block - on fork");
-                PushResult::Forked
-            }
-        };
-
-        let mut txn = this.write_transaction();
-        this.chain_store
-            .put_chain_info(&mut txn, &chain_info.head.hash(), &chain_info, true);
-        txn.commit();
+        }
+    }
 
-        Ok(result)
+    /// Updates the caches of the blockchain after the given block was committed to the main
+    /// chain. Fork proofs are reported once per epoch, so the fork proof cache is cleared at every
+    /// election block. The fork proofs included in any other block are remembered, so that they
+    /// aren't reported again. Aggregated voting keys are only kept for a few epochs, so the outdated
+    /// ones are evicted at every election block.
+    fn note_committed_block(&self, block: &Block) {
+        let mut known_fork_proofs = self.known_fork_proofs.lock();
+
+        if block.is_election_block() {
+            known_fork_proofs.clear();
+
+            self.aggregate_public_keys
+                .lock()
+                .evict_outdated(block.block_number());
+        } else if let Block::Micro(MicroBlock {
+            body: Some(ref body),
+            ..
+        }) = block
+        {
+            for proof in &body.fork_proofs {
+                known_fork_proofs.insert(proof.clone());
+            }
+        }
     }
 
     // To retain the option of having already taken a lock before this call the self was exchanged.
@@ -224,6 +360,16 @@ impl Blockchain {
         this: RwLockUpgradableReadGuard<Self>,
         block: Block,
     ) -> Result<PushResult, PushError> {
+        Self::push_wrapperfn(this, block, false).map(|details| details.kind())
+    }
+
+    /// Pushes a block into the chain. In contrast to `push`, this returns the details of the
+    /// result, i.e. the new head, the blocks that were reverted and adopted by a rebranch, or the
+    /// reason why the block was ignored.
+    pub fn push_with_details(
+        this: RwLockUpgradableReadGuard<Self>,
+        block: Block,
+    ) -> Result<PushDetails, PushError> {
         Self::push_wrapperfn(this, block, false)
     }
 
@@ -239,14 +385,410 @@ impl Blockchain {
         this: RwLockUpgradableReadGuard<Self>,
         block: Block,
     ) -> Result<PushResult, PushError> {
-        Self::push_wrapperfn(this, block, true)
+        Self::push_wrapperfn(this, block, true).map(|details| details.kind())
+    }
+
+    /// Pushes a chunk of consecutive micro blocks into the chain. In contrast to pushing the blocks
+    /// one by one, all blocks are verified and committed within a single write transaction, which
+    /// is considerably faster when syncing a large number of micro blocks.
+    ///
+    /// The blocks are applied in order for as long as they directly extend the chain. Processing
+    /// stops at the first block that doesn't, e.g. a macro block, an already known block or a
+    /// block on a fork. These blocks have to be pushed individually using `push`.
+    ///
+    /// Returns the number of blocks that were applied to the chain, together with
+    /// `PushResult::Extended` if at least one block was applied and `PushResult::Ignored`
+    /// otherwise. If any of the blocks fails to verify or to be committed, the whole transaction is
+    /// rolled back and none of the blocks in the chunk are applied.
+    pub fn push_chunk(
+        this: RwLockUpgradableReadGuard<Self>,
+        blocks: Vec<Block>,
+    ) -> Result<(PushResult, usize), PushError> {
+        let committed = this.commit_micro_blocks(&blocks)?;
+
+        let num_blocks = committed.blocks.len();
+        if num_blocks == 0 {
+            return Ok((PushResult::Ignored, 0));
+        }
+
+        Self::extend_committed(this, committed);
+
+        Ok((PushResult::Extended, num_blocks))
+    }
+
+    /// Pushes a batch of consecutive blocks into the chain, e.g. during the initial sync.
+    ///
+    /// Before any block is applied, the headers of all blocks are verified: The first block must
+    /// succeed a known block and every other block the previous one of the batch. Consecutive micro
+    /// blocks that directly extend the chain are then verified and committed within a single write
+    /// transaction, while all other blocks (e.g. macro blocks or blocks on a fork) are pushed
+    /// individually.
+    ///
+    /// Returns the `PushResult` of each block, in order. Processing stops at the first rejected
+    /// block, in which case the error is returned along with the results of the blocks that were
+    /// applied before. A batch with an invalid header is rejected without applying any block. If a
+    /// micro block fails the later checks, all micro blocks that were committed together with it
+    /// are rolled back as well, so only the blocks preceding that run of micro blocks remain in the
+    /// chain.
+    pub fn push_batch(
+        this: RwLockUpgradableReadGuard<Self>,
+        blocks: Vec<Block>,
+    ) -> Result<Vec<PushResult>, (Vec<PushResult>, PushError)> {
+        let lock = RwLockUpgradableReadGuard::rwlock(&this);
+
+        if let Err(e) = this.verify_batch(&blocks) {
+            return Err((vec![], e));
+        }
+
+        let mut results = Vec::with_capacity(blocks.len());
+        let mut guard = Some(this);
+        let mut i = 0;
+
+        while i < blocks.len() {
+            let this = guard.take().unwrap_or_else(|| lock.upgradable_read());
+
+            if blocks[i].is_micro() && blocks[i].parent_hash() == &this.state.head_hash {
+                let committed = match this.commit_micro_blocks(&blocks[i..]) {
+                    Ok(committed) => committed,
+                    Err(e) => return Err((results, e)),
+                };
+
+                let num_blocks = committed.blocks.len();
+                if num_blocks > 0 {
+                    results.extend(iter::repeat(PushResult::Extended).take(num_blocks));
+                    i += num_blocks;
+                    guard = Some(Self::extend_committed(this, committed));
+                    continue;
+                }
+            }
+
+            match Self::push(this, blocks[i].clone()) {
+                Ok(result) => results.push(result),
+                Err(e) => return Err((results, e)),
+            }
+            i += 1;
+        }
+
+        Ok(results)
+    }
+
+    /// Verifies the headers of a batch of blocks before any of them is committed. The first block
+    /// must succeed a known block and every other block the previous one of the batch.
+    ///
+    /// The seed of a block is only verified here if its proposer can already be determined, i.e.
+    /// if the preceding macro block is part of the chain. Otherwise it is verified once the block
+    /// is committed.
+    fn verify_batch(&self, blocks: &[Block]) -> Result<(), PushError> {
+        let mut parent = match blocks.first() {
+            Some(block) => self
+                .chain_store
+                .get_block(block.parent_hash(), false, None)
+                .ok_or_else(|| {
+                    warn!(
+                        %block,
+                        reason = "parent block is unknown",
+                        "Rejecting batch",
+                    );
+                    PushError::Orphan
+                })?,
+            None => return Ok(()),
+        };
+        let mut election_head_hash = self.election_head_hash();
+
+        for block in blocks {
+            if block.parent_hash() != &parent.hash() {
+                warn!(
+                    %block,
+                    reason = "block does not succeed the previous block of the batch",
+                    "Rejecting batch",
+                );
+                return Err(PushError::InvalidSuccessor);
+            }
+
+            Blockchain::verify_block_size(block)?;
+
+            let proposer_slot = self.get_block_proposer(block, &parent, None);
+            if let Err(e) = Blockchain::verify_block_header_with_parent(
+                self,
+                &block.header(),
+                &parent,
+                &election_head_hash,
+                proposer_slot
+                    .as_ref()
+                    .map(|slot| &slot.validator.signing_key),
+                block.is_skip(),
+                self.max_future_drift_ms,
+            ) {
+                warn!(%block, reason = "bad header", "Rejecting batch");
+                return Err(e);
+            }
+
+            if block.is_election() {
+                election_head_hash = block.hash();
+            }
+            parent = block.clone();
+        }
+
+        Ok(())
+    }
+
+    /// Verifies and commits the leading micro blocks of `blocks` that directly extend the chain
+    /// within a single write transaction. Stops at the first block that doesn't, e.g. a macro
+    /// block, an already known block or a block on a fork.
+    ///
+    /// If a block fails to verify or to be committed, the whole transaction is rolled back, i.e. none
+    /// of the blocks are committed.
+    fn commit_micro_blocks(&self, blocks: &[Block]) -> Result<CommittedBlocks, PushError> {
+        let mut txn = self.write_transaction();
+
+        let mut prev_info = self.state.main_chain.clone();
+        let mut prev_hash = self.state.head_hash.clone();
+
+        let mut applied_blocks = Vec::with_capacity(blocks.len());
+        let mut block_logs = Vec::with_capacity(blocks.len());
+        let mut forks = Vec::with_capacity(blocks.len());
+
+        for block in blocks {
+            // Only micro blocks that directly extend the chain can be committed together.
+            if !block.is_micro() || block.parent_hash() != &prev_hash {
+                break;
+            }
+
+            let block_hash = block.hash();
+
+            // Stop if we already know this block.
+            if self
+                .chain_store
+                .get_chain_info(&block_hash, false, Some(&txn))
+                .is_some()
+            {
+                break;
+            }
+
+            if let Err(e) = self.verify_block(block, &prev_info, &txn, false) {
+                txn.abort();
+                return Err(e);
+            }
+
+            let block_forks = self.detect_forks(block, &prev_info, &txn);
+
+            let block_log = match self.check_and_commit(&self.state, block, &mut txn) {
+                Ok(block_log) => block_log,
+                Err(e) => {
+                    txn.abort();
+                    return Err(e);
+                }
+            };
+
+            let mut chain_info = ChainInfo::from_block(block.clone(), &prev_info);
+            chain_info.on_main_chain = true;
+            prev_info.main_chain_successor = Some(block_hash.clone());
+
+            self.chain_store
+                .put_chain_info(&mut txn, &block_hash, &chain_info, true);
+            self.chain_store
+                .put_chain_info(&mut txn, &prev_hash, &prev_info, false);
+
+            applied_blocks.push((block_hash.clone(), block.clone()));
+            block_logs.push(block_log);
+            forks.push(block_forks);
+
+            prev_info = chain_info;
+            prev_hash = block_hash;
+        }
+
+        if applied_blocks.is_empty() {
+            txn.abort();
+        } else {
+            self.chain_store.set_head(&mut txn, &prev_hash);
+            txn.commit();
+        }
+
+        Ok(CommittedBlocks {
+            head_info: prev_info,
+            head_hash: prev_hash,
+            blocks: applied_blocks,
+            block_logs,
+            forks,
+        })
+    }
+
+    /// Updates the blockchain state to the blocks committed by `commit_micro_blocks` and notifies
+    /// the listeners about them.
+    fn extend_committed(
+        this: RwLockUpgradableReadGuard<Self>,
+        committed: CommittedBlocks,
+    ) -> RwLockUpgradableReadGuard<Self> {
+        if committed.blocks.is_empty() {
+            return this;
+        }
+
+        // Upgrade the lock as late as possible.
+        let mut this = RwLockUpgradableReadGuard::upgrade_untimed(this);
+
+        this.state.main_chain = committed.head_info;
+        this.state.head_hash = committed.head_hash;
+
+        // Downgrade the lock again as the notify listeners might want to acquire read access themselves.
+        let this = RwLockWriteGuard::downgrade_to_upgradable(this);
+
+        for (((block_hash, block), block_log), forks) in committed
+            .blocks
+            .into_iter()
+            .zip(committed.block_logs)
+            .zip(committed.forks)
+        {
+            this.note_committed_block(&block);
+            this.report_forks(forks);
+
+            let num_transactions = block.num_transactions();
+            #[cfg(feature = "metrics")]
+            this.metrics.note_extend(num_transactions);
+            debug!(
+                block = %block_hash,
+                num_transactions,
+                kind = "extend",
+                "Accepted block",
+            );
+
+            this.notifier
+                .notify(BlockchainEvent::Extended(block_hash.clone()));
+            this.notify_confirmed_transactions(&block_hash, &block);
+            this.log_notifier.notify(block_log);
+        }
+
+        this
+    }
+
+    /// Reverts the main chain back to the block with the given hash. Only micro blocks can be
+    /// reverted, so the target block must be on the main chain and must not precede the latest
+    /// macro block. Returns the reverted blocks, ordered from the oldest to the newest one, so that
+    /// their transactions can be re-injected into the mempool.
+    pub fn revert_to(
+        this: RwLockUpgradableReadGuard<Self>,
+        target_hash: &Blake2bHash,
+    ) -> Result<Vec<(Blake2bHash, Block)>, PushError> {
+        let mut write_txn = this.write_transaction();
+
+        let target_info = this
+            .chain_store
+            .get_chain_info(target_hash, true, Some(&write_txn))
+            .filter(|chain_info| chain_info.on_main_chain);
+
+        let mut target_info = match target_info {
+            Some(chain_info) => chain_info,
+            None => {
+                warn!(
+                    block = %target_hash,
+                    reason = "block is not on the main chain",
+                    "Rejecting revert",
+                );
+                write_txn.abort();
+                return Err(PushError::InvalidRevertTarget);
+            }
+        };
+
+        // Refuse to cross a macro block, since macro blocks are final.
+        if target_info.head.block_number() < this.state.macro_info.head.block_number() {
+            warn!(
+                block = %target_info.head,
+                reason = "block already finalized",
+                "Rejecting revert",
+            );
+            write_txn.abort();
+            return Err(PushError::InvalidRevertTarget);
+        }
+
+        let mut revert_chain: Vec<(Blake2bHash, ChainInfo)> = vec![];
+        let mut block_logs = Vec::new();
+        let mut current = (this.state.head_hash.clone(), this.state.main_chain.clone());
+
+        while &current.0 != target_hash {
+            let block = current.1.head.clone();
+            let prev_hash = block.parent_hash().clone();
+
+            let prev_info = this
+                .chain_store
+                .get_chain_info(&prev_hash, true, Some(&write_txn))
+                .expect("Corrupted store: Failed to find main chain predecessor while reverting");
+
+            block_logs.push(this.revert_accounts(&this.state.accounts, &mut write_txn, &block)?);
+
+            assert_eq!(
+                prev_info.head.state_root(),
+                &this.state.accounts.get_root(Some(&write_txn)),
+                "Failed to revert main chain - inconsistent state"
+            );
+
+            revert_chain.push(current);
+
+            current = (prev_hash, prev_info);
+        }
+
+        // Unset onMainChain flag / mainChainSuccessor on the reverted blocks.
+        for reverted_block in revert_chain.iter_mut() {
+            reverted_block.1.on_main_chain = false;
+            reverted_block.1.main_chain_successor = None;
+
+            this.chain_store.put_chain_info(
+                &mut write_txn,
+                &reverted_block.0,
+                &reverted_block.1,
+                false,
+            );
+        }
+
+        // The target block becomes the new head.
+        target_info.main_chain_successor = None;
+        this.chain_store
+            .put_chain_info(&mut write_txn, target_hash, &target_info, false);
+
+        // Commit transaction & update head.
+        this.chain_store.set_head(&mut write_txn, target_hash);
+        write_txn.commit();
+
+        // Upgrade the lock as late as possible.
+        let mut this = RwLockUpgradableReadGuard::upgrade(this);
+
+        this.state.main_chain = target_info;
+        this.state.head_hash = target_hash.clone();
+
+        // Downgrade the lock again as the notified listeners might want to acquire read themselves.
+        let this = RwLockWriteGuard::downgrade_to_upgradable(this);
+
+        let mut reverted_blocks = Vec::with_capacity(revert_chain.len());
+        for (hash, chain_info) in revert_chain.into_iter().rev() {
+            debug!(
+                block = %chain_info.head,
+                num_transactions = chain_info.head.num_transactions(),
+                "Reverted block",
+            );
+            reverted_blocks.push((hash, chain_info.head));
+        }
+
+        debug!(
+            block = %this.state.main_chain.head,
+            num_reverted_blocks = reverted_blocks.len(),
+            "Reverted chain",
+        );
+        #[cfg(feature = "metrics")]
+        this.metrics.note_rebranch(&reverted_blocks, &[]);
+
+        this.notifier.notify(BlockchainEvent::RevertedTo(
+            target_hash.clone(),
+            reverted_blocks.clone(),
+        ));
+
+        this.log_notifier.notify_vec(block_logs);
+
+        Ok(reverted_blocks)
     }
 
     fn push_wrapperfn(
         this: RwLockUpgradableReadGuard<Self>,
         block: Block,
         trust: bool,
-    ) -> Result<PushResult, PushError> {
+    ) -> Result<PushDetails, PushError> {
         #[cfg(not(feature = "metrics"))]
         {
             Self::do_push(this, block, trust)
@@ -266,12 +808,13 @@ impl Blockchain {
         block_hash: Blake2bHash,
         mut chain_info: ChainInfo,
         mut prev_info: ChainInfo,
-    ) -> Result<PushResult, PushError> {
+        forks: Vec<ForkEvent>,
+    ) -> Result<PushDetails, PushError> {
         let mut txn = this.write_transaction();
 
-        let block_number = this.block_number() + 1;
-        let is_macro_block = policy::is_macro_block_at(block_number);
-        let is_election_block = policy::is_election_block_at(block_number);
+        let block_number = chain_info.head.block_number();
+        let is_macro_block = chain_info.head.is_macro();
+        let is_election_block = chain_info.head.is_election_block();
 
         let block_info = this.check_and_commit(&this.state, &chain_info.head, &mut txn);
         let block_log = match block_info {
@@ -291,6 +834,13 @@ impl Blockchain {
             .put_chain_info(&mut txn, chain_info.head.parent_hash(), &prev_info, false);
         this.chain_store.set_head(&mut txn, &block_hash);
 
+        if is_macro_block && this.prune_bodies {
+            this.chain_store.prune_bodies_before(
+                block_number.saturating_sub(policy::TRANSACTION_VALIDITY_WINDOW),
+                &mut txn,
+            );
+        }
+
         if is_election_block {
             this.chain_store.prune_epoch(
                 policy::epoch_at(block_number).saturating_sub(MAX_EPOCHS_STORED),
@@ -314,7 +864,9 @@ impl Blockchain {
                 let old_slots = this.state.current_slots.take().unwrap();
                 this.state.previous_slots.replace(old_slots);
 
-                let new_slots = macro_block.get_validators().unwrap();
+                let new_slots = macro_block
+                    .get_next_validators()
+                    .expect("Election block is missing the validators of the next epoch");
                 this.state.current_slots.replace(new_slots);
             }
         }
@@ -325,12 +877,16 @@ impl Blockchain {
         // Downgrade the lock again as the notify listeners might want to acquire read access themselves.
         let this = RwLockWriteGuard::downgrade_to_upgradable(this);
 
+        this.note_committed_block(&this.state.main_chain.head);
+        this.report_forks(forks);
+
         let num_transactions = this.state.main_chain.head.num_transactions();
         #[cfg(feature = "metrics")]
         this.metrics.note_extend(num_transactions);
         debug!(
             block = %this.state.main_chain.head,
             num_transactions,
+            total_fee = %this.state.main_chain.head.sum_transaction_fees(),
             kind = "extend",
             "Accepted block",
         );
@@ -341,12 +897,18 @@ impl Blockchain {
         } else if is_macro_block {
             this.notifier.notify(BlockchainEvent::Finalized(block_hash));
         } else {
-            this.notifier.notify(BlockchainEvent::Extended(block_hash));
+            this.notifier
+                .notify(BlockchainEvent::Extended(block_hash.clone()));
         }
 
+        this.notify_confirmed_transactions(&block_hash, &this.state.main_chain.head);
+
         this.log_notifier.notify(block_log);
 
-        Ok(PushResult::Extended)
+        Ok(PushDetails::new(
+            PushResult::Extended,
+            this.state.head_hash.clone(),
+        ))
     }
 
     /// Rebranches the current main chain.
@@ -354,7 +916,8 @@ impl Blockchain {
         this: RwLockUpgradableReadGuard<Blockchain>,
         block_hash: Blake2bHash,
         chain_info: ChainInfo,
-    ) -> Result<PushResult, PushError> {
+        forks: Vec<ForkEvent>,
+    ) -> Result<PushDetails, PushError> {
         let target_block = chain_info.head.header();
         debug!(block = %target_block, "Rebranching");
 
@@ -374,6 +937,17 @@ impl Blockchain {
                 .get_chain_info(&prev_hash, true, Some(&read_txn))
                 .expect("Corrupted store: Failed to find fork predecessor while rebranching");
 
+            // The fork blocks need to be applied, which requires their bodies.
+            if current.1.body_pruned {
+                warn!(
+                    block = %target_block,
+                    reason = "fork block body was pruned",
+                    fork_block = %current.1.head,
+                    "Rejecting block",
+                );
+                return Err(PushError::BodyPruned);
+            }
+
             fork_chain.push(current);
 
             current = (prev_hash, prev_info);
@@ -516,14 +1090,16 @@ impl Blockchain {
             this.state.macro_info = new_head_info.clone();
             this.state.macro_head_hash = new_head_hash.clone();
 
-            if policy::is_election_block_at(new_head_info.head.block_number()) {
+            if new_head_info.head.is_election_block() {
                 this.state.election_head = macro_block.clone();
                 this.state.election_head_hash = new_head_hash.clone();
 
                 let old_slots = this.state.current_slots.take().unwrap();
                 this.state.previous_slots.replace(old_slots);
 
-                let new_slots = macro_block.get_validators().unwrap();
+                let new_slots = macro_block
+                    .get_next_validators()
+                    .expect("Election block is missing the validators of the next epoch");
                 this.state.current_slots.replace(new_slots);
             }
         }
@@ -546,6 +1122,7 @@ impl Blockchain {
 
         let mut adopted_blocks = Vec::with_capacity(fork_chain.len());
         for (hash, chain_info) in fork_chain.into_iter().rev() {
+            this.note_committed_block(&chain_info.head);
             debug!(
                 block = %chain_info.head,
                 num_transactions = chain_info.head.num_transactions(),
@@ -565,12 +1142,30 @@ impl Blockchain {
         this.metrics
             .note_rebranch(&reverted_blocks, &adopted_blocks);
 
-        let event = BlockchainEvent::Rebranched(reverted_blocks, adopted_blocks);
+        this.report_forks(forks);
+
+        let details = PushDetails {
+            reverted_blocks: reverted_blocks
+                .iter()
+                .map(|(hash, _)| hash.clone())
+                .collect(),
+            adopted_blocks: adopted_blocks
+                .iter()
+                .map(|(hash, _)| hash.clone())
+                .collect(),
+            ..PushDetails::new(PushResult::Rebranched, this.state.head_hash.clone())
+        };
+
+        let event = BlockchainEvent::Rebranched(reverted_blocks, adopted_blocks.clone());
         this.notifier.notify(event);
 
+        for (hash, block) in &adopted_blocks {
+            this.notify_confirmed_transactions(hash, block);
+        }
+
         this.log_notifier.notify_vec(block_logs);
 
-        Ok(PushResult::Rebranched)
+        Ok(details)
     }
 
     fn check_and_commit(
@@ -617,3 +1212,13 @@ impl Blockchain {
         block_log
     }
 }
+
+/// The micro blocks committed to the database by `Blockchain::commit_micro_blocks`, which still
+/// have to be applied to the blockchain state.
+struct CommittedBlocks {
+    head_info: ChainInfo,
+    head_hash: Blake2bHash,
+    blocks: Vec<(Blake2bHash, Block)>,
+    block_logs: Vec<BlockLog>,
+    forks: Vec<Vec<ForkEvent>>,
+}
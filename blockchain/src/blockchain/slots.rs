@@ -95,10 +95,7 @@ impl Blockchain {
         };
 
         // Shuffle the slots vector using the Fisher–Yates shuffle.
-        for i in (1..slots.len()).rev() {
-            let r = rng.next_u64_max((i + 1) as u64) as usize;
-            slots.swap(r, i);
-        }
+        rng.shuffle(&mut slots);
 
         // Now simply take the offset modulo the number of viable slots and that will give us
         // the chosen slot.
@@ -3,8 +3,9 @@ use std::cmp::Ordering;
 use beserial::Serialize;
 use nimiq_block::{
     Block, BlockBody, BlockError, BlockHeader, BlockType, ForkProof, MacroBlock, MacroBody,
-    MicroJustification, SkipBlockInfo, TendermintProof,
+    Message, MicroBlock, MicroJustification, MultiSignature, MultisigError, SkipBlockInfo,
 };
+use nimiq_bls::SigHash;
 use nimiq_database::Transaction as DBtx;
 use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_keys::PublicKey as SchnorrPublicKey;
@@ -17,12 +18,37 @@ use crate::{AbstractBlockchain, Blockchain, PushError};
 
 /// Implements methods to verify the validity of blocks.
 impl Blockchain {
+    /// Verifies that a micro block doesn't exceed the maximum block size. This is cheap to check and
+    /// allows to reject clearly oversized blocks before verifying any signatures.
+    pub fn verify_block_size(block: &Block) -> Result<(), PushError> {
+        if block.is_micro() {
+            let block_size = block.estimated_serialized_size();
+            if block_size > MicroBlock::MAX_SIZE {
+                warn!(
+                    %block,
+                    block_size,
+                    max_size = MicroBlock::MAX_SIZE,
+                    reason = "Micro block size exceeds maximum size",
+                    "Rejecting block"
+                );
+                return Err(PushError::InvalidBlock(BlockError::SizeExceededDetail {
+                    actual: block_size,
+                    max: MicroBlock::MAX_SIZE,
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Verifies the header of a block. This function is used when we are pushing a normal block
     /// into the chain. It cannot be used when syncing, since this performs more strict checks than
     /// the ones we make when syncing.
     /// This only performs checks that can be made BEFORE the state is updated with the block. All
     /// checks that require the updated state (ex: if an account has enough funds) are made on the
     /// verify_block_state method.
+    /// Blocks with a timestamp more than `max_future_drift_ms` ahead of the blockchain's time are
+    /// rejected as being from the future.
     // Note: This is an associated method because we need to use it on the nano-blockchain. There
     //       might be a better way to do this though.
     pub fn verify_block_header<B: AbstractBlockchain>(
@@ -32,6 +58,36 @@ impl Blockchain {
         txn_opt: Option<&DBtx>,
         check_seed: bool,
         skip_block: bool,
+        max_future_drift_ms: u64,
+    ) -> Result<(), PushError> {
+        // Check if the block's immediate predecessor is part of the chain.
+        let prev_info = blockchain
+            .get_chain_info(header.parent_hash(), false, txn_opt)
+            .ok_or(PushError::Orphan)?;
+
+        Self::verify_block_header_with_parent(
+            blockchain,
+            header,
+            &prev_info.head,
+            &blockchain.election_head_hash(),
+            if check_seed { Some(signing_key) } else { None },
+            skip_block,
+            max_future_drift_ms,
+        )
+    }
+
+    /// Verifies the header of a block against the given parent block, which doesn't need to be
+    /// part of the chain yet, e.g. when verifying a batch of blocks before committing any of them.
+    /// `election_head_hash` is the hash of the latest election block preceding the block. The
+    /// seed is only verified if the signing key of the block's proposer is given.
+    pub(crate) fn verify_block_header_with_parent<B: AbstractBlockchain>(
+        blockchain: &B,
+        header: &BlockHeader,
+        parent: &Block,
+        election_head_hash: &Blake2bHash,
+        signing_key: Option<&SchnorrPublicKey>,
+        skip_block: bool,
+        max_future_drift_ms: u64,
     ) -> Result<(), PushError> {
         // Check the version
         if header.version() != policy::VERSION {
@@ -58,13 +114,8 @@ impl Blockchain {
             return Err(PushError::InvalidBlock(BlockError::ExtraDataTooLarge));
         }
 
-        // Check if the block's immediate predecessor is part of the chain.
-        let prev_info = blockchain
-            .get_chain_info(header.parent_hash(), false, txn_opt)
-            .ok_or(PushError::Orphan)?;
-
         // Check that the block is a valid successor of its predecessor.
-        let next_block_type = blockchain.get_next_block_type(Some(prev_info.head.block_number()));
+        let next_block_type = blockchain.get_next_block_type(Some(parent.block_number()));
         if header.ty() != next_block_type {
             warn!(
                 header = %header,
@@ -75,7 +126,7 @@ impl Blockchain {
         }
 
         // Check the block number.
-        let next_block_number = prev_info.head.block_number() + 1;
+        let next_block_number = parent.block_number() + 1;
         if header.block_number() != next_block_number {
             warn!(
                 header = %header,
@@ -89,11 +140,11 @@ impl Blockchain {
 
         // Check that the current block timestamp is equal or greater than the timestamp of the
         // previous block.
-        if header.timestamp() < prev_info.head.timestamp() {
+        if header.timestamp() < parent.timestamp() {
             warn!(
                 header = %header,
                 obtained_timestamp = header.timestamp(),
-                parent_timestamp   = prev_info.head.timestamp(),
+                parent_timestamp   = parent.timestamp(),
                 reason = "Block timestamp precedes parent timestamp",
                 "Rejecting block"
             );
@@ -101,13 +152,11 @@ impl Blockchain {
         }
 
         // Check that skip blocks has the expected timestamp
-        if skip_block
-            && header.timestamp() != prev_info.head.timestamp() + policy::BLOCK_PRODUCER_TIMEOUT
-        {
+        if skip_block && header.timestamp() != parent.timestamp() + policy::BLOCK_PRODUCER_TIMEOUT {
             warn!(
                 header = %header,
                 obtained_timestamp = header.timestamp(),
-                expected_timestamp   = prev_info.head.timestamp() + policy::BLOCK_PRODUCER_TIMEOUT,
+                expected_timestamp   = parent.timestamp() + policy::BLOCK_PRODUCER_TIMEOUT,
                 reason = "Unexpected timestamp for a skip block",
                 "Rejecting block"
             );
@@ -119,13 +168,13 @@ impl Blockchain {
         // Check that the current block timestamp subtracting the node's current time is less than or equal
         // to the allowed maximum drift. Basically, we check that the block isn't from the future.
         // Both times are given in Unix time standard in millisecond precision.
-        let timestamp_diff = header.timestamp().saturating_sub(blockchain.now());
-        if timestamp_diff > policy::TIMESTAMP_MAX_DRIFT {
+        let now = blockchain.now();
+        if header.is_from_future(now, max_future_drift_ms) {
             warn!(
                 header = %header,
                 block_timestamp = header.timestamp(),
-                obtained_timestamp_diff = timestamp_diff,
-                max_timestamp_drift     = policy::TIMESTAMP_MAX_DRIFT,
+                obtained_timestamp_diff = header.timestamp_drift(now),
+                max_timestamp_drift     = max_future_drift_ms,
                 reason = "Block timestamp exceeds allowed maximum drift",
                 "Rejecting block"
             );
@@ -133,31 +182,32 @@ impl Blockchain {
         }
 
         // Check if the seed was signed by the intended producer.
-        if check_seed {
+        if let Some(signing_key) = signing_key {
             if skip_block {
                 // In skip blocks the VRF seed must be carried over (because a new VRF seed requires a new leader)
-                if header.seed() != prev_info.head.seed() {
+                if header.seed() != parent.seed() {
                     warn!(header = %header,
                         reason = "Invalid seed",
                         "Rejecting skip block");
-                    return Err(PushError::InvalidBlock(BlockError::InvalidSeed));
+                    return Err(PushError::InvalidBlock(BlockError::SkipBlockSeedMismatch));
                 }
-            } else if let Err(e) = header.seed().verify(prev_info.head.seed(), signing_key) {
+            } else if let Err(e) = header.seed().verify(parent.seed(), signing_key) {
                 warn!(header = %header,
                       reason = "Invalid seed",
-                      "Rejecting block vrf_error={:?}", e);
-                return Err(PushError::InvalidBlock(BlockError::InvalidSeed));
+                      vrf_error = %e,
+                      "Rejecting block");
+                return Err(PushError::InvalidBlock(BlockError::InvalidSeed(e)));
             }
         }
 
         if header.ty() == BlockType::Macro {
             // Check if the parent election hash matches the current election head hash
             let parent_election_hash = header.parent_election_hash().unwrap();
-            if parent_election_hash != &blockchain.election_head_hash() {
+            if parent_election_hash != election_head_hash {
                 warn!(
                     header = %header,
                     parent_election_hash = %parent_election_hash,
-                    blockchain_election_hash = %blockchain.election_head_hash(),
+                    blockchain_election_hash = %election_head_hash,
                     reason = "Wrong parent election hash",
                     "Rejecting block"
                 );
@@ -208,11 +258,14 @@ impl Blockchain {
                                 vrf_entropy: micro_block.header.seed.entropy(),
                             };
 
-                            if !justification
-                                .verify(&skip_block_info, &blockchain.current_validators().unwrap())
-                            {
+                            if let Err(error) = Self::verify_multisig(
+                                blockchain,
+                                &justification.sig,
+                                &skip_block_info.hash_with_prefix(),
+                            ) {
                                 warn!(
                                     %block,
+                                    %error,
                                     reason = "Bad skip block proof",
                                     "Rejecting block");
                                 return Err(PushError::InvalidBlock(
@@ -225,18 +278,29 @@ impl Blockchain {
             }
             Block::Macro(macro_block) => {
                 // Verify the Tendermint proof.
-                if check_signature
-                    && !TendermintProof::verify(
-                        macro_block,
-                        &blockchain.current_validators().unwrap(),
-                    )
-                {
-                    warn!(
-                        %block,
-                        reason = "Macro block with bad justification",
-                        "Rejecting block"
-                    );
-                    return Err(PushError::InvalidBlock(BlockError::InvalidJustification));
+                if check_signature {
+                    let justification = macro_block.justification.as_ref().ok_or_else(|| {
+                        warn!(
+                            %block,
+                            reason = "Macro block without justification",
+                            "Rejecting block"
+                        );
+                        PushError::InvalidBlock(BlockError::InvalidJustification)
+                    })?;
+
+                    if let Err(error) = Self::verify_multisig(
+                        blockchain,
+                        &justification.sig,
+                        &justification.message_hash(macro_block),
+                    ) {
+                        warn!(
+                            %block,
+                            %error,
+                            reason = "Macro block with bad justification",
+                            "Rejecting block"
+                        );
+                        return Err(PushError::InvalidBlock(BlockError::InvalidJustification));
+                    }
                 }
             }
         }
@@ -244,6 +308,30 @@ impl Blockchain {
         Ok(())
     }
 
+    /// Verifies a multisignature of the current validators over the given message hash. If the
+    /// blockchain keeps a cache of aggregated voting keys, the signers' aggregated key is taken
+    /// from it instead of being aggregated again.
+    fn verify_multisig<B: AbstractBlockchain>(
+        blockchain: &B,
+        multisig: &MultiSignature,
+        message_hash: &SigHash,
+    ) -> Result<u16, MultisigError> {
+        let validators = blockchain.current_validators().unwrap();
+
+        match blockchain.aggregate_public_key_cache() {
+            Some(cache) => {
+                let agg_pk = cache.lock().get_or_aggregate(
+                    policy::last_election_block(blockchain.block_number()),
+                    &blockchain.election_head_hash(),
+                    multisig,
+                    &validators,
+                )?;
+                multisig.verify_aggregate(&agg_pk, message_hash)
+            }
+            None => multisig.verify_against_validators(&validators, message_hash),
+        }
+    }
+
     /// Verifies the body of a block.
     /// This only performs checks that can be made BEFORE the state is updated with the block. All
     /// checks that require the updated state (ex: if an account has enough funds) are made on the
@@ -409,7 +497,15 @@ impl Blockchain {
                 // If this is an election block, check if the pk_tree_root matches the validators.
                 if is_election {
                     let pk_tree_root = MacroBlock::pk_tree_root(body.validators.as_ref().unwrap());
-                    if pk_tree_root != *body.pk_tree_root.as_ref().unwrap() {
+                    let body_pk_tree_root = body.pk_tree_root.as_ref().unwrap();
+                    if pk_tree_root != *body_pk_tree_root {
+                        warn!(
+                            %header,
+                            expected_pk_tree_root = %hex::encode(&pk_tree_root),
+                            pk_tree_root = %hex::encode(body_pk_tree_root),
+                            reason = "pk_tree_root doesn't match the validators",
+                            "Rejecting block"
+                        );
                         return Err(PushError::InvalidBlock(BlockError::InvalidPkTreeRoot));
                     }
                 }
@@ -11,7 +11,7 @@ use std::sync::Arc;
 use crate::blockchain_state::BlockchainState;
 #[cfg(feature = "metrics")]
 use crate::chain_metrics::BlockchainMetrics;
-use crate::{AbstractBlockchain, Blockchain, BlockchainEvent, Direction};
+use crate::{AbstractBlockchain, Blockchain, BlockchainEvent, Direction, SlashRecord};
 use nimiq_trie::key_nibbles::KeyNibbles;
 
 /// Implements several wrapper functions.
@@ -54,6 +54,70 @@ impl Blockchain {
         )
     }
 
+    /// Fetches all blocks at the given height, including the ones on forks. The blocks include
+    /// their bodies, unless these were pruned.
+    pub fn get_blocks_at_height(&self, height: u32) -> Vec<Block> {
+        self.chain_store.get_blocks_at(height, true, None)
+    }
+
+    /// Fetches the main chain block at the given height, including its body.
+    pub fn get_main_chain_block_at(&self, height: u32) -> Option<Block> {
+        self.chain_store
+            .get_chain_info_at(height, true, None)
+            .filter(|chain_info| chain_info.on_main_chain)
+            .map(|chain_info| chain_info.head)
+    }
+
+    /// Returns the slashes applied by the main chain blocks of the given epoch, ordered by block
+    /// number.
+    pub fn get_slashes(&self, epoch: u32) -> Vec<SlashRecord> {
+        self.chain_store.get_slashes(
+            policy::first_block_of(epoch),
+            policy::election_block_of(epoch),
+            None,
+        )
+    }
+
+    /// Returns all stored slashes of the validator with the given address, ordered by block
+    /// number.
+    pub fn get_validator_slashes(&self, validator_address: &Address) -> Vec<SlashRecord> {
+        self.chain_store
+            .get_validator_slashes(validator_address, None)
+    }
+
+    /// Estimates the block reward, in Lunas, that the validator with the given address receives for
+    /// its slots in the given epoch, see `StakingContract::compute_epoch_reward`. The duration of
+    /// the epoch is extrapolated from the average block time of the chain.
+    /// Returns zero if the epoch isn't the current one, if the validator has no slots in it, or if
+    /// there are no blocks yet to compute the average block time from.
+    pub fn compute_epoch_reward(&self, validator_address: &Address, epoch: u32) -> u64 {
+        let head_block_number = self.block_number();
+
+        // The current slots are the ones of the epoch of the next block.
+        if head_block_number == 0 || epoch != policy::epoch_at(head_block_number + 1) {
+            return 0;
+        }
+
+        let validators = match self.current_validators() {
+            Some(validators) => validators,
+            None => return 0,
+        };
+
+        let average_block_time =
+            (self.head().timestamp() - self.genesis_timestamp) / head_block_number as u64;
+        let start_timestamp = self.election_head().header.timestamp;
+        let end_timestamp = start_timestamp + average_block_time * policy::BLOCKS_PER_EPOCH as u64;
+
+        self.get_staking_contract().compute_epoch_reward(
+            &validators,
+            validator_address,
+            self.genesis_supply,
+            self.genesis_timestamp,
+            start_timestamp,
+            end_timestamp,
+        )
+    }
+
     /// Returns the current staking contract.
     pub fn get_staking_contract(&self) -> StakingContract {
         let staking_contract_address = StakingContract::get_key_staking_contract();
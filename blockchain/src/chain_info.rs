@@ -22,6 +22,8 @@ pub struct ChainInfo {
     pub main_chain_successor: Option<Blake2bHash>,
     // The sum of all transaction fees in this chain. It resets every batch.
     pub cum_tx_fees: Coin,
+    // A boolean stating if the body of this block was pruned from the chain store.
+    pub body_pruned: bool,
 }
 
 impl ChainInfo {
@@ -32,6 +34,7 @@ impl ChainInfo {
             on_main_chain,
             main_chain_successor: None,
             cum_tx_fees: Coin::ZERO,
+            body_pruned: false,
         }
     }
 
@@ -52,6 +55,7 @@ impl ChainInfo {
             main_chain_successor: None,
             head: block,
             cum_tx_fees,
+            body_pruned: false,
         }
     }
 }
@@ -87,6 +91,7 @@ impl Serialize for ChainInfo {
         size += Serialize::serialize(&self.on_main_chain, writer)?;
         size += Serialize::serialize(&self.main_chain_successor, writer)?;
         size += Serialize::serialize(&self.cum_tx_fees, writer)?;
+        size += Serialize::serialize(&self.body_pruned, writer)?;
         Ok(size)
     }
 
@@ -108,6 +113,7 @@ impl Serialize for ChainInfo {
         size += Serialize::serialized_size(&self.on_main_chain);
         size += Serialize::serialized_size(&self.main_chain_successor);
         size += Serialize::serialized_size(&self.cum_tx_fees);
+        size += Serialize::serialized_size(&self.body_pruned);
         size
     }
 }
@@ -152,12 +158,19 @@ impl Deserialize for ChainInfo {
         let on_main_chain = Deserialize::deserialize(reader)?;
         let main_chain_successor = Deserialize::deserialize(reader)?;
         let cum_tx_fees = Deserialize::deserialize(reader)?;
+        // Chain infos stored before block bodies could be pruned end here.
+        let body_pruned = match Deserialize::deserialize(reader) {
+            Ok(body_pruned) => body_pruned,
+            Err(SerializingError::IoError(e)) if e.kind() == io::ErrorKind::UnexpectedEof => false,
+            Err(e) => return Err(e),
+        };
 
         Ok(ChainInfo {
             head,
             on_main_chain,
             main_chain_successor,
             cum_tx_fees,
+            body_pruned,
         })
     }
 }
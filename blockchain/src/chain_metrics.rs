@@ -1,4 +1,4 @@
-use crate::{PushError, PushResult};
+use crate::{PushDetails, PushError, PushResult};
 use nimiq_block::Block;
 use nimiq_block::BlockBody::Micro;
 use nimiq_hash::Blake2bHash;
@@ -56,8 +56,8 @@ impl BlockchainMetrics {
     }
 
     #[inline]
-    pub fn note_push_result(&self, push_result: &Result<PushResult, PushError>) {
-        let push_result = match push_result {
+    pub fn note_push_result(&self, push_result: &Result<PushDetails, PushError>) {
+        let push_result = match push_result.as_ref().map(PushDetails::kind) {
             Ok(PushResult::Known) => BlockPushResult::Known,
             Ok(PushResult::Extended) => BlockPushResult::Extended,
             Ok(PushResult::Rebranched) => BlockPushResult::Rebranched,
@@ -95,8 +95,8 @@ impl BlockchainMetrics {
     #[inline]
     pub fn note_rebranch(
         &self,
-        reverted_blocks: &Vec<(Blake2bHash, Block)>,
-        adopted_blocks: &Vec<(Blake2bHash, Block)>,
+        reverted_blocks: &[(Blake2bHash, Block)],
+        adopted_blocks: &[(Blake2bHash, Block)],
     ) {
         for (_, micro_block) in reverted_blocks {
             if let Some(Micro(micro_body)) = micro_block.body() {
@@ -1,3 +1,5 @@
+use std::ops::Deref;
+
 use nimiq_account::Receipts;
 use nimiq_block::Block;
 use nimiq_database::cursor::{ReadCursor, WriteCursor};
@@ -5,9 +7,11 @@ use nimiq_database::{
     Database, DatabaseFlags, Environment, ReadTransaction, Transaction, WriteTransaction,
 };
 use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
 use nimiq_primitives::policy;
 
 use crate::chain_info::ChainInfo;
+use crate::slash_record::SlashRecord;
 use crate::Direction;
 
 /// Maximum number of epochs (other than the current one) that the ChainStore will store fully.
@@ -25,6 +29,10 @@ pub struct ChainStore {
     height_idx: Database,
     // A database of the transaction receipts for a block, by their corresponding block hashes.
     receipt_db: Database,
+    // A database of the slashes applied by main chain blocks, indexed by block number.
+    slash_db: Database,
+    // A database of the numbers of the blocks that slashed a validator, indexed by its address.
+    validator_slash_idx: Database,
 }
 
 impl ChainStore {
@@ -32,6 +40,8 @@ impl ChainStore {
     const BLOCK_DB_NAME: &'static str = "Block";
     const HEIGHT_IDX_NAME: &'static str = "HeightIndex";
     const RECEIPT_DB_NAME: &'static str = "Receipts";
+    const SLASH_DB_NAME: &'static str = "Slashes";
+    const VALIDATOR_SLASH_IDX_NAME: &'static str = "ValidatorSlashIndex";
 
     const HEAD_KEY: &'static str = "head";
 
@@ -44,12 +54,22 @@ impl ChainStore {
         );
         let receipt_db = env
             .open_database_with_flags(Self::RECEIPT_DB_NAME.to_string(), DatabaseFlags::UINT_KEYS);
+        let slash_db = env.open_database_with_flags(
+            Self::SLASH_DB_NAME.to_string(),
+            DatabaseFlags::DUPLICATE_KEYS | DatabaseFlags::UINT_KEYS,
+        );
+        let validator_slash_idx = env.open_database_with_flags(
+            Self::VALIDATOR_SLASH_IDX_NAME.to_string(),
+            DatabaseFlags::DUPLICATE_KEYS | DatabaseFlags::DUP_FIXED_SIZE_VALUES,
+        );
         ChainStore {
             env,
             chain_db,
             block_db,
             height_idx,
             receipt_db,
+            slash_db,
+            validator_slash_idx,
         }
     }
 
@@ -64,6 +84,8 @@ impl ChainStore {
         txn.put(&self.chain_db, ChainStore::HEAD_KEY, hash);
     }
 
+    /// Returns the chain info of the block with the given hash. If the body is requested but was
+    /// pruned, the chain info is returned without it and `body_pruned` is set.
     pub fn get_chain_info(
         &self,
         hash: &Blake2bHash,
@@ -84,7 +106,7 @@ impl ChainStore {
             None => return None,
         };
 
-        if include_body {
+        if include_body && !chain_info.body_pruned {
             if let Some(block) = txn.get(&self.block_db, hash) {
                 chain_info.head = block;
             } else {
@@ -95,6 +117,8 @@ impl ChainStore {
         Some(chain_info)
     }
 
+    /// Returns the chain info of the main chain block at the given height. If the body is
+    /// requested but was pruned, the chain info is returned without it and `body_pruned` is set.
     pub fn get_chain_info_at(
         &self,
         block_height: u32,
@@ -132,7 +156,7 @@ impl ChainStore {
             };
         };
 
-        if include_body {
+        if include_body && !chain_info.body_pruned {
             if let Some(block) = txn.get(&self.block_db, &block_hash) {
                 chain_info.head = block;
             } else {
@@ -170,6 +194,8 @@ impl ChainStore {
         txn.remove_item(&self.height_idx, &height, hash);
     }
 
+    /// Returns the block with the given hash. If the body is requested, `None` is also returned
+    /// when the body of the block was pruned.
     pub fn get_block(
         &self,
         hash: &Blake2bHash,
@@ -193,6 +219,8 @@ impl ChainStore {
         }
     }
 
+    /// Returns the main chain block at the given height. If the body is requested, `None` is also
+    /// returned when the body of the block was pruned.
     pub fn get_block_at(
         &self,
         block_height: u32,
@@ -200,6 +228,7 @@ impl ChainStore {
         txn_option: Option<&Transaction>,
     ) -> Option<Block> {
         self.get_chain_info_at(block_height, include_body, txn_option)
+            .filter(|chain_info| !(include_body && chain_info.body_pruned))
             .map(|chain_info| chain_info.head)
     }
 
@@ -258,39 +287,72 @@ impl ChainStore {
         blocks
     }
 
-    fn get_blocks_backward(
-        &self,
-        start_block_hash: &Blake2bHash,
-        count: u32,
+    /// Returns an iterator over the main chain blocks with a block number between `start` and
+    /// `end` (both inclusive). Blocks are loaded lazily, one at a time, by following the links
+    /// between them. In forward direction the blocks are returned in ascending order, in
+    /// backward direction in descending order.
+    pub fn iter_blocks<'a>(
+        &'a self,
+        start: u32,
+        end: u32,
+        direction: Direction,
         include_body: bool,
-        txn_option: Option<&Transaction>,
-    ) -> Vec<Block> {
-        let read_txn: ReadTransaction;
-        let txn = match txn_option {
-            Some(txn) => txn,
-            None => {
-                read_txn = ReadTransaction::new(&self.env);
-                &read_txn
-            }
+        txn_option: Option<&'a Transaction<'a>>,
+    ) -> impl Iterator<Item = Block> + 'a {
+        let (first, bound) = match direction {
+            Direction::Forward => (start, end),
+            Direction::Backward => (end, start),
         };
 
-        let mut blocks = Vec::new();
-        let start_block = match self.get_block(start_block_hash, false, Some(txn)) {
-            Some(block) => block,
-            None => return blocks,
+        let mut iter = BlockIter::new(self, txn_option, direction, include_body, bound);
+        if start <= end {
+            iter.next_hash = self
+                .get_chain_info_at(first, false, Some(&iter.txn))
+                .map(|chain_info| chain_info.head.hash());
+        }
+        iter
+    }
+
+    /// Returns an iterator over the blocks following the given block in the given direction,
+    /// excluding the block itself. Forward iteration follows the main chain, backward iteration
+    /// follows the parent links.
+    fn iter_blocks_after<'a>(
+        &'a self,
+        start_block_hash: &Blake2bHash,
+        direction: Direction,
+        include_body: bool,
+        txn_option: Option<&'a Transaction<'a>>,
+    ) -> BlockIter<'a> {
+        let bound = match direction {
+            Direction::Forward => u32::MAX,
+            Direction::Backward => 0,
         };
 
-        let mut hash = start_block.parent_hash().clone();
-        while (blocks.len() as u32) < count {
-            if let Some(block) = self.get_block(&hash, include_body, Some(txn)) {
-                hash = block.parent_hash().clone();
-                blocks.push(block);
-            } else {
-                break;
-            }
+        let mut iter = BlockIter::new(self, txn_option, direction, include_body, bound);
+        if let Some(chain_info) = self.get_chain_info(start_block_hash, false, Some(&iter.txn)) {
+            iter.next_hash = match direction {
+                Direction::Forward => chain_info.main_chain_successor,
+                Direction::Backward => Some(chain_info.head.parent_hash().clone()),
+            };
         }
+        iter
+    }
 
-        blocks
+    fn get_blocks_backward(
+        &self,
+        start_block_hash: &Blake2bHash,
+        count: u32,
+        include_body: bool,
+        txn_option: Option<&Transaction>,
+    ) -> Vec<Block> {
+        self.iter_blocks_after(
+            start_block_hash,
+            Direction::Backward,
+            include_body,
+            txn_option,
+        )
+        .take(count as usize)
+        .collect()
     }
 
     fn get_blocks_forward(
@@ -300,36 +362,14 @@ impl ChainStore {
         include_body: bool,
         txn_option: Option<&Transaction>,
     ) -> Vec<Block> {
-        let read_txn: ReadTransaction;
-        let txn = match txn_option {
-            Some(txn) => txn,
-            None => {
-                read_txn = ReadTransaction::new(&self.env);
-                &read_txn
-            }
-        };
-
-        let mut blocks = Vec::new();
-        let mut chain_info = match self.get_chain_info(start_block_hash, false, Some(txn)) {
-            Some(chain_info) => chain_info,
-            None => return blocks,
-        };
-
-        while (blocks.len() as u32) < count {
-            if let Some(ref successor) = chain_info.main_chain_successor {
-                let chain_info_opt = self.get_chain_info(successor, include_body, Some(txn));
-                if chain_info_opt.is_none() {
-                    break;
-                }
-
-                chain_info = chain_info_opt.unwrap();
-                blocks.push(chain_info.head);
-            } else {
-                break;
-            }
-        }
-
-        blocks
+        self.iter_blocks_after(
+            start_block_hash,
+            Direction::Forward,
+            include_body,
+            txn_option,
+        )
+        .take(count as usize)
+        .collect()
     }
 
     /// Returns None if given start_block_hash is not a macro block.
@@ -468,6 +508,65 @@ impl ChainStore {
                 txn.remove_item(&self.height_idx, &height, &hash);
             }
         }
+
+        // The slashes applied by the pruned blocks are dropped as well.
+        let mut block_numbers: Vec<u32> = self
+            .get_slashes(
+                policy::first_block_of(epoch_number),
+                policy::election_block_of(epoch_number),
+                Some(txn),
+            )
+            .iter()
+            .map(|slash| slash.block_number)
+            .collect();
+        block_numbers.dedup();
+
+        for block_number in block_numbers {
+            self.remove_slashes(txn, block_number);
+        }
+    }
+
+    /// Removes the bodies of all micro blocks below the given block number, while keeping their
+    /// chain infos. The chain infos are marked as pruned, so that a missing body can be told apart
+    /// from an inconsistent store. Since pruning is done regularly, this walks down from the given
+    /// block number and stops at the first height that was already pruned.
+    pub fn prune_bodies_before(&self, block_number: u32, txn: &mut WriteTransaction) {
+        for height in (1..block_number).rev() {
+            // Macro block bodies are kept.
+            if policy::is_macro_block_at(height) {
+                continue;
+            }
+
+            let mut hashes = Vec::new();
+            let mut cursor = txn.cursor(&self.height_idx);
+            let mut hash = cursor.seek_key::<u32, Blake2bHash>(&height);
+            while let Some(block_hash) = hash {
+                hashes.push(block_hash);
+                hash = cursor
+                    .next_duplicate::<u32, Blake2bHash>()
+                    .map(|(_, hash)| hash);
+            }
+            drop(cursor);
+
+            let mut num_pruned = 0;
+            for hash in hashes {
+                let mut chain_info: ChainInfo = match txn.get(&self.chain_db, &hash) {
+                    Some(chain_info) => chain_info,
+                    None => continue,
+                };
+
+                if !chain_info.body_pruned {
+                    chain_info.body_pruned = true;
+                    txn.put_reserve(&self.chain_db, &hash, &chain_info);
+                    txn.remove(&self.block_db, &hash);
+                    num_pruned += 1;
+                }
+            }
+
+            if num_pruned == 0 {
+                break;
+            }
+        }
     }
 
     pub fn put_receipts(&self, txn: &mut WriteTransaction, block_height: u32, receipts: &Receipts) {
@@ -491,6 +590,105 @@ impl ChainStore {
         txn.get(&self.receipt_db, &block_height)
     }
 
+    /// Stores the slashes applied by the main chain block with the given number.
+    pub fn put_slashes(
+        &self,
+        txn: &mut WriteTransaction,
+        block_number: u32,
+        slashes: &[SlashRecord],
+    ) {
+        for slash in slashes {
+            txn.put(&self.slash_db, &block_number, slash);
+            txn.put(
+                &self.validator_slash_idx,
+                &slash.validator_address,
+                &block_number,
+            );
+        }
+    }
+
+    /// Removes the slashes applied by the block with the given number, e.g. when it is reverted.
+    pub fn remove_slashes(&self, txn: &mut WriteTransaction, block_number: u32) {
+        for slash in self.get_slashes(block_number, block_number, Some(txn)) {
+            txn.remove_item(
+                &self.validator_slash_idx,
+                &slash.validator_address,
+                &block_number,
+            );
+        }
+
+        txn.remove(&self.slash_db, &block_number);
+    }
+
+    /// Returns the slashes applied by main chain blocks with a block number between `start` and
+    /// `end` (both inclusive), ordered by block number.
+    pub fn get_slashes(
+        &self,
+        start: u32,
+        end: u32,
+        txn_option: Option<&Transaction>,
+    ) -> Vec<SlashRecord> {
+        let read_txn: ReadTransaction;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = ReadTransaction::new(&self.env);
+                &read_txn
+            }
+        };
+
+        let mut slashes = Vec::new();
+        let mut cursor = txn.cursor(&self.slash_db);
+        let mut entry = cursor.seek_range_key::<u32, SlashRecord>(&start);
+        while let Some((block_number, slash)) = entry {
+            if block_number > end {
+                break;
+            }
+            slashes.push(slash);
+            entry = cursor.next::<u32, SlashRecord>();
+        }
+
+        slashes
+    }
+
+    /// Returns the stored slashes of the validator with the given address, ordered by block
+    /// number.
+    pub fn get_validator_slashes(
+        &self,
+        validator_address: &Address,
+        txn_option: Option<&Transaction>,
+    ) -> Vec<SlashRecord> {
+        let read_txn: ReadTransaction;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = ReadTransaction::new(&self.env);
+                &read_txn
+            }
+        };
+
+        let mut block_numbers = Vec::new();
+        let mut cursor = txn.cursor(&self.validator_slash_idx);
+        let mut block_number = cursor.seek_key::<Address, u32>(validator_address);
+        while let Some(number) = block_number {
+            block_numbers.push(number);
+            block_number = cursor
+                .next_duplicate::<Address, u32>()
+                .map(|(_, number)| number);
+        }
+        drop(cursor);
+
+        // Block numbers are stored in native byte order and the duplicates are compared
+        // byte-wise, so the cursor does not return them in numeric order.
+        block_numbers.sort_unstable();
+
+        block_numbers
+            .into_iter()
+            .flat_map(|number| self.get_slashes(number, number, Some(txn)))
+            .filter(|slash| &slash.validator_address == validator_address)
+            .collect()
+    }
+
     pub fn clear_receipts(&self, txn: &mut WriteTransaction) {
         let mut cursor = txn.write_cursor(&self.receipt_db);
         let mut pos: Option<(u32, Receipts)> = cursor.first();
@@ -501,3 +699,88 @@ impl ChainStore {
         }
     }
 }
+
+/// A database transaction that is either borrowed from the caller or owned by the iterator.
+enum TransactionRef<'a> {
+    Borrowed(&'a Transaction<'a>),
+    Owned(ReadTransaction<'a>),
+}
+
+impl<'a> Deref for TransactionRef<'a> {
+    type Target = Transaction<'a>;
+
+    fn deref(&self) -> &Transaction<'a> {
+        match self {
+            TransactionRef::Borrowed(txn) => txn,
+            TransactionRef::Owned(txn) => txn,
+        }
+    }
+}
+
+/// An iterator over consecutive blocks in the chain store. It yields blocks until it reaches the
+/// bound block number or a block that is not in the store.
+struct BlockIter<'a> {
+    chain_store: &'a ChainStore,
+    txn: TransactionRef<'a>,
+    next_hash: Option<Blake2bHash>,
+    direction: Direction,
+    include_body: bool,
+    bound: u32,
+}
+
+impl<'a> BlockIter<'a> {
+    fn new(
+        chain_store: &'a ChainStore,
+        txn_option: Option<&'a Transaction<'a>>,
+        direction: Direction,
+        include_body: bool,
+        bound: u32,
+    ) -> Self {
+        let txn = match txn_option {
+            Some(txn) => TransactionRef::Borrowed(txn),
+            None => TransactionRef::Owned(ReadTransaction::new(&chain_store.env)),
+        };
+
+        BlockIter {
+            chain_store,
+            txn,
+            next_hash: None,
+            direction,
+            include_body,
+            bound,
+        }
+    }
+}
+
+impl<'a> Iterator for BlockIter<'a> {
+    type Item = Block;
+
+    fn next(&mut self) -> Option<Block> {
+        let hash = self.next_hash.take()?;
+        let chain_info =
+            self.chain_store
+                .get_chain_info(&hash, self.include_body, Some(&self.txn))?;
+
+        let block_number = chain_info.head.block_number();
+        match self.direction {
+            Direction::Forward => {
+                if block_number > self.bound {
+                    return None;
+                }
+                if block_number < self.bound {
+                    self.next_hash = chain_info.main_chain_successor;
+                }
+            }
+            Direction::Backward => {
+                if block_number < self.bound {
+                    return None;
+                }
+                if block_number > self.bound {
+                    self.next_hash = Some(chain_info.head.parent_hash().clone());
+                }
+            }
+        }
+
+        Some(chain_info.head)
+    }
+}
@@ -8,7 +8,14 @@ use nimiq_primitives::networks::NetworkId;
 /// An enum used when a fork is detected.
 #[derive(Clone, Debug)]
 pub enum ForkEvent {
-    Detected(ForkProof),
+    /// A validator produced two different micro blocks for the same slot.
+    Detected {
+        proof: ForkProof,
+        /// The block number at which the fork occurred.
+        block_number: u32,
+        /// The slot of the validator that produced the conflicting blocks.
+        slot: u16,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -16,8 +23,15 @@ pub enum BlockchainEvent {
     Extended(Blake2bHash),
     HistoryAdopted(Blake2bHash),
     Rebranched(Vec<(Blake2bHash, Block)>, Vec<(Blake2bHash, Block)>),
+    RevertedTo(Blake2bHash, Vec<(Blake2bHash, Block)>),
     Finalized(Blake2bHash),
     EpochFinalized(Blake2bHash),
+    TransactionConfirmed {
+        tx_hash: Blake2bHash,
+        block_hash: Blake2bHash,
+        block_number: u32,
+        index: usize,
+    },
 }
 
 impl BlockchainEvent {
@@ -32,8 +46,10 @@ impl BlockchainEvent {
                     unreachable!()
                 }
             }
+            Self::RevertedTo(h, _) => h,
             Self::Finalized(h) => h,
             Self::EpochFinalized(h) => h,
+            Self::TransactionConfirmed { block_hash, .. } => block_hash,
         }
         .clone()
     }
@@ -60,6 +76,53 @@ pub enum PushResult {
     Ignored,
 }
 
+/// The reason why a pushed block was ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IgnoreReason {
+    /// The block is on a chain that is inferior to the main chain.
+    InferiorChain,
+    /// The block doesn't succeed the latest macro block, which is final.
+    BeforeMacroBlock,
+}
+
+/// The detailed outcome of pushing a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushDetails {
+    kind: PushResult,
+    /// The hash of the head of the main chain after the push.
+    pub head_hash: Blake2bHash,
+    /// The hashes of the blocks that were reverted by a rebranch, from the oldest to the newest.
+    pub reverted_blocks: Vec<Blake2bHash>,
+    /// The hashes of the blocks that were adopted by a rebranch, from the oldest to the newest.
+    pub adopted_blocks: Vec<Blake2bHash>,
+    /// The reason why the block was ignored, if it was.
+    pub ignore_reason: Option<IgnoreReason>,
+}
+
+impl PushDetails {
+    pub(crate) fn new(kind: PushResult, head_hash: Blake2bHash) -> Self {
+        PushDetails {
+            kind,
+            head_hash,
+            reverted_blocks: vec![],
+            adopted_blocks: vec![],
+            ignore_reason: None,
+        }
+    }
+
+    pub(crate) fn ignored(head_hash: Blake2bHash, reason: IgnoreReason) -> Self {
+        PushDetails {
+            ignore_reason: Some(reason),
+            ..Self::new(PushResult::Ignored, head_hash)
+        }
+    }
+
+    /// Returns the kind of the push result.
+    pub fn kind(&self) -> PushResult {
+        self.kind.clone()
+    }
+}
+
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum PushError {
     #[error("Orphan block")]
@@ -78,6 +141,10 @@ pub enum PushError {
     AccountsError(#[from] AccountError),
     #[error("Invalid fork")]
     InvalidFork,
+    #[error("Invalid revert target")]
+    InvalidRevertTarget,
+    #[error("Block body was pruned")]
+    BodyPruned,
     #[error("Blockchain error: {0}")]
     BlockchainError(#[from] BlockchainError),
 }
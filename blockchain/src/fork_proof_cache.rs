@@ -0,0 +1,56 @@
+use std::collections::{HashSet, VecDeque};
+
+use nimiq_block::ForkProof;
+
+/// A bounded cache of fork proofs that are already known, either because they were reported or
+/// because they were included in a block. Once the cache is full, the least recently seen fork
+/// proof is evicted.
+pub(crate) struct ForkProofCache {
+    capacity: usize,
+    proofs: HashSet<ForkProof>,
+    // The cached fork proofs, ordered from the least to the most recently seen one.
+    order: VecDeque<ForkProof>,
+}
+
+impl ForkProofCache {
+    /// The default number of fork proofs kept in the cache.
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            proofs: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Inserts a fork proof into the cache. Returns `true` if the fork proof wasn't known before.
+    pub fn insert(&mut self, proof: ForkProof) -> bool {
+        if self.proofs.contains(&proof) {
+            self.order.retain(|known| known != &proof);
+            self.order.push_back(proof);
+            return false;
+        }
+
+        if self.order.len() >= self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.proofs.remove(&evicted);
+            }
+        }
+
+        self.proofs.insert(proof.clone());
+        self.order.push_back(proof);
+        true
+    }
+
+    /// Returns `true` if the fork proof is already known.
+    pub fn contains(&self, proof: &ForkProof) -> bool {
+        self.proofs.contains(proof)
+    }
+
+    /// Removes all fork proofs from the cache.
+    pub fn clear(&mut self) {
+        self.proofs.clear();
+        self.order.clear();
+    }
+}
@@ -2,13 +2,16 @@
 extern crate log;
 
 pub use abstract_blockchain::AbstractBlockchain;
+pub use aggregate_public_key_cache::AggregatePublicKeyCache;
 pub use blockchain::blockchain::{Blockchain, TransactionVerificationCache};
 pub use chain_info::ChainInfo;
 pub use chain_ordering::ChainOrdering;
 pub use error::*;
 pub use history::*;
+pub use slash_record::{SlashReason, SlashRecord};
 
 pub(crate) mod abstract_blockchain;
+pub(crate) mod aggregate_public_key_cache;
 pub(crate) mod blockchain;
 pub(crate) mod blockchain_state;
 pub(crate) mod chain_info;
@@ -17,5 +20,7 @@ pub mod chain_metrics;
 pub(crate) mod chain_ordering;
 pub(crate) mod chain_store;
 pub(crate) mod error;
+pub(crate) mod fork_proof_cache;
 pub(crate) mod history;
 pub mod reward;
+pub(crate) mod slash_record;
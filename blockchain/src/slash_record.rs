@@ -0,0 +1,73 @@
+use std::io;
+
+use beserial::{Deserialize, Serialize};
+use nimiq_account::{Inherent, InherentType};
+use nimiq_database::{FromDatabaseValue, IntoDatabaseValue};
+use nimiq_keys::Address;
+use nimiq_primitives::slots::SlashedSlot;
+
+/// The reason why a validator was slashed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum SlashReason {
+    /// The validator produced two different blocks at the same height.
+    ForkProof = 0,
+    /// The validator didn't produce its block in time and was skipped.
+    SkipBlock = 1,
+}
+
+/// A record of a slash that was applied by a block on the main chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlashRecord {
+    /// The block that applied the slash.
+    pub block_number: u32,
+    /// The block at which the slashable action occurred.
+    pub event_block: u32,
+    pub slot: u16,
+    pub validator_address: Address,
+    pub reason: SlashReason,
+}
+
+impl SlashRecord {
+    /// Creates the record for a slash inherent applied by the block with the given number.
+    /// Returns None if the inherent is not a slash inherent.
+    pub fn from_inherent(
+        block_number: u32,
+        reason: SlashReason,
+        inherent: &Inherent,
+    ) -> Option<SlashRecord> {
+        if inherent.ty != InherentType::Slash {
+            return None;
+        }
+
+        let slot: SlashedSlot = Deserialize::deserialize_from_vec(&inherent.data).ok()?;
+
+        Some(SlashRecord {
+            block_number,
+            event_block: slot.event_block,
+            slot: slot.slot,
+            validator_address: slot.validator_address,
+            reason,
+        })
+    }
+}
+
+impl IntoDatabaseValue for SlashRecord {
+    fn database_byte_size(&self) -> usize {
+        self.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize(&self, &mut bytes).unwrap();
+    }
+}
+
+impl FromDatabaseValue for SlashRecord {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = io::Cursor::new(bytes);
+        Ok(Deserialize::deserialize(&mut cursor)?)
+    }
+}
@@ -0,0 +1,207 @@
+use nimiq_block::{
+    Message, MicroJustification, MultiSignature, SignedSkipBlockInfo, SkipBlockInfo,
+};
+use nimiq_block_production::test_utils::TemporaryBlockProducer;
+use nimiq_blockchain::{AbstractBlockchain, AggregatePublicKeyCache};
+use nimiq_bls::AggregateSignature;
+use nimiq_collections::bitset::BitSet;
+use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_primitives::policy;
+use nimiq_primitives::slots::Validators;
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{produce_macro_blocks, voting_key};
+use nimiq_vrf::VrfSeed;
+
+fn skip_block_info() -> SkipBlockInfo {
+    SkipBlockInfo {
+        block_number: 42,
+        vrf_entropy: VrfSeed::default().entropy(),
+    }
+}
+
+fn create_multisig(slots: impl Iterator<Item = u16>) -> MultiSignature {
+    let signed_info =
+        SignedSkipBlockInfo::from_message(skip_block_info(), &voting_key().secret_key, 0);
+
+    let mut signers = BitSet::new();
+    for slot in slots {
+        signers.insert(slot as usize);
+    }
+
+    MultiSignature::new(
+        AggregateSignature::from_signatures(&[signed_info
+            .signature
+            .multiply(signers.len() as u16)]),
+        signers,
+    )
+}
+
+fn genesis_validators() -> (Blake2bHash, Validators) {
+    let producer = TemporaryBlockProducer::new();
+    let blockchain = producer.blockchain.read();
+    (
+        blockchain.election_head_hash(),
+        blockchain.current_validators().unwrap(),
+    )
+}
+
+#[test]
+fn it_returns_the_same_key_as_a_fresh_aggregation() {
+    let (election_hash, validators) = genesis_validators();
+    let mut cache = AggregatePublicKeyCache::default();
+
+    let multisig = create_multisig(0..policy::TWO_F_PLUS_ONE);
+    let fresh = multisig.aggregate_public_key(&validators).unwrap();
+
+    let missed = cache
+        .get_or_aggregate(0, &election_hash, &multisig, &validators)
+        .unwrap();
+    let hit = cache
+        .get_or_aggregate(0, &election_hash, &multisig, &validators)
+        .unwrap();
+
+    assert!(fresh == missed);
+    assert!(fresh == hit);
+    assert_eq!(cache.len(), 1);
+    assert!(cache.contains(&election_hash, &multisig.signers));
+
+    // The cached key verifies the multisignature.
+    assert_eq!(
+        multisig.verify_aggregate(&hit, &skip_block_info().hash_with_prefix()),
+        Ok(policy::TWO_F_PLUS_ONE)
+    );
+
+    // The same signers under another election block are cached separately.
+    let other_hash: Blake2bHash = [1u8; 32].hash();
+    cache
+        .get_or_aggregate(0, &other_hash, &multisig, &validators)
+        .unwrap();
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn it_evicts_the_least_recently_used_key() {
+    let (election_hash, validators) = genesis_validators();
+    let mut cache = AggregatePublicKeyCache::new(2, 1);
+
+    let multisig1 = create_multisig(0..policy::TWO_F_PLUS_ONE);
+    let multisig2 = create_multisig(1..policy::TWO_F_PLUS_ONE + 1);
+    let multisig3 = create_multisig(2..policy::TWO_F_PLUS_ONE + 2);
+
+    cache
+        .get_or_aggregate(0, &election_hash, &multisig1, &validators)
+        .unwrap();
+    cache
+        .get_or_aggregate(0, &election_hash, &multisig2, &validators)
+        .unwrap();
+
+    // Using the first key again makes the second one the least recently used.
+    cache
+        .get_or_aggregate(0, &election_hash, &multisig1, &validators)
+        .unwrap();
+    cache
+        .get_or_aggregate(0, &election_hash, &multisig3, &validators)
+        .unwrap();
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains(&election_hash, &multisig1.signers));
+    assert!(!cache.contains(&election_hash, &multisig2.signers));
+    assert!(cache.contains(&election_hash, &multisig3.signers));
+}
+
+#[test]
+fn it_evicts_keys_outside_of_the_retention_window() {
+    let (election_hash, validators) = genesis_validators();
+    let next_election_hash: Blake2bHash = [1u8; 32].hash();
+    let mut cache = AggregatePublicKeyCache::new(16, 1);
+
+    let multisig = create_multisig(0..policy::TWO_F_PLUS_ONE);
+    cache
+        .get_or_aggregate(0, &election_hash, &multisig, &validators)
+        .unwrap();
+    cache
+        .get_or_aggregate(
+            policy::BLOCKS_PER_EPOCH,
+            &next_election_hash,
+            &multisig,
+            &validators,
+        )
+        .unwrap();
+
+    // The previous epoch is still within the retention window.
+    cache.evict_outdated(policy::BLOCKS_PER_EPOCH);
+    assert_eq!(cache.len(), 2);
+
+    cache.evict_outdated(2 * policy::BLOCKS_PER_EPOCH);
+    assert_eq!(cache.len(), 1);
+    assert!(!cache.contains(&election_hash, &multisig.signers));
+    assert!(cache.contains(&next_election_hash, &multisig.signers));
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn it_caches_keys_when_verifying_justifications() {
+    let producer = TemporaryBlockProducer::new();
+    assert!(producer
+        .blockchain
+        .read()
+        .aggregate_public_keys
+        .lock()
+        .is_empty());
+
+    // The skip block proof is verified against the current validators when it is pushed.
+    let skip_block = producer.next_block(vec![], true);
+    let signers = match skip_block.unwrap_micro_ref().justification {
+        Some(MicroJustification::Skip(ref proof)) => &proof.sig.signers,
+        _ => panic!("Skip block without a skip block proof"),
+    };
+
+    let blockchain = producer.blockchain.read();
+    let cache = blockchain.aggregate_public_keys.lock();
+    assert!(cache.contains(&blockchain.election_head_hash(), signers));
+}
+
+#[test]
+fn it_evicts_cached_keys_at_election_blocks() {
+    let producer = TemporaryBlockProducer::new();
+    let genesis_hash = producer.blockchain.read().election_head_hash();
+
+    let skip_block = producer.next_block(vec![], true);
+    let signers = match skip_block.unwrap_micro_ref().justification {
+        Some(MicroJustification::Skip(ref proof)) => proof.sig.signers.clone(),
+        _ => panic!("Skip block without a skip block proof"),
+    };
+    assert!(producer
+        .blockchain
+        .read()
+        .aggregate_public_keys
+        .lock()
+        .contains(&genesis_hash, &signers));
+
+    // The key of the genesis epoch is kept until the retention window has passed.
+    produce_macro_blocks(
+        &producer.producer,
+        &producer.blockchain,
+        policy::BATCHES_PER_EPOCH as usize,
+    );
+    assert!(producer
+        .blockchain
+        .read()
+        .aggregate_public_keys
+        .lock()
+        .contains(&genesis_hash, &signers));
+
+    produce_macro_blocks(
+        &producer.producer,
+        &producer.blockchain,
+        policy::BATCHES_PER_EPOCH as usize,
+    );
+    assert!(!producer
+        .blockchain
+        .read()
+        .aggregate_public_keys
+        .lock()
+        .contains(&genesis_hash, &signers));
+}
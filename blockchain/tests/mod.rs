@@ -1,15 +1,24 @@
+use futures::{stream::BoxStream, FutureExt, StreamExt};
 use parking_lot::RwLock;
+use std::collections::HashSet;
+use std::str::FromStr;
 use std::sync::Arc;
 
+use beserial::{Deserialize, Serialize};
+use nimiq_account::{BlockLog, LogFilter};
 use nimiq_block::Block;
 use nimiq_block_production::{test_utils::TemporaryBlockProducer, BlockProducer};
-use nimiq_blockchain::{AbstractBlockchain, Blockchain};
-use nimiq_blockchain::{ForkEvent, PushResult};
+use nimiq_blockchain::{AbstractBlockchain, Blockchain, BlockchainEvent, ChainInfo, Direction};
+use nimiq_blockchain::{ForkEvent, IgnoreReason, PushError, PushResult, SlashReason};
 use nimiq_database::volatile::VolatileEnvironment;
 use nimiq_genesis::NetworkId;
+use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_keys::{Address, KeyPair, PrivateKey};
 use nimiq_primitives::policy;
 use nimiq_test_log::test;
-use nimiq_test_utils::blockchain::{signing_key, voting_key};
+use nimiq_test_utils::blockchain::{
+    generate_transactions, produce_macro_blocks, signing_key, voting_key, UNIT_KEY,
+};
 use nimiq_utils::time::OffsetTime;
 
 #[test]
@@ -231,6 +240,397 @@ fn it_can_rebranch_forks() {
     assert_eq!(temp_producer2.push(fork1d), Ok(PushResult::Ignored));
 }
 
+fn collect_block_logs(stream: &mut BoxStream<'static, BlockLog>) -> Vec<BlockLog> {
+    let mut block_logs = vec![];
+    while let Some(Some(block_log)) = stream.next().now_or_never() {
+        block_logs.push(block_log);
+    }
+    block_logs
+}
+
+#[test]
+fn it_filters_logs_by_address() {
+    let temp_producer1 = TemporaryBlockProducer::new();
+    let temp_producer2 = TemporaryBlockProducer::new();
+
+    let validator_address = temp_producer1
+        .blockchain
+        .read()
+        .current_validators()
+        .unwrap()
+        .validators[0]
+        .address
+        .clone();
+
+    let mut validator_logs = temp_producer1
+        .blockchain
+        .write()
+        .subscribe_logs(LogFilter::new(vec![validator_address.clone()], vec![]));
+    let mut unrelated_logs = temp_producer1
+        .blockchain
+        .write()
+        .subscribe_logs(LogFilter::new(vec![Address::from([0xffu8; 20])], vec![]));
+
+    // Only the skip blocks have logs touching the validator, namely the ones of its slashing.
+    // [0] - [0] - [0] - [1]
+    //          \- [1] - [1]
+    let block = temp_producer1.next_block(vec![], false);
+    temp_producer2.push(block).unwrap();
+
+    let inferior1 = temp_producer1.next_block(vec![], false);
+    let inferior2 = temp_producer1.next_block(vec![], true);
+
+    let fork1 = temp_producer2.next_block(vec![], true);
+    let fork2 = temp_producer2.next_block(vec![], false);
+
+    assert_eq!(
+        temp_producer1.push(fork1.clone()),
+        Ok(PushResult::Rebranched)
+    );
+    assert_eq!(temp_producer1.push(fork2), Ok(PushResult::Extended));
+
+    let block_logs = collect_block_logs(&mut validator_logs);
+    let mut applied = vec![];
+    let mut reverted = vec![];
+    for block_log in &block_logs {
+        let (block_hash, inherent_logs, tx_logs) = match block_log {
+            BlockLog::AppliedBlock {
+                block_hash,
+                inherent_logs,
+                tx_logs,
+                ..
+            } => {
+                applied.push(block_hash.clone());
+                (block_hash, inherent_logs, tx_logs)
+            }
+            BlockLog::RevertedBlock {
+                block_hash,
+                inherent_logs,
+                tx_logs,
+                ..
+            } => {
+                reverted.push(block_hash.clone());
+                (block_hash, inherent_logs, tx_logs)
+            }
+        };
+
+        assert!(
+            !inherent_logs.is_empty() || !tx_logs.is_empty(),
+            "Empty block log for {}",
+            block_hash
+        );
+        assert!(inherent_logs
+            .iter()
+            .chain(tx_logs.iter().flat_map(|tx_log| tx_log.logs.iter()))
+            .all(|log| log.is_related_to_address(&validator_address)));
+    }
+
+    assert_eq!(applied, vec![inferior2.hash(), fork1.hash()]);
+    // The reverted block without any logs touching the validator is left out.
+    assert_eq!(reverted, vec![inferior2.hash()]);
+    assert!(!reverted.contains(&inferior1.hash()));
+
+    assert!(collect_block_logs(&mut unrelated_logs).is_empty());
+}
+
+#[test]
+fn it_notifies_confirmed_transactions() {
+    let temp_producer = TemporaryBlockProducer::new();
+    let mut events = temp_producer.blockchain.write().notifier.as_stream();
+
+    let key_pair = KeyPair::from(PrivateKey::from_str(UNIT_KEY).unwrap());
+    let txs = generate_transactions(&key_pair, 1, NetworkId::UnitAlbatross, 3, 0);
+
+    let block = {
+        let blockchain = temp_producer.blockchain.read();
+        Block::Micro(temp_producer.producer.next_micro_block(
+            &blockchain,
+            blockchain.time.now() + 1000,
+            vec![],
+            txs.clone(),
+            vec![],
+            None,
+        ))
+    };
+    assert_eq!(temp_producer.push(block.clone()), Ok(PushResult::Extended));
+
+    let mut block_events = vec![];
+    while let Some(Some(event)) = events.next().now_or_never() {
+        block_events.push(event);
+    }
+
+    // The transactions are confirmed in the order in which they were included in the block.
+    let block_txs = block.transactions().unwrap();
+    assert_eq!(block_txs.len(), txs.len());
+
+    let mut expected_events = vec![BlockchainEvent::Extended(block.hash())];
+    for (index, tx) in block_txs.iter().enumerate() {
+        let tx_hash: Blake2bHash = tx.get_raw_transaction().hash();
+        assert!(txs.iter().any(|tx| tx.hash::<Blake2bHash>() == tx_hash));
+
+        expected_events.push(BlockchainEvent::TransactionConfirmed {
+            tx_hash,
+            block_hash: block.hash(),
+            block_number: 1,
+            index,
+        });
+    }
+    assert_eq!(block_events, expected_events);
+}
+
+fn collect_events(stream: &mut BoxStream<'static, BlockchainEvent>) -> Vec<BlockchainEvent> {
+    let mut events = vec![];
+    while let Some(Some(event)) = stream.next().now_or_never() {
+        events.push(event);
+    }
+    events
+}
+
+fn confirmed_transactions(block: &Block) -> Vec<BlockchainEvent> {
+    block
+        .transactions()
+        .map_or(&[][..], |txs| &txs[..])
+        .iter()
+        .enumerate()
+        .map(|(index, tx)| BlockchainEvent::TransactionConfirmed {
+            tx_hash: tx.get_raw_transaction().hash(),
+            block_hash: block.hash(),
+            block_number: block.block_number(),
+            index,
+        })
+        .collect()
+}
+
+fn next_block_with_transactions(
+    temp_producer: &TemporaryBlockProducer,
+    extra_data: Vec<u8>,
+    rng_seed: u64,
+) -> Block {
+    let key_pair = KeyPair::from(PrivateKey::from_str(UNIT_KEY).unwrap());
+    let txs = generate_transactions(&key_pair, 1, NetworkId::UnitAlbatross, 2, rng_seed);
+
+    let blockchain = temp_producer.blockchain.read();
+    let block_number = blockchain.block_number() + 1;
+    Block::Micro(temp_producer.producer.next_micro_block(
+        &blockchain,
+        blockchain.time.now() + block_number as u64 * 1000,
+        vec![],
+        txs,
+        extra_data,
+        None,
+    ))
+}
+
+#[test]
+fn it_notifies_confirmed_transactions_of_batches() {
+    let temp_producer1 = TemporaryBlockProducer::new();
+    let temp_producer2 = TemporaryBlockProducer::new();
+    let mut events = temp_producer2.blockchain.write().notifier.as_stream();
+
+    let mut blocks = vec![];
+    for rng_seed in 0..2 {
+        let block = next_block_with_transactions(&temp_producer1, vec![], rng_seed);
+        assert_eq!(temp_producer1.push(block.clone()), Ok(PushResult::Extended));
+        blocks.push(block);
+    }
+
+    // The micro blocks are committed together, but each of them confirms its own transactions.
+    assert_eq!(
+        Blockchain::push_batch(temp_producer2.blockchain.upgradable_read(), blocks.clone()),
+        Ok(vec![PushResult::Extended; 2])
+    );
+
+    let mut expected_events = vec![];
+    for block in &blocks {
+        expected_events.push(BlockchainEvent::Extended(block.hash()));
+        expected_events.extend(confirmed_transactions(block));
+    }
+    assert_eq!(expected_events.len(), 6);
+    assert_eq!(collect_events(&mut events), expected_events);
+}
+
+#[test]
+fn it_notifies_confirmed_transactions_on_rebranch() {
+    let temp_producer1 = TemporaryBlockProducer::new();
+    let temp_producer2 = TemporaryBlockProducer::new();
+
+    // [0] - [0] - [0]
+    //          \- [tx] - [1]
+    let block = temp_producer1.next_block(vec![], false);
+    temp_producer2.push(block).unwrap();
+
+    temp_producer1.next_block(vec![], false);
+
+    let fork1 = next_block_with_transactions(&temp_producer2, vec![0x48], 0);
+    assert_eq!(temp_producer2.push(fork1.clone()), Ok(PushResult::Extended));
+    let fork2 = temp_producer2.next_block(vec![], true);
+
+    let mut events = temp_producer1.blockchain.write().notifier.as_stream();
+    assert_eq!(temp_producer1.push(fork1.clone()), Ok(PushResult::Forked));
+    assert_eq!(temp_producer1.push(fork2), Ok(PushResult::Rebranched));
+
+    // The transactions of the adopted fork are confirmed after the rebranch.
+    let events = collect_events(&mut events);
+    let confirmations = confirmed_transactions(&fork1);
+    assert_eq!(confirmations.len(), 2);
+
+    let (rebranch, tail) = events.split_at(events.len() - confirmations.len());
+    assert!(matches!(
+        rebranch.last(),
+        Some(BlockchainEvent::Rebranched(_, _))
+    ));
+    assert_eq!(tail, &confirmations[..]);
+}
+
+#[test]
+fn it_reports_push_details() {
+    let temp_producer1 = TemporaryBlockProducer::new();
+    let temp_producer2 = TemporaryBlockProducer::new();
+
+    //              a     b
+    // [0] - [0] - [0] - [0]
+    //          \- [0] - [1]
+    let block = temp_producer1.next_block(vec![], false);
+    temp_producer2.push(block.clone()).unwrap();
+
+    let fork1a = temp_producer1.next_block(vec![0x48], false);
+    let fork1b = temp_producer1.next_block(vec![], false);
+    let fork2a = temp_producer2.next_block(vec![], false);
+    let fork2b = temp_producer2.next_block(vec![], true);
+
+    let details =
+        Blockchain::push_with_details(temp_producer1.blockchain.upgradable_read(), block).unwrap();
+    assert_eq!(details.kind(), PushResult::Known);
+    assert_eq!(details.head_hash, fork1b.hash());
+
+    let details =
+        Blockchain::push_with_details(temp_producer1.blockchain.upgradable_read(), fork2a.clone())
+            .unwrap();
+    assert_eq!(details.kind(), PushResult::Forked);
+    assert_eq!(details.head_hash, fork1b.hash());
+
+    let details =
+        Blockchain::push_with_details(temp_producer1.blockchain.upgradable_read(), fork2b.clone())
+            .unwrap();
+    assert_eq!(details.kind(), PushResult::Rebranched);
+    assert_eq!(details.head_hash, fork2b.hash());
+    assert_eq!(details.reverted_blocks, vec![fork1a.hash(), fork1b.hash()]);
+    assert_eq!(details.adopted_blocks, vec![fork2a.hash(), fork2b.hash()]);
+    assert_eq!(details.ignore_reason, None);
+
+    // The former main chain is now inferior.
+    temp_producer2.push(fork1a).unwrap();
+    let details =
+        Blockchain::push_with_details(temp_producer2.blockchain.upgradable_read(), fork1b).unwrap();
+    assert_eq!(details.kind(), PushResult::Ignored);
+    assert_eq!(details.head_hash, fork2b.hash());
+    assert_eq!(details.ignore_reason, Some(IgnoreReason::InferiorChain));
+}
+
+#[test]
+fn it_does_not_prune_bodies_by_default() {
+    let temp_producer = TemporaryBlockProducer::new();
+    assert!(!temp_producer.blockchain.read().prune_bodies);
+}
+
+#[test]
+fn it_decodes_chain_infos_without_pruning_flag() {
+    let temp_producer = TemporaryBlockProducer::new();
+    let block = temp_producer.next_block(vec![], false);
+    let chain_info = temp_producer
+        .blockchain
+        .read()
+        .chain_store
+        .get_chain_info(&block.hash(), false, None)
+        .unwrap();
+
+    // Chain infos stored by earlier versions lack the trailing pruning flag.
+    let mut serialized = chain_info.serialize_to_vec();
+    assert_eq!(serialized.pop(), Some(0));
+
+    let decoded = ChainInfo::deserialize_from_vec(&serialized).unwrap();
+    assert!(!decoded.body_pruned);
+    assert_eq!(decoded.head, chain_info.head);
+    assert_eq!(decoded.on_main_chain, chain_info.on_main_chain);
+    assert_eq!(decoded.cum_tx_fees, chain_info.cum_tx_fees);
+}
+
+#[test]
+fn it_rejects_rebranching_across_pruned_bodies() {
+    let temp_producer1 = TemporaryBlockProducer::new();
+    let temp_producer2 = TemporaryBlockProducer::new();
+
+    //              a     b
+    // [0] - [0] - [0] - [0]
+    //          \- [0] - [1]
+    let block = temp_producer1.next_block(vec![], false);
+    temp_producer2.push(block).unwrap();
+
+    let fork1a = temp_producer1.next_block(vec![0x48], false);
+    let fork2a = temp_producer2.next_block(vec![], false);
+
+    let fork1b = temp_producer1.next_block(vec![], false);
+    let fork2b = temp_producer2.next_block(vec![], true);
+
+    // Prune the bodies of all blocks of producer 1.
+    {
+        let blockchain = temp_producer1.blockchain.read();
+        let mut txn = blockchain.write_transaction();
+        blockchain.chain_store.prune_bodies_before(4, &mut txn);
+        txn.commit();
+
+        let chain_info = blockchain
+            .chain_store
+            .get_chain_info(&fork1a.hash(), true, None)
+            .unwrap();
+        assert!(chain_info.body_pruned);
+        assert!(chain_info.head.body().is_none());
+
+        // Requesting a pruned body yields no block instead of one without a body.
+        let chain_store = &blockchain.chain_store;
+        assert!(chain_store.get_block(&fork1a.hash(), true, None).is_none());
+        assert!(chain_store
+            .get_block_at(fork1a.block_number(), true, None)
+            .is_none());
+        assert!(chain_store
+            .get_block_at(fork1a.block_number(), false, None)
+            .is_some());
+    }
+
+    assert_eq!(temp_producer1.push(fork2a), Ok(PushResult::Forked));
+
+    // Rebranching would require reverting a block whose body was pruned.
+    assert_eq!(temp_producer1.push(fork2b), Err(PushError::BodyPruned));
+    assert_eq!(temp_producer1.blockchain.read().head_hash(), fork1b.hash());
+}
+
+#[test]
+fn it_can_get_blocks_at_height() {
+    let temp_producer1 = TemporaryBlockProducer::new();
+    let temp_producer2 = TemporaryBlockProducer::new();
+
+    let block = temp_producer1.next_block(vec![], false);
+    temp_producer2.push(block.clone()).unwrap();
+
+    let fork1 = temp_producer1.next_block(vec![0x48], false);
+    let fork2 = temp_producer2.next_block(vec![], false);
+    assert_eq!(temp_producer1.push(fork2.clone()), Ok(PushResult::Forked));
+
+    let blockchain = temp_producer1.blockchain.read();
+
+    assert_eq!(blockchain.get_blocks_at_height(1), vec![block.clone()]);
+    assert_eq!(blockchain.get_main_chain_block_at(1), Some(block));
+
+    let blocks = blockchain.get_blocks_at_height(2);
+    assert_eq!(blocks.len(), 2);
+    assert!(blocks.contains(&fork1));
+    assert!(blocks.contains(&fork2));
+    assert_eq!(blockchain.get_main_chain_block_at(2), Some(fork1));
+
+    assert!(blockchain.get_blocks_at_height(3).is_empty());
+    assert_eq!(blockchain.get_main_chain_block_at(3), None);
+}
+
 #[test]
 fn it_can_rebranch_at_macro_block() {
     // Build forks using two producers.
@@ -327,7 +727,7 @@ fn create_fork_proof() {
         .fork_notifier
         .register(move |e: &ForkEvent| {
             match e {
-                ForkEvent::Detected(_) => *event1_rc2.write().unwrap() = true,
+                ForkEvent::Detected { .. } => *event1_rc2.write().unwrap() = true,
             };
         });
 
@@ -344,3 +744,346 @@ fn create_fork_proof() {
     // Verify that the fork proof was generated
     assert!(*event1_rc1.read().unwrap());
 }
+
+#[test]
+fn it_reports_each_fork_proof_once() {
+    let producer1 = TemporaryBlockProducer::new();
+    let producer2 = TemporaryBlockProducer::new();
+    let producer3 = TemporaryBlockProducer::new();
+
+    let events = Arc::new(std::sync::RwLock::new(vec![]));
+    let events2 = events.clone();
+
+    producer1
+        .blockchain
+        .write()
+        .fork_notifier
+        .register(move |e: &ForkEvent| events2.write().unwrap().push(e.clone()));
+
+    // Three conflicting blocks for the same slot.
+    // [0] - [0] - [0]
+    //          \- [0]
+    //          \- [0]
+    let block = producer1.next_block(vec![], false);
+    producer2.push(block.clone()).unwrap();
+    producer3.push(block).unwrap();
+
+    producer1.next_block(vec![0x48], false);
+    let fork2 = producer2.next_block(vec![], false);
+    let fork3 = producer3.next_block(vec![0x49], false);
+
+    assert_eq!(producer1.push(fork2.clone()), Ok(PushResult::Forked));
+    assert_eq!(producer1.push(fork3), Ok(PushResult::Forked));
+    assert_eq!(producer1.push(fork2), Ok(PushResult::Known));
+
+    // One fork proof for the second block and two for the third one.
+    let events = events.read().unwrap();
+    assert_eq!(events.len(), 3);
+
+    let mut proofs = HashSet::new();
+    for event in events.iter() {
+        match event {
+            ForkEvent::Detected {
+                proof,
+                block_number,
+                ..
+            } => {
+                assert_eq!(*block_number, 2);
+                proofs.insert(proof.clone());
+            }
+        }
+    }
+    assert_eq!(proofs.len(), 3);
+}
+
+#[test]
+fn it_only_reports_fork_proofs_of_stored_blocks() {
+    let producer1 = TemporaryBlockProducer::new();
+    let producer2 = TemporaryBlockProducer::new();
+    let producer3 = TemporaryBlockProducer::new();
+
+    let events = Arc::new(std::sync::RwLock::new(vec![]));
+    let events2 = events.clone();
+
+    producer3
+        .blockchain
+        .write()
+        .fork_notifier
+        .register(move |e: &ForkEvent| events2.write().unwrap().push(e.clone()));
+
+    // [0] - [0] - [0]
+    //          \- [0]
+    let block = producer1.next_block(vec![], false);
+    producer2.push(block.clone()).unwrap();
+    producer3.push(block.clone()).unwrap();
+
+    let fork = producer2.next_block(vec![], false);
+    let block2 = producer1.next_block(vec![0x48], false);
+    let mut block3 = producer1.next_block(vec![], false);
+
+    // Store the fork block without keeping it on the main chain.
+    assert_eq!(producer3.push(fork), Ok(PushResult::Extended));
+    Blockchain::revert_to(producer3.blockchain.upgradable_read(), &block.hash()).unwrap();
+
+    // The fork is detected while committing the chunk, but the chunk is rolled back since its
+    // last block is invalid.
+    if let Block::Micro(ref mut micro_block) = block3 {
+        micro_block.header.extra_data = vec![0; 33];
+    }
+    assert!(Blockchain::push_chunk(
+        producer3.blockchain.upgradable_read(),
+        vec![block2.clone(), block3]
+    )
+    .is_err());
+    assert!(events.read().unwrap().is_empty());
+
+    // The fork is reported once the block is committed.
+    assert_eq!(
+        Blockchain::push_chunk(producer3.blockchain.upgradable_read(), vec![block2]),
+        Ok((PushResult::Extended, 1))
+    );
+    assert_eq!(events.read().unwrap().len(), 1);
+}
+
+#[test]
+fn it_records_and_reverts_slashes() {
+    let producer1 = TemporaryBlockProducer::new();
+    let producer2 = TemporaryBlockProducer::new();
+
+    // [0] - [0] - [0] - [1]
+    //          \- [1] - [1]
+    let block = producer1.next_block(vec![], false);
+    producer2.push(block).unwrap();
+
+    producer1.next_block(vec![], false);
+    let fork1 = producer2.next_block(vec![], true);
+
+    let inferior = producer1.next_block(vec![], true);
+    let fork2 = producer2.next_block(vec![], false);
+
+    // The skip block on the inferior chain was recorded.
+    {
+        let blockchain = producer1.blockchain.read();
+        let slashes = blockchain.get_slashes(policy::epoch_at(inferior.block_number()));
+        assert_eq!(slashes.len(), 1);
+        assert_eq!(slashes[0].block_number, 3);
+        assert_eq!(slashes[0].event_block, 3);
+        assert_eq!(slashes[0].reason, SlashReason::SkipBlock);
+    }
+
+    // Rebranching reverts the slash of the inferior chain and records the one of the fork.
+    assert_eq!(producer1.push(fork1), Ok(PushResult::Rebranched));
+    assert_eq!(producer1.push(fork2), Ok(PushResult::Extended));
+
+    let blockchain = producer1.blockchain.read();
+    let slashes = blockchain.get_slashes(policy::epoch_at(blockchain.block_number()));
+    assert_eq!(slashes.len(), 1);
+    assert_eq!(slashes[0].block_number, 2);
+    assert_eq!(slashes[0].event_block, 2);
+    assert_eq!(slashes[0].reason, SlashReason::SkipBlock);
+
+    let validator_address = slashes[0].validator_address.clone();
+    assert_eq!(
+        blockchain.get_validator_slashes(&validator_address),
+        slashes
+    );
+    assert!(blockchain
+        .get_validator_slashes(&Address::from([1u8; Address::SIZE]))
+        .is_empty());
+}
+
+#[test]
+fn it_records_and_prunes_fork_proof_slashes() {
+    let producer1 = TemporaryBlockProducer::new();
+    let producer2 = TemporaryBlockProducer::new();
+
+    let proofs = Arc::new(std::sync::RwLock::new(vec![]));
+    let proofs2 = proofs.clone();
+
+    producer1
+        .blockchain
+        .write()
+        .fork_notifier
+        .register(move |e: &ForkEvent| match e {
+            ForkEvent::Detected { proof, .. } => proofs2.write().unwrap().push(proof.clone()),
+        });
+
+    // [0] - [0] - [0] - [proof]
+    //          \- [0]
+    let block = producer1.next_block(vec![], false);
+    producer2.push(block).unwrap();
+
+    producer1.next_block(vec![0x48], false);
+    let fork = producer2.next_block(vec![], false);
+    assert_eq!(producer1.push(fork), Ok(PushResult::Forked));
+
+    let fork_proofs = proofs.read().unwrap().clone();
+    assert_eq!(fork_proofs.len(), 1);
+
+    let block = {
+        let blockchain = producer1.blockchain.read();
+        Block::Micro(producer1.producer.next_micro_block(
+            &blockchain,
+            blockchain.time.now() + 3000,
+            fork_proofs,
+            vec![],
+            vec![],
+            None,
+        ))
+    };
+    assert_eq!(producer1.push(block), Ok(PushResult::Extended));
+
+    let slashes = producer1.blockchain.read().get_slashes(1);
+    assert_eq!(slashes.len(), 1);
+    assert_eq!(slashes[0].block_number, 3);
+    assert_eq!(slashes[0].event_block, 2);
+    assert_eq!(slashes[0].reason, SlashReason::ForkProof);
+
+    let validator_address = slashes[0].validator_address.clone();
+    assert_eq!(
+        producer1
+            .blockchain
+            .read()
+            .get_validator_slashes(&validator_address),
+        slashes
+    );
+
+    // The slashes are kept as long as the blocks of their epoch.
+    produce_macro_blocks(
+        &producer1.producer,
+        &producer1.blockchain,
+        policy::BATCHES_PER_EPOCH as usize,
+    );
+    assert_eq!(
+        producer1
+            .blockchain
+            .read()
+            .get_validator_slashes(&validator_address),
+        slashes
+    );
+
+    produce_macro_blocks(
+        &producer1.producer,
+        &producer1.blockchain,
+        policy::BATCHES_PER_EPOCH as usize,
+    );
+    let blockchain = producer1.blockchain.read();
+    assert!(blockchain.get_slashes(1).is_empty());
+    assert!(blockchain
+        .get_validator_slashes(&validator_address)
+        .is_empty());
+}
+
+#[test]
+fn it_can_estimate_epoch_rewards() {
+    let producer = TemporaryBlockProducer::new();
+
+    let validator_address = producer
+        .blockchain
+        .read()
+        .current_validators()
+        .unwrap()
+        .iter()
+        .next()
+        .unwrap()
+        .address
+        .clone();
+
+    // There is no average block time at genesis.
+    assert_eq!(
+        producer
+            .blockchain
+            .read()
+            .compute_epoch_reward(&validator_address, 1),
+        0
+    );
+
+    producer.next_block(vec![], false);
+    producer.next_block(vec![], false);
+
+    let blockchain = producer.blockchain.read();
+
+    // The only validator owns all slots and receives the whole reward.
+    let reward = blockchain.compute_epoch_reward(&validator_address, 1);
+    assert!(reward > 0);
+    assert_eq!(reward % policy::SLOTS as u64, 0);
+
+    // Only the current epoch and validators with slots can be estimated.
+    assert_eq!(blockchain.compute_epoch_reward(&validator_address, 2), 0);
+    assert_eq!(
+        blockchain.compute_epoch_reward(&Address::from([1u8; Address::SIZE]), 1),
+        0
+    );
+}
+
+#[test]
+fn it_can_iterate_blocks_across_macro_blocks() {
+    let time = Arc::new(OffsetTime::new());
+    let env = VolatileEnvironment::new(10).unwrap();
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::new(env, NetworkId::UnitAlbatross, time).unwrap(),
+    ));
+
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    produce_macro_blocks(&producer, &blockchain, 2);
+
+    let blockchain = blockchain.read();
+    let start = policy::BLOCKS_PER_BATCH - 2;
+    let end = policy::BLOCKS_PER_BATCH + 2;
+
+    // Forward iteration returns the blocks in ascending order, crossing the macro block.
+    let blocks: Vec<Block> = blockchain
+        .chain_store
+        .iter_blocks(start, end, Direction::Forward, false, None)
+        .collect();
+    let block_numbers: Vec<u32> = blocks.iter().map(|block| block.block_number()).collect();
+    assert_eq!(block_numbers, (start..=end).collect::<Vec<_>>());
+    assert!(blocks[2].is_macro());
+    assert!(blocks.iter().all(|block| block.body().is_none()));
+
+    // Backward iteration returns the same blocks in descending order.
+    let blocks: Vec<Block> = blockchain
+        .chain_store
+        .iter_blocks(start, end, Direction::Backward, true, None)
+        .collect();
+    let block_numbers: Vec<u32> = blocks.iter().map(|block| block.block_number()).collect();
+    assert_eq!(block_numbers, (start..=end).rev().collect::<Vec<_>>());
+    assert!(blocks.iter().all(|block| block.body().is_some()));
+
+    // The iteration is lazy and stops at the head of the chain.
+    let head = blockchain.block_number();
+    let mut iter =
+        blockchain
+            .chain_store
+            .iter_blocks(head - 1, head + 10, Direction::Forward, false, None);
+    assert_eq!(
+        iter.next().map(|block| block.block_number()),
+        Some(head - 1)
+    );
+    assert_eq!(iter.next().map(|block| block.block_number()), Some(head));
+    assert!(iter.next().is_none());
+
+    // Empty ranges yield no blocks.
+    assert_eq!(
+        blockchain
+            .chain_store
+            .iter_blocks(end, start, Direction::Forward, false, None)
+            .count(),
+        0
+    );
+
+    // Fetching blocks by hash excludes the start block.
+    let start_hash = blockchain
+        .chain_store
+        .get_block_at(start, false, None)
+        .unwrap()
+        .hash();
+    let blocks = blockchain.get_blocks(&start_hash, 4, false, Direction::Forward);
+    let block_numbers: Vec<u32> = blocks.iter().map(|block| block.block_number()).collect();
+    assert_eq!(block_numbers, (start + 1..=end).collect::<Vec<_>>());
+
+    let blocks = blockchain.get_blocks(&start_hash, 2, true, Direction::Backward);
+    let block_numbers: Vec<u32> = blocks.iter().map(|block| block.block_number()).collect();
+    assert_eq!(block_numbers, vec![start - 1, start - 2]);
+    assert!(blocks.iter().all(|block| block.body().is_some()));
+}
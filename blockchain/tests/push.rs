@@ -1,13 +1,18 @@
-use nimiq_block::Block;
+use beserial::Serialize;
 use nimiq_block::BlockError;
+use nimiq_block::{Block, MicroBlock};
 use nimiq_block_production::test_custom_block::next_skip_block;
 use nimiq_block_production::test_custom_block::{next_macro_block, next_micro_block, BlockConfig};
 use nimiq_block_production::test_utils::TemporaryBlockProducer;
 use nimiq_blockchain::PushError::InvalidBlock;
-use nimiq_blockchain::{PushError, PushResult};
+use nimiq_blockchain::{AbstractBlockchain, Blockchain, PushError, PushResult};
 use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_primitives::coin::Coin;
+use nimiq_primitives::networks::NetworkId;
 use nimiq_primitives::policy;
-use nimiq_vrf::VrfSeed;
+use nimiq_transaction::{ExecutedTransaction, Transaction};
+use nimiq_vrf::{VrfError, VrfSeed};
 
 pub fn expect_push_micro_block(config: BlockConfig, expected_res: Result<PushResult, PushError>) {
     if !config.macro_only {
@@ -87,7 +92,7 @@ fn push_rebranch(config: BlockConfig, expected_res: &Result<PushResult, PushErro
     let expected_result = match &expected_res {
         // Skip blocks carry over the seed of the previous block. An incorrect seed if it matches
         // the previous block seed would fail as an invalid skip block proof
-        Err(PushError::InvalidBlock(BlockError::InvalidSeed)) => {
+        Err(PushError::InvalidBlock(BlockError::InvalidSeed(_))) => {
             &Err(PushError::InvalidBlock(BlockError::InvalidSkipBlockProof))
         }
         _ => expected_res,
@@ -298,6 +303,44 @@ fn it_validates_body_hash() {
     );
 }
 
+#[test]
+fn it_validates_block_size() {
+    let temp_producer = TemporaryBlockProducer::new();
+
+    let mut micro_block = {
+        let blockchain = &temp_producer.blockchain.read();
+        next_micro_block(
+            &temp_producer.producer.signing_key,
+            blockchain,
+            &BlockConfig::default(),
+        )
+    };
+
+    // Fill the body with transactions until the block is clearly oversized.
+    let transaction = Transaction::new_basic(
+        Address::from([1u8; Address::SIZE]),
+        Address::from([2u8; Address::SIZE]),
+        Coin::from_u64_unchecked(1),
+        Coin::ZERO,
+        1,
+        NetworkId::UnitAlbatross,
+    );
+    let body = micro_block.body.as_mut().unwrap();
+    while body.serialized_size() <= MicroBlock::MAX_SIZE {
+        body.transactions
+            .push(ExecutedTransaction::Ok(transaction.clone()));
+    }
+
+    let block = Block::Micro(micro_block);
+    assert_eq!(
+        temp_producer.push(block.clone()),
+        Err(PushError::InvalidBlock(BlockError::SizeExceededDetail {
+            actual: block.estimated_serialized_size(),
+            max: MicroBlock::MAX_SIZE,
+        }))
+    );
+}
+
 #[test]
 fn it_validates_seed() {
     expect_push_micro_block(
@@ -305,7 +348,9 @@ fn it_validates_seed() {
             seed: Some(VrfSeed::default()),
             ..Default::default()
         },
-        Err(InvalidBlock(BlockError::InvalidSeed)),
+        Err(InvalidBlock(BlockError::InvalidSeed(
+            VrfError::InvalidSignature,
+        ))),
     );
 }
 
@@ -363,3 +408,242 @@ fn it_validates_tendermint_round_number() {
         Err(InvalidBlock(BlockError::InvalidJustification)),
     );
 }
+
+#[test]
+fn it_can_push_chunks() {
+    let temp_producer1 = TemporaryBlockProducer::new();
+    let temp_producer2 = TemporaryBlockProducer::new();
+    let temp_producer3 = TemporaryBlockProducer::new();
+
+    let blocks: Vec<Block> = (0..100)
+        .map(|_| temp_producer1.next_block(vec![], false))
+        .collect();
+
+    // Push the blocks one by one.
+    for block in blocks.iter().cloned() {
+        assert_eq!(temp_producer2.push(block), Ok(PushResult::Extended));
+    }
+
+    // Push the blocks in chunks. Blocks that can't be pushed as part of a chunk (i.e. macro
+    // blocks) are pushed individually.
+    let mut i = 0;
+    while i < blocks.len() {
+        let chunk = blocks[i..].to_vec();
+        let (result, num_applied) =
+            Blockchain::push_chunk(temp_producer3.blockchain.upgradable_read(), chunk).unwrap();
+
+        if num_applied == 0 {
+            assert_eq!(result, PushResult::Ignored);
+            assert_eq!(
+                temp_producer3.push(blocks[i].clone()),
+                Ok(PushResult::Extended)
+            );
+            i += 1;
+        } else {
+            assert_eq!(result, PushResult::Extended);
+            i += num_applied;
+        }
+    }
+
+    let blockchain2 = temp_producer2.blockchain.read();
+    let blockchain3 = temp_producer3.blockchain.read();
+    assert_eq!(blockchain2.head_hash(), blockchain3.head_hash());
+    assert_eq!(blockchain2.head_hash(), blocks.last().unwrap().hash());
+    assert_eq!(
+        blockchain2.state().accounts.get_root(None),
+        blockchain3.state().accounts.get_root(None)
+    );
+}
+
+#[test]
+fn it_rolls_back_invalid_chunks() {
+    let temp_producer1 = TemporaryBlockProducer::new();
+    let temp_producer2 = TemporaryBlockProducer::new();
+
+    let mut blocks: Vec<Block> = (0..3)
+        .map(|_| temp_producer1.next_block(vec![], false))
+        .collect();
+
+    let invalid_block = {
+        let blockchain = temp_producer1.blockchain.read();
+        next_micro_block(
+            &temp_producer1.producer.signing_key,
+            &blockchain,
+            &BlockConfig {
+                state_root: Some(Blake2bHash::default()),
+                ..Default::default()
+            },
+        )
+    };
+    blocks.push(Block::Micro(invalid_block));
+
+    let head_hash = temp_producer2.blockchain.read().head_hash();
+
+    assert_eq!(
+        Blockchain::push_chunk(temp_producer2.blockchain.upgradable_read(), blocks),
+        Err(InvalidBlock(BlockError::AccountsHashMismatch))
+    );
+
+    // None of the blocks must have been applied.
+    assert_eq!(temp_producer2.blockchain.read().head_hash(), head_hash);
+}
+
+#[test]
+fn it_can_push_batches() {
+    let temp_producer1 = TemporaryBlockProducer::new();
+    let temp_producer2 = TemporaryBlockProducer::new();
+
+    // The blocks include a macro block, which is pushed individually.
+    let blocks: Vec<Block> = (0..50)
+        .map(|_| temp_producer1.next_block(vec![], false))
+        .collect();
+
+    let results =
+        Blockchain::push_batch(temp_producer2.blockchain.upgradable_read(), blocks.clone())
+            .unwrap();
+    assert_eq!(results, vec![PushResult::Extended; blocks.len()]);
+
+    let blockchain1 = temp_producer1.blockchain.read();
+    let blockchain2 = temp_producer2.blockchain.read();
+    assert_eq!(blockchain2.head_hash(), blocks.last().unwrap().hash());
+    assert_eq!(
+        blockchain1.state().accounts.get_root(None),
+        blockchain2.state().accounts.get_root(None)
+    );
+}
+
+#[test]
+fn it_rejects_non_contiguous_batches() {
+    let temp_producer1 = TemporaryBlockProducer::new();
+    let temp_producer2 = TemporaryBlockProducer::new();
+
+    let head_hash = temp_producer2.blockchain.read().head_hash();
+
+    let mut blocks: Vec<Block> = (0..10)
+        .map(|_| temp_producer1.next_block(vec![], false))
+        .collect();
+    blocks.remove(5);
+
+    // None of the blocks preceding the gap must have been applied.
+    assert_eq!(
+        Blockchain::push_batch(temp_producer2.blockchain.upgradable_read(), blocks.clone()),
+        Err((vec![], PushError::InvalidSuccessor))
+    );
+    assert_eq!(temp_producer2.blockchain.read().head_hash(), head_hash);
+
+    // A batch that doesn't succeed any known block is rejected.
+    assert_eq!(
+        Blockchain::push_batch(
+            temp_producer2.blockchain.upgradable_read(),
+            blocks[6..].to_vec()
+        ),
+        Err((vec![], PushError::Orphan))
+    );
+}
+
+#[test]
+fn it_rejects_batches_with_an_invalid_block_header() {
+    let temp_producer1 = TemporaryBlockProducer::new();
+    let temp_producer2 = TemporaryBlockProducer::new();
+
+    let head_hash = temp_producer2.blockchain.read().head_hash();
+
+    let mut blocks: Vec<Block> = (0..10)
+        .map(|_| temp_producer1.next_block(vec![], false))
+        .collect();
+
+    // Invalidate the header of the middle block while keeping the batch contiguous.
+    if let Block::Micro(ref mut block) = blocks[5] {
+        block.header.extra_data = vec![0; 33];
+    }
+    let hash = blocks[5].hash();
+    if let Block::Micro(ref mut block) = blocks[6] {
+        block.header.parent_hash = hash;
+    }
+
+    assert_eq!(
+        Blockchain::push_batch(temp_producer2.blockchain.upgradable_read(), blocks),
+        Err((vec![], InvalidBlock(BlockError::ExtraDataTooLarge)))
+    );
+
+    // None of the blocks preceding the invalid one must have been applied.
+    assert_eq!(temp_producer2.blockchain.read().head_hash(), head_hash);
+}
+
+#[test]
+fn it_returns_the_results_of_applied_blocks_on_batch_failure() {
+    let temp_producer1 = TemporaryBlockProducer::new();
+    let temp_producer2 = TemporaryBlockProducer::new();
+
+    let mut blocks: Vec<Block> = (0..policy::BLOCKS_PER_BATCH + 5)
+        .map(|_| temp_producer1.next_block(vec![], false))
+        .collect();
+    let macro_index = blocks.iter().position(|block| block.is_macro()).unwrap();
+
+    // Remove the body of a micro block after the macro block, so that only its header is valid.
+    if let Block::Micro(ref mut block) = blocks[macro_index + 3] {
+        block.body = None;
+    }
+
+    // The micro blocks following the macro block are rolled back together with the invalid one.
+    assert_eq!(
+        Blockchain::push_batch(temp_producer2.blockchain.upgradable_read(), blocks.clone()),
+        Err((
+            vec![PushResult::Extended; macro_index + 1],
+            InvalidBlock(BlockError::MissingBody)
+        ))
+    );
+    assert_eq!(
+        temp_producer2.blockchain.read().head_hash(),
+        blocks[macro_index].hash()
+    );
+}
+
+#[test]
+fn it_can_revert_to_block() {
+    let temp_producer = TemporaryBlockProducer::new();
+
+    let target_block = temp_producer.next_block(vec![], false);
+    let target_hash = target_block.hash();
+
+    let blocks: Vec<Block> = (0..3)
+        .map(|_| temp_producer.next_block(vec![], false))
+        .collect();
+
+    let reverted_blocks =
+        Blockchain::revert_to(temp_producer.blockchain.upgradable_read(), &target_hash).unwrap();
+
+    // The reverted blocks are returned from the oldest to the newest one.
+    assert_eq!(reverted_blocks.len(), 3);
+    for ((hash, reverted_block), block) in reverted_blocks.iter().zip(&blocks) {
+        assert_eq!(hash, &block.hash());
+        assert_eq!(reverted_block, block);
+    }
+
+    let blockchain = temp_producer.blockchain.read();
+    assert_eq!(blockchain.head_hash(), target_hash);
+    assert_eq!(blockchain.block_number(), target_block.block_number());
+    assert_eq!(
+        &blockchain.state().accounts.get_root(None),
+        target_block.state_root()
+    );
+}
+
+#[test]
+fn it_cannot_revert_across_macro_blocks() {
+    let temp_producer = TemporaryBlockProducer::new();
+
+    let genesis_hash = temp_producer.blockchain.read().head_hash();
+    for _ in 0..policy::BLOCKS_PER_BATCH {
+        temp_producer.next_block(vec![], false);
+    }
+    temp_producer.next_block(vec![], false);
+
+    let head_hash = temp_producer.blockchain.read().head_hash();
+
+    assert_eq!(
+        Blockchain::revert_to(temp_producer.blockchain.upgradable_read(), &genesis_hash),
+        Err(PushError::InvalidRevertTarget)
+    );
+    assert_eq!(temp_producer.blockchain.read().head_hash(), head_hash);
+}
@@ -1,4 +1,14 @@
+use std::io;
+
 use ark_ff::BigInteger768;
+use thiserror::Error;
+
+/// Errors that can happen when converting a vector of bits into bytes.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum BitsError {
+    #[error("number of bits ({0}) is not a multiple of 8")]
+    NotByteAligned(usize),
+}
 
 /// Transforms a vector of bytes into the corresponding vector of bits (booleans).
 /// The output is in the same format as the input (e.g. if the input is in big-endian, the output
@@ -15,17 +25,64 @@ pub fn bytes_to_bits(bytes: &[u8]) -> Vec<bool> {
     bits
 }
 
+/// Transforms a vector of bytes into a vector of bits (booleans), where the bits of each byte are
+/// in little-endian order. The order of the bytes is kept.
+pub fn bytes_to_bits_le(bytes: &[u8]) -> Vec<bool> {
+    let mut bits = vec![];
+
+    for byte in bytes {
+        bits.extend(byte_to_le_bits(*byte));
+    }
+
+    bits
+}
+
+/// Transforms a vector of bits, where the bits of each byte are in big-endian order, into the
+/// corresponding vector of bytes. This is the inverse of `bytes_to_bits`.
+/// Fails if the number of bits is not a multiple of 8.
+pub fn bits_to_bytes(bits: &[bool]) -> Result<Vec<u8>, BitsError> {
+    if bits.len() % 8 != 0 {
+        return Err(BitsError::NotByteAligned(bits.len()));
+    }
+
+    Ok(bits.chunks(8).map(byte_from_be_bits).collect())
+}
+
+/// Transforms a vector of bits, where the bits of each byte are in big-endian order, into the
+/// corresponding vector of bytes. This is the inverse of `bytes_to_bits`.
+/// Panics if the number of bits is not a multiple of 8, see `bits_to_bytes`.
+pub fn bytes_from_be_bits(bits: &[bool]) -> Vec<u8> {
+    bits_to_bytes(bits).unwrap()
+}
+
 /// Creates a BigInteger from an array of bytes in big-endian format.
-pub fn big_int_from_bytes_be<R: std::io::Read>(reader: &mut R) -> BigInteger768 {
+/// Fails if the reader contains less than 96 bytes.
+pub fn try_big_int_from_bytes_be<R: io::Read>(reader: &mut R) -> Result<BigInteger768, io::Error> {
     let mut res = [0u64; 12];
 
     for num in res.iter_mut().rev() {
         let mut bytes = [0u8; 8];
-        reader.read_exact(&mut bytes).unwrap();
+        reader.read_exact(&mut bytes)?;
         *num = u64::from_be_bytes(bytes);
     }
 
-    BigInteger768::new(res)
+    Ok(BigInteger768::new(res))
+}
+
+/// Creates a BigInteger from an array of bytes in big-endian format.
+/// Panics if the reader contains less than 96 bytes, see `try_big_int_from_bytes_be`.
+pub fn big_int_from_bytes_be<R: io::Read>(reader: &mut R) -> BigInteger768 {
+    try_big_int_from_bytes_be(reader).unwrap()
+}
+
+/// Writes a BigInteger as an array of 96 bytes in big-endian format. This is the inverse of
+/// `big_int_from_bytes_be`.
+pub fn big_int_to_bytes_be<W: io::Write>(n: &BigInteger768, writer: &mut W) -> io::Result<()> {
+    for num in n.0.iter().rev() {
+        writer.write_all(&num.to_be_bytes())?;
+    }
+
+    Ok(())
 }
 
 /// Transforms a vector of little endian bits into a u8.
@@ -46,8 +103,11 @@ pub fn byte_from_le_bits(bits: &[bool]) -> u8 {
 }
 
 /// Transforms a vector of little endian bits into a vector of u8's in big-endian.
-pub fn bytes_be_from_le_bits(bits: &[bool]) -> Vec<u8> {
-    assert_eq!(bits.len() % 8, 0);
+/// Fails if the number of bits is not a multiple of 8.
+pub fn try_bytes_be_from_le_bits(bits: &[bool]) -> Result<Vec<u8>, BitsError> {
+    if bits.len() % 8 != 0 {
+        return Err(BitsError::NotByteAligned(bits.len()));
+    }
 
     let mut bytes = vec![];
 
@@ -57,7 +117,13 @@ pub fn bytes_be_from_le_bits(bits: &[bool]) -> Vec<u8> {
 
     bytes.reverse();
 
-    bytes
+    Ok(bytes)
+}
+
+/// Transforms a vector of little endian bits into a vector of u8's in big-endian.
+/// Panics if the number of bits is not a multiple of 8, see `try_bytes_be_from_le_bits`.
+pub fn bytes_be_from_le_bits(bits: &[bool]) -> Vec<u8> {
+    try_bytes_be_from_le_bits(bits).unwrap()
 }
 
 /// Transforms a u8 into a vector of little endian bits.
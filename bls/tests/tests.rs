@@ -1,7 +1,13 @@
 use ark_ec::ProjectiveCurve;
-use rand::thread_rng;
+use ark_ff::BigInteger768;
+use rand::{thread_rng, Rng};
 
 use beserial::{Deserialize, Serialize};
+use nimiq_bls::utils::{
+    big_int_from_bytes_be, big_int_to_bytes_be, bits_to_bytes, bytes_be_from_le_bits,
+    bytes_from_be_bits, bytes_to_bits, bytes_to_bits_le, try_big_int_from_bytes_be,
+    try_bytes_be_from_le_bits, BitsError,
+};
 use nimiq_bls::*;
 use nimiq_test_log::test;
 use nimiq_utils::key_rng::SecureGenerate;
@@ -190,3 +196,116 @@ fn aggregate_signatures_serialization() {
         &AggregateSignature::deserialize_from_vec(&ser_agg_sig).unwrap()
     ));
 }
+
+#[test]
+fn big_int_bytes_be_round_trip() {
+    let rng = &mut thread_rng();
+
+    let mut bytes = vec![];
+    big_int_to_bytes_be(&BigInteger768::from(258), &mut bytes).unwrap();
+    assert_eq!(bytes.len(), 96);
+    assert!(bytes[..94].iter().all(|byte| *byte == 0));
+    assert_eq!(bytes[94..], [1, 2]);
+
+    for _ in 0..1000 {
+        let n = BigInteger768::new(rng.gen());
+
+        let mut bytes = vec![];
+        big_int_to_bytes_be(&n, &mut bytes).unwrap();
+        assert_eq!(bytes.len(), 96);
+
+        assert_eq!(big_int_from_bytes_be(&mut &bytes[..]), n);
+    }
+}
+
+#[test]
+fn bytes_bits_round_trip() {
+    let rng = &mut thread_rng();
+
+    assert_eq!(
+        bytes_to_bits(&[0b1000_0001, 0b0000_0110])[..8],
+        [true, false, false, false, false, false, false, true]
+    );
+    assert_eq!(
+        bytes_to_bits_le(&[0b0000_0110, 0b1000_0000]),
+        [
+            false, true, true, false, false, false, false, false, false, false, false, false,
+            false, false, false, true
+        ]
+    );
+    assert!(bytes_from_be_bits(&[]).is_empty());
+
+    for len in 0..100 {
+        let bytes: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+
+        let bits = bytes_to_bits(&bytes);
+        assert_eq!(bits.len(), 8 * len);
+        assert_eq!(bytes_from_be_bits(&bits), bytes);
+
+        // The little-endian bits are the big-endian bits with each byte reversed.
+        let mut bits_le = bits;
+        for chunk in bits_le.chunks_mut(8) {
+            chunk.reverse();
+        }
+        assert_eq!(bytes_to_bits_le(&bytes), bits_le);
+    }
+}
+
+#[test]
+#[should_panic]
+fn bytes_from_be_bits_rejects_partial_bytes() {
+    bytes_from_be_bits(&[true; 9]);
+}
+
+#[test]
+fn try_big_int_from_bytes_be_rejects_truncated_readers() {
+    let rng = &mut thread_rng();
+
+    let n = BigInteger768::new(rng.gen());
+    let mut bytes = vec![];
+    big_int_to_bytes_be(&n, &mut bytes).unwrap();
+
+    assert_eq!(try_big_int_from_bytes_be(&mut &bytes[..]).unwrap(), n);
+
+    // Extra bytes are left in the reader.
+    bytes.push(42);
+    let mut reader = &bytes[..];
+    assert_eq!(try_big_int_from_bytes_be(&mut reader).unwrap(), n);
+    assert_eq!(reader, [42]);
+
+    for len in [0, 1, 8, 95] {
+        let err = try_big_int_from_bytes_be(&mut &bytes[..len]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+}
+
+#[test]
+fn fallible_bit_conversions_reject_partial_bytes() {
+    let rng = &mut thread_rng();
+
+    for len in 0..100 {
+        let bits: Vec<bool> = (0..len).map(|_| rng.gen()).collect();
+
+        if len % 8 == 0 {
+            assert_eq!(bits_to_bytes(&bits).unwrap(), bytes_from_be_bits(&bits));
+            assert_eq!(
+                try_bytes_be_from_le_bits(&bits).unwrap(),
+                bytes_be_from_le_bits(&bits)
+            );
+        } else {
+            assert_eq!(bits_to_bytes(&bits), Err(BitsError::NotByteAligned(len)));
+            assert_eq!(
+                try_bytes_be_from_le_bits(&bits),
+                Err(BitsError::NotByteAligned(len))
+            );
+        }
+    }
+
+    // The little endian bits of each byte are read in order, but the bytes are reversed.
+    let mut bits = bytes_to_bits_le(&[1, 2]);
+    assert_eq!(try_bytes_be_from_le_bits(&bits).unwrap(), [2, 1]);
+    assert_eq!(bits_to_bytes(&bytes_to_bits(&[1, 2])).unwrap(), [1, 2]);
+
+    bits.pop();
+    assert!(try_bytes_be_from_le_bits(&bits).is_err());
+}
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::iter::{repeat, FromIterator};
 use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign};
 
@@ -280,6 +281,18 @@ impl PartialEq for BitSet {
     }
 }
 
+impl Hash for BitSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Trailing zero words are ignored by `eq`, so they must not be hashed either.
+        let len = self
+            .store
+            .iter()
+            .rposition(|word| *word != 0)
+            .map_or(0, |i| i + 1);
+        self.store[..len].hash(state);
+    }
+}
+
 impl Serialize for BitSet {
     fn serialize<W: WriteBytesExt>(&self, writer: &mut W) -> Result<usize, SerializingError> {
         let mut size = 0;
@@ -499,6 +512,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn it_hashes_equal_sets_equally() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let hash = |set: &BitSet| {
+            let mut hasher = DefaultHasher::new();
+            set.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let mut set1 = BitSet::new();
+        set1.insert(1);
+        set1.insert(2);
+        set1.insert(130);
+
+        // The intersection may keep trailing zero words of the larger set.
+        let set2 = sample_bitset() & set1.clone();
+
+        let mut set3 = BitSet::new();
+        set3.insert(2);
+        set3.insert(1);
+
+        assert_eq!(set2, set3);
+        assert_eq!(hash(&set2), hash(&set3));
+        assert_ne!(hash(&set1), hash(&set3));
+    }
+
     #[test]
     fn it_correctly_computes_intersection() {
         let set1 = sample_bitset();
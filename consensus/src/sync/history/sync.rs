@@ -42,7 +42,7 @@ pub(crate) enum Job<TNetwork: Network> {
 pub struct HistorySync<TNetwork: Network> {
     pub(crate) blockchain: Arc<RwLock<Blockchain>>,
     pub(crate) network: Arc<TNetwork>,
-    pub(crate) network_event_rx: SubscribeEvents<TNetwork::PeerId>,
+    pub(crate) network_event_rx: SubscribeEvents<TNetwork::PeerId, TNetwork::AddressType>,
     pub(crate) peers: HashMap<TNetwork::PeerId, usize>,
     pub(crate) epoch_ids_stream:
         FuturesUnordered<BoxFuture<'static, Option<EpochIds<TNetwork::PeerId>>>>,
@@ -66,7 +66,7 @@ impl<TNetwork: Network> HistorySync<TNetwork> {
     pub fn new(
         blockchain: Arc<RwLock<Blockchain>>,
         network: Arc<TNetwork>,
-        network_event_rx: SubscribeEvents<TNetwork::PeerId>,
+        network_event_rx: SubscribeEvents<TNetwork::PeerId, TNetwork::AddressType>,
     ) -> Self {
         Self {
             blockchain,
@@ -27,7 +27,7 @@ impl<TNetwork: Network> HistorySync<TNetwork> {
                     self.remove_peer(peer_id);
                     self.peers.remove(&peer_id);
                 }
-                Ok(NetworkEvent::PeerJoined(peer_id)) => {
+                Ok(NetworkEvent::PeerJoined { peer_id, .. }) => {
                     // Request epoch_ids from the peer that joined.
                     self.add_peer(peer_id);
                 }
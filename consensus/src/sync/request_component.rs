@@ -55,7 +55,7 @@ pub struct BlockRequestComponent<TNetwork: Network + 'static> {
     peers: HashSet<TNetwork::PeerId>, // this map holds the strong references to up-to-date peers
     outdated_peers: HashSet<TNetwork::PeerId>, //
     outdated_timeouts: HashMap<TNetwork::PeerId, Instant>,
-    network_event_rx: SubscribeEvents<TNetwork::PeerId>,
+    network_event_rx: SubscribeEvents<TNetwork::PeerId, TNetwork::AddressType>,
 }
 
 impl<TNetwork: Network + 'static> BlockRequestComponent<TNetwork> {
@@ -65,7 +65,7 @@ impl<TNetwork: Network + 'static> BlockRequestComponent<TNetwork> {
 
     pub fn new(
         sync_method: Pin<Box<dyn HistorySyncStream<TNetwork::PeerId>>>,
-        network_event_rx: SubscribeEvents<TNetwork::PeerId>,
+        network_event_rx: SubscribeEvents<TNetwork::PeerId, TNetwork::AddressType>,
         network: Arc<TNetwork>,
     ) -> Self {
         Self {
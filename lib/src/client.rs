@@ -105,17 +105,19 @@ impl ClientInner {
             .collect();
 
         // Setup libp2p network
-        let network_config = NetworkConfig::new(
+        let mut network_config = NetworkConfig::new(
             identity_keypair,
             peer_contact,
             seeds,
             network_info.genesis_hash().clone(),
             false,
         );
+        network_config.seed_lists = network_info.seed_lists().clone();
+        network_config.limits = config.network.limits.clone();
 
         log::debug!("listen_addresses = {:?}", config.network.listen_addresses);
 
-        let network = Arc::new(Network::new(Arc::clone(&time), network_config).await);
+        let network = Arc::new(Network::new(Arc::clone(&time), network_config).await?);
 
         // Start buffering network events as early as possible
         let network_events = network.subscribe_events();
@@ -126,9 +128,9 @@ impl ClientInner {
             config.consensus.sync_mode,
             config.database,
         )?;
-        let blockchain = Arc::new(RwLock::new(
-            Blockchain::new(environment.clone(), config.network_id, time).unwrap(),
-        ));
+        let mut blockchain = Blockchain::new(environment.clone(), config.network_id, time).unwrap();
+        blockchain.prune_bodies = config.consensus.prune_bodies;
+        let blockchain = Arc::new(RwLock::new(blockchain));
 
         // Open wallet
         #[cfg(feature = "wallet")]
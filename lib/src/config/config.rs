@@ -17,7 +17,7 @@ use nimiq_database::{mdbx::MdbxEnvironment, volatile::VolatileEnvironment, Envir
 #[cfg(feature = "validator")]
 use nimiq_keys::{Address, KeyPair, PrivateKey};
 use nimiq_mempool::{config::MempoolConfig, filter::MempoolRules};
-use nimiq_network_libp2p::{Keypair as IdentityKeypair, Multiaddr};
+use nimiq_network_libp2p::{Keypair as IdentityKeypair, LimitConfig, Multiaddr};
 use nimiq_primitives::networks::NetworkId;
 use nimiq_utils::file_store::FileStore;
 #[cfg(feature = "validator")]
@@ -66,6 +66,10 @@ pub struct ConsensusConfig {
     pub sync_mode: SyncMode,
     #[builder(default = "3")]
     pub min_peers: usize,
+    /// Whether to prune the bodies of micro blocks outside of the transaction validity window.
+    /// Nodes serving the block history to other nodes must keep all bodies.
+    #[builder(default)]
+    pub prune_bodies: bool,
 }
 
 impl Default for ConsensusConfig {
@@ -73,6 +77,7 @@ impl Default for ConsensusConfig {
         ConsensusConfig {
             sync_mode: SyncMode::default(),
             min_peers: 3,
+            prune_bodies: false,
         }
     }
 }
@@ -96,6 +101,10 @@ pub struct NetworkConfig {
 
     #[builder(default)]
     pub seeds: Vec<Seed>,
+
+    /// Limits on the number of connected peers, in total, per IP and per subnet.
+    #[builder(default)]
+    pub limits: LimitConfig,
 }
 
 /// Contains which protocol to use and the configuration needed for that protocol.
@@ -712,6 +721,28 @@ impl ClientConfigBuilder {
         self
     }
 
+    /// Builds the peer limits from the configuration file settings, keeping the defaults for
+    /// limits that aren't set.
+    fn limit_config(settings: &config_file::NetworkLimitSettings) -> LimitConfig {
+        let mut limits = LimitConfig::default();
+        if let Some(peer_count_max) = settings.peer_count_max {
+            limits.peer_count_max = peer_count_max;
+        }
+        if let Some(peer_count_per_ip_max) = settings.peer_count_per_ip_max {
+            limits.peer_count_per_ip_max = peer_count_per_ip_max;
+        }
+        if let Some(peer_count_per_subnet_max) = settings.peer_count_per_subnet_max {
+            limits.peer_count_per_subnet_max = peer_count_per_subnet_max;
+        }
+        if let Some(ipv4_subnet_mask) = settings.ipv4_subnet_mask {
+            limits.ipv4_subnet_mask = ipv4_subnet_mask;
+        }
+        if let Some(ipv6_subnet_mask) = settings.ipv6_subnet_mask {
+            limits.ipv6_subnet_mask = ipv6_subnet_mask;
+        }
+        limits
+    }
+
     /// Applies settings from a configuration file
     pub fn config_file(&mut self, config_file: &ConfigFile) -> Result<&mut Self, Error> {
         // TODO: if the config field of `listen_addresses` is empty, we should at least add `/ip4/127.0.0.1/...`
@@ -731,6 +762,13 @@ impl ClientConfigBuilder {
                 .unwrap_or_default(),
 
             seeds: config_file.network.seed_nodes.clone(),
+
+            limits: config_file
+                .network
+                .limits
+                .as_ref()
+                .map(Self::limit_config)
+                .unwrap_or_default(),
         });
 
         // Configure consensus
@@ -741,6 +779,9 @@ impl ClientConfigBuilder {
         if let Some(min_peers) = config_file.consensus.min_peers {
             consensus.min_peers = min_peers;
         }
+        if let Some(prune_bodies) = config_file.consensus.prune_bodies {
+            consensus.prune_bodies = prune_bodies;
+        }
         self.consensus(consensus);
 
         // Configure network
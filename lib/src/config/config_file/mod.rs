@@ -133,6 +133,20 @@ pub struct NetworkSettings {
 
     pub tls: Option<TlsSettings>,
     pub instant_inbound: Option<bool>,
+
+    pub limits: Option<NetworkLimitSettings>,
+}
+
+/// Limits on the peers that connections are accepted from. Limits that aren't set keep their
+/// default values.
+#[derive(Clone, Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkLimitSettings {
+    pub peer_count_max: Option<usize>,
+    pub peer_count_per_ip_max: Option<usize>,
+    pub peer_count_per_subnet_max: Option<usize>,
+    pub ipv4_subnet_mask: Option<u8>,
+    pub ipv6_subnet_mask: Option<u8>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -182,6 +196,7 @@ pub struct ConsensusSettings {
     #[serde(default)]
     pub network: Network,
     pub min_peers: Option<usize>,
+    pub prune_bodies: Option<bool>,
 }
 
 #[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
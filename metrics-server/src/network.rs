@@ -1,6 +1,7 @@
 use prometheus_client::registry::Registry;
 
 use crate::NumericClosureMetric;
+use nimiq_network_interface::network::Network as NetworkInterface;
 use nimiq_network_libp2p::Network;
 use std::sync::Arc;
 
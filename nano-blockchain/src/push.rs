@@ -45,6 +45,9 @@ impl NanoBlockchain {
             .get_slot_owner_at(block.block_number(), offset, None)
             .expect("Failed to find slot owner!");
 
+        // Check the block size.
+        Blockchain::verify_block_size(&block)?;
+
         // Check the header.
         Blockchain::verify_block_header(
             self,
@@ -53,6 +56,7 @@ impl NanoBlockchain {
             None,
             true,
             block.is_skip(),
+            policy::TIMESTAMP_MAX_DRIFT,
         )?;
 
         // If this is an election block, check the body.
@@ -1,2 +1,3 @@
 mod prove;
 mod setup;
+mod verify_batch;
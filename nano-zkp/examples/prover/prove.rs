@@ -29,6 +29,9 @@ fn main() {
 
     let start = Instant::now();
 
+    // The proving keys are loaded once and reused for all epochs.
+    let nano_zkp = NanoZKP::default();
+
     let mut genesis_state_commitment = vec![];
     let mut genesis_data = None;
     let mut proof = Proof::default();
@@ -48,16 +51,19 @@ fn main() {
         println!("Proving epoch {}", i + 1);
 
         // Generate proof.
-        proof = NanoZKP::prove(
-            initial_pks,
-            initial_header_hash,
-            final_pks.clone(),
-            block,
-            genesis_data.clone(),
-            true,
-            true,
-        )
-        .unwrap();
+        proof = nano_zkp
+            .prove(
+                initial_pks,
+                initial_header_hash,
+                final_pks.clone(),
+                block,
+                genesis_data.clone(),
+                true,
+                true,
+                None,
+                None,
+            )
+            .unwrap();
 
         // Save proof to file.
         if !Path::new("proofs/").is_dir() {
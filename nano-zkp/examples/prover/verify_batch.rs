@@ -0,0 +1,143 @@
+use std::time::Instant;
+
+use ark_crypto_primitives::{CircuitSpecificSetupSNARK, SNARK};
+use ark_groth16::Groth16;
+use ark_mnt6_753::{Fr as MNT6Fr, G2Projective as G2MNT6, MNT6_753};
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::prelude::AllocVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{test_rng, UniformRand};
+
+use nimiq_bls::utils::bytes_to_bits;
+use nimiq_nano_primitives::{state_commitment, vk_commitment};
+use nimiq_nano_zkp::utils::pack_inputs;
+use nimiq_nano_zkp::{ElectionMacroBlockData, NanoZKP};
+
+/// The number of public inputs of the Merger Wrapper circuit.
+const NUM_INPUTS: usize = 6;
+
+/// A circuit that only has public inputs. Verifying its proofs costs the same as verifying proofs
+/// for the Merger Wrapper circuit, but creating them is much faster.
+#[derive(Clone)]
+struct InputsCircuit {
+    // Inputs (public)
+    inputs: Vec<MNT6Fr>,
+}
+
+impl ConstraintSynthesizer<MNT6Fr> for InputsCircuit {
+    /// This function generates the constraints for the circuit.
+    fn generate_constraints(self, cs: ConstraintSystemRef<MNT6Fr>) -> Result<(), SynthesisError> {
+        for input in self.inputs {
+            FpVar::new_input(cs.clone(), || Ok(input))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The number of epochs whose proofs are verified as a chain.
+const NUM_EPOCHS: usize = 10;
+
+/// Calculates the public inputs for the state of the given election block.
+fn state_inputs(block: &ElectionMacroBlockData) -> Vec<MNT6Fr> {
+    pack_inputs(bytes_to_bits(&state_commitment(
+        block.block_number,
+        block.header_hash,
+        block.pks.clone(),
+    )))
+}
+
+/// Compares the time needed to verify proofs one at a time with the time needed to verify them
+/// as a batch, for batches of 1, 10 and 100 proofs. Then it does the same for the proofs of 10
+/// consecutive epochs, which are verified with `NanoZKP::verify_chain`.
+/// Run this example with `cargo run --all-features --release --example verify_batch`.
+fn main() {
+    // Create random number generator.
+    let rng = &mut test_rng();
+
+    println!("====== Generating keys and proofs ======");
+
+    let circuit = InputsCircuit {
+        inputs: vec![MNT6Fr::from(0u64); NUM_INPUTS],
+    };
+
+    let (pk, vk) = Groth16::<MNT6_753>::setup(circuit, rng).unwrap();
+
+    let mut proofs = vec![];
+
+    for _ in 0..100 {
+        let inputs: Vec<MNT6Fr> = (0..NUM_INPUTS).map(|_| MNT6Fr::rand(rng)).collect();
+
+        let circuit = InputsCircuit {
+            inputs: inputs.clone(),
+        };
+
+        let proof = Groth16::<MNT6_753>::prove(&pk, circuit, rng).unwrap();
+
+        proofs.push((proof, inputs));
+    }
+
+    for n in [1, 10, 100] {
+        println!("====== Verifying {} proofs ======", n);
+
+        let start = Instant::now();
+
+        for (proof, inputs) in &proofs[..n] {
+            assert!(Groth16::<MNT6_753>::verify(&vk, inputs, proof).unwrap());
+        }
+
+        println!("Single verification: {:?}", start.elapsed());
+
+        let start = Instant::now();
+
+        assert!(NanoZKP::verify_batch(&proofs[..n], &vk).unwrap());
+
+        println!("Batch verification: {:?}", start.elapsed());
+    }
+
+    println!("====== Verifying {} epochs ======", NUM_EPOCHS);
+
+    let blocks: Vec<ElectionMacroBlockData> = (0..=NUM_EPOCHS as u32)
+        .map(|i| ElectionMacroBlockData {
+            block_number: 100 * i,
+            header_hash: [i as u8; 32],
+            pks: vec![G2MNT6::rand(rng); 2],
+        })
+        .collect();
+
+    let mut epoch_proofs = vec![];
+
+    for epoch in blocks.windows(2) {
+        let mut inputs = state_inputs(&epoch[0]);
+        inputs.append(&mut state_inputs(&epoch[1]));
+        inputs.append(&mut pack_inputs(bytes_to_bits(&vk_commitment(vk.clone()))));
+
+        let proof = Groth16::<MNT6_753>::prove(&pk, InputsCircuit { inputs }, rng).unwrap();
+
+        epoch_proofs.push((epoch[1].clone(), proof));
+    }
+
+    let start = Instant::now();
+
+    for (epoch, (block, proof)) in epoch_proofs.iter().enumerate() {
+        assert!(NanoZKP::verify_from_key(
+            &vk,
+            blocks[epoch].block_number,
+            blocks[epoch].header_hash,
+            blocks[epoch].pks.clone(),
+            block.block_number,
+            block.header_hash,
+            block.pks.clone(),
+            proof.clone(),
+        )
+        .unwrap());
+    }
+
+    println!("Sequential verification: {:?}", start.elapsed());
+
+    let start = Instant::now();
+
+    assert!(NanoZKP::verify_chain(&vk, &blocks[0], &epoch_proofs).unwrap());
+
+    println!("Chain verification: {:?}", start.elapsed());
+}
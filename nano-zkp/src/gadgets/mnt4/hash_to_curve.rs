@@ -10,7 +10,7 @@ use ark_r1cs_std::prelude::{AllocVar, Boolean, EqGadget};
 use ark_r1cs_std::{R1CSVar, ToConstraintFieldGadget};
 use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
 
-use nimiq_bls::utils::{big_int_from_bytes_be, byte_from_be_bits, byte_to_le_bits};
+use nimiq_bls::utils::{big_int_from_bytes_be, byte_to_le_bits, bytes_from_be_bits};
 
 use crate::gadgets::mnt4::YToBitGadget;
 use crate::utils::reverse_inner_byte_order;
@@ -135,12 +135,9 @@ impl HashToCurve {
     /// Returns the nonce i (as a vector of big endian bits), such that (x + i) is a valid x coordinate for G1.
     /// The nonce i is always a u8, i.e., consists of 8 bits.
     fn try_and_increment(x_bits: Vec<bool>, y: bool) -> (Vec<bool>, G1Affine) {
-        // Prepare the bits to transform into field element.
-        let mut bytes = vec![];
-
-        for i in 0..96 {
-            bytes.push(byte_from_be_bits(&x_bits[i * 8..(i + 1) * 8]))
-        }
+        // Prepare the bits to transform into field element. The 768 bits always make up the 96
+        // bytes of a big integer.
+        let bytes = bytes_from_be_bits(&x_bits[..96 * 8]);
 
         // Transform the x-coordinate into a field element.
         let mut x = MNT4Fr::from_repr(big_int_from_bytes_be(&mut &bytes[..])).unwrap();
@@ -159,6 +156,6 @@ impl HashToCurve {
             x += &MNT4Fr::one();
         }
 
-        unreachable!()
+        panic!("No valid x coordinate found within 256 nonces")
     }
 }
@@ -4,4 +4,5 @@
 pub mod mnt4;
 #[allow(dead_code)]
 pub mod mnt6;
+pub mod poseidon;
 pub mod y_to_bit;
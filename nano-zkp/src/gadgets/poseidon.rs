@@ -0,0 +1,93 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_sponge::constraints::CryptographicSpongeVar;
+use ark_sponge::poseidon::constraints::PoseidonSpongeVar;
+use ark_sponge::Absorb;
+
+use crate::poseidon::{create_parameters, DefaultPoseidonParameters};
+
+/// This is a gadget that calculates a Poseidon hash. It uses the same parameters as the off-circuit
+/// `poseidon::hash` function and produces the same result, so values hashed outside of the circuit
+/// can be recomputed inside of it.
+pub struct PoseidonHashGadget {}
+
+impl PoseidonHashGadget {
+    /// Calculates the Poseidon hash of the given field elements, using the parameters for a state
+    /// width of 3. The inputs are absorbed into a Poseidon sponge and the hash is the first field
+    /// element squeezed out of it.
+    pub fn evaluate<F: PrimeField + Absorb + DefaultPoseidonParameters>(
+        cs: ConstraintSystemRef<F>,
+        inputs: &[FpVar<F>],
+    ) -> Result<FpVar<F>, SynthesisError> {
+        let parameters = create_parameters::<F>(F::PARAMS_T3);
+
+        let mut sponge = PoseidonSpongeVar::new(cs, &parameters);
+
+        sponge.absorb(&inputs.to_vec())?;
+
+        Ok(sponge.squeeze_field_elements(1)?.remove(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_mnt4_753::Fr as MNT4Fr;
+    use ark_mnt6_753::Fr as MNT6Fr;
+    use ark_r1cs_std::prelude::AllocVar;
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+
+    use nimiq_test_log::test;
+
+    use crate::poseidon;
+
+    use super::*;
+
+    #[test]
+    fn poseidon_hash_mnt4_works() {
+        // Initialize the constraint system.
+        let cs = ConstraintSystem::<MNT4Fr>::new_ref();
+
+        // Create random number generator.
+        let rng = &mut test_rng();
+
+        // Create random field elements.
+        let inputs: Vec<MNT4Fr> = (0..5).map(|_| MNT4Fr::rand(rng)).collect();
+
+        // Evaluate Poseidon hash using the primitive version.
+        let primitive_hash = poseidon::hash(&inputs);
+
+        // Allocate the field elements in the circuit.
+        let inputs_var = Vec::<FpVar<MNT4Fr>>::new_witness(cs.clone(), || Ok(inputs)).unwrap();
+
+        // Evaluate Poseidon hash using the gadget version.
+        let gadget_hash = PoseidonHashGadget::evaluate(cs, &inputs_var).unwrap();
+
+        assert_eq!(primitive_hash, gadget_hash.value().unwrap())
+    }
+
+    #[test]
+    fn poseidon_hash_mnt6_works() {
+        // Initialize the constraint system.
+        let cs = ConstraintSystem::<MNT6Fr>::new_ref();
+
+        // Create random number generator.
+        let rng = &mut test_rng();
+
+        // Create random field elements.
+        let inputs: Vec<MNT6Fr> = (0..5).map(|_| MNT6Fr::rand(rng)).collect();
+
+        // Evaluate Poseidon hash using the primitive version.
+        let primitive_hash = poseidon::hash(&inputs);
+
+        // Allocate the field elements in the circuit.
+        let inputs_var = Vec::<FpVar<MNT6Fr>>::new_witness(cs.clone(), || Ok(inputs)).unwrap();
+
+        // Evaluate Poseidon hash using the gadget version.
+        let gadget_hash = PoseidonHashGadget::evaluate(cs, &inputs_var).unwrap();
+
+        assert_eq!(primitive_hash, gadget_hash.value().unwrap())
+    }
+}
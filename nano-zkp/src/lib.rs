@@ -6,7 +6,5 @@ pub(crate) mod circuits;
 pub(crate) mod gadgets;
 
 pub(crate) mod nano_zkp;
+pub mod poseidon;
 pub mod utils;
-
-#[allow(dead_code)]
-mod poseidon;
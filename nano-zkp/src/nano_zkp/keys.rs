@@ -0,0 +1,135 @@
+use std::any::Any;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use ark_ec::PairingEngine;
+use ark_groth16::ProvingKey;
+use ark_serialize::CanonicalDeserialize;
+
+#[cfg(feature = "metrics")]
+use crate::nano_zkp::metrics::NanoZKPMetrics;
+use crate::{NanoZKP, NanoZKPError};
+
+/// The proving key that was loaded last, together with the name of its circuit. Proving keys are
+/// several gigabytes large, so only one of them is kept. The keys are for different curves, so it
+/// is stored type-erased.
+pub(crate) type ProvingKeyCache = Option<(String, Arc<dyn Any + Send + Sync>)>;
+
+impl NanoZKP {
+    /// Creates a new instance that loads the proving keys from the given directory. The proving
+    /// key that was loaded last is kept in memory, so that consecutive proofs for the same circuit
+    /// don't load it again.
+    /// The verifying keys are loaded from `verifying_keys/`, see `with_verifying_keys_dir`.
+    pub fn new(keys_dir: PathBuf) -> Self {
+        Self {
+            keys_dir,
+            verifying_keys_dir: PathBuf::from("verifying_keys"),
+            cache: Arc::new(RwLock::new(None)),
+            #[cfg(feature = "metrics")]
+            metrics: Default::default(),
+        }
+    }
+
+    /// Returns the directory from which the proving keys are loaded.
+    pub fn keys_dir(&self) -> &Path {
+        &self.keys_dir
+    }
+
+    /// Sets the directory from which the verifying keys are loaded while proving. The prover needs
+    /// them for the proofs of the child circuits and to check its proofs in debug mode.
+    pub fn with_verifying_keys_dir(mut self, verifying_keys_dir: PathBuf) -> Self {
+        self.verifying_keys_dir = verifying_keys_dir;
+        self
+    }
+
+    /// Returns the directory from which the verifying keys are loaded while proving.
+    pub fn verifying_keys_dir(&self) -> &Path {
+        &self.verifying_keys_dir
+    }
+
+    /// Returns the path of the verifying key for the circuit with the given name.
+    #[cfg(feature = "prover")]
+    pub(crate) fn verifying_key_path(&self, name: &str) -> PathBuf {
+        self.verifying_keys_dir.join(format!("{}.bin", name))
+    }
+
+    /// Drops the cached proving key, so that it is loaded from disk again the next time it is
+    /// needed.
+    pub fn clear_cache(&self) {
+        *self.cache.write().unwrap() = None;
+    }
+
+    /// Returns the current metrics.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> Arc<NanoZKPMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Returns the proving key for the circuit with the given name, e.g. `merger_wrapper`. It is
+    /// taken from the cache if it was the last key loaded, otherwise it is loaded from
+    /// `<keys_dir>/<name>.bin` and replaces the cached key.
+    pub fn proving_key<E: PairingEngine>(
+        &self,
+        name: &str,
+    ) -> Result<Arc<ProvingKey<E>>, NanoZKPError> {
+        if let Some(proving_key) = self.cached_proving_key(name) {
+            return Ok(proving_key);
+        }
+
+        // Hold the write lock while loading, so that a key is never loaded twice.
+        let mut cache = self.cache.write().unwrap();
+
+        if let Some(proving_key) = Self::cache_entry(&cache, name) {
+            #[cfg(feature = "metrics")]
+            self.metrics.note_cache_hit();
+            return Ok(proving_key);
+        }
+
+        #[cfg(feature = "metrics")]
+        self.metrics.note_cache_miss();
+
+        // Drop the previous key before loading the new one, so that they aren't both in memory.
+        *cache = None;
+
+        let file = File::open(self.keys_dir.join(format!("{}.bin", name)))?;
+
+        let proving_key = Arc::new(ProvingKey::<E>::deserialize_unchecked(
+            &mut BufReader::new(file),
+        )?);
+
+        *cache = Some((name.to_string(), proving_key.clone()));
+
+        Ok(proving_key)
+    }
+
+    fn cached_proving_key<E: PairingEngine>(&self, name: &str) -> Option<Arc<ProvingKey<E>>> {
+        let proving_key = Self::cache_entry(&self.cache.read().unwrap(), name)?;
+
+        #[cfg(feature = "metrics")]
+        self.metrics.note_cache_hit();
+
+        Some(proving_key)
+    }
+
+    /// Returns the cached proving key if it belongs to the circuit with the given name.
+    fn cache_entry<E: PairingEngine>(
+        cache: &ProvingKeyCache,
+        name: &str,
+    ) -> Option<Arc<ProvingKey<E>>> {
+        match cache {
+            Some((cached_name, proving_key)) if cached_name == name => {
+                proving_key.clone().downcast::<ProvingKey<E>>().ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for NanoZKP {
+    /// Loads the proving keys from `proving_keys/`, which is where the setup stores them.
+    fn default() -> Self {
+        NanoZKP::new(PathBuf::from("proving_keys"))
+    }
+}
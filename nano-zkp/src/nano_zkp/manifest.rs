@@ -0,0 +1,244 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use ark_ec::PairingEngine;
+use ark_groth16::VerifyingKey;
+use ark_serialize::CanonicalDeserialize;
+
+use nimiq_hash::{Blake2bHash, Blake2bHasher, Hasher};
+use nimiq_nano_primitives::{PK_TREE_BREADTH, PK_TREE_DEPTH};
+use nimiq_primitives::policy::SLOTS;
+
+use crate::{NanoZKP, NanoZKPError};
+
+/// The name of the manifest file in a keys directory.
+pub const MANIFEST_FILE: &str = "manifest.txt";
+
+/// The circuits of the nano sync program, in the order in which their keys are generated.
+pub const CIRCUITS: [&str; 10] = [
+    "pk_tree_5",
+    "pk_tree_4",
+    "pk_tree_3",
+    "pk_tree_2",
+    "pk_tree_1",
+    "pk_tree_0",
+    "macro_block",
+    "macro_block_wrapper",
+    "merger",
+    "merger_wrapper",
+];
+
+/// A file listed in a key manifest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// The name of the circuit the key belongs to.
+    pub circuit: String,
+    /// The hash of the parameters the circuit was created for, see `KeyManifest::parameter_hash`.
+    pub parameter_hash: Blake2bHash,
+    /// The Blake2b hash of the file content.
+    pub checksum: Blake2bHash,
+    /// The path of the file, relative to the keys directory.
+    pub file: String,
+}
+
+/// The manifest of a keys directory. It lists the proving and verifying key files of each circuit
+/// with their checksums, so that corrupted or outdated keys are detected before they are used.
+/// It is stored as `manifest.txt` with one `<circuit> <parameter hash> <checksum> <file>` line
+/// per file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyManifest {
+    entries: Vec<ManifestEntry>,
+}
+
+impl KeyManifest {
+    /// Creates a manifest for the proving and verifying keys of the given circuits in the given
+    /// keys directory.
+    pub fn create(dir: &Path, circuits: &[&str]) -> Result<Self, NanoZKPError> {
+        let mut entries = vec![];
+
+        for circuit in circuits {
+            for file in Self::key_files(circuit) {
+                entries.push(ManifestEntry {
+                    circuit: circuit.to_string(),
+                    parameter_hash: Self::parameter_hash(circuit),
+                    checksum: Self::checksum(&dir.join(&file))?,
+                    file,
+                });
+            }
+        }
+
+        Ok(KeyManifest { entries })
+    }
+
+    /// Reads the manifest of the given keys directory.
+    pub fn read(dir: &Path) -> Result<Self, NanoZKPError> {
+        let content = fs::read_to_string(dir.join(MANIFEST_FILE))?;
+
+        let mut entries = vec![];
+
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+
+            if columns.len() != 4 {
+                return Err(NanoZKPError::InvalidManifest(format!(
+                    "expected 4 columns, found {}",
+                    columns.len()
+                )));
+            }
+
+            let parse_hash = |hex: &str| {
+                Blake2bHash::from_str(hex)
+                    .map_err(|_| NanoZKPError::InvalidManifest(format!("invalid hash {}", hex)))
+            };
+
+            entries.push(ManifestEntry {
+                circuit: columns[0].to_string(),
+                parameter_hash: parse_hash(columns[1])?,
+                checksum: parse_hash(columns[2])?,
+                file: columns[3].to_string(),
+            });
+        }
+
+        Ok(KeyManifest { entries })
+    }
+
+    /// Writes the manifest to the given keys directory.
+    pub fn write(&self, dir: &Path) -> Result<(), NanoZKPError> {
+        let mut file = File::create(dir.join(MANIFEST_FILE))?;
+
+        for entry in &self.entries {
+            writeln!(
+                file,
+                "{} {} {} {}",
+                entry.circuit,
+                entry.parameter_hash.to_hex(),
+                entry.checksum.to_hex(),
+                entry.file
+            )?;
+        }
+
+        file.sync_all()?;
+
+        Ok(())
+    }
+
+    /// Checks that the manifest lists the proving and verifying key of every circuit, that all
+    /// files listed in it were created for the current parameters and that their content matches
+    /// the checksum.
+    pub fn verify(&self, dir: &Path) -> Result<(), NanoZKPError> {
+        // Check that no key is missing before hashing any of the files.
+        for circuit in CIRCUITS {
+            for file in Self::key_files(circuit) {
+                if !self
+                    .entries
+                    .iter()
+                    .any(|entry| entry.circuit == circuit && entry.file == file)
+                {
+                    return Err(NanoZKPError::InvalidManifest(format!(
+                        "missing entry for {}",
+                        file
+                    )));
+                }
+            }
+        }
+
+        for entry in &self.entries {
+            if entry.parameter_hash != Self::parameter_hash(&entry.circuit) {
+                return Err(NanoZKPError::ParameterMismatch(entry.circuit.clone()));
+            }
+
+            if entry.checksum != Self::checksum(&dir.join(&entry.file))? {
+                return Err(NanoZKPError::ChecksumMismatch(entry.file.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[ManifestEntry] {
+        &self.entries
+    }
+
+    /// Returns the hash of the parameters that the keys of the given circuit depend on. Keys that
+    /// were generated for other parameters can't be used.
+    pub fn parameter_hash(circuit: &str) -> Blake2bHash {
+        let mut hasher = Blake2bHasher::default();
+        hasher.write_all(circuit.as_bytes()).unwrap();
+        hasher.write_all(&SLOTS.to_be_bytes()).unwrap();
+        hasher
+            .write_all(&(PK_TREE_BREADTH as u64).to_be_bytes())
+            .unwrap();
+        hasher
+            .write_all(&(PK_TREE_DEPTH as u64).to_be_bytes())
+            .unwrap();
+        hasher.finish()
+    }
+
+    /// Hashes the given file. The file is streamed, so that multi-gigabyte keys are never held in
+    /// memory.
+    fn checksum(path: &Path) -> Result<Blake2bHash, NanoZKPError> {
+        let mut hasher = Blake2bHasher::default();
+        io::copy(&mut BufReader::new(File::open(path)?), &mut hasher)?;
+        Ok(hasher.finish())
+    }
+
+    fn key_files(circuit: &str) -> [String; 2] {
+        [
+            format!("proving_keys/{}.bin", circuit),
+            format!("verifying_keys/{}.bin", circuit),
+        ]
+    }
+}
+
+/// The keys in a directory whose manifest was verified, as returned by `NanoZKP::load_from_dir`.
+pub struct Keys {
+    dir: PathBuf,
+    manifest: KeyManifest,
+}
+
+impl Keys {
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn manifest(&self) -> &KeyManifest {
+        &self.manifest
+    }
+
+    /// Returns an instance that loads its proving and verifying keys from this directory.
+    pub fn nano_zkp(&self) -> NanoZKP {
+        NanoZKP::new(self.dir.join("proving_keys"))
+            .with_verifying_keys_dir(self.dir.join("verifying_keys"))
+    }
+
+    /// Loads the verifying key of the given circuit. The file is streamed instead of being read
+    /// into memory first.
+    pub fn verifying_key<E: PairingEngine>(
+        &self,
+        circuit: &str,
+    ) -> Result<VerifyingKey<E>, NanoZKPError> {
+        let file = File::open(self.dir.join(format!("verifying_keys/{}.bin", circuit)))?;
+
+        Ok(VerifyingKey::deserialize_unchecked(&mut BufReader::new(
+            file,
+        ))?)
+    }
+}
+
+impl NanoZKP {
+    /// Loads the keys from a directory created by `NanoZKP::setup_to_dir`. Fails if the manifest
+    /// is missing or malformed, if the keys were generated for other parameters or if any file
+    /// doesn't match its checksum.
+    pub fn load_from_dir(path: &Path) -> Result<Keys, NanoZKPError> {
+        let manifest = KeyManifest::read(path)?;
+
+        manifest.verify(path)?;
+
+        Ok(Keys {
+            dir: path.to_path_buf(),
+            manifest,
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::registry::Registry;
+
+#[derive(Default, Clone)]
+pub struct NanoZKPMetrics {
+    key_cache_hits: Counter,
+    key_cache_misses: Counter,
+}
+
+impl NanoZKPMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "proving_key_cache_hits",
+            "Number of proving keys that were taken from the cache",
+            Box::new(self.key_cache_hits.clone()),
+        );
+        registry.register(
+            "proving_key_cache_misses",
+            "Number of proving keys that had to be loaded from disk",
+            Box::new(self.key_cache_misses.clone()),
+        );
+    }
+
+    /// Returns the number of proving keys that were taken from the cache.
+    pub fn cache_hits(&self) -> u64 {
+        self.key_cache_hits.get()
+    }
+
+    /// Returns the number of proving keys that had to be loaded from disk.
+    pub fn cache_misses(&self) -> u64 {
+        self.key_cache_misses.get()
+    }
+
+    pub(crate) fn note_cache_hit(&self) {
+        self.key_cache_hits.inc();
+    }
+
+    pub(crate) fn note_cache_miss(&self) {
+        self.key_cache_misses.inc();
+    }
+}
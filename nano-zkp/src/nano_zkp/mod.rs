@@ -1,11 +1,23 @@
 use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
 
 use ark_groth16::Proof;
-use ark_mnt6_753::MNT6_753;
+use ark_mnt6_753::{G2Projective as G2MNT6, MNT6_753};
 use ark_relations::r1cs::SynthesisError;
 use ark_serialize::SerializationError;
 use thiserror::Error;
 
+use nimiq_bls::utils::BitsError;
+
+pub use manifest::{KeyManifest, Keys, ManifestEntry, CIRCUITS, MANIFEST_FILE};
+#[cfg(feature = "metrics")]
+pub use metrics::NanoZKPMetrics;
+
+mod keys;
+mod manifest;
+#[cfg(feature = "metrics")]
+mod metrics;
 #[cfg(feature = "prover")]
 mod prove;
 #[cfg(feature = "prover")]
@@ -14,11 +26,52 @@ mod verify;
 
 /// This the main struct for the nano-zkp crate. It provides methods to setup (create the
 /// proving and verifying keys), create proofs and verify proofs for the nano sync circuit.
-pub struct NanoZKP;
+/// An instance keeps the proving key it loaded last in memory, so that consecutive proofs for the
+/// same circuit don't need to load it from disk again.
+pub struct NanoZKP {
+    // The directory from which the proving keys are loaded.
+    keys_dir: PathBuf,
+    // The directory from which the verifying keys are loaded while proving.
+    verifying_keys_dir: PathBuf,
+    // The proving key that was loaded last.
+    cache: Arc<RwLock<keys::ProvingKeyCache>>,
+    #[cfg(feature = "metrics")]
+    metrics: Arc<NanoZKPMetrics>,
+}
 
 /// This is the proof type for the NanoZKP. It is just an alias, for convenience.
 pub type NanoProof = Proof<MNT6_753>;
 
+/// The state committed to by an election macro block: its block number, its header hash and the
+/// public keys of the validators it selects. A nano-zkp proof proves the transition from one such
+/// state to another.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElectionMacroBlockData {
+    pub block_number: u32,
+    pub header_hash: [u8; 32],
+    pub pks: Vec<G2MNT6>,
+}
+
+/// The steps of the proof generation pipeline. They are passed to the progress callback of
+/// `NanoZKP::prove` in the order in which they are executed.
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofStage {
+    /// Calculating the public key tree roots and the Merkle proofs for its leaves.
+    WitnessGeneration,
+    /// Proving the node at the given level and position of the public key tree. The leaves are at
+    /// level 5 and the root is at level 0.
+    PkTree { level: usize, position: usize },
+    /// Proving the Macro Block circuit.
+    MacroBlock,
+    /// Proving the Macro Block Wrapper circuit.
+    MacroBlockWrapper,
+    /// Proving the Merger circuit, which aggregates the previous epoch's proof with this one.
+    Merger,
+    /// Proving the Merger Wrapper circuit. This produces the final proof.
+    MergerWrapper,
+}
+
 #[derive(Error, Debug)]
 pub enum NanoZKPError {
     #[error("filesystem error")]
@@ -27,4 +80,18 @@ pub enum NanoZKPError {
     Serialization(#[from] SerializationError),
     #[error("circuit error")]
     Circuit(#[from] SynthesisError),
+    #[error("invalid public input: {0}")]
+    InvalidInput(#[from] BitsError),
+    #[error("proof generation was cancelled")]
+    Cancelled,
+    #[error("invalid key manifest: {0}")]
+    InvalidManifest(String),
+    #[error("keys of circuit {0} were generated for other parameters")]
+    ParameterMismatch(String),
+    #[error("checksum mismatch for key file {0}")]
+    ChecksumMismatch(String),
+    #[error("election block of epoch {0} doesn't follow the previous election block")]
+    BrokenChain(usize),
+    #[error("proof for epoch {0} is invalid")]
+    InvalidEpochProof(usize),
 }
@@ -1,16 +1,18 @@
 use std::fs;
 use std::fs::{DirBuilder, File};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use ark_crypto_primitives::SNARK;
 use ark_ec::{PairingEngine, ProjectiveCurve};
 use ark_ff::Zero;
-use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_groth16::{Groth16, Proof, VerifyingKey};
 use ark_mnt4_753::{Fr as MNT4Fr, MNT4_753};
 use ark_mnt6_753::{Fr as MNT6Fr, G1Projective as G1MNT6, G2Projective as G2MNT6, MNT6_753};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::UniformRand;
-use rand::{thread_rng, CryptoRng, Rng};
+use rand::rngs::OsRng;
+use rand::{CryptoRng, Rng, RngCore};
 
 use nimiq_bls::pedersen::{pedersen_generators, pedersen_hash};
 use nimiq_bls::utils::{byte_to_le_bits, bytes_to_bits};
@@ -27,12 +29,14 @@ use crate::circuits::mnt6::{
     MacroBlockWrapperCircuit, MergerWrapperCircuit, PKTreeNodeCircuit as NodeMNT6,
 };
 use crate::utils::pack_inputs;
-use crate::{NanoZKP, NanoZKPError};
+use crate::{NanoZKP, NanoZKPError, ProofStage};
 
 impl NanoZKP {
     /// This function generates a proof for a new epoch, it uses the entire nano sync program. Note
     /// that the proof generation can easily take longer than 12 hours.
+    #[allow(clippy::too_many_arguments)]
     pub fn prove(
+        &self,
         // The public keys of the validators of the initial state. So, the validators that were
         // selected in the previous election macro block and that are now signing this election
         // macro block.
@@ -55,8 +59,55 @@ impl NanoZKP {
         // This is a flag indicating if we want to run this function in debug mode. It will verify
         // each proof it creates right after the proof is generated.
         debug_mode: bool,
+        // An optional callback that gets invoked every time the proof generation enters a new
+        // stage. Stages whose proofs were loaded from the cache are not reported.
+        progress: Option<&dyn Fn(ProofStage)>,
+        // An optional flag that cancels the proof generation once it is set. It is checked before
+        // each stage, and the proof generation then returns `NanoZKPError::Cancelled`. The proofs
+        // of the completed stages are kept, so that a later call with proof caching can resume.
+        cancelled: Option<&AtomicBool>,
     ) -> Result<Proof<MNT6_753>, NanoZKPError> {
-        let rng = &mut thread_rng();
+        self.prove_with_rng(
+            initial_pks,
+            initial_header_hash,
+            final_pks,
+            block,
+            genesis_data,
+            proof_caching,
+            debug_mode,
+            progress,
+            cancelled,
+            &mut OsRng,
+        )
+    }
+
+    /// This function is the same as `prove`, but it draws all randomness from the given RNG. With
+    /// a seeded RNG, the same inputs and proving keys always produce the same proof.
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        initial_pks: Vec<G2MNT6>,
+        initial_header_hash: [u8; 32],
+        final_pks: Vec<G2MNT6>,
+        block: MacroBlock,
+        genesis_data: Option<(Proof<MNT6_753>, Vec<u8>)>,
+        proof_caching: bool,
+        debug_mode: bool,
+        progress: Option<&dyn Fn(ProofStage)>,
+        cancelled: Option<&AtomicBool>,
+        rng: &mut R,
+    ) -> Result<Proof<MNT6_753>, NanoZKPError> {
+        let enter_stage = |stage| {
+            if cancelled.map_or(false, |cancelled| cancelled.load(Ordering::Acquire)) {
+                return Err(NanoZKPError::Cancelled);
+            }
+            if let Some(progress) = progress {
+                progress(stage);
+            }
+            Ok(())
+        };
+
+        enter_stage(ProofStage::WitnessGeneration)?;
 
         // Serialize the initial public keys into bits and chunk them into the number of leaves.
         let mut bytes = Vec::new();
@@ -102,7 +153,12 @@ impl NanoZKP {
 
             println!("generating pk_tree_5_{}", i);
 
-            NanoZKP::prove_pk_tree_leaf(
+            enter_stage(ProofStage::PkTree {
+                level: 5,
+                position: i,
+            })?;
+
+            self.prove_pk_tree_leaf(
                 rng,
                 "pk_tree_5",
                 i,
@@ -122,7 +178,12 @@ impl NanoZKP {
 
             println!("generating pk_tree_4_{}", i);
 
-            NanoZKP::prove_pk_tree_node_mnt6(
+            enter_stage(ProofStage::PkTree {
+                level: 4,
+                position: i,
+            })?;
+
+            self.prove_pk_tree_node_mnt6(
                 rng,
                 "pk_tree_4",
                 i,
@@ -143,7 +204,12 @@ impl NanoZKP {
 
             println!("generating pk_tree_3_{}", i);
 
-            NanoZKP::prove_pk_tree_node_mnt4(
+            enter_stage(ProofStage::PkTree {
+                level: 3,
+                position: i,
+            })?;
+
+            self.prove_pk_tree_node_mnt4(
                 rng,
                 "pk_tree_3",
                 i,
@@ -164,7 +230,12 @@ impl NanoZKP {
 
             println!("generating pk_tree_2_{}", i);
 
-            NanoZKP::prove_pk_tree_node_mnt6(
+            enter_stage(ProofStage::PkTree {
+                level: 2,
+                position: i,
+            })?;
+
+            self.prove_pk_tree_node_mnt6(
                 rng,
                 "pk_tree_2",
                 i,
@@ -185,7 +256,12 @@ impl NanoZKP {
 
             println!("generating pk_tree_1_{}", i);
 
-            NanoZKP::prove_pk_tree_node_mnt4(
+            enter_stage(ProofStage::PkTree {
+                level: 1,
+                position: i,
+            })?;
+
+            self.prove_pk_tree_node_mnt4(
                 rng,
                 "pk_tree_1",
                 i,
@@ -202,7 +278,12 @@ impl NanoZKP {
         if !(proof_caching && Path::new("proofs/pk_tree_0_0.bin").exists()) {
             println!("generating pk_tree_0_0");
 
-            NanoZKP::prove_pk_tree_node_mnt6(
+            enter_stage(ProofStage::PkTree {
+                level: 0,
+                position: 0,
+            })?;
+
+            self.prove_pk_tree_node_mnt6(
                 rng,
                 "pk_tree_0",
                 0,
@@ -219,7 +300,9 @@ impl NanoZKP {
         if !(proof_caching && Path::new("proofs/macro_block.bin").exists()) {
             println!("generating macro_block");
 
-            NanoZKP::prove_macro_block(
+            enter_stage(ProofStage::MacroBlock)?;
+
+            self.prove_macro_block(
                 rng,
                 &initial_pks,
                 &initial_pk_tree_root,
@@ -235,7 +318,9 @@ impl NanoZKP {
         if !(proof_caching && Path::new("proofs/macro_block_wrapper.bin").exists()) {
             println!("generating macro_block_wrapper");
 
-            NanoZKP::prove_macro_block_wrapper(
+            enter_stage(ProofStage::MacroBlockWrapper)?;
+
+            self.prove_macro_block_wrapper(
                 rng,
                 &initial_pks,
                 initial_header_hash,
@@ -249,7 +334,9 @@ impl NanoZKP {
         if !(proof_caching && Path::new("proofs/merger.bin").exists()) {
             println!("generating merger");
 
-            NanoZKP::prove_merger(
+            enter_stage(ProofStage::Merger)?;
+
+            self.prove_merger(
                 rng,
                 &initial_pks,
                 initial_header_hash,
@@ -263,7 +350,9 @@ impl NanoZKP {
         // Start generating proof for Merger Wrapper.
         println!("generating merger wrapper");
 
-        let proof = NanoZKP::prove_merger_wrapper(
+        enter_stage(ProofStage::MergerWrapper)?;
+
+        let proof = self.prove_merger_wrapper(
             rng,
             &initial_pks,
             initial_header_hash,
@@ -280,7 +369,9 @@ impl NanoZKP {
         Ok(proof)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn prove_pk_tree_leaf<R: CryptoRng + Rng>(
+        &self,
         rng: &mut R,
         name: &str,
         position: usize,
@@ -290,10 +381,8 @@ impl NanoZKP {
         signer_bitmap: &[bool],
         debug_mode: bool,
     ) -> Result<(), NanoZKPError> {
-        // Load the proving key from file.
-        let mut file = File::open(format!("proving_keys/{}.bin", name))?;
-
-        let proving_key = ProvingKey::deserialize_unchecked(&mut file)?;
+        // Load the proving key, from file or from the cache.
+        let proving_key = self.proving_key::<MNT4_753>(name)?;
 
         // Calculate the aggregate public key commitment.
         let mut agg_pk = G2MNT6::zero();
@@ -338,12 +427,12 @@ impl NanoZKP {
         );
 
         // Create the proof.
-        let proof = Groth16::<MNT4_753>::prove(&proving_key, circuit, rng)?;
+        let proof = Groth16::<MNT4_753>::prove(&*proving_key, circuit, rng)?;
 
         // Optionally verify the proof.
         if debug_mode {
             // Load the proving key from file.
-            let mut file = File::open(format!("verifying_keys/{}.bin", name))?;
+            let mut file = File::open(self.verifying_key_path(name))?;
 
             let verifying_key = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -370,7 +459,9 @@ impl NanoZKP {
         NanoZKP::proof_to_file(proof, name, Some(position))
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn prove_pk_tree_node_mnt6<R: CryptoRng + Rng>(
+        &self,
         rng: &mut R,
         name: &str,
         position: usize,
@@ -381,13 +472,11 @@ impl NanoZKP {
         signer_bitmap: &[bool],
         debug_mode: bool,
     ) -> Result<(), NanoZKPError> {
-        // Load the proving key from file.
-        let mut file = File::open(format!("proving_keys/{}.bin", name))?;
-
-        let proving_key = ProvingKey::deserialize_unchecked(&mut file)?;
+        // Load the proving key, from file or from the cache.
+        let proving_key = self.proving_key::<MNT6_753>(name)?;
 
         // Load the verifying key from file.
-        let mut file = File::open(format!("verifying_keys/{}.bin", vk_file))?;
+        let mut file = File::open(self.verifying_key_path(vk_file))?;
 
         let vk_child = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -469,12 +558,12 @@ impl NanoZKP {
         );
 
         // Create the proof.
-        let proof = Groth16::<MNT6_753>::prove(&proving_key, circuit, rng)?;
+        let proof = Groth16::<MNT6_753>::prove(&*proving_key, circuit, rng)?;
 
         // Optionally verify the proof.
         if debug_mode {
             // Load the proving key from file.
-            let mut file = File::open(format!("verifying_keys/{}.bin", name))?;
+            let mut file = File::open(self.verifying_key_path(name))?;
 
             let verifying_key = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -503,7 +592,9 @@ impl NanoZKP {
         NanoZKP::proof_to_file(proof, name, Some(position))
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn prove_pk_tree_node_mnt4<R: CryptoRng + Rng>(
+        &self,
         rng: &mut R,
         name: &str,
         position: usize,
@@ -514,13 +605,11 @@ impl NanoZKP {
         signer_bitmap: &[bool],
         debug_mode: bool,
     ) -> Result<(), NanoZKPError> {
-        // Load the proving key from file.
-        let mut file = File::open(format!("proving_keys/{}.bin", name))?;
-
-        let proving_key = ProvingKey::deserialize_unchecked(&mut file)?;
+        // Load the proving key, from file or from the cache.
+        let proving_key = self.proving_key::<MNT4_753>(name)?;
 
         // Load the verifying key from file.
-        let mut file = File::open(format!("verifying_keys/{}.bin", vk_file))?;
+        let mut file = File::open(self.verifying_key_path(vk_file))?;
 
         let vk_child = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -596,12 +685,12 @@ impl NanoZKP {
         );
 
         // Create the proof.
-        let proof = Groth16::<MNT4_753>::prove(&proving_key, circuit, rng)?;
+        let proof = Groth16::<MNT4_753>::prove(&*proving_key, circuit, rng)?;
 
         // Optionally verify the proof.
         if debug_mode {
             // Load the proving key from file.
-            let mut file = File::open(format!("verifying_keys/{}.bin", name))?;
+            let mut file = File::open(self.verifying_key_path(name))?;
 
             let verifying_key = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -628,7 +717,9 @@ impl NanoZKP {
         NanoZKP::proof_to_file(proof, name, Some(position))
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn prove_macro_block<R: CryptoRng + Rng>(
+        &self,
         rng: &mut R,
         initial_pks: &[G2MNT6],
         initial_pk_tree_root: &[u8],
@@ -638,13 +729,11 @@ impl NanoZKP {
         block: &MacroBlock,
         debug_mode: bool,
     ) -> Result<(), NanoZKPError> {
-        // Load the proving key from file.
-        let mut file = File::open("proving_keys/macro_block.bin")?;
-
-        let proving_key = ProvingKey::deserialize_unchecked(&mut file)?;
+        // Load the proving key, from file or from the cache.
+        let proving_key = self.proving_key::<MNT4_753>("macro_block")?;
 
         // Load the verifying key from file.
-        let mut file = File::open("verifying_keys/pk_tree_0.bin")?;
+        let mut file = File::open(self.verifying_key_path("pk_tree_0"))?;
 
         let vk_pk_tree = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -696,12 +785,12 @@ impl NanoZKP {
         );
 
         // Create the proof.
-        let proof = Groth16::<MNT4_753>::prove(&proving_key, circuit, rng)?;
+        let proof = Groth16::<MNT4_753>::prove(&*proving_key, circuit, rng)?;
 
         // Optionally verify the proof.
         if debug_mode {
             // Load the proving key from file.
-            let mut file = File::open("verifying_keys/macro_block.bin")?;
+            let mut file = File::open(self.verifying_key_path("macro_block"))?;
 
             let verifying_key = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -725,6 +814,7 @@ impl NanoZKP {
     }
 
     fn prove_macro_block_wrapper<R: CryptoRng + Rng>(
+        &self,
         rng: &mut R,
         initial_pks: &[G2MNT6],
         initial_header_hash: [u8; 32],
@@ -732,13 +822,11 @@ impl NanoZKP {
         block: &MacroBlock,
         debug_mode: bool,
     ) -> Result<(), NanoZKPError> {
-        // Load the proving key from file.
-        let mut file = File::open("proving_keys/macro_block_wrapper.bin")?;
-
-        let proving_key = ProvingKey::deserialize_unchecked(&mut file)?;
+        // Load the proving key, from file or from the cache.
+        let proving_key = self.proving_key::<MNT6_753>("macro_block_wrapper")?;
 
         // Load the verifying key from file.
-        let mut file = File::open("verifying_keys/macro_block.bin")?;
+        let mut file = File::open(self.verifying_key_path("macro_block"))?;
 
         let vk_macro_block = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -769,12 +857,12 @@ impl NanoZKP {
         );
 
         // Create the proof.
-        let proof = Groth16::<MNT6_753>::prove(&proving_key, circuit, rng)?;
+        let proof = Groth16::<MNT6_753>::prove(&*proving_key, circuit, rng)?;
 
         // Optionally verify the proof.
         if debug_mode {
             // Load the proving key from file.
-            let mut file = File::open("verifying_keys/macro_block_wrapper.bin")?;
+            let mut file = File::open(self.verifying_key_path("macro_block_wrapper"))?;
 
             let verifying_key = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -797,7 +885,9 @@ impl NanoZKP {
         NanoZKP::proof_to_file(proof, "macro_block_wrapper", None)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn prove_merger<R: CryptoRng + Rng>(
+        &self,
         rng: &mut R,
         initial_pks: &[G2MNT6],
         initial_header_hash: [u8; 32],
@@ -806,13 +896,11 @@ impl NanoZKP {
         genesis_data: Option<(Proof<MNT6_753>, Vec<u8>)>,
         debug_mode: bool,
     ) -> Result<(), NanoZKPError> {
-        // Load the proving key from file.
-        let mut file = File::open("proving_keys/merger.bin")?;
-
-        let proving_key = ProvingKey::deserialize_unchecked(&mut file)?;
+        // Load the proving key, from file or from the cache.
+        let proving_key = self.proving_key::<MNT4_753>("merger")?;
 
         // Load the verifying key for Macro Block Wrapper from file.
-        let mut file = File::open("verifying_keys/macro_block_wrapper.bin")?;
+        let mut file = File::open(self.verifying_key_path("macro_block_wrapper"))?;
 
         let vk_macro_block_wrapper = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -822,7 +910,7 @@ impl NanoZKP {
         let proof_macro_block_wrapper = Proof::deserialize_unchecked(&mut file)?;
 
         // Load the verifying key for Merger Wrapper from file.
-        let mut file = File::open("verifying_keys/merger_wrapper.bin")?;
+        let mut file = File::open(self.verifying_key_path("merger_wrapper"))?;
 
         let vk_merger_wrapper = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -874,12 +962,12 @@ impl NanoZKP {
         );
 
         // Create the proof.
-        let proof = Groth16::<MNT4_753>::prove(&proving_key, circuit, rng)?;
+        let proof = Groth16::<MNT4_753>::prove(&*proving_key, circuit, rng)?;
 
         // Optionally verify the proof.
         if debug_mode {
             // Load the proving key from file.
-            let mut file = File::open("verifying_keys/merger.bin")?;
+            let mut file = File::open(self.verifying_key_path("merger"))?;
 
             let verifying_key = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -904,7 +992,9 @@ impl NanoZKP {
         NanoZKP::proof_to_file(proof, "merger", None)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn prove_merger_wrapper<R: CryptoRng + Rng>(
+        &self,
         rng: &mut R,
         initial_pks: &[G2MNT6],
         initial_header_hash: [u8; 32],
@@ -913,13 +1003,11 @@ impl NanoZKP {
         genesis_data: Option<(Proof<MNT6_753>, Vec<u8>)>,
         debug_mode: bool,
     ) -> Result<Proof<MNT6_753>, NanoZKPError> {
-        // Load the proving key from file.
-        let mut file = File::open("proving_keys/merger_wrapper.bin")?;
-
-        let proving_key = ProvingKey::deserialize_unchecked(&mut file)?;
+        // Load the proving key, from file or from the cache.
+        let proving_key = self.proving_key::<MNT6_753>("merger_wrapper")?;
 
         // Load the verifying key from file.
-        let mut file = File::open("verifying_keys/merger.bin")?;
+        let mut file = File::open(self.verifying_key_path("merger"))?;
 
         let vk_merger = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -929,7 +1017,7 @@ impl NanoZKP {
         let proof = Proof::deserialize_unchecked(&mut file)?;
 
         // Load the verifying key for Merger Wrapper from file.
-        let mut file = File::open("verifying_keys/merger_wrapper.bin")?;
+        let mut file = File::open(self.verifying_key_path("merger_wrapper"))?;
 
         let vk_merger_wrapper = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -963,12 +1051,12 @@ impl NanoZKP {
         );
 
         // Create the proof.
-        let proof = Groth16::<MNT6_753>::prove(&proving_key, circuit, rng)?;
+        let proof = Groth16::<MNT6_753>::prove(&*proving_key, circuit, rng)?;
 
         // Optionally verify the proof.
         if debug_mode {
             // Load the proving key from file.
-            let mut file = File::open("verifying_keys/merger_wrapper.bin")?;
+            let mut file = File::open(self.verifying_key_path("merger_wrapper"))?;
 
             let verifying_key = VerifyingKey::deserialize_unchecked(&mut file)?;
 
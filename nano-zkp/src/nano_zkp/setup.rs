@@ -8,7 +8,8 @@ use ark_mnt4_753::{Fr as MNT4Fr, G1Projective as G1MNT4, G2Projective as G2MNT4,
 use ark_mnt6_753::{Fr as MNT6Fr, G1Projective as G1MNT6, G2Projective as G2MNT6, MNT6_753};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::UniformRand;
-use rand::{thread_rng, CryptoRng, Rng};
+use rand::rngs::StdRng;
+use rand::{thread_rng, CryptoRng, Rng, SeedableRng};
 
 use nimiq_bls::utils::bytes_to_bits;
 use nimiq_nano_primitives::{MacroBlock, PK_TREE_BREADTH, PK_TREE_DEPTH};
@@ -20,7 +21,7 @@ use crate::circuits::mnt4::{
 use crate::circuits::mnt6::{
     MacroBlockWrapperCircuit, MergerWrapperCircuit, PKTreeNodeCircuit as NodeMNT6,
 };
-use crate::{NanoZKP, NanoZKPError};
+use crate::{KeyManifest, NanoZKP, NanoZKPError, CIRCUITS};
 
 impl NanoZKP {
     /// This function generates the parameters (proving and verifying keys) for the entire nano sync
@@ -28,32 +29,53 @@ impl NanoZKP {
     /// order is absolutely necessary because each circuit needs a verifying key from the circuit "below"
     /// it. Note that the parameter generation can take longer than one hour, even two on some computers.
     pub fn setup() -> Result<(), NanoZKPError> {
-        let rng = &mut thread_rng();
+        NanoZKP::setup_in_dir(Path::new(""), &mut thread_rng())
+    }
+
+    /// This function is the same as `setup`, but it stores the keys in the `proving_keys` and
+    /// `verifying_keys` subdirectories of the given directory, together with a manifest of their
+    /// checksums. The keys can then be loaded with `NanoZKP::load_from_dir`.
+    /// All randomness is drawn from an RNG seeded with the given seed, so the same seed always
+    /// produces the same keys. Anyone who knows the seed can forge proofs, so it must be kept secret
+    /// outside of tests.
+    pub fn setup_to_dir(path: &Path, seed: [u8; 32]) -> Result<KeyManifest, NanoZKPError> {
+        NanoZKP::setup_in_dir(path, &mut StdRng::from_seed(seed))?;
+
+        let manifest = KeyManifest::create(path, &CIRCUITS)?;
+        manifest.write(path)?;
 
-        NanoZKP::setup_pk_tree_leaf(rng, "pk_tree_5")?;
+        Ok(manifest)
+    }
+
+    fn setup_in_dir<R: CryptoRng + Rng>(dir: &Path, rng: &mut R) -> Result<(), NanoZKPError> {
+        NanoZKP::setup_pk_tree_leaf(dir, rng, "pk_tree_5")?;
 
-        NanoZKP::setup_pk_tree_node_mnt6(rng, "pk_tree_5", "pk_tree_4", 4)?;
+        NanoZKP::setup_pk_tree_node_mnt6(dir, rng, "pk_tree_5", "pk_tree_4", 4)?;
 
-        NanoZKP::setup_pk_tree_node_mnt4(rng, "pk_tree_4", "pk_tree_3", 3)?;
+        NanoZKP::setup_pk_tree_node_mnt4(dir, rng, "pk_tree_4", "pk_tree_3", 3)?;
 
-        NanoZKP::setup_pk_tree_node_mnt6(rng, "pk_tree_3", "pk_tree_2", 2)?;
+        NanoZKP::setup_pk_tree_node_mnt6(dir, rng, "pk_tree_3", "pk_tree_2", 2)?;
 
-        NanoZKP::setup_pk_tree_node_mnt4(rng, "pk_tree_2", "pk_tree_1", 1)?;
+        NanoZKP::setup_pk_tree_node_mnt4(dir, rng, "pk_tree_2", "pk_tree_1", 1)?;
 
-        NanoZKP::setup_pk_tree_node_mnt6(rng, "pk_tree_1", "pk_tree_0", 0)?;
+        NanoZKP::setup_pk_tree_node_mnt6(dir, rng, "pk_tree_1", "pk_tree_0", 0)?;
 
-        NanoZKP::setup_macro_block(rng)?;
+        NanoZKP::setup_macro_block(dir, rng)?;
 
-        NanoZKP::setup_macro_block_wrapper(rng)?;
+        NanoZKP::setup_macro_block_wrapper(dir, rng)?;
 
-        NanoZKP::setup_merger(rng)?;
+        NanoZKP::setup_merger(dir, rng)?;
 
-        NanoZKP::setup_merger_wrapper(rng)?;
+        NanoZKP::setup_merger_wrapper(dir, rng)?;
 
         Ok(())
     }
 
-    fn setup_pk_tree_leaf<R: CryptoRng + Rng>(rng: &mut R, name: &str) -> Result<(), NanoZKPError> {
+    fn setup_pk_tree_leaf<R: CryptoRng + Rng>(
+        dir: &Path,
+        rng: &mut R,
+        name: &str,
+    ) -> Result<(), NanoZKPError> {
         // Create dummy inputs.
         let pks = vec![G2MNT6::rand(rng); SLOTS as usize / PK_TREE_BREADTH];
 
@@ -80,17 +102,18 @@ impl NanoZKP {
         let (pk, vk) = Groth16::<MNT4_753>::setup(circuit, rng)?;
 
         // Save keys to file.
-        NanoZKP::keys_to_file(pk, vk, name)
+        NanoZKP::keys_to_file(dir, pk, vk, name)
     }
 
     fn setup_pk_tree_node_mnt6<R: CryptoRng + Rng>(
+        dir: &Path,
         rng: &mut R,
         vk_file: &str,
         name: &str,
         tree_level: usize,
     ) -> Result<(), NanoZKPError> {
         // Load the verifying key from file.
-        let mut file = File::open(format!("verifying_keys/{}.bin", vk_file))?;
+        let mut file = File::open(dir.join(format!("verifying_keys/{}.bin", vk_file)))?;
 
         let vk_child = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -133,17 +156,18 @@ impl NanoZKP {
         let (pk, vk) = Groth16::<MNT6_753>::setup(circuit, rng)?;
 
         // Save keys to file.
-        NanoZKP::keys_to_file(pk, vk, name)
+        NanoZKP::keys_to_file(dir, pk, vk, name)
     }
 
     fn setup_pk_tree_node_mnt4<R: CryptoRng + Rng>(
+        dir: &Path,
         rng: &mut R,
         vk_file: &str,
         name: &str,
         tree_level: usize,
     ) -> Result<(), NanoZKPError> {
         // Load the verifying key from file.
-        let mut file = File::open(format!("verifying_keys/{}.bin", vk_file))?;
+        let mut file = File::open(dir.join(format!("verifying_keys/{}.bin", vk_file)))?;
 
         let vk_child = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -186,12 +210,12 @@ impl NanoZKP {
         let (pk, vk) = Groth16::<MNT4_753>::setup(circuit, rng)?;
 
         // Save keys to file.
-        NanoZKP::keys_to_file(pk, vk, name)
+        NanoZKP::keys_to_file(dir, pk, vk, name)
     }
 
-    fn setup_macro_block<R: CryptoRng + Rng>(rng: &mut R) -> Result<(), NanoZKPError> {
+    fn setup_macro_block<R: CryptoRng + Rng>(dir: &Path, rng: &mut R) -> Result<(), NanoZKPError> {
         // Load the verifying key from file.
-        let mut file = File::open("verifying_keys/pk_tree_0.bin")?;
+        let mut file = File::open(dir.join("verifying_keys/pk_tree_0.bin"))?;
 
         let vk_pk_tree = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -257,12 +281,15 @@ impl NanoZKP {
         let (pk, vk) = Groth16::<MNT4_753>::setup(circuit, rng)?;
 
         // Save keys to file.
-        NanoZKP::keys_to_file(pk, vk, "macro_block")
+        NanoZKP::keys_to_file(dir, pk, vk, "macro_block")
     }
 
-    fn setup_macro_block_wrapper<R: CryptoRng + Rng>(rng: &mut R) -> Result<(), NanoZKPError> {
+    fn setup_macro_block_wrapper<R: CryptoRng + Rng>(
+        dir: &Path,
+        rng: &mut R,
+    ) -> Result<(), NanoZKPError> {
         // Load the verifying key from file.
-        let mut file = File::open("verifying_keys/macro_block.bin")?;
+        let mut file = File::open(dir.join("verifying_keys/macro_block.bin"))?;
 
         let vk_macro_block = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -288,12 +315,12 @@ impl NanoZKP {
         let (pk, vk) = Groth16::<MNT6_753>::setup(circuit, rng)?;
 
         // Save keys to file.
-        NanoZKP::keys_to_file(pk, vk, "macro_block_wrapper")
+        NanoZKP::keys_to_file(dir, pk, vk, "macro_block_wrapper")
     }
 
-    fn setup_merger<R: CryptoRng + Rng>(rng: &mut R) -> Result<(), NanoZKPError> {
+    fn setup_merger<R: CryptoRng + Rng>(dir: &Path, rng: &mut R) -> Result<(), NanoZKPError> {
         // Load the verifying key from file.
-        let mut file = File::open("verifying_keys/macro_block_wrapper.bin")?;
+        let mut file = File::open(dir.join("verifying_keys/macro_block_wrapper.bin"))?;
 
         let vk_macro_block_wrapper = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -346,12 +373,15 @@ impl NanoZKP {
         let (pk, vk) = Groth16::<MNT4_753>::setup(circuit, rng)?;
 
         // Save keys to file.
-        NanoZKP::keys_to_file(pk, vk, "merger")
+        NanoZKP::keys_to_file(dir, pk, vk, "merger")
     }
 
-    fn setup_merger_wrapper<R: CryptoRng + Rng>(rng: &mut R) -> Result<(), NanoZKPError> {
+    fn setup_merger_wrapper<R: CryptoRng + Rng>(
+        dir: &Path,
+        rng: &mut R,
+    ) -> Result<(), NanoZKPError> {
         // Load the verifying key from file.
-        let mut file = File::open("verifying_keys/merger.bin")?;
+        let mut file = File::open(dir.join("verifying_keys/merger.bin"))?;
 
         let vk_merger = VerifyingKey::deserialize_unchecked(&mut file)?;
 
@@ -380,31 +410,34 @@ impl NanoZKP {
         let (pk, vk) = Groth16::<MNT6_753>::setup(circuit, rng)?;
 
         // Save keys to file.
-        NanoZKP::keys_to_file(pk, vk, "merger_wrapper")
+        NanoZKP::keys_to_file(dir, pk, vk, "merger_wrapper")
     }
 
     fn keys_to_file<T: PairingEngine>(
+        dir: &Path,
         pk: ProvingKey<T>,
         vk: VerifyingKey<T>,
         name: &str,
     ) -> Result<(), NanoZKPError> {
         // Save proving key to file.
-        if !Path::new("proving_keys/").is_dir() {
-            DirBuilder::new().create("proving_keys/")?;
+        let proving_keys = dir.join("proving_keys");
+        if !proving_keys.is_dir() {
+            DirBuilder::new().recursive(true).create(&proving_keys)?;
         }
 
-        let mut file = File::create(format!("proving_keys/{}.bin", name))?;
+        let mut file = File::create(proving_keys.join(format!("{}.bin", name)))?;
 
         pk.serialize_unchecked(&mut file)?;
 
         file.sync_all()?;
 
         // Save verifying key to file.
-        if !Path::new("verifying_keys/").is_dir() {
-            DirBuilder::new().create("verifying_keys/")?;
+        let verifying_keys = dir.join("verifying_keys");
+        if !verifying_keys.is_dir() {
+            DirBuilder::new().recursive(true).create(&verifying_keys)?;
         }
 
-        let mut file = File::create(format!("verifying_keys/{}.bin", name))?;
+        let mut file = File::create(verifying_keys.join(format!("{}.bin", name)))?;
 
         vk.serialize_unchecked(&mut file)?;
 
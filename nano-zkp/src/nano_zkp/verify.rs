@@ -1,15 +1,21 @@
-use std::fs::File;
+use std::fs;
+use std::path::Path;
 
 use ark_crypto_primitives::SNARK;
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, Zero};
 use ark_groth16::{Groth16, Proof, VerifyingKey};
-use ark_mnt6_753::{G2Projective as G2MNT6, MNT6_753};
+use ark_mnt6_753::{Fr as MNT6Fr, G1Projective as G1MNT6, G2Projective as G2MNT6, MNT6_753};
+use ark_relations::r1cs::SynthesisError;
 use ark_serialize::CanonicalDeserialize;
+use ark_std::UniformRand;
+use rand::thread_rng;
 
 use nimiq_bls::utils::bytes_to_bits;
 use nimiq_nano_primitives::{state_commitment, vk_commitment};
 
-use crate::utils::pack_inputs;
-use crate::{NanoZKP, NanoZKPError};
+use crate::utils::try_pack_inputs;
+use crate::{ElectionMacroBlockData, NanoProof, NanoZKP, NanoZKPError};
 
 impl NanoZKP {
     /// This function verifies a proof for the Merger Wrapper circuit, which implicitly is a proof for
@@ -37,31 +43,260 @@ impl NanoZKP {
         proof: Proof<MNT6_753>,
     ) -> Result<bool, NanoZKPError> {
         // Load the verifying key from file.
-        let mut file = File::open("verifying_keys/merger_wrapper.bin")?;
+        let vk = NanoZKP::load_verifying_key(&fs::read("verifying_keys/merger_wrapper.bin")?)?;
 
-        let vk = VerifyingKey::deserialize_unchecked(&mut file)?;
+        NanoZKP::verify_from_key(
+            &vk,
+            initial_block_number,
+            initial_header_hash,
+            initial_pks,
+            final_block_number,
+            final_header_hash,
+            final_pks,
+            proof,
+        )
+    }
 
+    /// This function deserializes a verifying key for the Merger Wrapper circuit, as it is written
+    /// to `verifying_keys/merger_wrapper.bin` by the setup. It doesn't need the proving keys nor the
+    /// `prover` feature, so it can be used by nodes that only verify proofs.
+    /// Since the key may come from an untrusted source, all of its points are checked to be on the
+    /// curve and in the right subgroup.
+    pub fn load_verifying_key(bytes: &[u8]) -> Result<VerifyingKey<MNT6_753>, NanoZKPError> {
+        Ok(VerifyingKey::deserialize_uncompressed(bytes)?)
+    }
+
+    /// This function verifies a proof for the Merger Wrapper circuit using the given verifying key,
+    /// instead of loading it from file. Otherwise, it is identical to `verify`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_from_key(
+        // The verifying key for the Merger Wrapper circuit.
+        vk: &VerifyingKey<MNT6_753>,
+        // The block number of the initial block.
+        initial_block_number: u32,
+        // The header hash of the initial block.
+        initial_header_hash: [u8; 32],
+        // The public keys of the validators of the initial block.
+        initial_pks: Vec<G2MNT6>,
+        // The block number of the final block.
+        final_block_number: u32,
+        // The header hash of the final block.
+        final_header_hash: [u8; 32],
+        // The public keys of the validators of the final block.
+        final_pks: Vec<G2MNT6>,
+        // The SNARK proof for this circuit.
+        proof: Proof<MNT6_753>,
+    ) -> Result<bool, NanoZKPError> {
         // Prepare the inputs.
+        let inputs = NanoZKP::public_inputs(
+            vk,
+            initial_block_number,
+            initial_header_hash,
+            initial_pks,
+            final_block_number,
+            final_header_hash,
+            final_pks,
+        )?;
+
+        // Verify proof.
+        let result = Groth16::<MNT6_753>::verify(vk, &inputs, &proof)?;
+
+        // Return result.
+        Ok(result)
+    }
+
+    /// This function calculates the public inputs of the Merger Wrapper circuit for the given
+    /// initial and final states and verifying key. These are the inputs that `verify_batch`
+    /// expects for each proof.
+    /// It fails if the number of inputs doesn't match the verifying key or if the inputs can't be
+    /// packed into field elements.
+    pub fn public_inputs(
+        // The verifying key for the Merger Wrapper circuit.
+        vk: &VerifyingKey<MNT6_753>,
+        // The block number of the initial block.
+        initial_block_number: u32,
+        // The header hash of the initial block.
+        initial_header_hash: [u8; 32],
+        // The public keys of the validators of the initial block.
+        initial_pks: Vec<G2MNT6>,
+        // The block number of the final block.
+        final_block_number: u32,
+        // The header hash of the final block.
+        final_header_hash: [u8; 32],
+        // The public keys of the validators of the final block.
+        final_pks: Vec<G2MNT6>,
+    ) -> Result<Vec<MNT6Fr>, NanoZKPError> {
         let mut inputs = vec![];
 
-        inputs.append(&mut pack_inputs(bytes_to_bits(&state_commitment(
+        inputs.append(&mut try_pack_inputs(bytes_to_bits(&state_commitment(
             initial_block_number,
             initial_header_hash,
             initial_pks,
-        ))));
+        )))?);
 
-        inputs.append(&mut pack_inputs(bytes_to_bits(&state_commitment(
+        inputs.append(&mut try_pack_inputs(bytes_to_bits(&state_commitment(
             final_block_number,
             final_header_hash,
             final_pks,
-        ))));
+        )))?);
 
-        inputs.append(&mut pack_inputs(bytes_to_bits(&vk_commitment(vk.clone()))));
+        inputs.append(&mut try_pack_inputs(bytes_to_bits(&vk_commitment(
+            vk.clone(),
+        )))?);
 
-        // Verify proof.
-        let result = Groth16::<MNT6_753>::verify(&vk, &inputs, &proof)?;
+        if inputs.len() + 1 != vk.gamma_abc_g1.len() {
+            return Err(SynthesisError::MalformedVerifyingKey.into());
+        }
 
-        // Return result.
-        Ok(result)
+        Ok(inputs)
+    }
+
+    /// This function verifies a batch of proofs for the Merger Wrapper circuit, each given together
+    /// with its public inputs (as calculated by `public_inputs`), using the verifying key stored in
+    /// `verifying_keys/merger_wrapper.bin`. See `verify_proof_batch_from_dir` for the details.
+    pub fn verify_proof_batch(
+        proofs: &[(Proof<MNT6_753>, Vec<MNT6Fr>)],
+    ) -> Result<bool, NanoZKPError> {
+        NanoZKP::verify_proof_batch_from_dir(proofs, Path::new("verifying_keys"))
+    }
+
+    /// This function verifies a batch of proofs for the Merger Wrapper circuit, each given together
+    /// with its public inputs (as calculated by `public_inputs`), using the verifying key stored in
+    /// `merger_wrapper.bin` in the given directory. See `verify_batch` for the details.
+    /// It returns false if the batch fails as a whole, without telling which proof is invalid. To
+    /// find it, callers can verify halves of the batch until a single proof is left.
+    pub fn verify_proof_batch_from_dir(
+        proofs: &[(Proof<MNT6_753>, Vec<MNT6Fr>)],
+        verifying_keys_dir: &Path,
+    ) -> Result<bool, NanoZKPError> {
+        // Load the verifying key from file.
+        let vk =
+            NanoZKP::load_verifying_key(&fs::read(verifying_keys_dir.join("merger_wrapper.bin"))?)?;
+
+        NanoZKP::verify_batch(proofs, &vk)
+    }
+
+    /// This function verifies a batch of proofs, each given together with its public inputs, using
+    /// a single pairing check. It returns true only if every proof in the batch is valid.
+    /// Each Groth16 verification equation `e(A, B) = e(alpha, beta) * e(L, gamma) * e(C, delta)`,
+    /// where L is the linear combination of the public inputs, is raised to a random scalar r. The
+    /// resulting equations are multiplied together, so that the right-hand side only needs three
+    /// pairings for the whole batch. Since the scalars are unknown to the prover, an invalid proof
+    /// makes the batch fail except with negligible probability.
+    pub fn verify_batch(
+        proofs: &[(Proof<MNT6_753>, Vec<MNT6Fr>)],
+        vk: &VerifyingKey<MNT6_753>,
+    ) -> Result<bool, NanoZKPError> {
+        if proofs.is_empty() {
+            return Ok(true);
+        }
+
+        let rng = &mut thread_rng();
+
+        let mut pairs: Vec<(
+            <MNT6_753 as PairingEngine>::G1Prepared,
+            <MNT6_753 as PairingEngine>::G2Prepared,
+        )> = Vec::with_capacity(proofs.len() + 3);
+
+        // The sums of the random scalars, of the input combinations and of the C points, each
+        // weighted by the random scalar of its proof.
+        let mut r_sum = MNT6Fr::zero();
+        let mut l_sum = G1MNT6::zero();
+        let mut c_sum = G1MNT6::zero();
+
+        for (proof, inputs) in proofs {
+            if inputs.len() + 1 != vk.gamma_abc_g1.len() {
+                return Err(SynthesisError::MalformedVerifyingKey.into());
+            }
+
+            let r = MNT6Fr::rand(rng);
+
+            // Calculate the linear combination of the public inputs.
+            let mut l = vk.gamma_abc_g1[0].into_projective();
+            for (input, base) in inputs.iter().zip(&vk.gamma_abc_g1[1..]) {
+                l += base.mul(input.into_repr());
+            }
+
+            r_sum += r;
+            l_sum += l.mul(r.into_repr());
+            c_sum += proof.c.mul(r.into_repr());
+
+            pairs.push((
+                proof.a.mul(r.into_repr()).into_affine().into(),
+                proof.b.into(),
+            ));
+        }
+
+        // Move the right-hand side to the left, so that the product must be one.
+        pairs.push((
+            (-vk.alpha_g1.mul(r_sum.into_repr())).into_affine().into(),
+            vk.beta_g2.into(),
+        ));
+        pairs.push(((-l_sum).into_affine().into(), vk.gamma_g2.into()));
+        pairs.push(((-c_sum).into_affine().into(), vk.delta_g2.into()));
+
+        Ok(MNT6_753::product_of_pairings(&pairs).is_one())
+    }
+
+    /// This function verifies the proofs of consecutive epochs, as a syncing light client receives
+    /// them. Each proof is given together with the election block it ends at and proves the
+    /// transition from the previous election block, starting with the given initial block, to it.
+    /// The proofs are first verified as a batch with `verify_batch`. Only if the batch fails, they
+    /// are verified one by one to find the invalid one.
+    /// It fails with `BrokenChain` if the block numbers don't increase and with
+    /// `InvalidEpochProof` for the first invalid proof, both carrying the index of the epoch.
+    pub fn verify_chain(
+        // The verifying key for the Merger Wrapper circuit.
+        vk: &VerifyingKey<MNT6_753>,
+        // The election block the first proof starts at. Most likely, it will be the genesis block.
+        initial_block: &ElectionMacroBlockData,
+        // The election block of each epoch together with the proof for that epoch.
+        proofs: &[(ElectionMacroBlockData, NanoProof)],
+    ) -> Result<bool, NanoZKPError> {
+        // The commitment to the verifying key is the same for all proofs and the final state of
+        // each proof is the initial state of the next one, so each is only calculated once.
+        let vk_inputs = try_pack_inputs(bytes_to_bits(&vk_commitment(vk.clone())))?;
+        let state_inputs = |block: &ElectionMacroBlockData| {
+            try_pack_inputs(bytes_to_bits(&state_commitment(
+                block.block_number,
+                block.header_hash,
+                block.pks.clone(),
+            )))
+        };
+
+        let mut batch = Vec::with_capacity(proofs.len());
+
+        let mut previous_block = initial_block;
+        let mut previous_inputs = state_inputs(initial_block)?;
+
+        for (i, (block, proof)) in proofs.iter().enumerate() {
+            if block.block_number <= previous_block.block_number {
+                return Err(NanoZKPError::BrokenChain(i));
+            }
+
+            let block_inputs = state_inputs(block)?;
+
+            let mut inputs = previous_inputs;
+            inputs.extend_from_slice(&block_inputs);
+            inputs.extend_from_slice(&vk_inputs);
+
+            batch.push((proof.clone(), inputs));
+
+            previous_block = block;
+            previous_inputs = block_inputs;
+        }
+
+        if NanoZKP::verify_batch(&batch, vk)? {
+            return Ok(true);
+        }
+
+        for (i, (proof, inputs)) in batch.iter().enumerate() {
+            if !Groth16::<MNT6_753>::verify(vk, inputs, proof)? {
+                return Err(NanoZKPError::InvalidEpochProof(i));
+            }
+        }
+
+        // If every proof is valid, the batch is valid as well. So this can't be reached.
+        Ok(false)
     }
 }
@@ -1,7 +1,11 @@
+//! This module contains the parameters of the Poseidon hash for the fields of the MNT4-753 and
+//! MNT6-753 curves, and the off-circuit version of the hash.
+
 use ark_ff::{FpParameters, PrimeField};
 use ark_sponge::poseidon::{
-    find_poseidon_ark_and_mds, PoseidonDefaultParametersEntry, PoseidonParameters,
+    find_poseidon_ark_and_mds, PoseidonDefaultParametersEntry, PoseidonParameters, PoseidonSponge,
 };
+use ark_sponge::{Absorb, CryptographicSponge};
 
 pub trait DefaultPoseidonParameters {
     const PARAMS_T3: PoseidonDefaultParametersEntry;
@@ -30,5 +34,20 @@ pub fn create_parameters<F: PrimeField>(
     }
 }
 
-mod mnt4;
-mod mnt6;
+/// Calculates the Poseidon hash of the given field elements, using the parameters for a state
+/// width of 3 (`PARAMS_T3`). The inputs are absorbed into a Poseidon sponge and the hash is the
+/// first field element squeezed out of it.
+/// The result is bit-exact with the in-circuit `PoseidonHashGadget`, so it can be used to
+/// precompute values that are hashed again inside the circuits.
+pub fn hash<F: PrimeField + Absorb + DefaultPoseidonParameters>(inputs: &[F]) -> F {
+    let parameters = create_parameters::<F>(F::PARAMS_T3);
+
+    let mut sponge = PoseidonSponge::new(&parameters);
+
+    sponge.absorb(&inputs.to_vec());
+
+    sponge.squeeze_field_elements::<F>(1)[0]
+}
+
+pub mod mnt4;
+pub mod mnt6;
@@ -10,6 +10,7 @@ use ark_std::ops::MulAssign;
 use ark_std::UniformRand;
 use rand::{rngs::SmallRng, seq::SliceRandom, RngCore, SeedableRng};
 
+use nimiq_bls::utils::BitsError;
 use nimiq_nano_primitives::{pk_tree_construct, state_commitment, MacroBlock};
 use nimiq_primitives::policy::{BLOCKS_PER_EPOCH, SLOTS, TWO_F_PLUS_ONE};
 
@@ -46,6 +47,18 @@ pub fn pack_inputs<F: PrimeField>(mut input: Vec<bool>) -> Vec<F> {
     result
 }
 
+/// Takes a vector of booleans and converts it into a vector of field elements, just like
+/// `pack_inputs`. It is meant for the public inputs that are derived from bytes, like the state
+/// and verifying key commitments.
+/// Fails if the number of bits is not a multiple of 8.
+pub fn try_pack_inputs<F: PrimeField>(input: Vec<bool>) -> Result<Vec<F>, BitsError> {
+    if input.len() % 8 != 0 {
+        return Err(BitsError::NotByteAligned(input.len()));
+    }
+
+    Ok(pack_inputs(input))
+}
+
 /// Takes a vector of Booleans and transforms it into a vector of a vector of Booleans, ready to be
 /// transformed into field elements, which is the way we represent inputs to circuits (as a gadget).
 /// This assumes that both the constraint field and the target field have the same size in bits
@@ -0,0 +1,66 @@
+//! Fixtures that are shared by the prover tests.
+
+use ark_crypto_primitives::{CircuitSpecificSetupSNARK, SNARK};
+use ark_ff::Zero;
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_mnt6_753::{Fr as MNT6Fr, MNT6_753};
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::prelude::AllocVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::rand::{CryptoRng, RngCore};
+use ark_std::UniformRand;
+
+/// A circuit that only has public inputs, so that its keys can be generated quickly. With the
+/// same public inputs as the Merger Wrapper circuit, its proofs can be verified like those of the
+/// Merger Wrapper circuit.
+#[derive(Clone)]
+pub struct InputsCircuit {
+    // Inputs (public)
+    pub inputs: Vec<MNT6Fr>,
+}
+
+impl ConstraintSynthesizer<MNT6Fr> for InputsCircuit {
+    /// This function generates the constraints for the circuit.
+    fn generate_constraints(self, cs: ConstraintSystemRef<MNT6Fr>) -> Result<(), SynthesisError> {
+        for input in self.inputs {
+            FpVar::new_input(cs.clone(), || Ok(input))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates the keys for an `InputsCircuit` with the given number of public inputs.
+pub fn setup<R: RngCore + CryptoRng>(
+    num_inputs: usize,
+    rng: &mut R,
+) -> (ProvingKey<MNT6_753>, VerifyingKey<MNT6_753>) {
+    let circuit = InputsCircuit {
+        inputs: vec![MNT6Fr::zero(); num_inputs],
+    };
+
+    Groth16::<MNT6_753>::setup(circuit, rng).unwrap()
+}
+
+/// Creates the given number of proofs for random inputs, each together with its inputs.
+pub fn prove_random_inputs<R: RngCore + CryptoRng>(
+    pk: &ProvingKey<MNT6_753>,
+    num_proofs: usize,
+    rng: &mut R,
+) -> Vec<(Proof<MNT6_753>, Vec<MNT6Fr>)> {
+    let num_inputs = pk.vk.gamma_abc_g1.len() - 1;
+
+    (0..num_proofs)
+        .map(|_| {
+            let inputs: Vec<MNT6Fr> = (0..num_inputs).map(|_| MNT6Fr::rand(rng)).collect();
+
+            let circuit = InputsCircuit {
+                inputs: inputs.clone(),
+            };
+
+            let proof = Groth16::<MNT6_753>::prove(pk, circuit, rng).unwrap();
+
+            (proof, inputs)
+        })
+        .collect()
+}
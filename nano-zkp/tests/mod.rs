@@ -1,2 +1,4 @@
 #[cfg(feature = "prover")]
+mod common;
+#[cfg(feature = "prover")]
 mod prover;
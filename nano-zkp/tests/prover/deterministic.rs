@@ -0,0 +1,39 @@
+use ark_serialize::CanonicalSerialize;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use nimiq_nano_zkp::utils::create_test_blocks;
+use nimiq_nano_zkp::NanoZKP;
+use nimiq_test_log::test;
+
+fn prove_with_seed(seed: u64) -> Vec<u8> {
+    let (initial_pks, initial_header_hash, final_pks, block, _) = create_test_blocks(0);
+
+    let proof = NanoZKP::default()
+        .prove_with_rng(
+            initial_pks,
+            initial_header_hash,
+            final_pks,
+            block,
+            None,
+            false,
+            false,
+            None,
+            None,
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .unwrap();
+
+    let mut bytes = vec![];
+    proof.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+// This test takes a very long time to finish, so run it only when necessary.
+#[test]
+#[ignore]
+fn seeded_proofs_are_identical() {
+    NanoZKP::setup().unwrap();
+
+    assert_eq!(prove_with_seed(42), prove_with_seed(42));
+}
@@ -0,0 +1,181 @@
+use std::fs::{self, DirBuilder, File};
+use std::path::Path;
+
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_mnt6_753::MNT6_753;
+use ark_serialize::CanonicalSerialize;
+use ark_std::test_rng;
+
+use nimiq_nano_zkp::{KeyManifest, NanoZKP, NanoZKPError, CIRCUITS, MANIFEST_FILE};
+use nimiq_test_log::test;
+
+use crate::common::setup;
+
+/// Stores small keys in place of the keys of every circuit and writes a manifest for them.
+fn create_keys_dir(dir: &Path) -> (ProvingKey<MNT6_753>, VerifyingKey<MNT6_753>) {
+    let (pk, vk) = setup(1, &mut test_rng());
+
+    for (subdir, bytes) in [
+        ("proving_keys", {
+            let mut bytes = vec![];
+            pk.serialize_unchecked(&mut bytes).unwrap();
+            bytes
+        }),
+        ("verifying_keys", {
+            let mut bytes = vec![];
+            vk.serialize_unchecked(&mut bytes).unwrap();
+            bytes
+        }),
+    ] {
+        DirBuilder::new().create(dir.join(subdir)).unwrap();
+        for circuit in CIRCUITS {
+            fs::write(dir.join(subdir).join(format!("{}.bin", circuit)), &bytes).unwrap();
+        }
+    }
+
+    KeyManifest::create(dir, &CIRCUITS)
+        .unwrap()
+        .write(dir)
+        .unwrap();
+
+    (pk, vk)
+}
+
+#[test]
+fn keys_can_be_loaded_from_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let (pk, vk) = create_keys_dir(dir.path());
+
+    let keys = NanoZKP::load_from_dir(dir.path()).unwrap();
+    assert_eq!(keys.dir(), dir.path());
+    assert_eq!(keys.manifest().entries().len(), 2 * CIRCUITS.len());
+    assert_eq!(keys.manifest(), &KeyManifest::read(dir.path()).unwrap());
+
+    assert_eq!(
+        keys.verifying_key::<MNT6_753>("merger_wrapper").unwrap(),
+        vk
+    );
+    assert_eq!(
+        *keys
+            .nano_zkp()
+            .proving_key::<MNT6_753>("merger_wrapper")
+            .unwrap(),
+        pk
+    );
+
+    // The prover loads the verifying keys of the child circuits from the same directory.
+    assert_eq!(
+        keys.nano_zkp().verifying_keys_dir(),
+        dir.path().join("verifying_keys")
+    );
+}
+
+#[test]
+fn corrupted_keys_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    create_keys_dir(dir.path());
+
+    // Flip a single bit of the proving key.
+    let path = dir.path().join("proving_keys/merger_wrapper.bin");
+    let mut bytes = fs::read(&path).unwrap();
+    let middle = bytes.len() / 2;
+    bytes[middle] ^= 1;
+    fs::write(&path, bytes).unwrap();
+
+    match NanoZKP::load_from_dir(dir.path()) {
+        Err(NanoZKPError::ChecksumMismatch(file)) => {
+            assert_eq!(file, "proving_keys/merger_wrapper.bin")
+        }
+        Err(error) => panic!("Unexpected error: {}", error),
+        Ok(_) => panic!("Corrupted keys were accepted"),
+    }
+
+    // A missing file is reported as well.
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        NanoZKP::load_from_dir(dir.path()),
+        Err(NanoZKPError::Filesystem(_))
+    ));
+}
+
+#[test]
+fn invalid_manifests_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    create_keys_dir(dir.path());
+
+    let manifest_path = dir.path().join(MANIFEST_FILE);
+    let manifest = fs::read_to_string(&manifest_path).unwrap();
+
+    // Keys that were generated for different parameters.
+    fs::write(
+        &manifest_path,
+        manifest.replacen(
+            &KeyManifest::parameter_hash("merger_wrapper").to_hex(),
+            &KeyManifest::parameter_hash("merger").to_hex(),
+            1,
+        ),
+    )
+    .unwrap();
+    assert!(matches!(
+        NanoZKP::load_from_dir(dir.path()),
+        Err(NanoZKPError::ParameterMismatch(circuit)) if circuit == "merger_wrapper"
+    ));
+
+    // A manifest that omits the keys of a circuit.
+    let omitted: String = manifest
+        .lines()
+        .filter(|line| !line.starts_with("macro_block "))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    fs::write(&manifest_path, omitted).unwrap();
+    assert!(matches!(
+        NanoZKP::load_from_dir(dir.path()),
+        Err(NanoZKPError::InvalidManifest(_))
+    ));
+
+    // An empty manifest.
+    fs::write(&manifest_path, "").unwrap();
+    assert!(matches!(
+        NanoZKP::load_from_dir(dir.path()),
+        Err(NanoZKPError::InvalidManifest(_))
+    ));
+
+    // Malformed lines.
+    fs::write(
+        &manifest_path,
+        "merger_wrapper 00 proving_keys/merger_wrapper.bin\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        NanoZKP::load_from_dir(dir.path()),
+        Err(NanoZKPError::InvalidManifest(_))
+    ));
+
+    // A missing manifest.
+    fs::remove_file(&manifest_path).unwrap();
+    assert!(matches!(
+        NanoZKP::load_from_dir(dir.path()),
+        Err(NanoZKPError::Filesystem(_))
+    ));
+}
+
+// This test takes a very long time to finish, so run it only when necessary.
+#[test]
+#[ignore]
+fn setup_keys_can_be_loaded_from_dir() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let manifest = NanoZKP::setup_to_dir(dir.path(), [42; 32]).unwrap();
+    assert_eq!(manifest.entries().len(), 2 * CIRCUITS.len());
+
+    let keys = NanoZKP::load_from_dir(dir.path()).unwrap();
+    assert_eq!(keys.manifest(), &manifest);
+
+    let mut file = File::open(dir.path().join("verifying_keys/merger_wrapper.bin")).unwrap();
+    let mut bytes = vec![];
+    std::io::copy(&mut file, &mut bytes).unwrap();
+    assert_eq!(
+        keys.verifying_key::<MNT6_753>("merger_wrapper").unwrap(),
+        NanoZKP::load_verifying_key(&bytes).unwrap()
+    );
+}
@@ -1 +1,6 @@
+mod deterministic;
+mod key_manifest;
+mod progress;
+mod proving_key_cache;
 mod recursive_input;
+mod verifying_key;
@@ -0,0 +1,120 @@
+use std::cell::RefCell;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use nimiq_nano_zkp::utils::create_test_blocks;
+use nimiq_nano_zkp::{NanoZKP, NanoZKPError, ProofStage};
+use nimiq_test_log::test;
+
+// This test takes a very long time to finish, so run it only when necessary.
+#[test]
+#[ignore]
+fn prove_reports_progress() {
+    NanoZKP::setup().unwrap();
+
+    let (initial_pks, initial_header_hash, final_pks, block, _) = create_test_blocks(0);
+
+    let stages = RefCell::new(vec![]);
+
+    NanoZKP::default()
+        .prove(
+            initial_pks,
+            initial_header_hash,
+            final_pks,
+            block,
+            None,
+            false,
+            false,
+            Some(&|stage| stages.borrow_mut().push(stage)),
+            None,
+        )
+        .unwrap();
+
+    let mut expected = vec![ProofStage::WitnessGeneration];
+
+    for level in (0..6).rev() {
+        for position in 0..(1 << level) {
+            expected.push(ProofStage::PkTree { level, position });
+        }
+    }
+
+    expected.extend([
+        ProofStage::MacroBlock,
+        ProofStage::MacroBlockWrapper,
+        ProofStage::Merger,
+        ProofStage::MergerWrapper,
+    ]);
+
+    assert_eq!(stages.into_inner(), expected);
+}
+
+#[test]
+fn prove_returns_when_cancelled_before_the_first_stage() {
+    let (initial_pks, initial_header_hash, final_pks, block, _) = create_test_blocks(0);
+
+    let stages = RefCell::new(vec![]);
+    let cancelled = AtomicBool::new(true);
+
+    // No proving key is loaded, since the proof generation stops before the first stage.
+    let result = NanoZKP::default().prove(
+        initial_pks,
+        initial_header_hash,
+        final_pks,
+        block,
+        None,
+        false,
+        false,
+        Some(&|stage| stages.borrow_mut().push(stage)),
+        Some(&cancelled),
+    );
+
+    assert!(matches!(result, Err(NanoZKPError::Cancelled)));
+    assert!(stages.into_inner().is_empty());
+}
+
+// This test takes a very long time to finish, so run it only when necessary.
+#[test]
+#[ignore]
+fn prove_stops_when_cancelled_before_the_wrapper_stage() {
+    NanoZKP::setup().unwrap();
+
+    let (initial_pks, initial_header_hash, final_pks, block, _) = create_test_blocks(0);
+
+    let stages = RefCell::new(vec![]);
+    let cancelled = AtomicBool::new(false);
+
+    let result = NanoZKP::default().prove(
+        initial_pks,
+        initial_header_hash,
+        final_pks,
+        block,
+        None,
+        false,
+        false,
+        Some(&|stage| {
+            // Cancel once the last stage before the Merger Wrapper is reached.
+            if stage == ProofStage::Merger {
+                cancelled.store(true, Ordering::Release);
+            }
+            stages.borrow_mut().push(stage)
+        }),
+        Some(&cancelled),
+    );
+
+    assert!(matches!(result, Err(NanoZKPError::Cancelled)));
+
+    // Every stage up to the Merger was reported at least once.
+    let stages = stages.into_inner();
+    assert_eq!(stages.first(), Some(&ProofStage::WitnessGeneration));
+    for level in 0..6 {
+        assert!(stages
+            .iter()
+            .any(|stage| matches!(stage, ProofStage::PkTree { level: l, .. } if *l == level)));
+    }
+    assert!(stages.contains(&ProofStage::MacroBlock));
+    assert!(stages.contains(&ProofStage::MacroBlockWrapper));
+    assert_eq!(stages.last(), Some(&ProofStage::Merger));
+
+    // The prover keeps the proofs of the completed stages for resuming, remove them.
+    fs::remove_dir_all("proofs/").unwrap();
+}
@@ -0,0 +1,76 @@
+use std::fs::{self, File};
+
+use ark_mnt6_753::MNT6_753;
+use ark_serialize::CanonicalSerialize;
+use ark_std::test_rng;
+
+use nimiq_nano_zkp::{NanoZKP, NanoZKPError};
+use nimiq_test_log::test;
+
+use crate::common::setup;
+
+#[test]
+fn proving_keys_are_only_loaded_once() {
+    let keys_dir = tempfile::tempdir().unwrap();
+
+    // Create the proving key and store it in the keys directory.
+    let (pk, _) = setup(1, &mut test_rng());
+
+    let path = keys_dir.path().join("input.bin");
+    let mut file = File::create(&path).unwrap();
+    pk.serialize_unchecked(&mut file).unwrap();
+    file.sync_all().unwrap();
+
+    let nano_zkp = NanoZKP::new(keys_dir.path().to_path_buf());
+    assert_eq!(nano_zkp.keys_dir(), keys_dir.path());
+
+    // The first call loads the key from disk.
+    assert_eq!(*nano_zkp.proving_key::<MNT6_753>("input").unwrap(), pk);
+
+    // The second call doesn't touch the disk anymore.
+    fs::remove_file(&path).unwrap();
+    assert_eq!(*nano_zkp.proving_key::<MNT6_753>("input").unwrap(), pk);
+
+    #[cfg(feature = "metrics")]
+    {
+        assert_eq!(nano_zkp.metrics().cache_misses(), 1);
+        assert_eq!(nano_zkp.metrics().cache_hits(), 1);
+    }
+
+    // After clearing the cache, the key must be loaded from disk again.
+    nano_zkp.clear_cache();
+    assert!(matches!(
+        nano_zkp.proving_key::<MNT6_753>("input"),
+        Err(NanoZKPError::Filesystem(_))
+    ));
+}
+
+#[test]
+fn only_the_last_proving_key_is_cached() {
+    let keys_dir = tempfile::tempdir().unwrap();
+
+    // Store the same proving key for two circuits.
+    let (pk, _) = setup(1, &mut test_rng());
+
+    for name in ["first", "second"] {
+        let mut file = File::create(keys_dir.path().join(format!("{}.bin", name))).unwrap();
+        pk.serialize_unchecked(&mut file).unwrap();
+        file.sync_all().unwrap();
+    }
+
+    let nano_zkp = NanoZKP::new(keys_dir.path().to_path_buf());
+
+    // Loading the second key evicts the first one.
+    assert_eq!(*nano_zkp.proving_key::<MNT6_753>("first").unwrap(), pk);
+    assert_eq!(*nano_zkp.proving_key::<MNT6_753>("second").unwrap(), pk);
+
+    fs::remove_file(keys_dir.path().join("first.bin")).unwrap();
+    fs::remove_file(keys_dir.path().join("second.bin")).unwrap();
+
+    // The second key is still cached, but the first one must be loaded from disk again.
+    assert_eq!(*nano_zkp.proving_key::<MNT6_753>("second").unwrap(), pk);
+    assert!(matches!(
+        nano_zkp.proving_key::<MNT6_753>("first"),
+        Err(NanoZKPError::Filesystem(_))
+    ));
+}
@@ -11,8 +11,8 @@ use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisE
 use ark_std::test_rng;
 use rand::RngCore;
 
-use nimiq_bls::utils::bytes_to_bits;
-use nimiq_nano_zkp::utils::{pack_inputs, prepare_inputs, unpack_inputs};
+use nimiq_bls::utils::{bytes_to_bits, BitsError};
+use nimiq_nano_zkp::utils::{pack_inputs, prepare_inputs, try_pack_inputs, unpack_inputs};
 use nimiq_test_log::test;
 
 const NUMBER_OF_BITS: usize = 1024;
@@ -100,6 +100,27 @@ impl ConstraintSynthesizer<MNT6Fr> for OuterCircuit {
     }
 }
 
+#[test]
+fn try_pack_inputs_requires_whole_bytes() {
+    // Create random number generator.
+    let rng = &mut test_rng();
+
+    let mut bytes = [0u8; NUMBER_OF_BITS / 8];
+    rng.fill_bytes(&mut bytes);
+
+    let bits = bytes_to_bits(&bytes);
+
+    assert_eq!(
+        try_pack_inputs::<MNT6Fr>(bits.clone()),
+        Ok(pack_inputs(bits.clone()))
+    );
+
+    assert_eq!(
+        try_pack_inputs::<MNT6Fr>(bits[..NUMBER_OF_BITS - 1].to_vec()),
+        Err(BitsError::NotByteAligned(NUMBER_OF_BITS - 1))
+    );
+}
+
 // This test takes a very long time to finish, so run it only when necessary.
 #[test]
 #[ignore]
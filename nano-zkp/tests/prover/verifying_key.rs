@@ -0,0 +1,220 @@
+use std::fs::{self, File};
+
+use ark_crypto_primitives::SNARK;
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::One;
+use ark_groth16::{Groth16, VerifyingKey};
+use ark_mnt6_753::{Fr as MNT6Fr, G2Projective as G2MNT6, MNT6_753};
+use ark_serialize::CanonicalSerialize;
+use ark_std::{test_rng, UniformRand};
+
+use nimiq_bls::utils::bytes_to_bits;
+use nimiq_nano_primitives::{state_commitment, vk_commitment};
+use nimiq_nano_zkp::utils::pack_inputs;
+use nimiq_nano_zkp::{ElectionMacroBlockData, NanoZKP, NanoZKPError};
+use nimiq_test_log::test;
+
+use crate::common::{prove_random_inputs, setup, InputsCircuit};
+
+fn state_inputs(block_number: u32, header_hash: [u8; 32], pks: Vec<G2MNT6>) -> Vec<MNT6Fr> {
+    pack_inputs(bytes_to_bits(&state_commitment(
+        block_number,
+        header_hash,
+        pks,
+    )))
+}
+
+#[test]
+fn verifying_key_can_be_loaded_from_bytes() {
+    // Create random number generator.
+    let rng = &mut test_rng();
+
+    let initial_pks = vec![G2MNT6::prime_subgroup_generator(); 2];
+    let final_pks = vec![G2MNT6::prime_subgroup_generator().double(); 2];
+
+    // The size of the verifying key commitment doesn't depend on the verifying key.
+    let num_inputs = 2 * state_inputs(1, [0; 32], initial_pks.clone()).len()
+        + pack_inputs::<MNT6Fr>(bytes_to_bits(&vk_commitment(VerifyingKey::default()))).len();
+
+    // Create the keys.
+    let (pk, vk) = setup(num_inputs, rng);
+
+    // Create the proof.
+    let mut inputs = state_inputs(1, [1; 32], initial_pks.clone());
+    inputs.append(&mut state_inputs(2, [2; 32], final_pks.clone()));
+    inputs.append(&mut pack_inputs(bytes_to_bits(&vk_commitment(vk.clone()))));
+
+    let proof = Groth16::<MNT6_753>::prove(&pk, InputsCircuit { inputs }, rng).unwrap();
+
+    // Serialize the verifying key and load it again.
+    let mut bytes = vec![];
+    vk.serialize_unchecked(&mut bytes).unwrap();
+
+    let loaded_vk = NanoZKP::load_verifying_key(&bytes).unwrap();
+    assert_eq!(loaded_vk, vk);
+
+    // Verify the proof with the loaded key.
+    assert!(NanoZKP::verify_from_key(
+        &loaded_vk,
+        1,
+        [1; 32],
+        initial_pks.clone(),
+        2,
+        [2; 32],
+        final_pks.clone(),
+        proof.clone(),
+    )
+    .unwrap());
+
+    // The proof doesn't verify for a different final state.
+    assert!(!NanoZKP::verify_from_key(
+        &loaded_vk,
+        1,
+        [1; 32],
+        initial_pks,
+        3,
+        [2; 32],
+        final_pks,
+        proof,
+    )
+    .unwrap());
+
+    // Truncated keys can't be loaded.
+    assert!(NanoZKP::load_verifying_key(&bytes[..bytes.len() / 2]).is_err());
+
+    // Keys with points that aren't on the curve can't be loaded either.
+    let mut corrupted_bytes = bytes.clone();
+    corrupted_bytes[0] ^= 1;
+    assert!(NanoZKP::load_verifying_key(&corrupted_bytes).is_err());
+}
+
+#[test]
+fn proofs_can_be_verified_in_batch() {
+    // Create random number generator.
+    let rng = &mut test_rng();
+
+    // Create the keys and proofs for random inputs.
+    let (pk, vk) = setup(3, rng);
+    let mut proofs = prove_random_inputs(&pk, 10, rng);
+
+    // All proofs are valid.
+    assert!(NanoZKP::verify_batch(&proofs, &vk).unwrap());
+    assert!(NanoZKP::verify_batch(&[], &vk).unwrap());
+
+    // A single wrong input makes the batch fail.
+    proofs[4].1[1] += MNT6Fr::one();
+    assert!(!NanoZKP::verify_batch(&proofs, &vk).unwrap());
+    assert!(NanoZKP::verify_batch(&proofs[..4], &vk).unwrap());
+
+    // Inputs of the wrong length are rejected.
+    proofs[0].1.pop();
+    assert!(NanoZKP::verify_batch(&proofs[..4], &vk).is_err());
+}
+
+#[test]
+fn chained_proofs_can_be_verified_in_batch() {
+    // Create random number generator.
+    let rng = &mut test_rng();
+
+    let blocks: Vec<ElectionMacroBlockData> = (0..5)
+        .map(|i| ElectionMacroBlockData {
+            block_number: 100 * i,
+            header_hash: [i as u8; 32],
+            pks: vec![G2MNT6::rand(rng); 2],
+        })
+        .collect();
+
+    let num_inputs = 2 * state_inputs(0, [0; 32], blocks[0].pks.clone()).len()
+        + pack_inputs::<MNT6Fr>(bytes_to_bits(&vk_commitment(VerifyingKey::default()))).len();
+
+    // Create the keys.
+    let (pk, vk) = setup(num_inputs, rng);
+
+    // Create a proof for each epoch.
+    let mut proofs = vec![];
+    for epoch in blocks.windows(2) {
+        let mut inputs = state_inputs(
+            epoch[0].block_number,
+            epoch[0].header_hash,
+            epoch[0].pks.clone(),
+        );
+        inputs.append(&mut state_inputs(
+            epoch[1].block_number,
+            epoch[1].header_hash,
+            epoch[1].pks.clone(),
+        ));
+        inputs.append(&mut pack_inputs(bytes_to_bits(&vk_commitment(vk.clone()))));
+
+        let proof = Groth16::<MNT6_753>::prove(&pk, InputsCircuit { inputs }, rng).unwrap();
+
+        proofs.push((epoch[1].clone(), proof));
+    }
+
+    // All proofs are valid.
+    assert!(NanoZKP::verify_chain(&vk, &blocks[0], &proofs).unwrap());
+    assert!(NanoZKP::verify_chain(&vk, &blocks[0], &[]).unwrap());
+
+    // The chain can start at any election block.
+    assert!(NanoZKP::verify_chain(&vk, &blocks[1], &proofs[1..]).unwrap());
+
+    // Block numbers must increase.
+    assert!(matches!(
+        NanoZKP::verify_chain(&vk, &blocks[1], &proofs),
+        Err(NanoZKPError::BrokenChain(0))
+    ));
+
+    // Skipping an epoch breaks the chain of states.
+    let mut skipped = proofs.clone();
+    skipped.remove(1);
+    assert!(matches!(
+        NanoZKP::verify_chain(&vk, &blocks[0], &skipped),
+        Err(NanoZKPError::InvalidEpochProof(1))
+    ));
+
+    // A tampered proof is identified.
+    let mut tampered = proofs.clone();
+    tampered[2].1.a = tampered[2].1.a.into_projective().double().into_affine();
+    assert!(matches!(
+        NanoZKP::verify_chain(&vk, &blocks[0], &tampered),
+        Err(NanoZKPError::InvalidEpochProof(2))
+    ));
+
+    // So is a proof for a different election block.
+    let mut tampered = proofs;
+    tampered[3].0.header_hash = [42; 32];
+    assert!(matches!(
+        NanoZKP::verify_chain(&vk, &blocks[0], &tampered),
+        Err(NanoZKPError::InvalidEpochProof(3))
+    ));
+}
+
+#[test]
+fn proofs_can_be_verified_in_batch_with_the_stored_key() {
+    // Create random number generator.
+    let rng = &mut test_rng();
+
+    // Create the keys and proofs for random inputs.
+    let (pk, vk) = setup(3, rng);
+    let mut proofs = prove_random_inputs(&pk, 5, rng);
+
+    // Without a verifying key, the batch can't be verified.
+    let dir = tempfile::tempdir().unwrap();
+    let verifying_keys_dir = dir.path().join("verifying_keys");
+    assert!(NanoZKP::verify_proof_batch_from_dir(&proofs, &verifying_keys_dir).is_err());
+
+    // Store the verifying key where the setup would.
+    fs::create_dir(&verifying_keys_dir).unwrap();
+    let mut file = File::create(verifying_keys_dir.join("merger_wrapper.bin")).unwrap();
+    vk.serialize_unchecked(&mut file).unwrap();
+    file.sync_all().unwrap();
+
+    // All proofs are valid.
+    assert!(NanoZKP::verify_proof_batch_from_dir(&proofs, &verifying_keys_dir).unwrap());
+
+    // A single bad proof makes the batch fail.
+    proofs[2].0.a = proofs[2].0.a.into_projective().double().into_affine();
+    assert!(!NanoZKP::verify_proof_batch_from_dir(&proofs, &verifying_keys_dir).unwrap());
+
+    // The proofs before it are still valid as a batch.
+    assert!(NanoZKP::verify_proof_batch_from_dir(&proofs[..2], &verifying_keys_dir).unwrap());
+}
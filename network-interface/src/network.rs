@@ -13,13 +13,19 @@ use crate::{
 };
 
 #[derive(Clone, Debug)]
-pub enum NetworkEvent<P> {
-    PeerJoined(P),
+pub enum NetworkEvent<P, A> {
+    PeerJoined {
+        peer_id: P,
+        /// The address of the connection to the peer.
+        address: A,
+        /// The protocol IDs the peer supports, as reported by the peer itself.
+        protocols: Vec<String>,
+    },
     PeerLeft(P),
 }
 
-pub type SubscribeEvents<PeerId> =
-    BoxStream<'static, Result<NetworkEvent<PeerId>, BroadcastStreamRecvError>>;
+pub type SubscribeEvents<PeerId, Address> =
+    BoxStream<'static, Result<NetworkEvent<PeerId, Address>, BroadcastStreamRecvError>>;
 
 pub trait Topic {
     type Item: Serialize + Deserialize + Send + Sync + Debug + 'static;
@@ -51,10 +57,20 @@ pub trait Network: Send + Sync + 'static {
     type RequestId: Copy + Debug + Display + Eq + Send + Sync + 'static;
 
     fn get_peers(&self) -> Vec<Self::PeerId>;
+
+    /// Returns the number of connected peers.
+    fn peer_count(&self) -> usize {
+        self.get_peers().len()
+    }
+
     fn has_peer(&self, peer_id: Self::PeerId) -> bool;
-    async fn disconnect_peer(&self, peer_id: Self::PeerId, close_reason: CloseReason);
+    async fn disconnect_peer(
+        &self,
+        peer_id: Self::PeerId,
+        close_reason: CloseReason,
+    ) -> Result<(), Self::Error>;
 
-    fn subscribe_events(&self) -> SubscribeEvents<Self::PeerId>;
+    fn subscribe_events(&self) -> SubscribeEvents<Self::PeerId, Self::AddressType>;
 
     async fn subscribe<T>(
         &self,
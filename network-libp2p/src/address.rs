@@ -0,0 +1,47 @@
+use libp2p::{core::multiaddr::Protocol, Multiaddr};
+
+use nimiq_peer_address::address::NetAddress;
+
+/// Returns the IP of a multiaddress, if it starts with one.
+pub(crate) fn ip_of(address: &Multiaddr) -> Option<NetAddress> {
+    match address.iter().next() {
+        Some(Protocol::Ip4(ip)) => Some(NetAddress::IPv4(ip)),
+        Some(Protocol::Ip6(ip)) => Some(NetAddress::IPv6(ip)),
+        _ => None,
+    }
+}
+
+/// Checks whether a multiaddress can't be reached from the internet, because its IP is private
+/// (see `NetAddress::is_private`). Addresses without an IP, e.g. DNS names, are assumed to be
+/// reachable.
+pub(crate) fn is_unroutable(address: &Multiaddr) -> bool {
+    ip_of(address).map_or(false, |ip| ip.is_private())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_unroutable;
+    use nimiq_test_log::test;
+
+    #[test]
+    fn it_detects_unroutable_addresses() {
+        for address in [
+            "/ip4/10.0.0.1/tcp/8443/wss",
+            "/ip4/192.168.1.10/tcp/8443/wss",
+            "/ip4/127.0.0.1/tcp/8443/ws",
+            "/ip6/::1/tcp/8443/ws",
+            "/ip6/fe80::1/tcp/8443/ws",
+        ] {
+            assert!(is_unroutable(&address.parse().unwrap()), "{}", address);
+        }
+
+        for address in [
+            "/ip4/8.8.8.8/tcp/8443/wss",
+            "/ip6/2001:db8::1/tcp/8443/wss",
+            "/dns/localhost/tcp/8443/wss",
+            "/memory/1234",
+        ] {
+            assert!(!is_unroutable(&address.parse().unwrap()), "{}", address);
+        }
+    }
+}
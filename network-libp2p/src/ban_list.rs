@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use libp2p::PeerId;
+use tokio::time::{Duration, Instant};
+
+/// The list of banned peers together with the point in time at which their ban expires.
+#[derive(Debug, Default)]
+pub struct BanList {
+    /// The expiry of the ban for each banned peer. Peers without an expiry are banned until the
+    /// node is restarted.
+    banned: HashMap<PeerId, Option<Instant>>,
+}
+
+impl BanList {
+    /// Bans a peer for the given duration, or until restart if no duration is given. Durations
+    /// that reach beyond the representable time are treated like no duration. If the peer is
+    /// already banned, its ban is only extended, never shortened.
+    pub fn ban(&mut self, peer_id: PeerId, duration: Option<Duration>) {
+        let now = Instant::now();
+        self.remove_expired(now);
+
+        let expiry = duration.and_then(|duration| now.checked_add(duration));
+        let current_expiry = self.banned.entry(peer_id).or_insert(expiry);
+        if let (Some(current), Some(new)) = (*current_expiry, expiry) {
+            if current < new {
+                *current_expiry = expiry;
+            }
+        } else {
+            *current_expiry = None;
+        }
+    }
+
+    /// Checks if a peer is currently banned.
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        match self.banned.get(peer_id) {
+            Some(Some(expiry)) => *expiry > Instant::now(),
+            Some(None) => true,
+            None => false,
+        }
+    }
+
+    /// Removes all bans that expired before the given point in time.
+    fn remove_expired(&mut self, now: Instant) {
+        self.banned
+            .retain(|_, expiry| expiry.map_or(true, |expiry| expiry > now));
+    }
+}
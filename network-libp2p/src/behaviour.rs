@@ -11,6 +11,7 @@ use libp2p::{
     },
     identify::{Identify, IdentifyConfig, IdentifyEvent},
     kad::{store::MemoryStore, Kademlia, KademliaEvent},
+    ping::{Ping, PingConfig, PingEvent, PingFailure},
     request_response::{
         ProtocolSupport, RequestResponse, RequestResponseConfig,
         RequestResponseEvent as ReqResEvent,
@@ -28,6 +29,7 @@ use nimiq_macros::store_waker;
 use nimiq_utils::time::OffsetTime;
 
 use crate::{
+    ban_list::BanList,
     connection_pool::{
         behaviour::{ConnectionPoolBehaviour, ConnectionPoolEvent},
         handler::HandlerError as ConnectionPoolError,
@@ -44,12 +46,15 @@ use crate::{
 pub type NimiqNetworkBehaviourError = EitherError<
     EitherError<
         EitherError<
-            EitherError<EitherError<std::io::Error, DiscoveryError>, GossipsubHandlerError>,
-            std::io::Error,
+            EitherError<
+                EitherError<EitherError<std::io::Error, DiscoveryError>, GossipsubHandlerError>,
+                std::io::Error,
+            >,
+            ConnectionPoolError,
         >,
-        ConnectionPoolError,
+        ConnectionHandlerUpgrErr<std::io::Error>,
     >,
-    ConnectionHandlerUpgrErr<std::io::Error>,
+    PingFailure,
 >;
 
 pub type RequestResponseEvent = ReqResEvent<IncomingRequest, OutgoingResponse>;
@@ -60,6 +65,7 @@ pub enum NimiqEvent {
     Discovery(DiscoveryEvent),
     Gossip(GossipsubEvent),
     Identify(IdentifyEvent),
+    Ping(PingEvent),
     Pool(ConnectionPoolEvent),
     RequestResponse(RequestResponseEvent),
 }
@@ -88,6 +94,12 @@ impl From<IdentifyEvent> for NimiqEvent {
     }
 }
 
+impl From<PingEvent> for NimiqEvent {
+    fn from(event: PingEvent) -> Self {
+        Self::Ping(event)
+    }
+}
+
 impl From<ConnectionPoolEvent> for NimiqEvent {
     fn from(event: ConnectionPoolEvent) -> Self {
         Self::Pool(event)
@@ -109,6 +121,7 @@ pub struct NimiqBehaviour {
     pub identify: Identify,
     pub pool: ConnectionPoolBehaviour,
     pub request_response: RequestResponse<MessageCodec>,
+    pub ping: Ping,
 
     #[behaviour(ignore)]
     contacts: Arc<RwLock<PeerContactBook>>,
@@ -124,7 +137,7 @@ pub struct NimiqBehaviour {
 }
 
 impl NimiqBehaviour {
-    pub fn new(config: Config, clock: Arc<OffsetTime>) -> Self {
+    pub fn new(config: Config, clock: Arc<OffsetTime>, ban_list: Arc<RwLock<BanList>>) -> Self {
         let public_key = config.keypair.public();
         let peer_id = public_key.to_peer_id();
 
@@ -163,7 +176,12 @@ impl NimiqBehaviour {
         let identify = Identify::new(identify_config);
 
         // Connection pool behaviour
-        let pool = ConnectionPoolBehaviour::new(Arc::clone(&contacts), config.seeds);
+        let pool = ConnectionPoolBehaviour::new(
+            Arc::clone(&contacts),
+            config.seeds,
+            ban_list,
+            config.limits,
+        );
 
         // Request Response behaviour
         let codec = MessageCodec::default();
@@ -172,6 +190,9 @@ impl NimiqBehaviour {
         let request_response =
             RequestResponse::new(codec, iter::once((protocol, ProtocolSupport::Full)), config);
 
+        // Ping behaviour
+        let ping = Ping::new(PingConfig::default());
+
         Self {
             dht,
             discovery,
@@ -179,6 +200,7 @@ impl NimiqBehaviour {
             identify,
             pool,
             request_response,
+            ping,
             events: VecDeque::new(),
             contacts,
             update_scores,
@@ -210,6 +232,10 @@ impl NimiqBehaviour {
         Poll::Pending
     }
 
+    pub fn contacts(&self) -> Arc<RwLock<PeerContactBook>> {
+        Arc::clone(&self.contacts)
+    }
+
     pub fn add_peer_address(&mut self, peer_id: PeerId, address: Multiaddr) {
         // Add address to the DHT
         self.dht.add_address(&peer_id, address);
@@ -269,6 +295,12 @@ impl NetworkBehaviourEventProcess<ConnectionPoolEvent> for NimiqBehaviour {
     }
 }
 
+impl NetworkBehaviourEventProcess<PingEvent> for NimiqBehaviour {
+    fn inject_event(&mut self, event: PingEvent) {
+        self.emit_event(event);
+    }
+}
+
 impl NetworkBehaviourEventProcess<RequestResponseEvent> for NimiqBehaviour {
     fn inject_event(&mut self, event: RequestResponseEvent) {
         self.emit_event(event);
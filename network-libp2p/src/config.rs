@@ -11,20 +11,77 @@ use std::{
 };
 
 use nimiq_hash::Blake2bHash;
+use nimiq_peer_address::address::seed_list::SeedList;
+use thiserror::Error;
 
 use crate::discovery::{behaviour::DiscoveryConfig, peer_contacts::PeerContact};
 
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum ConfigError {
+    #[error("Connection limit must be greater than zero: {0}")]
+    ZeroConnectionLimit(&'static str),
+    #[error("Subnet mask /{1} is longer than an {0} address")]
+    InvalidSubnetMask(&'static str, u8),
+}
+
+/// Limits on the peers the connection pool accepts connections from.
+#[derive(Clone, Debug)]
+pub struct LimitConfig {
+    /// Maximum number of connected peers.
+    pub peer_count_max: usize,
+    /// Maximum number of connected peers with the same IP.
+    pub peer_count_per_ip_max: usize,
+    /// Maximum number of connected peers in the same subnet.
+    pub peer_count_per_subnet_max: usize,
+    /// Prefix length of IPv4 subnets.
+    pub ipv4_subnet_mask: u8,
+    /// Prefix length of IPv6 subnets.
+    pub ipv6_subnet_mask: u8,
+}
+
+impl Default for LimitConfig {
+    fn default() -> Self {
+        Self {
+            peer_count_max: 4000,
+            peer_count_per_ip_max: 20,
+            peer_count_per_subnet_max: 4,
+            ipv4_subnet_mask: 24,
+            ipv6_subnet_mask: 64,
+        }
+    }
+}
+
 pub struct Config {
     pub keypair: Keypair,
     pub peer_contact: PeerContact,
     pub seeds: Vec<Multiaddr>,
+    /// Seed lists whose seeds are dialed in addition to `seeds`. Lists with a public key are only
+    /// used if their signature is valid.
+    pub seed_lists: Vec<SeedList>,
     pub discovery: DiscoveryConfig,
     pub kademlia: KademliaConfig,
     pub gossipsub: GossipsubConfig,
     pub memory_transport: bool,
+    /// Maximum number of established incoming connections.
+    pub incoming_connection_limit: u32,
+    /// Maximum number of established outgoing connections.
+    pub outgoing_connection_limit: u32,
+    /// Maximum number of simultaneous connections per peer.
+    pub peer_connection_limit: u32,
+    /// Limits on the peers per IP and subnet.
+    pub limits: LimitConfig,
 }
 
 impl Config {
+    /// Default maximum number of established incoming connections.
+    pub const DEFAULT_INCOMING_CONNECTION_LIMIT: u32 = 4800;
+
+    /// Default maximum number of established outgoing connections.
+    pub const DEFAULT_OUTGOING_CONNECTION_LIMIT: u32 = 4800;
+
+    /// Default maximum number of simultaneous connections per peer.
+    pub const DEFAULT_PEER_CONNECTION_LIMIT: u32 = 2;
+
     pub fn new(
         keypair: Keypair,
         peer_contact: PeerContact,
@@ -63,10 +120,93 @@ impl Config {
             keypair,
             peer_contact,
             seeds,
+            seed_lists: vec![],
             discovery: DiscoveryConfig::new(genesis_hash),
             kademlia,
             gossipsub,
             memory_transport,
+            incoming_connection_limit: Self::DEFAULT_INCOMING_CONNECTION_LIMIT,
+            outgoing_connection_limit: Self::DEFAULT_OUTGOING_CONNECTION_LIMIT,
+            peer_connection_limit: Self::DEFAULT_PEER_CONNECTION_LIMIT,
+            limits: LimitConfig::default(),
+        }
+    }
+
+    /// Downloads the seed lists that don't hold any seeds yet, i.e. those that are only known by
+    /// their URL. The lists are downloaded concurrently, each limited by the timeouts of
+    /// `SeedList::load_remote`. A seed list that can't be loaded stays empty and doesn't contribute
+    /// any seeds.
+    #[cfg(feature = "remote-seed-list")]
+    pub async fn load_seed_lists(&mut self) {
+        let loads = self
+            .seed_lists
+            .iter_mut()
+            .filter(|seed_list| seed_list.seeds().is_empty())
+            .map(|seed_list| async move {
+                let result = SeedList::load_remote(
+                    seed_list.url().as_str(),
+                    seed_list.public_key().as_ref(),
+                )
+                .await;
+
+                match result {
+                    Ok(loaded) => *seed_list = loaded,
+                    Err(error) => log::warn!(
+                        seed_list = %seed_list.url(),
+                        %error,
+                        "Failed to load seed list"
+                    ),
+                }
+            });
+
+        futures::future::join_all(loads).await;
+    }
+
+    /// Returns the addresses of the seeds of all seed lists. Seed lists whose signature doesn't
+    /// match their public key are skipped.
+    pub fn seed_list_addresses(&self) -> Vec<Multiaddr> {
+        self.seed_lists
+            .iter()
+            .filter(|seed_list| match seed_list.public_key() {
+                Some(public_key) if !seed_list.validate(public_key) => {
+                    log::warn!(
+                        seed_list = %seed_list.url(),
+                        "Skipping seed list with invalid signature"
+                    );
+                    false
+                }
+                _ => true,
+            })
+            .flat_map(|seed_list| seed_list.multiaddrs())
+            .collect()
+    }
+
+    /// Checks that the configuration is consistent.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.incoming_connection_limit == 0 {
+            return Err(ConfigError::ZeroConnectionLimit("incoming"));
+        }
+        if self.outgoing_connection_limit == 0 {
+            return Err(ConfigError::ZeroConnectionLimit("outgoing"));
+        }
+        if self.peer_connection_limit == 0 {
+            return Err(ConfigError::ZeroConnectionLimit("peer"));
+        }
+        if self.limits.peer_count_per_subnet_max == 0 {
+            return Err(ConfigError::ZeroConnectionLimit("subnet"));
+        }
+        if self.limits.ipv4_subnet_mask > 32 {
+            return Err(ConfigError::InvalidSubnetMask(
+                "IPv4",
+                self.limits.ipv4_subnet_mask,
+            ));
+        }
+        if self.limits.ipv6_subnet_mask > 128 {
+            return Err(ConfigError::InvalidSubnetMask(
+                "IPv6",
+                self.limits.ipv6_subnet_mask,
+            ));
         }
+        Ok(())
     }
 }
@@ -5,10 +5,9 @@ use std::{
     time::{Duration, Instant, SystemTime},
 };
 
-use ip_network::IpNetwork;
 use libp2p::swarm::dial_opts::PeerCondition;
 use libp2p::{
-    core::{connection::ConnectionId, multiaddr::Protocol, ConnectedPoint},
+    core::{connection::ConnectionId, ConnectedPoint},
     swarm::{
         dial_opts::DialOpts, CloseConnection, ConnectionHandler, DialError, IntoConnectionHandler,
         NetworkBehaviour, NetworkBehaviourAction, PollParameters,
@@ -21,14 +20,21 @@ use rand::thread_rng;
 use tokio::time::Interval;
 
 use nimiq_macros::store_waker;
+use nimiq_peer_address::address::{NetAddress, SubnetKey};
 
-use crate::discovery::peer_contacts::{PeerContactBook, Services};
+use crate::{
+    address::ip_of,
+    ban_list::BanList,
+    config::LimitConfig,
+    discovery::peer_contacts::{PeerContactBook, Services},
+};
 
 use super::handler::ConnectionPoolHandler;
 
 #[derive(Clone, Debug)]
 struct ConnectionPoolLimits {
-    ip_count: HashMap<IpNetwork, usize>,
+    ip_count: HashMap<NetAddress, usize>,
+    subnet_count: HashMap<SubnetKey, usize>,
     ipv4_count: usize,
     ipv6_count: usize,
 }
@@ -36,25 +42,17 @@ struct ConnectionPoolLimits {
 #[derive(Clone, Debug)]
 struct ConnectionPoolConfig {
     peer_count_desired: usize,
-    peer_count_max: usize,
-    peer_count_per_ip_max: usize,
-    peer_count_per_subnet_max: usize,
-    ipv4_subnet_mask: u8,
-    ipv6_subnet_mask: u8,
+    limits: LimitConfig,
     dialing_count_max: usize,
     retry_down_after: Duration,
     housekeeping_interval: Duration,
 }
 
-impl Default for ConnectionPoolConfig {
-    fn default() -> Self {
+impl ConnectionPoolConfig {
+    fn new(limits: LimitConfig) -> Self {
         Self {
             peer_count_desired: 12,
-            peer_count_max: 4000,
-            peer_count_per_ip_max: 20,
-            peer_count_per_subnet_max: 20,
-            ipv4_subnet_mask: 24,
-            ipv6_subnet_mask: 96,
+            limits,
             dialing_count_max: 3,
             retry_down_after: Duration::from_secs(60 * 10), // 10 minutes
             housekeeping_interval: Duration::from_secs(60 * 2), // 2 minutes
@@ -162,6 +160,7 @@ type PoolNetworkBehaviourAction =
 pub struct ConnectionPoolBehaviour {
     pub contacts: Arc<RwLock<PeerContactBook>>,
     seeds: Vec<Multiaddr>,
+    ban_list: Arc<RwLock<BanList>>,
 
     peer_ids: ConnectionState<PeerId>,
     addresses: ConnectionState<Multiaddr>,
@@ -172,24 +171,31 @@ pub struct ConnectionPoolBehaviour {
 
     limits: ConnectionPoolLimits,
     config: ConnectionPoolConfig,
-    banned: HashMap<IpNetwork, SystemTime>,
+    banned: HashMap<SubnetKey, SystemTime>,
     waker: Option<Waker>,
     housekeeping_timer: Interval,
 }
 
 impl ConnectionPoolBehaviour {
-    pub fn new(contacts: Arc<RwLock<PeerContactBook>>, seeds: Vec<Multiaddr>) -> Self {
+    pub fn new(
+        contacts: Arc<RwLock<PeerContactBook>>,
+        seeds: Vec<Multiaddr>,
+        ban_list: Arc<RwLock<BanList>>,
+        limits: LimitConfig,
+    ) -> Self {
+        let config = ConnectionPoolConfig::new(limits);
         let limits = ConnectionPoolLimits {
             ip_count: HashMap::new(),
+            subnet_count: HashMap::new(),
             ipv4_count: 0,
             ipv6_count: 0,
         };
-        let config = ConnectionPoolConfig::default();
         let housekeeping_timer = tokio::time::interval(config.housekeeping_interval);
 
         Self {
             contacts,
             seeds,
+            ban_list,
             peer_ids: ConnectionState::new(2, config.retry_down_after),
             addresses: ConnectionState::new(4, config.retry_down_after),
             actions: VecDeque::new(),
@@ -261,13 +267,17 @@ impl ConnectionPoolBehaviour {
         let contacts = self.contacts.read();
         let own_contact = contacts.get_own_contact();
         let own_peer_id = own_contact.peer_id();
+        let ban_list = self.ban_list.read();
 
         // TODO Services
         contacts
             .query(own_contact.protocols(), Services::all()) // TODO Services
             .filter_map(|contact| {
                 let peer_id = contact.peer_id();
-                if peer_id != own_peer_id && self.peer_ids.can_dial(peer_id) {
+                if peer_id != own_peer_id
+                    && self.peer_ids.can_dial(peer_id)
+                    && !ban_list.is_banned(peer_id)
+                {
                     Some(*peer_id)
                 } else {
                     None
@@ -311,32 +321,55 @@ impl ConnectionPoolBehaviour {
         self.peer_ids.housekeeping();
         self.addresses.housekeeping();
 
-        for (ip, time) in self.banned.clone() {
+        for (subnet, time) in self.banned.clone() {
             if time < SystemTime::now() {
-                self.banned.remove(&ip);
+                self.banned.remove(&subnet);
             }
         }
 
         self.maintain_peers();
     }
 
-    pub fn _ban_ip(&mut self, ip: IpNetwork) {
+    /// Bans all IPs in the given subnet for 10 minutes. To ban a single IP, use its subnet with
+    /// the full prefix length.
+    pub fn _ban_subnet(&mut self, subnet: SubnetKey) {
         if self
             .banned
-            .insert(ip, SystemTime::now() + Duration::from_secs(60 * 10)) // 10 minutes
+            .insert(subnet, SystemTime::now() + Duration::from_secs(60 * 10)) // 10 minutes
             .is_none()
         {
-            debug!(%ip, "IP added to banned set of peers");
+            debug!(%subnet, "Subnet added to banned set of peers");
         } else {
-            debug!(%ip, "IP already part of banned set of peers");
+            debug!(%subnet, "Subnet already part of banned set of peers");
         }
     }
 
-    pub fn _unban_ip(&mut self, ip: IpNetwork) {
-        if self.banned.remove(&ip).is_some() {
-            debug!(%ip, "IP removed from banned set of peers");
+    pub fn _unban_subnet(&mut self, subnet: SubnetKey) {
+        if self.banned.remove(&subnet).is_some() {
+            debug!(%subnet, "Subnet removed from banned set of peers");
         } else {
-            debug!(%ip, "IP was not part of banned set of peers");
+            debug!(%subnet, "Subnet was not part of banned set of peers");
+        }
+    }
+
+    fn is_banned(&self, ip: &NetAddress) -> bool {
+        self.banned
+            .keys()
+            .any(|subnet| ip.is_in_subnet(subnet.address(), subnet.prefix_bits()))
+    }
+
+    /// Returns the key of the subnet the given IP is in, according to the configured masks.
+    /// Private IPs (including loopback) have no subnet, since the per-subnet limit would
+    /// otherwise cap the number of peers on a local network.
+    fn subnet_of(&self, ip: &NetAddress) -> Option<SubnetKey> {
+        if ip.is_private() {
+            return None;
+        }
+
+        match ip {
+            NetAddress::IPv4(_) => ip.subnet_key(self.config.limits.ipv4_subnet_mask),
+            NetAddress::IPv6(_) => ip.subnet_key(self.config.limits.ipv6_subnet_mask),
+            _ => None,
         }
     }
 }
@@ -381,6 +414,18 @@ impl NetworkBehaviour for ConnectionPoolBehaviour {
             }
         }
 
+        // Close connections to banned peers, regardless of any other connections to them.
+        if self.ban_list.read().is_banned(peer_id) {
+            debug!(%peer_id, "Peer is banned");
+            self.actions
+                .push_back(NetworkBehaviourAction::CloseConnection {
+                    peer_id: *peer_id,
+                    connection: CloseConnection::One(*connection_id),
+                });
+            self.wake();
+            return;
+        }
+
         // Ignore connection if another connection to this peer already exists.
         // TODO Do we still want to subject it to the IP limit checks?
         if other_established > 0 {
@@ -392,27 +437,19 @@ impl NetworkBehaviour for ConnectionPoolBehaviour {
             return;
         }
 
-        // Get IP from multiaddress if it exists.
-        let ip = match address.iter().next() {
-            Some(Protocol::Ip4(ip)) => {
-                IpNetwork::new_truncate(ip, self.config.ipv4_subnet_mask).ok()
-            }
-            Some(Protocol::Ip6(ip)) => {
-                IpNetwork::new_truncate(ip, self.config.ipv6_subnet_mask).ok()
-            }
-            _ => None,
-        };
+        // Get IP and subnet from multiaddress if it exists.
+        let ip_and_subnet = ip_of(address).map(|ip| (ip, self.subnet_of(&ip)));
 
         // If we have an IP, check connection limits per IP/subnet.
-        if let Some(ip) = ip {
+        if let Some((ip, subnet)) = ip_and_subnet {
             let mut close_connection = false;
 
-            if self.banned.get(&ip).is_some() {
+            if self.is_banned(&ip) {
                 debug!(%ip, "IP is banned");
                 close_connection = true;
             }
 
-            if self.config.peer_count_per_ip_max
+            if self.config.limits.peer_count_per_ip_max
                 < self
                     .limits
                     .ip_count
@@ -424,23 +461,21 @@ impl NetworkBehaviour for ConnectionPoolBehaviour {
                 close_connection = true;
             }
 
-            if ip.is_ipv4()
-                && (self.config.peer_count_per_subnet_max
-                    < self.limits.ipv4_count.saturating_add(1))
-            {
-                debug!("Max peer connections per IPv4 subnet limit reached");
-                close_connection = true;
-            }
-
-            if ip.is_ipv6()
-                && (self.config.peer_count_per_subnet_max
-                    < self.limits.ipv6_count.saturating_add(1))
-            {
-                debug!("Max peer connections per IPv6 subnet limit reached");
-                close_connection = true;
+            if let Some(subnet) = subnet {
+                if self.config.limits.peer_count_per_subnet_max
+                    < self
+                        .limits
+                        .subnet_count
+                        .get(&subnet)
+                        .unwrap_or(&0)
+                        .saturating_add(1)
+                {
+                    debug!(%subnet, "Max peer connections per subnet limit reached");
+                    close_connection = true;
+                }
             }
 
-            if self.config.peer_count_max
+            if self.config.limits.peer_count_max
                 < self
                     .limits
                     .ipv4_count
@@ -461,16 +496,18 @@ impl NetworkBehaviour for ConnectionPoolBehaviour {
                 return;
             }
 
-            // Increment peer counts per IP
+            // Increment peer counts per IP and subnet
             let value = self.limits.ip_count.entry(ip).or_insert(0);
             *value = value.saturating_add(1);
+            if let Some(subnet) = subnet {
+                let value = self.limits.subnet_count.entry(subnet).or_insert(0);
+                *value = value.saturating_add(1);
+            }
             match ip {
-                IpNetwork::V4(..) => {
+                NetAddress::IPv4(..) => {
                     self.limits.ipv4_count = self.limits.ipv4_count.saturating_add(1)
                 }
-                IpNetwork::V6(..) => {
-                    self.limits.ipv6_count = self.limits.ipv6_count.saturating_add(1)
-                }
+                _ => self.limits.ipv6_count = self.limits.ipv6_count.saturating_add(1),
             };
         }
 
@@ -502,26 +539,31 @@ impl NetworkBehaviour for ConnectionPoolBehaviour {
 
         let address = endpoint.get_remote_address();
 
-        let ip = match address.iter().next() {
-            Some(Protocol::Ip4(ip)) => {
-                IpNetwork::new_truncate(ip, self.config.ipv4_subnet_mask).unwrap()
-            }
-            Some(Protocol::Ip6(ip)) => {
-                IpNetwork::new_truncate(ip, self.config.ipv6_subnet_mask).unwrap()
-            }
-            _ => return, // TODO: Review if we need to handle additional protocols
+        let ip = match ip_of(address) {
+            Some(ip) => ip,
+            None => return, // TODO: Review if we need to handle additional protocols
         };
 
-        // Decrement IP counters
+        // Decrement IP and subnet counters
         let value = self.limits.ip_count.entry(ip).or_insert(1);
         *value = value.saturating_sub(1);
         if *self.limits.ip_count.get(&ip).unwrap() == 0 {
             self.limits.ip_count.remove(&ip);
         }
 
+        if let Some(subnet) = self.subnet_of(&ip) {
+            let value = self.limits.subnet_count.entry(subnet).or_insert(1);
+            *value = value.saturating_sub(1);
+            if *self.limits.subnet_count.get(&subnet).unwrap() == 0 {
+                self.limits.subnet_count.remove(&subnet);
+            }
+        }
+
         match ip {
-            IpNetwork::V4(..) => self.limits.ipv4_count = self.limits.ipv4_count.saturating_sub(1),
-            IpNetwork::V6(..) => self.limits.ipv6_count = self.limits.ipv6_count.saturating_sub(1),
+            NetAddress::IPv4(..) => {
+                self.limits.ipv4_count = self.limits.ipv4_count.saturating_sub(1)
+            }
+            _ => self.limits.ipv6_count = self.limits.ipv6_count.saturating_sub(1),
         };
 
         self.addresses.mark_closed(address.clone());
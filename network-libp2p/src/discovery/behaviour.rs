@@ -175,6 +175,9 @@ impl NetworkBehaviour for DiscoveryBehaviour {
         if remaining_established == 0 {
             // There are no more remaining connections to this peer
             self.connected_peers.remove(peer_id);
+            self.peer_contact_book
+                .write()
+                .remove_connected_address(peer_id);
         }
     }
 
@@ -186,12 +189,25 @@ impl NetworkBehaviour for DiscoveryBehaviour {
         _failed_addresses: Option<&Vec<Multiaddr>>,
         other_established: usize,
     ) {
+        self.events
+            .push_back(NetworkBehaviourAction::NotifyHandler {
+                peer_id: *peer_id,
+                handler: NotifyHandler::One(*connection_id),
+                event: HandlerInEvent::RemoteAddress(endpoint.get_remote_address().clone()),
+            });
+
         if other_established == 0 {
             trace!(%peer_id, ?connection_id, ?endpoint, "DiscoveryBehaviour::inject_connection_established:");
 
             // This is the first connection to this peer
             self.connected_peers.insert(*peer_id);
 
+            // Record the address of the connection regardless of its direction, we don't need to
+            // wait for the peer exchange to know how we reach the peer.
+            self.peer_contact_book
+                .write()
+                .set_connected_address(*peer_id, endpoint.get_remote_address().clone());
+
             if endpoint.is_dialer() {
                 self.events
                     .push_back(NetworkBehaviourAction::NotifyHandler {
@@ -23,6 +23,8 @@ use beserial::SerializingError;
 use nimiq_hash::Blake2bHash;
 use nimiq_utils::tagged_signing::TaggedKeypair;
 
+use crate::address::is_unroutable;
+
 use super::{
     behaviour::DiscoveryConfig,
     message_codec::{MessageReader, MessageWriter},
@@ -33,6 +35,8 @@ use super::{
 #[derive(Clone, Debug)]
 pub enum HandlerInEvent {
     ObservedAddress(Multiaddr),
+    /// The address of the other peer for this connection.
+    RemoteAddress(Multiaddr),
 }
 
 #[derive(Clone, Debug)]
@@ -122,6 +126,11 @@ pub struct DiscoveryHandler {
     /// The addresses which we observed for the other peer.
     observed_addresses: Vec<Multiaddr>,
 
+    /// Whether the other peer is connected through a routable address. Private and loopback
+    /// addresses are not advertised to such peers. Until the remote address is known, the peer is
+    /// treated as external, so that private addresses are never leaked.
+    is_external_peer: bool,
+
     /// The challenge nonce we send to this peer.
     challenge_nonce: ChallengeNonce,
 
@@ -165,6 +174,7 @@ impl DiscoveryHandler {
             peer_contact_book,
             _peer_contact: None,
             observed_addresses: vec![],
+            is_external_peer: true,
             challenge_nonce: ChallengeNonce::generate(),
             state: HandlerState::Init,
             services_filter: Services::empty(),
@@ -194,6 +204,7 @@ impl DiscoveryHandler {
 
     /// Get peer contacts from our contact book to send to this peer. The contacts are filtered according to the peer's
     /// protocols and service filters, they are limited to the number of peers specified by the peer.
+    /// External peers don't receive contacts that only have private or loopback addresses.
     fn get_peer_contacts(&self, peer_contact_book: &PeerContactBook) -> Vec<SignedPeerContact> {
         let n = self.peer_list_limit.unwrap() as usize;
 
@@ -201,12 +212,29 @@ impl DiscoveryHandler {
 
         peer_contact_book
             .query(self.protocols_filter, self.services_filter)
+            .filter(|contact| {
+                !self.is_external_peer || contact.addresses().any(|address| !is_unroutable(address))
+            })
             .choose_multiple(&mut rng, n)
             .into_iter()
             .map(|c| c.signed().clone())
             .collect()
     }
 
+    /// Get our own peer contact to send to this peer. For external peers, the private and loopback
+    /// addresses are removed from it, which requires signing it again.
+    fn get_own_contact(&self, peer_contact_book: &PeerContactBook) -> SignedPeerContact {
+        let own_contact = peer_contact_book.get_own_contact();
+
+        if !self.is_external_peer || !own_contact.addresses().any(is_unroutable) {
+            return own_contact.signed().clone();
+        }
+
+        let mut contact = own_contact.contact().clone();
+        contact.addresses.retain(|address| !is_unroutable(address));
+        contact.sign(&self.keypair)
+    }
+
     /// Checks if both inbound and outbound are available and transitions to sending a handshake. This includes waking
     /// waker to continue polling.
     fn check_connected(&mut self) {
@@ -274,6 +302,9 @@ impl ConnectionHandler for DiscoveryHandler {
                 // We only use this during handshake and are not waiting on it, so we don't need to wake anything.
                 self.observed_addresses.push(address);
             }
+            HandlerInEvent::RemoteAddress(address) => {
+                self.is_external_peer = !is_unroutable(&address);
+            }
         }
     }
 
@@ -392,10 +423,7 @@ impl ConnectionHandler for DiscoveryHandler {
                                     self.protocols_filter = protocols;
 
                                     let msg = DiscoveryMessage::HandshakeAck {
-                                        peer_contact: peer_contact_book
-                                            .get_own_contact()
-                                            .signed()
-                                            .clone(),
+                                        peer_contact: self.get_own_contact(&peer_contact_book),
                                         response_signature,
                                         update_interval: Some(
                                             self.config.update_interval.as_secs(),
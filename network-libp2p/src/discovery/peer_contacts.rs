@@ -407,6 +407,10 @@ pub struct PeerContactBook {
     own_peer_contact: PeerContactInfo,
 
     peer_contacts: HashMap<PeerId, Arc<PeerContactInfo>>,
+
+    /// The addresses through which we are connected to peers. Unlike the addresses of a peer
+    /// contact, these are observed by us instead of being signed by the peer.
+    connected_addresses: HashMap<PeerId, Multiaddr>,
 }
 
 impl PeerContactBook {
@@ -415,6 +419,7 @@ impl PeerContactBook {
             config,
             own_peer_contact: own_peer_contact.into(),
             peer_contacts: HashMap::new(),
+            connected_addresses: HashMap::new(),
         }
     }
 
@@ -483,6 +488,21 @@ impl PeerContactBook {
         })
     }
 
+    /// Records the address through which we are connected to a peer.
+    pub fn set_connected_address(&mut self, peer_id: PeerId, address: Multiaddr) {
+        self.connected_addresses.insert(peer_id, address);
+    }
+
+    /// Forgets the address through which we were connected to a peer.
+    pub fn remove_connected_address(&mut self, peer_id: &PeerId) {
+        self.connected_addresses.remove(peer_id);
+    }
+
+    /// Returns the address through which we are connected to a peer, if any.
+    pub fn get_connected_address(&self, peer_id: &PeerId) -> Option<&Multiaddr> {
+        self.connected_addresses.get(peer_id)
+    }
+
     pub fn update_scores(&self, gossipsub: &Gossipsub) {
         let contacts = self.peer_contacts.iter();
 
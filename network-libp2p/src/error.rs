@@ -1,6 +1,7 @@
 use thiserror::Error;
 
 use crate::behaviour::NimiqNetworkBehaviourError;
+use crate::config::ConfigError;
 use crate::dispatch::codecs::typed::MessageCodec;
 
 #[derive(Debug, Error)]
@@ -8,6 +9,12 @@ pub enum NetworkError {
     #[error("Dial error: {0}")]
     Dial(#[from] libp2p::swarm::DialError),
 
+    #[error("Invalid peer URI: {0}")]
+    PeerUri(#[from] nimiq_peer_address::address::peer_uri::PeerUriError),
+
+    #[error("Invalid configuration: {0}")]
+    Config(#[from] ConfigError),
+
     #[error("Failed to send action to swarm task")]
     Send,
 
@@ -41,6 +48,15 @@ pub enum NetworkError {
     #[error("Already unsubscribed to topic: {topic_name}")]
     AlreadyUnsubscribed { topic_name: &'static str },
 
+    #[error("Unknown peer: {0}")]
+    UnknownPeer(libp2p::PeerId),
+
+    #[error("Peer is banned: {0}")]
+    BannedPeer(libp2p::PeerId),
+
+    #[error("Ping to peer failed: {0}")]
+    PingFailed(libp2p::PeerId),
+
     #[error("Unknown Request ID")]
     UnknownRequestId,
 
@@ -1,6 +1,8 @@
 #[macro_use]
 extern crate log;
 
+mod address;
+mod ban_list;
 mod behaviour;
 mod config;
 mod connection_pool;
@@ -18,6 +20,6 @@ pub const DISCOVERY_PROTOCOL: &[u8] = b"/nimiq/discovery/0.0.1";
 
 pub use libp2p::{self, identity::Keypair, swarm::NetworkInfo, Multiaddr, PeerId};
 
-pub use config::Config;
+pub use config::{Config, ConfigError, LimitConfig};
 pub use error::NetworkError;
-pub use network::Network;
+pub use network::{ConnectionDirection, Network, PeerInfo};
@@ -3,15 +3,17 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 #[cfg(feature = "metrics")]
 use std::time::Instant;
 
 use async_trait::async_trait;
 use bytes::{Buf, Bytes};
-use futures::{ready, stream::BoxStream, Stream, StreamExt};
+use futures::{future, ready, stream::BoxStream, Stream, StreamExt};
 use libp2p::core::transport::MemoryTransport;
 use libp2p::{
     core,
+    core::ConnectedPoint,
     core::{muxing::StreamMuxerBox, transport::Boxed},
     dns,
     gossipsub::{
@@ -21,10 +23,11 @@ use libp2p::{
     identify::IdentifyEvent,
     identity::Keypair,
     kad::{
-        store::RecordStore, GetRecordOk, InboundRequest, KademliaEvent, QueryId, QueryResult,
-        Quorum, Record,
+        store::RecordStore, GetRecordError, GetRecordOk, InboundRequest, KademliaEvent, QueryId,
+        QueryResult, Quorum, Record,
     },
     noise,
+    ping::{PingEvent, PingSuccess},
     request_response::{OutboundFailure, RequestId, RequestResponseMessage, ResponseChannel},
     swarm::{dial_opts::DialOpts, ConnectionLimits, NetworkInfo, SwarmBuilder, SwarmEvent},
     tcp, websocket, yamux, Multiaddr, PeerId, Swarm, Transport,
@@ -47,6 +50,7 @@ use nimiq_network_interface::{
         RequestError, RequestType,
     },
 };
+use nimiq_peer_address::address::PeerUri;
 use nimiq_utils::time::OffsetTime;
 use nimiq_validator_network::validator_record::SignedValidatorRecord;
 
@@ -54,15 +58,15 @@ use nimiq_validator_network::validator_record::SignedValidatorRecord;
 use crate::network_metrics::NetworkMetrics;
 use crate::rate_limiting::RateLimit;
 use crate::{
+    address::is_unroutable,
+    ban_list::BanList,
     behaviour::{NimiqBehaviour, NimiqEvent, NimiqNetworkBehaviourError, RequestResponseEvent},
     connection_pool::behaviour::ConnectionPoolEvent,
+    discovery::peer_contacts::{PeerContactBook, Services},
     dispatch::codecs::typed::{IncomingRequest, OutgoingResponse},
     Config, NetworkError,
 };
 
-/// Maximum simultaneous libp2p connections per peer
-const MAX_CONNECTIONS_PER_PEER: u32 = 2;
-
 type NimiqSwarm = Swarm<NimiqBehaviour>;
 
 #[derive(Debug)]
@@ -124,8 +128,18 @@ pub(crate) enum NetworkAction {
         listen_addresses: Vec<Multiaddr>,
     },
     StartConnecting,
-    DisconnectPeer {
+    Disconnect {
+        peer_id: PeerId,
+        output: oneshot::Sender<Result<(), NetworkError>>,
+    },
+    BanPeer {
+        peer_id: PeerId,
+        duration: Option<Duration>,
+        output: oneshot::Sender<Result<(), NetworkError>>,
+    },
+    Ping {
         peer_id: PeerId,
+        output: oneshot::Sender<Result<Duration, NetworkError>>,
     },
 }
 
@@ -163,6 +177,7 @@ struct TaskState {
     requests_initiated: HashMap<RequestId, Instant>,
     response_channels: HashMap<RequestId, ResponseChannel<OutgoingResponse>>,
     receive_requests: HashMap<RequestType, mpsc::Sender<(Bytes, RequestId, PeerId)>>,
+    pings: HashMap<PeerId, Vec<oneshot::Sender<Result<Duration, NetworkError>>>>,
 }
 
 #[derive(Clone, Debug)]
@@ -176,10 +191,71 @@ impl PubsubId<PeerId> for GossipsubId<PeerId> {
         self.propagation_source
     }
 }
+
+/// The direction of the connection to a peer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionDirection {
+    /// The peer dialed us.
+    Inbound,
+    /// We dialed the peer.
+    Outbound,
+}
+
+/// Metadata of the connection to a peer, recorded when the first connection to it is established.
+#[derive(Clone, Debug)]
+struct ConnectionInfo {
+    direction: ConnectionDirection,
+    remote_address: Multiaddr,
+    established: TokioInstant,
+    rtt: Option<Duration>,
+    /// The protocol IDs the peer supports, once it identified itself.
+    protocols: Option<Vec<String>>,
+    /// Whether the connection pool accepted the peer.
+    accepted: bool,
+}
+
+impl ConnectionInfo {
+    fn new(endpoint: &ConnectedPoint) -> Self {
+        let direction = if endpoint.is_dialer() {
+            ConnectionDirection::Outbound
+        } else {
+            ConnectionDirection::Inbound
+        };
+
+        Self {
+            direction,
+            remote_address: endpoint.get_remote_address().clone(),
+            established: TokioInstant::now(),
+            rtt: None,
+            protocols: None,
+            accepted: false,
+        }
+    }
+}
+
+/// Information about a connected peer.
+#[derive(Clone, Debug)]
+pub struct PeerInfo {
+    pub peer_id: PeerId,
+    /// The addresses we know for the peer.
+    pub addresses: Vec<Multiaddr>,
+    /// The direction of the first connection established to the peer.
+    pub direction: ConnectionDirection,
+    /// The services the peer provides, if we know its peer contact.
+    pub services: Option<Services>,
+    /// The time since the first connection to the peer was established.
+    pub connection_age: Duration,
+    /// The last measured round-trip time to the peer, if any.
+    pub rtt: Option<Duration>,
+}
+
 pub struct Network {
     local_peer_id: PeerId,
     connected_peers: Arc<RwLock<HashSet<PeerId>>>,
-    events_tx: broadcast::Sender<NetworkEvent<PeerId>>,
+    connections: Arc<RwLock<HashMap<PeerId, ConnectionInfo>>>,
+    contacts: Arc<RwLock<PeerContactBook>>,
+    ban_list: Arc<RwLock<BanList>>,
+    events_tx: broadcast::Sender<NetworkEvent<PeerId, Multiaddr>>,
     action_tx: mpsc::Sender<NetworkAction>,
     validate_tx: mpsc::UnboundedSender<ValidateMessage<PeerId>>,
     peer_request_limits: Arc<Mutex<HashMap<PeerId, HashMap<u16, RateLimit>>>>,
@@ -197,11 +273,22 @@ impl Network {
     ///             offset by exchanging their wall-time with other peers.
     ///  - `config`: The network configuration, containing key pair, and other behavior-specific configuration.
     ///
-    pub async fn new(clock: Arc<OffsetTime>, config: Config) -> Self {
-        let swarm = Self::new_swarm(clock, config);
+    pub async fn new(clock: Arc<OffsetTime>, mut config: Config) -> Result<Self, NetworkError> {
+        config.validate()?;
+
+        // Add the seeds of the seed lists that could be validated.
+        #[cfg(feature = "remote-seed-list")]
+        config.load_seed_lists().await;
+        let seed_list_addresses = config.seed_list_addresses();
+        config.seeds.extend(seed_list_addresses);
+
+        let ban_list = Arc::new(RwLock::new(BanList::default()));
+        let swarm = Self::new_swarm(clock, config, Arc::clone(&ban_list));
 
         let local_peer_id = *Swarm::local_peer_id(&swarm);
         let connected_peers = Arc::new(RwLock::new(HashSet::new()));
+        let connections = Arc::new(RwLock::new(HashMap::new()));
+        let contacts = swarm.behaviour().contacts();
 
         let (events_tx, _) = broadcast::channel(64);
         let (action_tx, action_rx) = mpsc::channel(64);
@@ -218,22 +305,27 @@ impl Network {
             action_rx,
             validate_rx,
             Arc::clone(&connected_peers),
+            Arc::clone(&connections),
+            Arc::clone(&ban_list),
             Arc::clone(&peer_request_limits),
             Arc::clone(&rate_limits_pending_deletion),
             #[cfg(feature = "metrics")]
             metrics.clone(),
         ));
 
-        Self {
+        Ok(Self {
             local_peer_id,
             connected_peers,
+            connections,
+            contacts,
+            ban_list,
             events_tx,
             action_tx,
             validate_tx,
             peer_request_limits,
             #[cfg(feature = "metrics")]
             metrics,
-        }
+        })
     }
 
     fn new_transport(
@@ -282,21 +374,24 @@ impl Network {
         }
     }
 
-    fn new_swarm(clock: Arc<OffsetTime>, config: Config) -> Swarm<NimiqBehaviour> {
+    fn new_swarm(
+        clock: Arc<OffsetTime>,
+        config: Config,
+        ban_list: Arc<RwLock<BanList>>,
+    ) -> Swarm<NimiqBehaviour> {
         let local_peer_id = PeerId::from(config.keypair.public());
 
         let transport = Self::new_transport(&config.keypair, config.memory_transport).unwrap();
 
-        let behaviour = NimiqBehaviour::new(config, clock);
-
         let limits = ConnectionLimits::default()
             .with_max_pending_incoming(Some(16))
             .with_max_pending_outgoing(Some(16))
-            .with_max_established_incoming(Some(4800))
-            .with_max_established_outgoing(Some(4800))
-            .with_max_established_per_peer(Some(MAX_CONNECTIONS_PER_PEER));
+            .with_max_established_incoming(Some(config.incoming_connection_limit))
+            .with_max_established_outgoing(Some(config.outgoing_connection_limit))
+            .with_max_established_per_peer(Some(config.peer_connection_limit));
+
+        let behaviour = NimiqBehaviour::new(config, clock, ban_list);
 
-        // TODO add proper config
         SwarmBuilder::new(transport, behaviour, local_peer_id)
             .connection_limits(limits)
             .executor(Box::new(|fut| {
@@ -311,10 +406,12 @@ impl Network {
 
     async fn swarm_task(
         mut swarm: NimiqSwarm,
-        events_tx: broadcast::Sender<NetworkEvent<PeerId>>,
+        events_tx: broadcast::Sender<NetworkEvent<PeerId, Multiaddr>>,
         mut action_rx: mpsc::Receiver<NetworkAction>,
         mut validate_rx: mpsc::UnboundedReceiver<ValidateMessage<PeerId>>,
         connected_peers: Arc<RwLock<HashSet<PeerId>>>,
+        connections: Arc<RwLock<HashMap<PeerId, ConnectionInfo>>>,
+        ban_list: Arc<RwLock<BanList>>,
         peer_request_limits: Arc<Mutex<HashMap<PeerId, HashMap<u16, RateLimit>>>>,
         rate_limits_pending_deletion: Arc<Mutex<VecDeque<((PeerId, u16), TokioInstant)>>>,
         #[cfg(feature = "metrics")] metrics: Arc<NetworkMetrics>,
@@ -348,12 +445,12 @@ impl Network {
                     },
                     event = swarm.next() => {
                         if let Some(event) = event {
-                            Self::handle_event(event, &events_tx, &mut swarm, &mut task_state, &connected_peers, Arc::clone(&peer_request_limits), Arc::clone(&rate_limits_pending_deletion), #[cfg( feature = "metrics")] &metrics);
+                            Self::handle_event(event, &events_tx, &mut swarm, &mut task_state, &connected_peers, &connections, &ban_list, Arc::clone(&peer_request_limits), Arc::clone(&rate_limits_pending_deletion), #[cfg( feature = "metrics")] &metrics);
                         }
                     },
                     action = action_rx.recv() => {
                         if let Some(action) = action {
-                            Self::perform_action(action, &mut swarm, &mut task_state);
+                            Self::perform_action(action, &mut swarm, &mut task_state, &connections, &ban_list);
                         }
                         else {
                             // `action_rx.next()` will return `None` if all senders (i.e. the `Network` object) are dropped.
@@ -367,12 +464,43 @@ impl Network {
         .await
     }
 
+    /// Announces a peer as joined once the connection pool accepted it and the peer identified
+    /// itself, whichever happens last.
+    fn join_peer(
+        peer_id: PeerId,
+        connection: &ConnectionInfo,
+        events_tx: &broadcast::Sender<NetworkEvent<PeerId, Multiaddr>>,
+        connected_peers: &RwLock<HashSet<PeerId>>,
+    ) {
+        let protocols = match &connection.protocols {
+            Some(protocols) if connection.accepted => protocols.clone(),
+            _ => return,
+        };
+
+        if !connected_peers.write().insert(peer_id) {
+            error!(%peer_id, "Peer joined but it already exists");
+            return;
+        }
+
+        info!(%peer_id, "Peer joined");
+        let event = NetworkEvent::PeerJoined {
+            peer_id,
+            address: connection.remote_address.clone(),
+            protocols,
+        };
+        if let Err(error) = events_tx.send(event) {
+            error!(%peer_id, %error, "could not send peer joined event to channel");
+        }
+    }
+
     fn handle_event(
         event: SwarmEvent<NimiqEvent, NimiqNetworkBehaviourError>,
-        events_tx: &broadcast::Sender<NetworkEvent<PeerId>>,
+        events_tx: &broadcast::Sender<NetworkEvent<PeerId, Multiaddr>>,
         swarm: &mut NimiqSwarm,
         state: &mut TaskState,
         connected_peers: &RwLock<HashSet<PeerId>>,
+        connections: &RwLock<HashMap<PeerId, ConnectionInfo>>,
+        ban_list: &RwLock<BanList>,
         peer_request_limits: Arc<Mutex<HashMap<PeerId, HashMap<u16, RateLimit>>>>,
         rate_limits_pending_deletion: Arc<Mutex<VecDeque<((PeerId, u16), TokioInstant)>>>,
         #[cfg(feature = "metrics")] metrics: &Arc<NetworkMetrics>,
@@ -391,6 +519,17 @@ impl Network {
                     "Connection established",
                 );
 
+                // Connections to banned peers are closed by the connection pool, don't track them.
+                if ban_list.read().is_banned(&peer_id) {
+                    debug!(%peer_id, "Ignoring connection to banned peer");
+                    return;
+                }
+
+                connections
+                    .write()
+                    .entry(peer_id)
+                    .or_insert_with(|| ConnectionInfo::new(&endpoint));
+
                 if let Some(dial_errors) = concurrent_dial_errors {
                     for (addr, error) in dial_errors {
                         debug!(
@@ -443,9 +582,20 @@ impl Network {
 
                 // Remove Peer
                 if num_established == 0 {
-                    connected_peers.write().remove(&peer_id);
+                    let joined = connected_peers.write().remove(&peer_id);
+                    connections.write().remove(&peer_id);
                     swarm.behaviour_mut().remove_peer(peer_id);
 
+                    // Fail pending pings, we won't get any more measurements for this peer.
+                    for output in state.pings.remove(&peer_id).unwrap_or_default() {
+                        if output
+                            .send(Err(NetworkError::UnknownPeer(peer_id)))
+                            .is_err()
+                        {
+                            error!(%peer_id, error = "receiver hung up", "could not send ping result to channel");
+                        }
+                    }
+
                     // Removes or marks to remove the respective rate limits.
                     // Also cleans up the experied rate limits pending to delete.
                     Self::remove_rate_limits(
@@ -454,8 +604,11 @@ impl Network {
                         peer_id,
                     );
 
-                    if let Err(error) = events_tx.send(NetworkEvent::PeerLeft(peer_id)) {
-                        error!(%error, "could not send peer left event to channel");
+                    // Only peers that joined are announced as leaving.
+                    if joined {
+                        if let Err(error) = events_tx.send(NetworkEvent::PeerLeft(peer_id)) {
+                            error!(%error, "could not send peer left event to channel");
+                        }
                     }
                 }
             }
@@ -604,14 +757,27 @@ impl Network {
                                     }
                                 }
 
-                                if let Err(error) =
-                                    output.try_send((message, message_id, propagation_source))
-                                {
-                                    error!(
-                                        %topic,
-                                        %error,
-                                        "Failed to dispatch gossipsub message",
-                                    )
+                                match output.try_send((message, message_id, propagation_source)) {
+                                    Ok(()) => {}
+                                    Err(mpsc::error::TrySendError::Closed(_)) => {
+                                        // The subscriber dropped its stream, so there is no point in staying subscribed.
+                                        debug!(%topic, "Subscription stream closed, unsubscribing");
+                                        state.gossip_topics.remove(&topic);
+                                        if let Err(error) = swarm
+                                            .behaviour_mut()
+                                            .gossipsub
+                                            .unsubscribe(&IdentTopic::new(topic.as_str()))
+                                        {
+                                            error!(%topic, ?error, "Failed to unsubscribe from topic");
+                                        }
+                                    }
+                                    Err(error) => {
+                                        error!(
+                                            %topic,
+                                            %error,
+                                            "Failed to dispatch gossipsub message",
+                                        )
+                                    }
                                 }
                             } else {
                                 warn!(topic = %message.topic, "unknown topic hash");
@@ -639,8 +805,40 @@ impl Network {
                                     "Received identity",
                                 );
 
+                                // The first identification completes the peer's join.
+                                if let Some(connection) = connections.write().get_mut(&peer_id) {
+                                    if connection.protocols.is_none() {
+                                        connection.protocols = Some(info.protocols.clone());
+                                        Self::join_peer(
+                                            peer_id,
+                                            connection,
+                                            events_tx,
+                                            connected_peers,
+                                        );
+                                    }
+                                }
+
+                                // Private and loopback addresses are only useful if the peer
+                                // is in our own network, i.e. we are connected to it through
+                                // such an address as well.
+                                let is_local_peer = connections
+                                    .read()
+                                    .get(&peer_id)
+                                    .map_or(false, |connection| {
+                                        is_unroutable(&connection.remote_address)
+                                    });
+
                                 // Save identified peer listen addresses
                                 for listen_addr in info.listen_addrs {
+                                    if !is_local_peer && is_unroutable(&listen_addr) {
+                                        trace!(
+                                            %peer_id,
+                                            address = %listen_addr,
+                                            "Skipping unroutable listen address",
+                                        );
+                                        continue;
+                                    }
+
                                     swarm.behaviour_mut().add_peer_address(peer_id, listen_addr);
 
                                     // Bootstrap Kademlia if we're adding our first address
@@ -665,21 +863,75 @@ impl Network {
                                     %error,
                                     "Error while identifying remote peer",
                                 );
+
+                                // Don't hold back a peer that fails to identify itself, it joins
+                                // without any known protocols instead.
+                                if let Some(connection) = connections.write().get_mut(&peer_id) {
+                                    if connection.protocols.is_none() {
+                                        connection.protocols = Some(vec![]);
+                                        Self::join_peer(
+                                            peer_id,
+                                            connection,
+                                            events_tx,
+                                            connected_peers,
+                                        );
+                                    }
+                                }
                             }
                         }
                     }
+                    NimiqEvent::Ping(PingEvent { peer, result }) => match result {
+                        Ok(PingSuccess::Ping { rtt }) => {
+                            trace!(peer_id = %peer, ?rtt, "Measured round-trip time");
+                            if let Some(connection) = connections.write().get_mut(&peer) {
+                                connection.rtt = Some(rtt);
+                            }
+                            for output in state.pings.remove(&peer).unwrap_or_default() {
+                                if output.send(Ok(rtt)).is_err() {
+                                    error!(peer_id = %peer, error = "receiver hung up", "could not send ping result to channel");
+                                }
+                            }
+                        }
+                        Ok(PingSuccess::Pong) => {}
+                        Err(error) => {
+                            debug!(peer_id = %peer, %error, "Ping failed");
+                            for output in state.pings.remove(&peer).unwrap_or_default() {
+                                if output.send(Err(NetworkError::PingFailed(peer))).is_err() {
+                                    error!(peer_id = %peer, error = "receiver hung up", "could not send ping result to channel");
+                                }
+                            }
+                        }
+                    },
                     NimiqEvent::Pool(event) => {
                         match event {
                             ConnectionPoolEvent::PeerJoined { peer_id } => {
-                                if connected_peers.write().insert(peer_id) {
-                                    info!(%peer_id, "Peer joined");
-                                    if let Err(error) =
-                                        events_tx.send(NetworkEvent::PeerJoined(peer_id))
-                                    {
-                                        error!(%peer_id, %error, "could not send peer joined event to channel");
+                                if ban_list.read().is_banned(&peer_id) {
+                                    debug!(%peer_id, "Ignoring banned peer");
+                                } else if let Some(connection) =
+                                    connections.write().get_mut(&peer_id)
+                                {
+                                    connection.accepted = true;
+
+                                    // The address of an outbound connection is one the peer
+                                    // listens on, so we can use it right away instead of
+                                    // waiting for the identify protocol.
+                                    if connection.direction == ConnectionDirection::Outbound {
+                                        swarm.behaviour_mut().add_peer_address(
+                                            peer_id,
+                                            connection.remote_address.clone(),
+                                        );
                                     }
+
+                                    Self::join_peer(
+                                        peer_id,
+                                        connection,
+                                        events_tx,
+                                        connected_peers,
+                                    );
                                 } else {
-                                    error!(%peer_id, "Peer joined but it already exists");
+                                    // The connection was closed before the connection pool's
+                                    // event was processed, so the peer never joins.
+                                    debug!(%peer_id, "Peer disconnected before it joined");
                                 }
                             }
                         };
@@ -857,18 +1109,24 @@ impl Network {
         }
     }
 
-    fn perform_action(action: NetworkAction, swarm: &mut NimiqSwarm, state: &mut TaskState) {
+    fn perform_action(
+        action: NetworkAction,
+        swarm: &mut NimiqSwarm,
+        state: &mut TaskState,
+        connections: &RwLock<HashMap<PeerId, ConnectionInfo>>,
+        ban_list: &RwLock<BanList>,
+    ) {
         // FIXME implement compact debug format for NetworkAction
         // trace!(?action, "performing action");
 
         match action {
             NetworkAction::Dial { peer_id, output } => {
-                if output
-                    .send(
-                        Swarm::dial(swarm, DialOpts::peer_id(peer_id).build()).map_err(Into::into),
-                    )
-                    .is_err()
-                {
+                let result = if ban_list.read().is_banned(&peer_id) {
+                    Err(NetworkError::BannedPeer(peer_id))
+                } else {
+                    Swarm::dial(swarm, DialOpts::peer_id(peer_id).build()).map_err(Into::into)
+                };
+                if output.send(result).is_err() {
                     error!(%peer_id, error = "receiver hung up", "could not send dial to channel");
                 }
             }
@@ -1111,9 +1369,55 @@ impl Network {
             NetworkAction::StartConnecting => {
                 swarm.behaviour_mut().pool.start_connecting();
             }
-            NetworkAction::DisconnectPeer { peer_id } => {
-                if swarm.disconnect_peer_id(peer_id).is_err() {
+            NetworkAction::Disconnect { peer_id, output } => {
+                let result = swarm.disconnect_peer_id(peer_id).map_err(|_| {
                     warn!(%peer_id, "Peer already closed");
+                    NetworkError::UnknownPeer(peer_id)
+                });
+                if output.send(result).is_err() {
+                    error!(%peer_id, error = "receiver hung up", "could not send disconnect result to channel");
+                }
+            }
+            NetworkAction::BanPeer {
+                peer_id,
+                duration,
+                output,
+            } => {
+                ban_list.write().ban(peer_id, duration);
+                info!(%peer_id, ?duration, "Banned peer");
+
+                // Close any existing connection to the banned peer.
+                if swarm.is_connected(&peer_id) && swarm.disconnect_peer_id(peer_id).is_err() {
+                    warn!(%peer_id, "Peer already closed");
+                }
+
+                if output.send(Ok(())).is_err() {
+                    error!(%peer_id, error = "receiver hung up", "could not send ban result to channel");
+                }
+            }
+            NetworkAction::Ping { peer_id, output } => {
+                if !swarm.is_connected(&peer_id) {
+                    if output
+                        .send(Err(NetworkError::UnknownPeer(peer_id)))
+                        .is_err()
+                    {
+                        error!(%peer_id, error = "receiver hung up", "could not send ping result to channel");
+                    }
+                    return;
+                }
+
+                // Answer with the last measurement if there is one, otherwise wait for the next one.
+                let rtt = connections
+                    .read()
+                    .get(&peer_id)
+                    .and_then(|connection| connection.rtt);
+                match rtt {
+                    Some(rtt) => {
+                        if output.send(Ok(rtt)).is_err() {
+                            error!(%peer_id, error = "receiver hung up", "could not send ping result to channel");
+                        }
+                    }
+                    None => state.pings.entry(peer_id).or_default().push(output),
                 }
             }
         }
@@ -1140,6 +1444,11 @@ impl Network {
         }
     }
 
+    /// Dials the given peer URI. Only Ws and Wss URIs can be dialed, see `PeerUri::to_multiaddr`.
+    pub async fn dial_peer_uri(&self, uri: &PeerUri) -> Result<(), NetworkError> {
+        self.dial_address(uri.to_multiaddr()?).await
+    }
+
     pub async fn start_connecting(&self) {
         if let Err(error) = self
             .action_tx
@@ -1337,13 +1646,90 @@ impl Network {
         }
     }
 
-    pub fn peer_count(&self) -> usize {
-        self.connected_peers.read().len()
+    /// Bans a peer for the given duration, or until restart if no duration is given. Any existing
+    /// connection to the peer is closed and new connections are rejected until the ban expires.
+    pub async fn ban_peer(
+        &self,
+        peer_id: PeerId,
+        duration: Option<Duration>,
+    ) -> Result<(), NetworkError> {
+        let (output_tx, output_rx) = oneshot::channel();
+        self.action_tx
+            .clone()
+            .send(NetworkAction::BanPeer {
+                peer_id,
+                duration,
+                output: output_tx,
+            })
+            .await?;
+        output_rx.await?
+    }
+
+    /// Returns the round-trip time to a connected peer as measured by the ping protocol. If no
+    /// measurement is available yet, this waits for the next one.
+    pub async fn ping(&self, peer_id: PeerId) -> Result<Duration, NetworkError> {
+        let (output_tx, output_rx) = oneshot::channel();
+        self.action_tx
+            .clone()
+            .send(NetworkAction::Ping {
+                peer_id,
+                output: output_tx,
+            })
+            .await?;
+        output_rx.await?
+    }
+
+    /// Checks if a peer is currently banned.
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.ban_list.read().is_banned(peer_id)
+    }
+
+    /// Returns information about a connected peer, or `None` if we are not connected to it.
+    pub fn get_peer_info(&self, peer_id: &PeerId) -> Option<PeerInfo> {
+        let connection = self.connections.read().get(peer_id)?.clone();
+        let contact = self.contacts.read().get(peer_id);
+
+        let mut addresses: Vec<Multiaddr> = contact
+            .as_ref()
+            .map(|contact| contact.addresses().cloned().collect())
+            .unwrap_or_default();
+        if !addresses.contains(&connection.remote_address) {
+            addresses.push(connection.remote_address);
+        }
+
+        Some(PeerInfo {
+            peer_id: *peer_id,
+            addresses,
+            direction: connection.direction,
+            services: contact.map(|contact| contact.services()),
+            connection_age: connection.established.elapsed(),
+            rtt: connection.rtt,
+        })
+    }
+
+    /// Looks up multiple records in the DHT concurrently. The results are returned in the same
+    /// order as the given keys. Records that couldn't be found are returned as `None`, while any
+    /// other error fails the whole lookup.
+    pub async fn dht_get_many<K, V>(&self, keys: &[K]) -> Result<Vec<Option<V>>, NetworkError>
+    where
+        K: AsRef<[u8]> + Send + Sync,
+        V: Deserialize + Send + Sync,
+    {
+        future::join_all(keys.iter().map(|key| self.dht_get(key)))
+            .await
+            .into_iter()
+            .map(|result| match result {
+                Err(NetworkError::DhtGetRecord(GetRecordError::NotFound { .. })) => Ok(None),
+                result => result,
+            })
+            .collect()
     }
 
     pub async fn disconnect(&self) {
         for peer_id in self.get_peers() {
-            self.disconnect_peer(peer_id, CloseReason::Other).await;
+            if let Err(error) = self.disconnect_peer(peer_id, CloseReason::Other).await {
+                debug!(%peer_id, %error, "could not disconnect peer");
+            }
         }
     }
 
@@ -1508,21 +1894,31 @@ impl NetworkInterface for Network {
         self.connected_peers.read().iter().copied().collect()
     }
 
+    fn peer_count(&self) -> usize {
+        self.connected_peers.read().len()
+    }
+
     fn has_peer(&self, peer_id: PeerId) -> bool {
         self.connected_peers.read().contains(&peer_id)
     }
 
-    async fn disconnect_peer(&self, peer_id: PeerId, _close_reason: CloseReason) {
-        if let Err(error) = self
-            .action_tx
-            .send(NetworkAction::DisconnectPeer { peer_id })
-            .await
-        {
-            error!(%peer_id, %error, "could not send disconnect action to channel");
-        }
+    async fn disconnect_peer(
+        &self,
+        peer_id: PeerId,
+        _close_reason: CloseReason,
+    ) -> Result<(), NetworkError> {
+        let (output_tx, output_rx) = oneshot::channel();
+        self.action_tx
+            .clone()
+            .send(NetworkAction::Disconnect {
+                peer_id,
+                output: output_tx,
+            })
+            .await?;
+        output_rx.await?
     }
 
-    fn subscribe_events(&self) -> SubscribeEvents<PeerId> {
+    fn subscribe_events(&self) -> SubscribeEvents<PeerId, Multiaddr> {
         Box::pin(BroadcastStream::new(self.events_tx.subscribe()))
     }
 
@@ -1546,15 +1942,40 @@ impl NetworkInterface for Network {
 
         // Receive the mpsc::Receiver, but propagate errors first.
         let subscribe_rx = ReceiverStream::new(rx.await??);
+        let validate_tx = self.validate_tx.clone();
+
+        Ok(Box::pin(subscribe_rx.filter_map(
+            move |(msg, msg_id, source)| {
+                let id = GossipsubId {
+                    message_id: msg_id,
+                    propagation_source: source,
+                };
 
-        Ok(Box::pin(subscribe_rx.map(|(msg, msg_id, source)| {
-            let item: <T as Topic>::Item = Deserialize::deserialize_from_vec(&msg.data).unwrap();
-            let id = GossipsubId {
-                message_id: msg_id,
-                propagation_source: source,
-            };
-            (item, id)
-        })))
+                let item = match <T as Topic>::Item::deserialize_from_vec(&msg.data) {
+                    Ok(item) => Some((item, id)),
+                    Err(error) => {
+                        debug!(
+                            topic_name = <T as Topic>::NAME,
+                            %error,
+                            "Rejecting malformed gossipsub message",
+                        );
+                        // Malformed messages must not be propagated any further.
+                        if <T as Topic>::VALIDATE
+                            && validate_tx
+                                .send(ValidateMessage::new::<T>(id, MsgAcceptance::Reject))
+                                .is_err()
+                        {
+                            error!(
+                                error = "receiver hung up",
+                                "could not send message validation result to channel",
+                            );
+                        }
+                        None
+                    }
+                };
+                future::ready(item)
+            },
+        )))
     }
 
     async fn unsubscribe<T>(&self) -> Result<(), Self::Error>
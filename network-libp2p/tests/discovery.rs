@@ -230,6 +230,43 @@ pub async fn test_dialing_peer_from_contacts() {
     }
 }
 
+#[test(tokio::test)]
+pub async fn test_connected_addresses_are_recorded() {
+    let mut node1 = TestNode::new();
+    let node2 = TestNode::new();
+
+    let peer_contact_book1 = Arc::clone(&node1.peer_contact_book);
+    let peer_contact_book2 = Arc::clone(&node2.peer_contact_book);
+    let peer1_id = node1.peer_id;
+    let peer2_id = node2.peer_id;
+
+    node1.dial(node2.address.clone());
+
+    // Run both swarms until the peer exchange is established on both sides.
+    let mut established = 0;
+    futures::stream::select(node1.swarm, node2.swarm)
+        .take_while(move |e| {
+            if let SwarmEvent::Behaviour(DiscoveryEvent::Established { .. }) = e {
+                established += 1;
+            }
+
+            async move { established < 2 }
+        })
+        .for_each(|_| async {})
+        .await;
+
+    // The dialer knows the address it dialed.
+    assert_eq!(
+        peer_contact_book1.read().get_connected_address(&peer2_id),
+        Some(&node2.address)
+    );
+    // The listener knows the address of the inbound connection.
+    assert!(peer_contact_book2
+        .read()
+        .get_connected_address(&peer1_id)
+        .is_some());
+}
+
 #[test]
 fn test_housekeeping() {
     let mut config = PeerContactBookConfig::default();
@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::Duration};
+use std::{str::FromStr, sync::Arc, time::Duration};
 
 use futures::{Stream, StreamExt};
 use libp2p::{
@@ -12,6 +12,7 @@ use rand::{thread_rng, Rng};
 use tokio::time::timeout;
 
 use beserial::{Deserialize, Serialize};
+use nimiq_keys::{KeyPair, SecureGenerate};
 use nimiq_network_interface::network::{MsgAcceptance, NetworkEvent, Topic};
 use nimiq_network_interface::{network::Network as NetworkInterface, peer::CloseReason};
 use nimiq_network_libp2p::{
@@ -19,8 +20,9 @@ use nimiq_network_libp2p::{
         behaviour::DiscoveryConfig,
         peer_contacts::{PeerContact, Protocols, Services},
     },
-    Config, Network,
+    Config, ConfigError, LimitConfig, Network, NetworkError, DISCOVERY_PROTOCOL,
 };
+use nimiq_peer_address::address::{peer_uri::PeerUriError, seed_list::SeedList, PeerUri};
 use nimiq_test_log::test;
 use nimiq_utils::time::OffsetTime;
 
@@ -44,6 +46,7 @@ fn network_config(address: Multiaddr) -> Config {
         keypair,
         peer_contact,
         seeds: Vec::new(),
+        seed_lists: Vec::new(),
         discovery: DiscoveryConfig {
             genesis_hash: Default::default(),
             update_interval: Duration::from_secs(60),
@@ -58,18 +61,33 @@ fn network_config(address: Multiaddr) -> Config {
         kademlia: Default::default(),
         gossipsub,
         memory_transport: true,
+        incoming_connection_limit: Config::DEFAULT_INCOMING_CONNECTION_LIMIT,
+        outgoing_connection_limit: Config::DEFAULT_OUTGOING_CONNECTION_LIMIT,
+        peer_connection_limit: Config::DEFAULT_PEER_CONNECTION_LIMIT,
+        limits: LimitConfig::default(),
     }
 }
 
-fn assert_peer_joined(event: &NetworkEvent<PeerId>, wanted_peer_id: &PeerId) {
-    if let NetworkEvent::PeerJoined(peer_id) = event {
+fn assert_peer_joined(event: &NetworkEvent<PeerId, Multiaddr>, wanted_peer_id: &PeerId) {
+    if let NetworkEvent::PeerJoined {
+        peer_id,
+        address,
+        protocols,
+    } = event
+    {
         assert_eq!(peer_id, wanted_peer_id);
+        assert!(address.iter().next().is_some());
+        // The protocols are the ones the peer reported about itself.
+        let discovery_protocol = std::str::from_utf8(DISCOVERY_PROTOCOL).unwrap();
+        assert!(protocols
+            .iter()
+            .any(|protocol| protocol == discovery_protocol));
     } else {
         panic!("Event is not a NetworkEvent::PeerJoined: {:?}", event);
     }
 }
 
-fn assert_peer_left(event: &NetworkEvent<PeerId>, wanted_peer_id: &PeerId) {
+fn assert_peer_left(event: &NetworkEvent<PeerId, Multiaddr>, wanted_peer_id: &PeerId) {
     if let NetworkEvent::PeerLeft(peer_id) = event {
         assert_eq!(peer_id, wanted_peer_id);
     } else {
@@ -96,7 +114,9 @@ impl TestNetwork {
         self.next_address += 1;
 
         let clock = Arc::new(OffsetTime::new());
-        let net = Network::new(clock, network_config(address.clone())).await;
+        let net = Network::new(clock, network_config(address.clone()))
+            .await
+            .unwrap();
         net.listen_on(vec![address.clone()]).await;
 
         log::debug!(address = %address, peer_id = %net.get_local_peer_id(), "Creating node");
@@ -123,10 +143,14 @@ async fn create_connected_networks() -> (Network, Network) {
     let addr1 = multiaddr![Memory(thread_rng().gen::<u64>())];
     let addr2 = multiaddr![Memory(thread_rng().gen::<u64>())];
 
-    let net1 = Network::new(Arc::new(OffsetTime::new()), network_config(addr1.clone())).await;
+    let net1 = Network::new(Arc::new(OffsetTime::new()), network_config(addr1.clone()))
+        .await
+        .unwrap();
     net1.listen_on(vec![addr1.clone()]).await;
 
-    let net2 = Network::new(Arc::new(OffsetTime::new()), network_config(addr2.clone())).await;
+    let net2 = Network::new(Arc::new(OffsetTime::new()), network_config(addr2.clone()))
+        .await
+        .unwrap();
     net2.listen_on(vec![addr2.clone()]).await;
 
     log::debug!(address = %addr1, peer_id = %net1.get_local_peer_id(), "Network 1");
@@ -136,7 +160,7 @@ async fn create_connected_networks() -> (Network, Network) {
     let mut events2 = net2.subscribe_events();
 
     log::debug!("Dialing peer 1 from peer 2...");
-    net2.dial_address(addr1).await.unwrap();
+    net2.dial_address(addr1.clone()).await.unwrap();
 
     log::debug!("Waiting for join events");
 
@@ -148,6 +172,11 @@ async fn create_connected_networks() -> (Network, Network) {
     log::trace!(event = ?event2, "Event 2");
     assert_peer_joined(&event2, &net1.get_local_peer_id());
 
+    // Peer 2 dialed peer 1, so it joined with the address we dialed.
+    if let NetworkEvent::PeerJoined { address, .. } = event2 {
+        assert_eq!(address, addr1);
+    }
+
     (net1, net2)
 }
 
@@ -156,10 +185,14 @@ async fn create_double_connected_networks() -> (Network, Network) {
     let addr1 = multiaddr![Memory(thread_rng().gen::<u64>())];
     let addr2 = multiaddr![Memory(thread_rng().gen::<u64>())];
 
-    let net1 = Network::new(Arc::new(OffsetTime::new()), network_config(addr1.clone())).await;
+    let net1 = Network::new(Arc::new(OffsetTime::new()), network_config(addr1.clone()))
+        .await
+        .unwrap();
     net1.listen_on(vec![addr1.clone()]).await;
 
-    let net2 = Network::new(Arc::new(OffsetTime::new()), network_config(addr2.clone())).await;
+    let net2 = Network::new(Arc::new(OffsetTime::new()), network_config(addr2.clone()))
+        .await
+        .unwrap();
     net2.listen_on(vec![addr2.clone()]).await;
 
     log::debug!(address = %addr1, peer_id = %net1.get_local_peer_id(), "Network 1");
@@ -198,7 +231,9 @@ async fn create_network_with_n_peers(n_peers: usize) -> Vec<Network> {
 
         addresses.push(addr.clone());
 
-        let network = Network::new(Arc::new(OffsetTime::new()), network_config(addr.clone())).await;
+        let network = Network::new(Arc::new(OffsetTime::new()), network_config(addr.clone()))
+            .await
+            .unwrap();
         network.listen_on(vec![addr.clone()]).await;
 
         log::debug!(address = %addr, peer_id = %network.get_local_peer_id(), "Network {}", peer);
@@ -222,7 +257,7 @@ async fn create_network_with_n_peers(n_peers: usize) -> Vec<Network> {
         .take(n_peers * (n_peers - 1))
         .for_each(|event| async move {
             match event {
-                Ok(NetworkEvent::PeerJoined(_)) => {}
+                Ok(NetworkEvent::PeerJoined { .. }) => {}
                 _ => panic!("Unexpected NetworkEvent: {:?}", event),
             };
         });
@@ -274,7 +309,8 @@ async fn create_network_with_n_peers(n_peers: usize) -> Vec<Network> {
         assert!(network1.has_peer(*peer_id2));
         network1
             .disconnect_peer(*peer_id2, CloseReason::Other)
-            .await;
+            .await
+            .unwrap();
 
         // Assert the peer has left both networks
         let close_event1 = events1.next().await.unwrap().unwrap();
@@ -359,7 +395,8 @@ async fn connections_are_properly_closed_events() {
     let mut events2 = net2.subscribe_events();
 
     net2.disconnect_peer(*net1.local_peer_id(), CloseReason::Other)
-        .await;
+        .await
+        .unwrap();
     log::debug!("Closed peer");
 
     let event1 = events1.next().await.unwrap().unwrap();
@@ -382,7 +419,8 @@ async fn connections_are_properly_closed_peers() {
     let net1_peer_id = *net1.local_peer_id();
     drop(net1);
 
-    net2.disconnect_peer(net1_peer_id, CloseReason::Other).await;
+    // The connection might already be gone after dropping `net1`.
+    let _ = net2.disconnect_peer(net1_peer_id, CloseReason::Other).await;
     log::debug!("Closed peer");
 
     let event2 = events2.next().await.unwrap().unwrap();
@@ -392,6 +430,205 @@ async fn connections_are_properly_closed_peers() {
     assert_eq!(net2.get_peers(), &[]);
 }
 
+#[test(tokio::test)]
+async fn invalid_config_is_rejected() {
+    let mut config = network_config(multiaddr![Memory(thread_rng().gen::<u64>())]);
+    config.peer_connection_limit = 0;
+
+    match Network::new(Arc::new(OffsetTime::new()), config).await {
+        Err(NetworkError::Config(error)) => {
+            assert_eq!(error, ConfigError::ZeroConnectionLimit("peer"))
+        }
+        Err(error) => panic!("Unexpected error: {}", error),
+        Ok(_) => panic!("Invalid config was accepted"),
+    }
+
+    let mut config = network_config(multiaddr![Memory(thread_rng().gen::<u64>())]);
+    config.limits.ipv6_subnet_mask = 129;
+
+    match Network::new(Arc::new(OffsetTime::new()), config).await {
+        Err(NetworkError::Config(error)) => {
+            assert_eq!(error, ConfigError::InvalidSubnetMask("IPv6", 129))
+        }
+        Err(error) => panic!("Unexpected error: {}", error),
+        Ok(_) => panic!("Invalid config was accepted"),
+    }
+}
+
+fn signed_seed_list(key_pair: &KeyPair, seeds: &[&str]) -> SeedList {
+    let content = seeds.join("\n");
+    let signature = key_pair.sign(content.as_bytes());
+    let content = format!("{}\n{}", content, base64::encode(signature.to_bytes()));
+
+    let url = url::Url::parse("https://nimiq.dev/seeds.txt").unwrap();
+    SeedList::parse(url, &content, Some(&key_pair.public)).unwrap()
+}
+
+#[test]
+fn seed_lists_with_invalid_signatures_are_skipped() {
+    let key_pair = KeyPair::generate_default_csprng();
+    let valid = signed_seed_list(&key_pair, &["wss://seed1.nimiq.dev:8443"]);
+
+    // A seed injected into a signed list invalidates it.
+    let mut tampered = signed_seed_list(&key_pair, &["wss://seed2.nimiq.dev:8443"]);
+    tampered.add_seeds(vec![
+        PeerUri::from_str("wss://evil.example.com:8443").unwrap()
+    ]);
+
+    let mut config = network_config(multiaddr![Memory(thread_rng().gen::<u64>())]);
+    config.seed_lists = vec![valid.clone(), tampered];
+
+    // Only the seeds of the valid list are dialed.
+    let addresses = config.seed_list_addresses();
+    assert_eq!(addresses, valid.multiaddrs().collect::<Vec<_>>());
+    assert_eq!(addresses.len(), 1);
+    assert!(addresses
+        .iter()
+        .all(|address| !address.to_string().contains("evil.example.com")));
+}
+
+#[test(tokio::test)]
+async fn disconnecting_unknown_peer_fails() {
+    let (net1, _net2) = create_connected_networks().await;
+
+    let unknown_peer_id = PeerId::random();
+    assert!(!net1.has_peer(unknown_peer_id));
+
+    match net1
+        .disconnect_peer(unknown_peer_id, CloseReason::Other)
+        .await
+    {
+        Err(NetworkError::UnknownPeer(peer_id)) => assert_eq!(peer_id, unknown_peer_id),
+        result => panic!("Unexpected disconnect result: {:?}", result),
+    }
+}
+
+#[test(tokio::test)]
+async fn dialing_invalid_peer_uri_fails() {
+    let (net1, _net2) = create_connected_networks().await;
+
+    let uri = PeerUri::from_str("rtc://2b3f0f59334ef71ee7869b451139587f").unwrap();
+    assert!(matches!(
+        net1.dial_peer_uri(&uri).await,
+        Err(NetworkError::PeerUri(
+            PeerUriError::MultiaddrWithInvalidProtocol
+        ))
+    ));
+
+    let uri = PeerUri::from_str("wss://seed1.nimiq.dev/2b3f0f59334ef71ee7869b451139587f").unwrap();
+    assert!(matches!(
+        net1.dial_peer_uri(&uri).await,
+        Err(NetworkError::PeerUri(PeerUriError::InvalidPeerId))
+    ));
+}
+
+#[test(tokio::test)]
+async fn banned_peers_are_disconnected() {
+    let (net1, net2) = create_connected_networks().await;
+
+    let net2_peer_id = *net2.local_peer_id();
+    assert!(!net1.is_banned(&net2_peer_id));
+
+    let mut events1 = net1.subscribe_events();
+
+    net1.ban_peer(net2_peer_id, Some(Duration::from_secs(60)))
+        .await
+        .unwrap();
+    assert!(net1.is_banned(&net2_peer_id));
+
+    let event1 = events1.next().await.unwrap().unwrap();
+    assert_peer_left(&event1, &net2_peer_id);
+
+    assert_eq!(net1.get_peers(), &[]);
+}
+
+#[test(tokio::test)]
+async fn banned_peers_cannot_be_dialed() {
+    let (net1, net2) = create_connected_networks().await;
+
+    let net2_peer_id = *net2.local_peer_id();
+    let mut events1 = net1.subscribe_events();
+
+    net1.ban_peer(net2_peer_id, None).await.unwrap();
+
+    let event1 = events1.next().await.unwrap().unwrap();
+    assert_peer_left(&event1, &net2_peer_id);
+
+    match net1.dial_peer(net2_peer_id).await {
+        Err(NetworkError::BannedPeer(peer_id)) => assert_eq!(peer_id, net2_peer_id),
+        result => panic!("Unexpected dial result: {:?}", result),
+    }
+
+    // Connections initiated by the banned peer are closed without the peer ever joining.
+    net2.dial_peer(net1.get_local_peer_id()).await.unwrap();
+    assert!(timeout(Duration::from_secs(2), events1.next())
+        .await
+        .is_err());
+    assert_eq!(net1.get_peers(), &[]);
+
+    // A ban without a duration lasts until restart.
+    assert!(net1.is_banned(&net2_peer_id));
+}
+
+#[test(tokio::test)]
+async fn bans_with_huge_durations_are_permanent() {
+    let (net1, net2) = create_connected_networks().await;
+
+    let net2_peer_id = *net2.local_peer_id();
+
+    net1.ban_peer(net2_peer_id, Some(Duration::from_secs(u64::MAX)))
+        .await
+        .unwrap();
+    assert!(net1.is_banned(&net2_peer_id));
+}
+
+#[test(tokio::test)]
+async fn peer_bans_expire() {
+    let (net1, net2) = create_connected_networks().await;
+
+    let net2_peer_id = *net2.local_peer_id();
+    let mut events1 = net1.subscribe_events();
+
+    net1.ban_peer(net2_peer_id, Some(Duration::from_secs(1)))
+        .await
+        .unwrap();
+    assert!(net1.is_banned(&net2_peer_id));
+
+    let event1 = events1.next().await.unwrap().unwrap();
+    assert_peer_left(&event1, &net2_peer_id);
+
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert!(!net1.is_banned(&net2_peer_id));
+
+    net2.dial_peer(net1.get_local_peer_id()).await.unwrap();
+
+    let event1 = events1.next().await.unwrap().unwrap();
+    assert_peer_joined(&event1, &net2_peer_id);
+}
+
+#[test(tokio::test)]
+async fn ping_measures_round_trip_time() {
+    let (net1, net2) = create_connected_networks().await;
+
+    let net1_peer_id = *net1.local_peer_id();
+    let net2_peer_id = *net2.local_peer_id();
+
+    let rtt1 = net1.ping(net2_peer_id).await.unwrap();
+    let rtt2 = net2.ping(net1_peer_id).await.unwrap();
+    assert!(rtt1 < Duration::from_secs(1));
+    assert!(rtt2 < Duration::from_secs(1));
+
+    // The measurement is also recorded in the peer info.
+    let peer_info = net1.get_peer_info(&net2_peer_id).unwrap();
+    assert!(peer_info.rtt.is_some());
+
+    let unknown_peer_id = PeerId::random();
+    match net1.ping(unknown_peer_id).await {
+        Err(NetworkError::UnknownPeer(peer_id)) => assert_eq!(peer_id, unknown_peer_id),
+        result => panic!("Unexpected ping result: {:?}", result),
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct TestRecord {
     x: i32,
@@ -413,6 +650,28 @@ async fn dht_put_and_get() {
     assert_eq!(fetched_record, Some(put_record));
 }
 
+#[test(tokio::test)]
+async fn dht_get_many() {
+    let (net1, net2) = create_connected_networks().await;
+
+    // FIXME: Add delay while networks share their addresses
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let foo_record = TestRecord { x: 420 };
+    let bar_record = TestRecord { x: 42 };
+
+    net1.dht_put(b"foo", &foo_record).await.unwrap();
+    net1.dht_put(b"bar", &bar_record).await.unwrap();
+
+    let keys: [&[u8]; 3] = [b"bar", b"missing", b"foo"];
+    let fetched_records = net2.dht_get_many::<_, TestRecord>(&keys).await.unwrap();
+
+    assert_eq!(
+        fetched_records,
+        vec![Some(bar_record), None, Some(foo_record)]
+    );
+}
+
 pub struct TestTopic;
 
 impl Topic for TestTopic {
@@ -423,6 +682,18 @@ impl Topic for TestTopic {
     const VALIDATE: bool = true;
 }
 
+/// A topic that shares its name with `TestTopic`, but whose items can't be deserialized as
+/// `TestRecord`s.
+pub struct GarbageTopic;
+
+impl Topic for GarbageTopic {
+    type Item = u8;
+
+    const BUFFER_SIZE: usize = 8;
+    const NAME: &'static str = "hello_world";
+    const VALIDATE: bool = true;
+}
+
 fn consume_stream<T: std::fmt::Debug>(mut stream: impl Stream<Item = T> + Unpin + Send + 'static) {
     tokio::spawn(async move { while stream.next().await.is_some() {} });
 }
@@ -469,3 +740,159 @@ async fn test_gossipsub() {
     }
     net1.network_info().await.unwrap();
 }
+
+#[test(tokio::test)]
+async fn test_gossipsub_unsubscribe() {
+    let mut net = TestNetwork::new();
+
+    let net1 = net.spawn().await;
+    let net2 = net.spawn().await;
+
+    // Our Gossipsub configuration requires a minimum of 6 peers for the mesh network
+    for _ in 0..5i32 {
+        let net_n = net.spawn().await;
+        let stream_n = net_n.subscribe::<TestTopic>().await.unwrap();
+        consume_stream(stream_n);
+    }
+
+    let test_message = TestRecord { x: 42 };
+
+    let mut messages = net1.subscribe::<TestTopic>().await.unwrap();
+    consume_stream(net2.subscribe::<TestTopic>().await.unwrap());
+
+    tokio::time::sleep(Duration::from_secs(10)).await;
+
+    net2.publish::<TestTopic>(test_message.clone())
+        .await
+        .unwrap();
+
+    let (received_message, message_id) = messages.next().await.unwrap();
+    assert_eq!(received_message, test_message);
+    net1.validate_message::<TestTopic>(message_id, MsgAcceptance::Accept);
+
+    net1.unsubscribe::<TestTopic>().await.unwrap();
+
+    // Messages published after unsubscribing are not delivered anymore.
+    net2.publish::<TestTopic>(TestRecord { x: 43 })
+        .await
+        .unwrap();
+
+    // The subscription stream ends once we unsubscribed.
+    assert!(messages.next().await.is_none());
+
+    // Unsubscribing twice fails.
+    assert!(matches!(
+        net1.unsubscribe::<TestTopic>().await,
+        Err(NetworkError::AlreadyUnsubscribed { .. })
+    ));
+}
+
+#[test(tokio::test)]
+async fn test_gossipsub_rejects_malformed_messages() {
+    let mut net = TestNetwork::new();
+
+    let net1 = net.spawn().await;
+    let net2 = net.spawn().await;
+
+    // Our Gossipsub configuration requires a minimum of 6 peers for the mesh network
+    for _ in 0..5i32 {
+        let net_n = net.spawn().await;
+        let stream_n = net_n.subscribe::<TestTopic>().await.unwrap();
+        consume_stream(stream_n);
+    }
+
+    let mut messages = net1.subscribe::<TestTopic>().await.unwrap();
+    consume_stream(net2.subscribe::<GarbageTopic>().await.unwrap());
+
+    tokio::time::sleep(Duration::from_secs(10)).await;
+
+    // Publish a message that can't be deserialized, followed by a valid one.
+    net2.publish::<GarbageTopic>(0xff).await.unwrap();
+    let test_message = TestRecord { x: 42 };
+    net2.publish::<TestTopic>(test_message.clone())
+        .await
+        .unwrap();
+
+    // The malformed message is skipped and the stream is still alive.
+    let (received_message, message_id) = messages.next().await.unwrap();
+    assert_eq!(received_message, test_message);
+    net1.validate_message::<TestTopic>(message_id, MsgAcceptance::Accept);
+}
+
+#[test(tokio::test)]
+async fn test_gossipsub_unsubscribes_dropped_streams() {
+    let mut net = TestNetwork::new();
+
+    let net1 = net.spawn().await;
+    let net2 = net.spawn().await;
+
+    // Our Gossipsub configuration requires a minimum of 6 peers for the mesh network
+    for _ in 0..5i32 {
+        let net_n = net.spawn().await;
+        let stream_n = net_n.subscribe::<TestTopic>().await.unwrap();
+        consume_stream(stream_n);
+    }
+
+    let messages = net1.subscribe::<TestTopic>().await.unwrap();
+    consume_stream(net2.subscribe::<TestTopic>().await.unwrap());
+
+    tokio::time::sleep(Duration::from_secs(10)).await;
+
+    drop(messages);
+
+    // The next message for the topic notices the closed stream and unsubscribes.
+    net2.publish::<TestTopic>(TestRecord { x: 42 })
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    assert!(matches!(
+        net1.unsubscribe::<TestTopic>().await,
+        Err(NetworkError::AlreadyUnsubscribed { .. })
+    ));
+}
+
+#[test(tokio::test)]
+async fn test_gossipsub_relays_accepted_messages() {
+    let mut net = TestNetwork::new();
+
+    // All other nodes dial the first one, so messages between them are relayed by it.
+    let relay = net.spawn().await;
+    let publisher = net.spawn().await;
+    let receiver = net.spawn().await;
+
+    // Our Gossipsub configuration requires a minimum of 6 peers for the mesh network
+    for _ in 0..4i32 {
+        let net_n = net.spawn().await;
+        let stream_n = net_n.subscribe::<TestTopic>().await.unwrap();
+        consume_stream(stream_n);
+    }
+
+    let mut relay_messages = relay.subscribe::<TestTopic>().await.unwrap();
+    let mut received_messages = receiver.subscribe::<TestTopic>().await.unwrap();
+    consume_stream(publisher.subscribe::<TestTopic>().await.unwrap());
+
+    tokio::time::sleep(Duration::from_secs(10)).await;
+
+    let test_message = TestRecord { x: 42 };
+    publisher
+        .publish::<TestTopic>(test_message.clone())
+        .await
+        .unwrap();
+
+    let (message, message_id) = relay_messages.next().await.unwrap();
+    assert_eq!(message, test_message);
+
+    // The message isn't relayed before it was validated.
+    assert!(timeout(Duration::from_secs(1), received_messages.next())
+        .await
+        .is_err());
+
+    relay.validate_message::<TestTopic>(message_id, MsgAcceptance::Accept);
+
+    let (message, _) = timeout(Duration::from_secs(10), received_messages.next())
+        .await
+        .expect("Accepted message wasn't relayed")
+        .unwrap();
+    assert_eq!(message, test_message);
+}
@@ -25,7 +25,7 @@ use nimiq_network_libp2p::{
         behaviour::DiscoveryConfig,
         peer_contacts::{PeerContact, Protocols, Services},
     },
-    Config, Network, PeerId,
+    Config, LimitConfig, Network, PeerId, DISCOVERY_PROTOCOL,
 };
 use nimiq_test_log::test;
 use nimiq_utils::time::OffsetTime;
@@ -152,10 +152,14 @@ impl TestNetwork {
         let addr1 = multiaddr![Memory(thread_rng().gen::<u64>())];
         let addr2 = multiaddr![Memory(thread_rng().gen::<u64>())];
 
-        let net1 = Network::new(Arc::new(OffsetTime::new()), network_config(addr1.clone())).await;
+        let net1 = Network::new(Arc::new(OffsetTime::new()), network_config(addr1.clone()))
+            .await
+            .unwrap();
         net1.listen_on(vec![addr1.clone()]).await;
 
-        let net2 = Network::new(Arc::new(OffsetTime::new()), network_config(addr2.clone())).await;
+        let net2 = Network::new(Arc::new(OffsetTime::new()), network_config(addr2.clone()))
+            .await
+            .unwrap();
         net2.listen_on(vec![addr2.clone()]).await;
 
         log::debug!(address = %addr1, peer_id = %net1.get_local_peer_id(), "Network 1");
@@ -192,16 +196,24 @@ impl TestNetwork {
         let addr3 = multiaddr![Memory(thread_rng().gen::<u64>())];
         let addr4 = multiaddr![Memory(thread_rng().gen::<u64>())];
 
-        let net1 = Network::new(Arc::new(OffsetTime::new()), network_config(addr1.clone())).await;
+        let net1 = Network::new(Arc::new(OffsetTime::new()), network_config(addr1.clone()))
+            .await
+            .unwrap();
         net1.listen_on(vec![addr1.clone()]).await;
 
-        let net2 = Network::new(Arc::new(OffsetTime::new()), network_config(addr2.clone())).await;
+        let net2 = Network::new(Arc::new(OffsetTime::new()), network_config(addr2.clone()))
+            .await
+            .unwrap();
         net2.listen_on(vec![addr2.clone()]).await;
 
-        let net3 = Network::new(Arc::new(OffsetTime::new()), network_config(addr3.clone())).await;
+        let net3 = Network::new(Arc::new(OffsetTime::new()), network_config(addr3.clone()))
+            .await
+            .unwrap();
         net3.listen_on(vec![addr3.clone()]).await;
 
-        let net4 = Network::new(Arc::new(OffsetTime::new()), network_config(addr4.clone())).await;
+        let net4 = Network::new(Arc::new(OffsetTime::new()), network_config(addr4.clone()))
+            .await
+            .unwrap();
         net4.listen_on(vec![addr4.clone()]).await;
 
         log::debug!(address = %addr1, peer_id = %net1.get_local_peer_id(), "Network 1");
@@ -265,6 +277,7 @@ fn network_config(address: Multiaddr) -> Config {
         keypair,
         peer_contact,
         seeds: Vec::new(),
+        seed_lists: Vec::new(),
         discovery: DiscoveryConfig {
             genesis_hash: Default::default(),
             update_interval: Duration::from_secs(60),
@@ -279,18 +292,33 @@ fn network_config(address: Multiaddr) -> Config {
         kademlia: Default::default(),
         gossipsub,
         memory_transport: true,
+        incoming_connection_limit: Config::DEFAULT_INCOMING_CONNECTION_LIMIT,
+        outgoing_connection_limit: Config::DEFAULT_OUTGOING_CONNECTION_LIMIT,
+        peer_connection_limit: Config::DEFAULT_PEER_CONNECTION_LIMIT,
+        limits: LimitConfig::default(),
     }
 }
 
-fn assert_peer_joined(event: &NetworkEvent<PeerId>, wanted_peer_id: &PeerId) {
-    if let NetworkEvent::PeerJoined(peer_id) = event {
+fn assert_peer_joined(event: &NetworkEvent<PeerId, Multiaddr>, wanted_peer_id: &PeerId) {
+    if let NetworkEvent::PeerJoined {
+        peer_id,
+        address,
+        protocols,
+    } = event
+    {
         assert_eq!(peer_id, wanted_peer_id);
+        assert!(address.iter().next().is_some());
+        // The protocols are the ones the peer reported about itself.
+        let discovery_protocol = std::str::from_utf8(DISCOVERY_PROTOCOL).unwrap();
+        assert!(protocols
+            .iter()
+            .any(|protocol| protocol == discovery_protocol));
     } else {
         panic!("Event is not a NetworkEvent::PeerJoined: {:?}", event);
     }
 }
 
-fn assert_peer_left(event: &NetworkEvent<PeerId>, wanted_peer_id: &PeerId) {
+fn assert_peer_left(event: &NetworkEvent<PeerId, Multiaddr>, wanted_peer_id: &PeerId) {
     if let NetworkEvent::PeerLeft(peer_id) = event {
         assert_eq!(peer_id, wanted_peer_id);
     } else {
@@ -548,7 +576,8 @@ async fn disconnect_successfully(net1: &Arc<Network>, net2: &Arc<Network>) {
 
     log::debug!("Disconnecting peer 1 from peer 2");
     net2.disconnect_peer(net1.get_local_peer_id(), CloseReason::Other)
-        .await;
+        .await
+        .unwrap();
 
     log::debug!("Waiting for disconnect events");
 
@@ -71,21 +71,25 @@ pub mod tests {
     use nimiq_test_log::test;
 
     use super::network::MockNetworkError;
-    use super::{MockHub, MockPeerId};
+    use super::{MockAddress, MockHub, MockPeerId};
 
     pub async fn assert_peer_joined(
-        events: &mut SubscribeEvents<MockPeerId>,
+        events: &mut SubscribeEvents<MockPeerId, MockAddress>,
         expected_peer_id: MockPeerId,
     ) {
-        if let Some(Ok(NetworkEvent::PeerJoined(peer_id))) = events.next().await {
+        if let Some(Ok(NetworkEvent::PeerJoined {
+            peer_id, address, ..
+        })) = events.next().await
+        {
             assert_eq!(peer_id, expected_peer_id);
+            assert_eq!(address, MockAddress::from(expected_peer_id));
         } else {
             panic!("Expected PeerJoined event with id={}", expected_peer_id);
         }
     }
 
     pub async fn assert_peer_left(
-        events: &mut SubscribeEvents<MockPeerId>,
+        events: &mut SubscribeEvents<MockPeerId, MockAddress>,
         expected_peer_id: MockPeerId,
     ) {
         if let Some(Ok(NetworkEvent::PeerLeft(peer_id))) = events.next().await {
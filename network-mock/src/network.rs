@@ -32,6 +32,9 @@ pub enum MockNetworkError {
     #[error("Can't connect to peer: {0}")]
     CantConnect(MockAddress),
 
+    #[error("Unknown peer: {0}")]
+    UnknownPeer(MockPeerId),
+
     #[error("Network is not connected")]
     NotConnected,
 
@@ -322,13 +325,21 @@ impl Network for MockNetwork {
         self.peers.read().keys().copied().collect()
     }
 
+    fn peer_count(&self) -> usize {
+        self.peers.read().len()
+    }
+
     fn has_peer(&self, peer_id: MockPeerId) -> bool {
         self.peers.read().get(&peer_id).is_some()
     }
 
-    async fn disconnect_peer(&self, peer_id: MockPeerId, _: CloseReason) {
+    async fn disconnect_peer(
+        &self,
+        peer_id: MockPeerId,
+        _: CloseReason,
+    ) -> Result<(), MockNetworkError> {
         if !self.has_peer(peer_id) {
-            return;
+            return Err(MockNetworkError::UnknownPeer(peer_id));
         }
 
         let mut hub = self.hub.lock();
@@ -336,13 +347,19 @@ impl Network for MockNetwork {
         // Drops senders and thus the receiver stream will end
         hub.network_senders
             .retain(|k, _| k.network_recipient != peer_id.into());
+
+        Ok(())
     }
 
-    fn subscribe_events(&self) -> SubscribeEvents<MockPeerId> {
+    fn subscribe_events(&self) -> SubscribeEvents<MockPeerId, MockAddress> {
         Box::pin(
             BroadcastStream::new(self.peers.read().subscribe()).map(|maybe_ev| {
                 maybe_ev.map(|ev| match ev {
-                    observable_hash_map::Event::Add(peer_id) => NetworkEvent::PeerJoined(peer_id),
+                    observable_hash_map::Event::Add(peer_id) => NetworkEvent::PeerJoined {
+                        peer_id,
+                        address: peer_id.into(),
+                        protocols: vec![],
+                    },
                     observable_hash_map::Event::Remove(peer_id) => NetworkEvent::PeerLeft(peer_id),
                 })
             }),
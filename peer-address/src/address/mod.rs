@@ -1,3 +1,5 @@
+use thiserror::Error;
+
 use nimiq_hash::{Blake2bHash, Blake2bHasher, Hasher};
 use nimiq_keys::PublicKey;
 use nimiq_macros::{add_hex_io_fns_typed_arr, create_typed_array};
@@ -28,3 +30,99 @@ impl<'a> From<&'a PublicKey> for PeerId {
         PeerId::from(hash)
     }
 }
+
+impl<'a> From<&'a libp2p::PeerId> for PeerId {
+    /// Derives the peer ID from the multihash bytes of a libp2p peer ID.
+    fn from(peer_id: &'a libp2p::PeerId) -> Self {
+        let hash = Blake2bHasher::default().digest(&peer_id.to_bytes());
+        PeerId::from(hash)
+    }
+}
+
+impl PeerId {
+    /// The all-zero peer ID. No public key or libp2p peer ID is known to map to it, so it can be
+    /// used as a placeholder, e.g. in tests.
+    pub const ZERO: PeerId = PeerId([0u8; PeerId::SIZE]);
+
+    /// Encodes the peer ID in base58, the encoding libp2p uses to display peer IDs.
+    pub fn to_base58(&self) -> String {
+        bs58::encode(&self.0).into_string()
+    }
+
+    /// Decodes a base58 encoded peer ID.
+    pub fn from_base58(s: &str) -> Result<Self, PeerIdError> {
+        let bytes = bs58::decode(s).into_vec()?;
+        if bytes.len() != PeerId::SIZE {
+            return Err(PeerIdError::InvalidLength(bytes.len()));
+        }
+        Ok(PeerId::from(&bytes[..]))
+    }
+
+    /// The number of hex characters shown at each end of the short string representation.
+    const SHORT_STRING_CHARS: usize = 4;
+
+    /// Returns true if the hex encoding of the peer ID starts with the given prefix. The
+    /// comparison is case-insensitive and works per nibble, so the prefix may have an odd length.
+    /// Prefixes containing non-hex characters never match.
+    pub fn starts_with_hex(&self, prefix: &str) -> bool {
+        if prefix.len() > 2 * PeerId::SIZE {
+            return false;
+        }
+
+        prefix.chars().enumerate().all(|(i, c)| {
+            let byte = self.0[i / 2];
+            let nibble = if i % 2 == 0 { byte >> 4 } else { byte & 0x0f };
+            c.to_digit(16) == Some(nibble as u32)
+        })
+    }
+
+    /// Returns a short representation of the peer ID for logs, consisting of the first and the
+    /// last few characters of its hex encoding.
+    pub fn to_short_string(&self) -> String {
+        let hex = self.to_hex();
+        format!(
+            "{}..{}",
+            &hex[..PeerId::SHORT_STRING_CHARS],
+            &hex[hex.len() - PeerId::SHORT_STRING_CHARS..]
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PeerIdError {
+    #[error("Invalid base58 encoding: {0}")]
+    InvalidBase58(#[from] bs58::decode::Error),
+    #[error("Invalid peer ID length: {0} bytes")]
+    InvalidLength(usize),
+}
+
+#[cfg(feature = "serde-derive")]
+mod serde_derive {
+    use std::borrow::Cow;
+
+    use serde::{
+        de::{Deserialize, Deserializer, Error},
+        ser::{Serialize, Serializer},
+    };
+
+    use super::PeerId;
+
+    impl Serialize for PeerId {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_str(&self.to_hex())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for PeerId {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let data: Cow<'de, str> = Deserialize::deserialize(deserializer)?;
+            data.parse().map_err(Error::custom)
+        }
+    }
+}
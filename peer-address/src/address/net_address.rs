@@ -26,9 +26,17 @@ impl NetAddress {
         }
     }
 
+    /// Returns the address with all but the first `bit_count` bits set to zero, e.g. its /24
+    /// subnet for IPv4 or its /48 subnet for IPv6. Returns `None` if the prefix is longer than
+    /// the address, i.e. 32 bits for IPv4 and 128 bits for IPv6. Use `subnet_key` to clamp the
+    /// prefix instead. Pseudo addresses are returned unchanged.
     #[must_use]
-    pub fn subnet(&self, bit_count: u8) -> Self {
-        match self {
+    pub fn subnet(&self, bit_count: u8) -> Option<Self> {
+        if bit_count > self.bit_width().unwrap_or(u8::MAX) {
+            return None;
+        }
+
+        Some(match self {
             NetAddress::IPv4(ref ip) => {
                 let masked = ip_to_subnet(&ip.octets(), bit_count);
                 let mut masked_ip = [0u8; 4];
@@ -43,14 +51,75 @@ impl NetAddress {
             }
             NetAddress::Unspecified => NetAddress::Unspecified,
             NetAddress::Unknown => NetAddress::Unknown,
+        })
+    }
+
+    /// Returns the number of bits of the address, or `None` for pseudo addresses.
+    fn bit_width(&self) -> Option<u8> {
+        match self {
+            NetAddress::IPv4(_) => Some(32),
+            NetAddress::IPv6(_) => Some(128),
+            NetAddress::Unspecified | NetAddress::Unknown => None,
+        }
+    }
+
+    /// Checks whether both addresses are in the same subnet with the given prefix length. IPv4
+    /// and IPv6 addresses never share a subnet. `Unspecified` is only in the same subnet as
+    /// `Unspecified` and `Unknown` is never in any subnet.
+    pub fn is_in_subnet(&self, other: &NetAddress, prefix_bits: u8) -> bool {
+        match (self.subnet_key(prefix_bits), other.subnet_key(prefix_bits)) {
+            (Some(key), Some(other_key)) => key == other_key,
+            _ => false,
         }
     }
 
+    /// Returns the key of the subnet with the given prefix length that this address is in, or
+    /// `None` for `Unknown` addresses.
+    pub fn subnet_key(&self, prefix_bits: u8) -> Option<SubnetKey> {
+        let prefix_bits = match self {
+            NetAddress::Unspecified => 0,
+            NetAddress::Unknown => return None,
+            _ => min(prefix_bits, self.bit_width()?),
+        };
+
+        Some(SubnetKey {
+            address: self.subnet(prefix_bits)?,
+            prefix_bits,
+        })
+    }
+
     pub fn is_pseudo(&self) -> bool {
         let ty = self.get_type();
         ty == NetAddressType::Unknown || ty == NetAddressType::Unspecified
     }
 
+    /// Checks whether this is an address that can't be reached from the internet. These are the
+    /// IPv4 ranges of RFC 1918 (`10.0.0.0/8`, `172.16.0.0/12` and `192.168.0.0/16`), IPv6 unique
+    /// local addresses (`fc00::/7`), the link-local ranges `169.254.0.0/16` and `fe80::/10` and
+    /// loopback addresses, see `is_loopback`.
+    pub fn is_private(&self) -> bool {
+        match self {
+            NetAddress::IPv4(ip) => ip.is_private() || ip.is_link_local() || ip.is_loopback(),
+            NetAddress::IPv6(ip) => {
+                let first_segment = ip.segments()[0];
+                // Unique local (fc00::/7) or link-local (fe80::/10).
+                first_segment & 0xfe00 == 0xfc00
+                    || first_segment & 0xffc0 == 0xfe80
+                    || ip.is_loopback()
+            }
+            NetAddress::Unspecified | NetAddress::Unknown => false,
+        }
+    }
+
+    /// Checks whether this is a loopback address, i.e. in `127.0.0.0/8` or `::1`.
+    pub fn is_loopback(&self) -> bool {
+        match self {
+            NetAddress::IPv4(ip) => ip.is_loopback(),
+            NetAddress::IPv6(ip) => ip.is_loopback(),
+            NetAddress::Unspecified | NetAddress::Unknown => false,
+        }
+    }
+
     pub fn is_reliable(&self) -> bool {
         // TODO add reliability flag
         !self.is_pseudo()
@@ -65,6 +134,30 @@ impl NetAddress {
     }
 }
 
+/// Identifies a subnet, i.e. a masked address together with its prefix length. Two addresses are
+/// in the same subnet if they have the same key, so it can be used to group addresses in maps.
+#[derive(Debug, Ord, PartialOrd, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct SubnetKey {
+    address: NetAddress,
+    prefix_bits: u8,
+}
+
+impl SubnetKey {
+    pub fn address(&self) -> &NetAddress {
+        &self.address
+    }
+
+    pub fn prefix_bits(&self) -> u8 {
+        self.prefix_bits
+    }
+}
+
+impl fmt::Display for SubnetKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_bits)
+    }
+}
+
 fn ip_to_subnet(ip: &[u8], mut bit_count: u8) -> Vec<u8> {
     let mut mask: Vec<u8> = Vec::new();
     for &byte in ip {
@@ -1,7 +1,10 @@
 use std::fmt;
+use std::net::IpAddr;
 use std::str::FromStr;
 
 use hex::FromHex;
+use libp2p::multiaddr::Protocol as MultiaddrProtocol;
+use libp2p::{Multiaddr, PeerId as Libp2pPeerId};
 use thiserror::Error;
 use url::Url;
 
@@ -29,6 +32,10 @@ pub enum PeerUriError {
     UnexpectedQuery,
     #[error("Unexpected fragment in URI")]
     UnexpectedFragment,
+    #[error("Port is missing")]
+    MissingPort,
+    #[error("Invalid port number")]
+    InvalidPort,
     #[error("Unexpected port number")]
     UnexpectedPort,
     #[error("Unexpected path segment")]
@@ -43,6 +50,12 @@ pub enum PeerUriError {
     SeedNodeMissingPublicKey,
     #[error("The only allowed protocols for seed nodes are Wss and Ws")]
     SeedNodeWithInvalidProtocol,
+    #[error("Invalid multiaddr: {0}")]
+    InvalidMultiaddr(#[from] libp2p::multiaddr::Error),
+    #[error("Unsupported multiaddr component: {0}")]
+    UnsupportedMultiaddr(String),
+    #[error("Only Ws and Wss URIs can be converted to a multiaddr")]
+    MultiaddrWithInvalidProtocol,
 }
 
 impl FromStr for Protocol {
@@ -74,7 +87,9 @@ impl fmt::Display for Protocol {
     }
 }
 
-#[derive(Debug, Clone)]
+/// A peer URI, either in URL form (e.g. `wss://seed1.nimiq.dev:8443/<peer id>`) or as a
+/// multiaddr. Its string representation can be parsed again into an equal URI.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PeerUri {
     protocol: Protocol,
     hostname: Option<String>,
@@ -87,7 +102,17 @@ impl FromStr for PeerUri {
     type Err = PeerUriError;
 
     fn from_str(s: &str) -> Result<Self, PeerUriError> {
-        let url = Url::parse(s)?;
+        // Multiaddrs always start with a slash, URLs never do.
+        if s.starts_with('/') {
+            let multiaddr = Multiaddr::from_str(s)?;
+            return Self::from_multiaddr(&multiaddr);
+        }
+
+        let url = Url::parse(s).map_err(|e| match e {
+            url::ParseError::InvalidPort => PeerUriError::InvalidPort,
+            url::ParseError::EmptyHost => PeerUriError::MissingHostname,
+            e => PeerUriError::InvalidUri(e),
+        })?;
         Self::from_url(url)
     }
 }
@@ -175,6 +200,9 @@ impl PeerUri {
         match protocol {
             Protocol::Dumb | Protocol::Rtc => {
                 let peer_id = String::from(url.host_str().ok_or(PeerUriError::MissingPeerId)?);
+                if PeerId::from_str(&peer_id).is_err() {
+                    return Err(PeerUriError::InvalidPeerId);
+                }
                 if url.port().is_some() {
                     return Err(PeerUriError::UnexpectedPort);
                 }
@@ -196,6 +224,9 @@ impl PeerUri {
                     Some(ref public_key) if public_key.len() == 2 * PublicKey::SIZE => {
                         (None, path_segment)
                     }
+                    Some(ref peer_id) if Libp2pPeerId::from_str(peer_id).is_ok() => {
+                        (path_segment, None)
+                    }
                     None => (None, None),
                     _ => return Err(PeerUriError::InvalidPeerId),
                 };
@@ -210,6 +241,162 @@ impl PeerUri {
         }
     }
 
+    /// Creates a peer URI from a multiaddr of the form
+    /// `/{dns,dns4,dns6,ip4,ip6}/<host>/tcp/<port>/{ws,wss}[/p2p/<peer id>]`. The peer ID is
+    /// stored in its libp2p (base58) representation.
+    pub fn from_multiaddr(multiaddr: &Multiaddr) -> Result<Self, PeerUriError> {
+        let mut components = multiaddr.iter();
+
+        let hostname = match components.next() {
+            Some(MultiaddrProtocol::Dns(host))
+            | Some(MultiaddrProtocol::Dns4(host))
+            | Some(MultiaddrProtocol::Dns6(host)) => host.to_string(),
+            Some(MultiaddrProtocol::Ip4(ip)) => ip.to_string(),
+            Some(MultiaddrProtocol::Ip6(ip)) => format!("[{}]", ip),
+            Some(component) => {
+                return Err(PeerUriError::UnsupportedMultiaddr(component.to_string()))
+            }
+            None => return Err(PeerUriError::MissingHostname),
+        };
+
+        let port = match components.next() {
+            Some(MultiaddrProtocol::Tcp(port)) => port,
+            Some(component) => {
+                return Err(PeerUriError::UnsupportedMultiaddr(component.to_string()))
+            }
+            None => return Err(PeerUriError::MissingPort),
+        };
+
+        let protocol = match components.next() {
+            Some(MultiaddrProtocol::Ws(_)) => Protocol::Ws,
+            Some(MultiaddrProtocol::Wss(_)) => Protocol::Wss,
+            Some(component) => {
+                return Err(PeerUriError::UnsupportedMultiaddr(component.to_string()))
+            }
+            None => return Err(PeerUriError::UnknownProtocol),
+        };
+
+        let peer_id = match components.next() {
+            Some(MultiaddrProtocol::P2p(multihash)) => Some(
+                Libp2pPeerId::from_multihash(multihash)
+                    .map_err(|_| PeerUriError::InvalidPeerId)?
+                    .to_base58(),
+            ),
+            Some(component) => {
+                return Err(PeerUriError::UnsupportedMultiaddr(component.to_string()))
+            }
+            None => None,
+        };
+
+        if let Some(component) = components.next() {
+            return Err(PeerUriError::UnsupportedMultiaddr(component.to_string()));
+        }
+
+        // Like in URLs, the default port of the protocol is left out.
+        Ok(PeerUri {
+            protocol,
+            hostname: Some(hostname),
+            port: Some(port).filter(|port| Some(*port) != Self::default_port(protocol)),
+            peer_id,
+            public_key: None,
+        })
+    }
+
+    /// Converts this peer URI into a multiaddr that can be dialed by the libp2p network. Only Ws
+    /// and Wss URIs are supported. If no port is given, the default port of the protocol is used.
+    /// The peer ID, if present, must be a libp2p peer ID.
+    pub fn to_multiaddr(&self) -> Result<Multiaddr, PeerUriError> {
+        let ws = match self.protocol {
+            Protocol::Ws => MultiaddrProtocol::Ws("/".into()),
+            Protocol::Wss => MultiaddrProtocol::Wss("/".into()),
+            Protocol::Dumb | Protocol::Rtc => {
+                return Err(PeerUriError::MultiaddrWithInvalidProtocol)
+            }
+        };
+        let default_port =
+            Self::default_port(self.protocol).expect("Ws and Wss have a default port");
+
+        let hostname = self.hostname().ok_or(PeerUriError::MissingHostname)?;
+
+        // IPv6 hosts are enclosed in brackets in URIs.
+        let host = match hostname
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+        {
+            Ok(IpAddr::V4(ip)) => MultiaddrProtocol::Ip4(ip),
+            Ok(IpAddr::V6(ip)) => MultiaddrProtocol::Ip6(ip),
+            Err(_) => MultiaddrProtocol::Dns(hostname.into()),
+        };
+
+        let mut multiaddr = Multiaddr::empty()
+            .with(host)
+            .with(MultiaddrProtocol::Tcp(self.port.unwrap_or(default_port)))
+            .with(ws);
+
+        if let Some(peer_id) = self.peer_id() {
+            let peer_id =
+                Libp2pPeerId::from_str(peer_id).map_err(|_| PeerUriError::InvalidPeerId)?;
+            multiaddr.push(MultiaddrProtocol::P2p(peer_id.into()));
+        }
+
+        Ok(multiaddr)
+    }
+
+    /// Returns a URI that refers to the same peer at the same address, written in a canonical way,
+    /// so that URIs can be compared regardless of how they were written:
+    /// * Hostnames are lowercase and IPs are in their canonical representation.
+    /// * The default port of the protocol is left out.
+    /// * Peer IDs and public keys are replaced by the hex encoded peer ID they correspond to.
+    ///   Libp2p peer IDs are converted with `PeerId::from`.
+    ///
+    /// The result is only meant to be compared, it can't necessarily be dialed.
+    pub(crate) fn normalized(&self) -> PeerUri {
+        let hostname = self.hostname.as_ref().map(|hostname| {
+            match hostname
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+            {
+                Ok(IpAddr::V4(ip)) => ip.to_string(),
+                Ok(IpAddr::V6(ip)) => format!("[{}]", ip),
+                Err(_) => hostname.to_lowercase(),
+            }
+        });
+
+        let peer_id = match (&self.peer_id, &self.public_key) {
+            (Some(peer_id), _) => Some(
+                PeerId::from_str(peer_id)
+                    .or_else(|_| Libp2pPeerId::from_str(peer_id).map(|id| PeerId::from(&id)))
+                    .map_or_else(|_| peer_id.clone(), |id| id.to_hex()),
+            ),
+            (None, Some(public_key)) => Some(
+                PublicKey::from_hex(public_key)
+                    .map_or_else(|_| public_key.clone(), |key| PeerId::from(&key).to_hex()),
+            ),
+            (None, None) => None,
+        };
+
+        PeerUri {
+            protocol: self.protocol,
+            hostname,
+            port: self
+                .port
+                .filter(|port| Some(*port) != Self::default_port(self.protocol)),
+            peer_id,
+            public_key: None,
+        }
+    }
+
+    /// Returns the port that is used if a URI of the given protocol doesn't specify one.
+    fn default_port(protocol: Protocol) -> Option<u16> {
+        match protocol {
+            Protocol::Ws => Some(80),
+            Protocol::Wss => Some(443),
+            Protocol::Dumb | Protocol::Rtc => None,
+        }
+    }
+
     pub fn protocol(&self) -> Protocol {
         self.protocol
     }
@@ -287,7 +474,7 @@ impl From<PeerAddress> for PeerUri {
                 protocol,
                 peer_id,
                 hostname: Some(host),
-                port: Some(port),
+                port: Some(port).filter(|port| Some(*port) != PeerUri::default_port(protocol)),
                 public_key: None,
             },
         }
@@ -1,16 +1,149 @@
+use std::str::FromStr;
+#[cfg(feature = "remote-seed-list")]
+use std::time::Duration;
+
+use libp2p::Multiaddr;
+use thiserror::Error;
 use url::Url;
 
-use nimiq_keys::PublicKey;
+use nimiq_keys::{PublicKey, Signature};
+
+use crate::address::PeerUri;
+
+#[derive(Debug, Error)]
+pub enum SeedListError {
+    #[error("Invalid seed list URL: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+    #[cfg(feature = "remote-seed-list")]
+    #[error("Failed to fetch seed list: {0}")]
+    Fetch(#[from] reqwest::Error),
+    #[error("Seed list is not signed")]
+    MissingSignature,
+    #[error("Seed list signature doesn't match the expected signer")]
+    InvalidSignature,
+    #[error("Seed list doesn't contain any valid seed")]
+    NoValidSeeds,
+}
 
 #[derive(Clone, Debug)]
 pub struct SeedList {
     url: Url,
     public_key: Option<PublicKey>,
+    seeds: Vec<PeerUri>,
+    skipped: usize,
+    // The signature of the list and the content it was computed over.
+    signature: Option<Signature>,
+    signed_content: String,
 }
 
 impl SeedList {
+    /// The maximum time to establish the connection to the server of a remote seed list.
+    #[cfg(feature = "remote-seed-list")]
+    pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+    /// The maximum time to download a remote seed list, including establishing the connection.
+    #[cfg(feature = "remote-seed-list")]
+    pub const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
     pub fn new(url: Url, public_key: Option<PublicKey>) -> Self {
-        Self { url, public_key }
+        Self {
+            url,
+            public_key,
+            seeds: vec![],
+            skipped: 0,
+            signature: None,
+            signed_content: String::new(),
+        }
+    }
+
+    /// Downloads the seed list at the given URL and parses it with `SeedList::parse`. The download
+    /// fails if it takes longer than `CONNECT_TIMEOUT` to connect or `FETCH_TIMEOUT` in total.
+    #[cfg(feature = "remote-seed-list")]
+    pub async fn load_remote(
+        url: &str,
+        expected_signer: Option<&PublicKey>,
+    ) -> Result<SeedList, SeedListError> {
+        let url = Url::parse(url)?;
+
+        let client = reqwest::Client::builder()
+            .connect_timeout(Self::CONNECT_TIMEOUT)
+            .timeout(Self::FETCH_TIMEOUT)
+            .build()?;
+
+        let content = client
+            .get(url.clone())
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        SeedList::parse(url, &content, expected_signer)
+    }
+
+    /// Parses the content of a seed list. A seed list contains one peer URI per line. Empty lines
+    /// and lines starting with `#` are ignored. It may end with a line holding the base64 encoded
+    /// signature of all preceding lines, joined by `\n` and without trailing whitespace.
+    ///
+    /// If an expected signer is given, the list must be signed by it. Lines that are not valid
+    /// peer URIs are skipped and counted, but a list without any valid seed is rejected.
+    pub fn parse(
+        url: Url,
+        content: &str,
+        expected_signer: Option<&PublicKey>,
+    ) -> Result<SeedList, SeedListError> {
+        let mut lines: Vec<&str> = content.lines().map(str::trim_end).collect();
+
+        // Ignore trailing empty lines.
+        while lines.last().map_or(false, |line| line.is_empty()) {
+            lines.pop();
+        }
+
+        let signature = lines.last().and_then(|line| Self::parse_signature(line));
+        if signature.is_some() {
+            lines.pop();
+        }
+
+        if let Some(public_key) = expected_signer {
+            let signature = signature.ok_or(SeedListError::MissingSignature)?;
+            if !public_key.verify(&signature, lines.join("\n").as_bytes()) {
+                return Err(SeedListError::InvalidSignature);
+            }
+        }
+
+        let mut seed_list = SeedList::new(url, expected_signer.cloned());
+        seed_list.signature = signature;
+        seed_list.signed_content = lines.join("\n");
+
+        for line in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match PeerUri::from_str(line) {
+                Ok(seed) => seed_list.seeds.push(seed),
+                Err(e) => {
+                    log::warn!(
+                        seed_list = %seed_list.url,
+                        line,
+                        error = %e,
+                        "Skipping malformed seed"
+                    );
+                    seed_list.skipped += 1;
+                }
+            }
+        }
+
+        if seed_list.seeds.is_empty() {
+            return Err(SeedListError::NoValidSeeds);
+        }
+
+        Ok(seed_list)
+    }
+
+    fn parse_signature(line: &str) -> Option<Signature> {
+        let bytes = base64::decode(line).ok()?;
+        Signature::from_bytes(&bytes).ok()
     }
 
     pub fn url(&self) -> &Url {
@@ -20,4 +153,97 @@ impl SeedList {
     pub fn public_key(&self) -> &Option<PublicKey> {
         &self.public_key
     }
+
+    pub fn seeds(&self) -> &[PeerUri] {
+        &self.seeds
+    }
+
+    /// Returns the number of malformed lines that were skipped when parsing this list.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Adds the peers that were retrieved from this seed list. The signature of the list doesn't
+    /// cover them, so the list doesn't validate afterwards.
+    pub fn add_seeds<I: IntoIterator<Item = PeerUri>>(&mut self, seeds: I) {
+        self.seeds.extend(seeds);
+        self.signature = None;
+    }
+
+    /// Appends the seeds of the other list that are not already part of this list, keeping the
+    /// order of both lists. Duplicates are dropped, only the first occurrence of a seed is kept.
+    /// Seeds are duplicates if they have the same address and peer ID, see `PeerUri::normalized`.
+    /// The URL and signer of this list are kept. Like `add_seeds`, the list doesn't validate
+    /// anymore if its seeds changed, so lists should be validated before they are merged.
+    pub fn merge(&mut self, other: SeedList) {
+        let mut changed = self.dedup();
+
+        let mut normalized: Vec<PeerUri> = self.seeds.iter().map(PeerUri::normalized).collect();
+        for seed in other.seeds {
+            let normalized_seed = seed.normalized();
+            if !normalized.contains(&normalized_seed) {
+                normalized.push(normalized_seed);
+                self.seeds.push(seed);
+                changed = true;
+            }
+        }
+
+        self.skipped += other.skipped;
+        if changed {
+            self.signature = None;
+        }
+    }
+
+    /// Merges the given lists into one with `SeedList::merge`. If a seed is part of several
+    /// lists, its first occurrence wins, so sources should be given in order of precedence
+    /// (e.g. the built-in list first).
+    ///
+    /// Panics if no source is given.
+    pub fn from_sources(sources: Vec<SeedList>) -> SeedList {
+        let mut sources = sources.into_iter();
+        let mut merged = sources.next().expect("No seed list sources given");
+        if merged.dedup() {
+            merged.signature = None;
+        }
+
+        for source in sources {
+            merged.merge(source);
+        }
+
+        merged
+    }
+
+    /// Removes all but the first occurrence of each seed, comparing their normalized URIs. Returns
+    /// true if any seed was removed.
+    fn dedup(&mut self) -> bool {
+        let len = self.seeds.len();
+
+        let mut normalized = Vec::with_capacity(len);
+        let mut seeds = Vec::with_capacity(len);
+        for seed in self.seeds.drain(..) {
+            let normalized_seed = seed.normalized();
+            if !normalized.contains(&normalized_seed) {
+                normalized.push(normalized_seed);
+                seeds.push(seed);
+            }
+        }
+        self.seeds = seeds;
+
+        self.seeds.len() < len
+    }
+
+    /// Returns true if the seeds of this list are covered by a signature of the given key.
+    pub fn validate(&self, expected_key: &PublicKey) -> bool {
+        self.signature.as_ref().map_or(false, |signature| {
+            expected_key.verify(signature, self.signed_content.as_bytes())
+        })
+    }
+
+    /// Returns the seeds of this list as multiaddrs. Seeds that can't be represented as a
+    /// multiaddr are skipped.
+    pub fn multiaddrs(&self) -> impl Iterator<Item = Multiaddr> + '_ {
+        self.seeds
+            .iter()
+            .filter_map(|seed| seed.to_multiaddr().ok())
+    }
 }
@@ -1 +1,4 @@
+mod net_address;
+mod peer_id;
 mod peer_uri;
+mod seed_list;
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use nimiq_peer_address::address::NetAddress;
+use nimiq_test_log::test;
+
+fn addr(s: &str) -> NetAddress {
+    NetAddress::from_str(s).unwrap()
+}
+
+#[test]
+fn it_masks_ipv4_subnets() {
+    let ip = addr("192.168.213.17");
+
+    assert_eq!(ip.subnet(0), Some(addr("0.0.0.0")));
+    assert_eq!(ip.subnet(8), Some(addr("192.0.0.0")));
+    assert_eq!(ip.subnet(20), Some(addr("192.168.208.0")));
+    assert_eq!(ip.subnet(24), Some(addr("192.168.213.0")));
+    assert_eq!(ip.subnet(32), Some(ip));
+    assert_eq!(ip.subnet(33), None);
+    assert_eq!(ip.subnet(64), None);
+}
+
+#[test]
+fn it_masks_ipv6_subnets() {
+    let ip = addr("2001:db8:85a3:8d3:1319:8a2e:370:7348");
+
+    assert_eq!(ip.subnet(0), Some(addr("::")));
+    assert_eq!(ip.subnet(48), Some(addr("2001:db8:85a3::")));
+    assert_eq!(ip.subnet(63), Some(addr("2001:db8:85a3:8d2::")));
+    assert_eq!(ip.subnet(64), Some(addr("2001:db8:85a3:8d3::")));
+    assert_eq!(ip.subnet(128), Some(ip));
+    assert_eq!(ip.subnet(129), None);
+    assert_eq!(ip.subnet(255), None);
+
+    // Pseudo addresses have no width.
+    assert_eq!(
+        NetAddress::Unspecified.subnet(200),
+        Some(NetAddress::Unspecified)
+    );
+    assert_eq!(NetAddress::Unknown.subnet(24), Some(NetAddress::Unknown));
+}
+
+#[test]
+fn it_matches_subnets() {
+    let a = addr("10.1.2.3");
+    let b = addr("10.1.2.200");
+    let c = addr("10.1.3.3");
+
+    assert!(a.is_in_subnet(&b, 24));
+    assert!(!a.is_in_subnet(&c, 24));
+    assert!(a.is_in_subnet(&c, 16));
+    assert!(a.is_in_subnet(&c, 0));
+    assert!(!a.is_in_subnet(&b, 32));
+    assert!(a.is_in_subnet(&a, 32));
+
+    // Prefixes longer than the address compare the whole address.
+    assert!(a.is_in_subnet(&a, 200));
+    assert_eq!(a.subnet_key(200), a.subnet_key(32));
+
+    let v6 = addr("2001:db8::1");
+    assert!(v6.is_in_subnet(&addr("2001:db8::ffff"), 64));
+    assert!(v6.is_in_subnet(&v6, 128));
+    assert!(!v6.is_in_subnet(&addr("2001:db9::1"), 64));
+
+    // Different address families never share a subnet, not even with prefix 0.
+    assert!(!a.is_in_subnet(&v6, 0));
+    assert!(!a.is_in_subnet(&addr("::ffff:10.1.2.3"), 0));
+}
+
+#[test]
+fn it_handles_pseudo_addresses_in_subnets() {
+    let ip = addr("10.1.2.3");
+
+    assert!(NetAddress::Unspecified.is_in_subnet(&NetAddress::Unspecified, 24));
+    assert!(!NetAddress::Unspecified.is_in_subnet(&ip, 0));
+    assert!(!ip.is_in_subnet(&NetAddress::Unspecified, 0));
+
+    assert!(!NetAddress::Unknown.is_in_subnet(&NetAddress::Unknown, 0));
+    assert!(!NetAddress::Unknown.is_in_subnet(&ip, 0));
+    assert_eq!(NetAddress::Unknown.subnet_key(24), None);
+}
+
+#[test]
+fn it_groups_addresses_by_subnet_key() {
+    let mut counts = HashMap::new();
+    for ip in [
+        "10.1.2.3",
+        "10.1.2.4",
+        "10.1.3.3",
+        "2001:db8::1",
+        "2001:db8::2",
+    ] {
+        *counts.entry(addr(ip).subnet_key(24).unwrap()).or_insert(0) += 1;
+    }
+
+    assert_eq!(counts[&addr("10.1.2.77").subnet_key(24).unwrap()], 2);
+    assert_eq!(counts[&addr("10.1.3.0").subnet_key(24).unwrap()], 1);
+    assert_eq!(counts[&addr("2001:db8::").subnet_key(24).unwrap()], 2);
+
+    // Keys are ordered by address family, then by address.
+    let mut keys: Vec<_> = counts.keys().copied().collect();
+    keys.sort();
+    assert_eq!(
+        keys.iter().map(|key| key.to_string()).collect::<Vec<_>>(),
+        vec!["10.1.2.0/24", "10.1.3.0/24", "2001:d00::/24"]
+    );
+}
+
+#[test]
+fn it_classifies_private_addresses() {
+    for private in [
+        "10.0.0.0",
+        "10.255.255.255",
+        "172.16.0.1",
+        "172.31.255.255",
+        "192.168.1.1",
+        "169.254.10.20",
+        "fc00::1",
+        "fd12:3456:789a::1",
+        "fe80::1",
+        "febf:ffff::1",
+        "127.0.0.1",
+        "::1",
+    ] {
+        assert!(addr(private).is_private(), "{} should be private", private);
+    }
+
+    for public in [
+        "9.255.255.255",
+        "11.0.0.0",
+        "172.15.255.255",
+        "172.32.0.0",
+        "192.167.1.1",
+        "8.8.8.8",
+        "2001:db8::1",
+        "fec0::1",
+        "fbff::1",
+        "::",
+    ] {
+        assert!(
+            !addr(public).is_private(),
+            "{} shouldn't be private",
+            public
+        );
+    }
+
+    assert!(!NetAddress::Unspecified.is_private());
+    assert!(!NetAddress::Unknown.is_private());
+}
+
+#[test]
+fn it_classifies_loopback_addresses() {
+    for loopback in ["127.0.0.1", "127.255.255.254", "::1"] {
+        assert!(
+            addr(loopback).is_loopback(),
+            "{} should be loopback",
+            loopback
+        );
+    }
+
+    for other in ["128.0.0.1", "10.0.0.1", "::", "::2", "fe80::1"] {
+        assert!(!addr(other).is_loopback(), "{} isn't loopback", other);
+    }
+
+    assert!(!NetAddress::Unspecified.is_loopback());
+    assert!(!NetAddress::Unknown.is_loopback());
+}
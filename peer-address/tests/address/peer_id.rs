@@ -0,0 +1,119 @@
+use std::str::FromStr;
+
+use nimiq_hash::{Blake2bHasher, Hasher};
+use nimiq_keys::{KeyPair, SecureGenerate};
+use nimiq_peer_address::address::{PeerId, PeerIdError};
+use nimiq_test_log::test;
+
+#[test]
+fn it_can_display_and_parse_peer_ids() {
+    let peer_id = PeerId::from(&KeyPair::generate_default_csprng().public);
+
+    let hex = peer_id.to_string();
+    assert_eq!(hex.len(), 2 * PeerId::SIZE);
+    assert_eq!(hex, peer_id.to_hex());
+    assert_eq!(PeerId::from_str(&hex).unwrap(), peer_id);
+
+    assert_eq!(PeerId::ZERO.to_string(), "00000000000000000000000000000000");
+    assert_eq!(PeerId::ZERO, PeerId::default());
+}
+
+#[test]
+fn it_can_encode_and_decode_peer_ids_in_base58() {
+    let peer_id = PeerId::from(&KeyPair::generate_default_csprng().public);
+
+    let base58 = peer_id.to_base58();
+    assert_eq!(PeerId::from_base58(&base58).unwrap(), peer_id);
+
+    let peer_id = PeerId::from(&libp2p::PeerId::random());
+    assert_eq!(PeerId::from_base58(&peer_id.to_base58()).unwrap(), peer_id);
+
+    // Leading zero bytes are encoded as '1'.
+    assert_eq!(PeerId::ZERO.to_base58(), "1".repeat(PeerId::SIZE));
+    assert_eq!(
+        PeerId::from_base58(&PeerId::ZERO.to_base58()).unwrap(),
+        PeerId::ZERO
+    );
+
+    // The hex representation isn't valid base58 as it may contain '0'.
+    assert!(matches!(
+        PeerId::from_base58(&PeerId::ZERO.to_hex()),
+        Err(PeerIdError::InvalidBase58(_))
+    ));
+    assert_eq!(
+        PeerId::from_base58("2g"),
+        Err(PeerIdError::InvalidLength(1))
+    );
+}
+
+#[test]
+fn it_can_match_hex_prefixes() {
+    let peer_id = PeerId::from("0123456789abcdef0123456789abcdef");
+
+    assert!(peer_id.starts_with_hex(""));
+    assert!(peer_id.starts_with_hex("01"));
+    assert!(peer_id.starts_with_hex("0123456789ABCDEF"));
+    assert!(peer_id.starts_with_hex(&peer_id.to_hex()));
+
+    assert!(!peer_id.starts_with_hex("02"));
+    assert!(!peer_id.starts_with_hex("123"));
+    assert!(!peer_id.starts_with_hex(&format!("{}0", peer_id.to_hex())));
+}
+
+#[test]
+fn it_can_match_odd_length_hex_prefixes() {
+    let peer_id = PeerId::from("0123456789abcdef0123456789abcdef");
+
+    assert!(peer_id.starts_with_hex("0"));
+    assert!(peer_id.starts_with_hex("012"));
+    assert!(peer_id.starts_with_hex("0123456789aBcDe"));
+
+    assert!(!peer_id.starts_with_hex("1"));
+    assert!(!peer_id.starts_with_hex("013"));
+}
+
+#[test]
+fn it_rejects_invalid_hex_prefixes() {
+    let peer_id = PeerId::from("0123456789abcdef0123456789abcdef");
+
+    assert!(!peer_id.starts_with_hex("0x01"));
+    assert!(!peer_id.starts_with_hex("01g"));
+    assert!(!peer_id.starts_with_hex(" 01"));
+    assert!(!peer_id.starts_with_hex("0\u{663}"));
+}
+
+#[test]
+fn it_can_display_short_peer_ids() {
+    let peer_id = PeerId::from("0123456789abcdef0123456789abcdef");
+    assert_eq!(peer_id.to_short_string(), "0123..cdef");
+    assert_eq!(PeerId::ZERO.to_short_string(), "0000..0000");
+}
+
+#[test]
+fn it_can_convert_libp2p_peer_ids() {
+    let libp2p_peer_id = libp2p::PeerId::random();
+    let peer_id = PeerId::from(&libp2p_peer_id);
+
+    // The peer ID is derived from the hash of the multihash bytes.
+    let hash = Blake2bHasher::default().digest(&libp2p_peer_id.to_bytes());
+    assert_eq!(peer_id, PeerId::from(hash));
+    assert_eq!(PeerId::from(&libp2p_peer_id), peer_id);
+
+    // It survives a round trip through its string representation.
+    assert_eq!(PeerId::from_str(&peer_id.to_string()).unwrap(), peer_id);
+
+    assert_ne!(PeerId::from(&libp2p::PeerId::random()), peer_id);
+    assert_ne!(peer_id, PeerId::ZERO);
+}
+
+#[cfg(feature = "serde-derive")]
+#[test]
+fn it_can_serialize_peer_ids_with_serde() {
+    let peer_id = PeerId::from(&libp2p::PeerId::random());
+
+    let json = serde_json::to_string(&peer_id).unwrap();
+    assert_eq!(json, format!("\"{}\"", peer_id));
+    assert_eq!(serde_json::from_str::<PeerId>(&json).unwrap(), peer_id);
+
+    assert!(serde_json::from_str::<PeerId>("\"00ff\"").is_err());
+}
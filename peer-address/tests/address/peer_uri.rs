@@ -1,6 +1,10 @@
 use std::str::FromStr;
 
-use nimiq_peer_address::address::PeerUri;
+use libp2p::{Multiaddr, PeerId};
+use url::Url;
+
+use nimiq_peer_address::address::peer_uri::PeerUriError;
+use nimiq_peer_address::address::{PeerUri, SeedList};
 use nimiq_peer_address::protocol::Protocol;
 use nimiq_test_log::test;
 
@@ -98,3 +102,285 @@ fn test_parse_uri_ws() {
         Some(String::from("2b3f0f59334ef71ee7869b451139587f")).as_ref()
     );
 }
+
+#[test]
+fn test_multiaddr_dns_wss() {
+    let peer_id = PeerId::random();
+    let multiaddr = Multiaddr::from_str(&format!(
+        "/dns/seed1.nimiq.dev/tcp/8443/wss/p2p/{}",
+        peer_id
+    ))
+    .unwrap();
+
+    let uri = PeerUri::from_multiaddr(&multiaddr).unwrap();
+    assert_eq!(uri.protocol(), Protocol::Wss);
+    assert_eq!(
+        uri.hostname(),
+        Some(String::from("seed1.nimiq.dev")).as_ref()
+    );
+    assert_eq!(uri.port(), Some(8443));
+    assert_eq!(uri.peer_id(), Some(peer_id.to_base58()).as_ref());
+    assert_eq!(uri.to_multiaddr().unwrap(), multiaddr);
+
+    // The URI form can be parsed again.
+    let uri = PeerUri::from_str(&uri.to_string()).unwrap();
+    assert_eq!(uri.to_multiaddr().unwrap(), multiaddr);
+}
+
+#[test]
+fn test_multiaddr_ip6_ws() {
+    let peer_id = PeerId::random();
+    let multiaddr =
+        Multiaddr::from_str(&format!("/ip6/2001:db8::1/tcp/8080/ws/p2p/{}", peer_id)).unwrap();
+
+    let uri = PeerUri::from_multiaddr(&multiaddr).unwrap();
+    assert_eq!(uri.protocol(), Protocol::Ws);
+    assert_eq!(uri.hostname(), Some(String::from("[2001:db8::1]")).as_ref());
+    assert_eq!(uri.port(), Some(8080));
+    assert_eq!(uri.to_multiaddr().unwrap(), multiaddr);
+
+    let uri = PeerUri::from_str(&uri.to_string()).unwrap();
+    assert_eq!(uri.to_multiaddr().unwrap(), multiaddr);
+}
+
+#[test]
+fn test_multiaddr_without_peer_id() {
+    let multiaddr = Multiaddr::from_str("/ip4/127.0.0.1/tcp/8443/wss").unwrap();
+
+    let uri = PeerUri::from_str("/ip4/127.0.0.1/tcp/8443/wss").unwrap();
+    assert_eq!(uri.protocol(), Protocol::Wss);
+    assert_eq!(uri.hostname(), Some(String::from("127.0.0.1")).as_ref());
+    assert_eq!(uri.peer_id(), None);
+    assert_eq!(uri.to_multiaddr().unwrap(), multiaddr);
+
+    // Legacy URIs without a port use the default port of the protocol.
+    let uri = PeerUri::from_str("wss://seed-20.nimiq.com").unwrap();
+    assert_eq!(
+        uri.to_multiaddr().unwrap(),
+        Multiaddr::from_str("/dns/seed-20.nimiq.com/tcp/443/wss").unwrap()
+    );
+}
+
+#[test]
+fn test_multiaddr_invalid() {
+    let multiaddr = Multiaddr::from_str("/ip4/127.0.0.1/udp/8443").unwrap();
+    assert!(matches!(
+        PeerUri::from_multiaddr(&multiaddr),
+        Err(PeerUriError::UnsupportedMultiaddr(_))
+    ));
+
+    let uri = PeerUri::from_str("dumb://2b3f0f59334ef71ee7869b451139587f").unwrap();
+    assert!(matches!(
+        uri.to_multiaddr(),
+        Err(PeerUriError::MultiaddrWithInvalidProtocol)
+    ));
+
+    // Legacy peer IDs can't be mapped to libp2p peer IDs.
+    let uri =
+        PeerUri::from_str("wss://seed-20.nimiq.com/2b3f0f59334ef71ee7869b451139587f").unwrap();
+    assert!(matches!(
+        uri.to_multiaddr(),
+        Err(PeerUriError::InvalidPeerId)
+    ));
+}
+
+#[test]
+fn test_seed_list_multiaddrs() {
+    let mut seed_list = SeedList::new(Url::parse("https://nimiq.dev/seeds.txt").unwrap(), None);
+    seed_list.add_seeds(vec![
+        PeerUri::from_str("wss://seed1.nimiq.dev:8443").unwrap(),
+        PeerUri::from_str("dumb://2b3f0f59334ef71ee7869b451139587f").unwrap(),
+        PeerUri::from_str("ws://127.0.0.1:8080").unwrap(),
+    ]);
+
+    let multiaddrs: Vec<Multiaddr> = seed_list.multiaddrs().collect();
+    assert_eq!(
+        multiaddrs,
+        vec![
+            Multiaddr::from_str("/dns/seed1.nimiq.dev/tcp/8443/wss").unwrap(),
+            Multiaddr::from_str("/ip4/127.0.0.1/tcp/8080/ws").unwrap(),
+        ]
+    );
+}
+
+/// Generates valid peer URIs covering all protocols, host kinds, ports and peer identifiers.
+fn generate_valid_uris() -> Vec<String> {
+    let libp2p_peer_id = PeerId::random().to_base58();
+    let peer_ids = [
+        "2b3f0f59334ef71ee7869b451139587f".to_string(),
+        "2B3F0F59334EF71EE7869B451139587F".to_string(),
+        "00000000000000000000000000000000".to_string(),
+    ];
+    let paths = [
+        None,
+        Some(peer_ids[0].clone()),
+        Some("03a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8".to_string()),
+        Some(libp2p_peer_id.clone()),
+    ];
+    let hosts = ["seed1.nimiq.dev", "127.0.0.1", "[2001:db8::1]"];
+    let ports = [None, Some(1), Some(80), Some(443), Some(8443), Some(65535)];
+
+    let mut uris = vec![];
+
+    for protocol in ["dumb", "rtc"] {
+        for peer_id in &peer_ids {
+            uris.push(format!("{}://{}", protocol, peer_id));
+        }
+    }
+
+    for protocol in ["ws", "wss"] {
+        for host in hosts {
+            for port in ports {
+                for path in &paths {
+                    let mut uri = format!("{}://{}", protocol, host);
+                    if let Some(port) = port {
+                        uri.push_str(&format!(":{}", port));
+                    }
+                    if let Some(path) = path {
+                        uri.push_str(&format!("/{}", path));
+                    }
+                    uris.push(uri);
+                }
+            }
+        }
+    }
+
+    for protocol in ["ws", "wss"] {
+        for host in ["dns/seed1.nimiq.dev", "ip4/127.0.0.1", "ip6/2001:db8::1"] {
+            for port in [80, 443, 8443] {
+                uris.push(format!("/{}/tcp/{}/{}", host, port, protocol));
+                uris.push(format!(
+                    "/{}/tcp/{}/{}/p2p/{}",
+                    host, port, protocol, libp2p_peer_id
+                ));
+            }
+        }
+    }
+
+    uris
+}
+
+#[test]
+fn test_uri_round_trip() {
+    for s in generate_valid_uris() {
+        let uri = PeerUri::from_str(&s).unwrap_or_else(|e| panic!("Failed to parse {}: {}", s, e));
+        let string = uri.to_string();
+
+        assert_eq!(
+            PeerUri::from_str(&string).unwrap(),
+            uri,
+            "Round trip of {} via {} failed",
+            s,
+            string
+        );
+        assert_eq!(PeerUri::from_str(&string).unwrap().to_string(), string);
+    }
+}
+
+#[test]
+fn test_multiaddr_round_trip() {
+    let mut converted = 0;
+
+    for s in generate_valid_uris() {
+        let uri = PeerUri::from_str(&s).unwrap();
+
+        // Multiaddrs can't hold Nimiq peer IDs or public keys.
+        let multiaddr = match uri.to_multiaddr() {
+            Ok(multiaddr) => multiaddr,
+            Err(PeerUriError::MultiaddrWithInvalidProtocol) => {
+                assert!(matches!(uri.protocol(), Protocol::Dumb | Protocol::Rtc));
+                continue;
+            }
+            Err(PeerUriError::InvalidPeerId) => {
+                assert_eq!(uri.peer_id().unwrap().len(), 32);
+                continue;
+            }
+            Err(e) => panic!("Failed to convert {}: {}", s, e),
+        };
+        if uri.public_key().is_some() {
+            continue;
+        }
+
+        assert_eq!(
+            PeerUri::from_multiaddr(&multiaddr).unwrap(),
+            uri,
+            "Round trip of {} via {} failed",
+            s,
+            multiaddr
+        );
+        assert_eq!(
+            PeerUri::from_multiaddr(&multiaddr)
+                .unwrap()
+                .to_multiaddr()
+                .unwrap(),
+            multiaddr
+        );
+        converted += 1;
+    }
+
+    assert!(converted > 0);
+}
+
+#[test]
+fn test_parse_uri_unknown_protocol() {
+    assert!(matches!(
+        PeerUri::from_str("ftp://seed1.nimiq.dev"),
+        Err(PeerUriError::UnknownProtocol)
+    ));
+    assert!(matches!(
+        PeerUri::from_str("http://seed1.nimiq.dev:8443"),
+        Err(PeerUriError::UnknownProtocol)
+    ));
+    assert!(matches!(
+        PeerUri::from_str("/ip4/127.0.0.1/tcp/8443"),
+        Err(PeerUriError::UnknownProtocol)
+    ));
+}
+
+#[test]
+fn test_parse_uri_missing_host() {
+    assert!(matches!(
+        PeerUri::from_str("wss://:8443"),
+        Err(PeerUriError::MissingHostname)
+    ));
+    assert!(matches!(
+        PeerUri::from_str("/tcp/8443/wss"),
+        Err(PeerUriError::UnsupportedMultiaddr(_))
+    ));
+}
+
+#[test]
+fn test_parse_uri_invalid_port() {
+    assert!(matches!(
+        PeerUri::from_str("wss://seed1.nimiq.dev:65536"),
+        Err(PeerUriError::InvalidPort)
+    ));
+    assert!(matches!(
+        PeerUri::from_str("ws://seed1.nimiq.dev:port"),
+        Err(PeerUriError::InvalidPort)
+    ));
+    assert!(matches!(
+        PeerUri::from_str("dumb://2b3f0f59334ef71ee7869b451139587f:8443"),
+        Err(PeerUriError::UnexpectedPort)
+    ));
+}
+
+#[test]
+fn test_parse_uri_malformed_peer_id() {
+    assert!(matches!(
+        PeerUri::from_str("wss://seed1.nimiq.dev/not-a-peer-id"),
+        Err(PeerUriError::InvalidPeerId)
+    ));
+    assert!(matches!(
+        PeerUri::from_str("wss://seed1.nimiq.dev/2b3f0f59334ef71ee7869b451139587"),
+        Err(PeerUriError::InvalidPeerId)
+    ));
+    assert!(matches!(
+        PeerUri::from_str("dumb://not-a-peer-id"),
+        Err(PeerUriError::InvalidPeerId)
+    ));
+    assert!(matches!(
+        PeerUri::from_str("rtc://2b3f0f59334ef71ee7869b451139587f00"),
+        Err(PeerUriError::InvalidPeerId)
+    ));
+}
@@ -0,0 +1,198 @@
+use std::str::FromStr;
+
+use url::Url;
+
+use nimiq_keys::{KeyPair, PublicKey, SecureGenerate};
+use nimiq_peer_address::address::seed_list::{SeedList, SeedListError};
+use nimiq_peer_address::address::{PeerId, PeerUri};
+use nimiq_test_log::test;
+
+const VALID: &str = include_str!("fixtures/seed_list_valid.txt");
+const TAMPERED: &str = include_str!("fixtures/seed_list_tampered.txt");
+const UNSIGNED: &str = include_str!("fixtures/seed_list_unsigned.txt");
+
+fn url() -> Url {
+    Url::parse("https://nimiq.dev/seeds.txt").unwrap()
+}
+
+fn signer() -> PublicKey {
+    PublicKey::from_str("03a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8").unwrap()
+}
+
+#[test]
+fn it_accepts_valid_signed_list() {
+    let seed_list = SeedList::parse(url(), VALID, Some(&signer())).unwrap();
+
+    assert_eq!(seed_list.seeds().len(), 3);
+    assert_eq!(seed_list.skipped(), 1);
+    assert_eq!(seed_list.public_key(), &Some(signer()));
+    assert_eq!(seed_list.multiaddrs().count(), 3);
+}
+
+#[test]
+fn it_rejects_tampered_list() {
+    assert!(matches!(
+        SeedList::parse(url(), TAMPERED, Some(&signer())),
+        Err(SeedListError::InvalidSignature)
+    ));
+
+    // Without an expected signer, the signature isn't checked.
+    let seed_list = SeedList::parse(url(), TAMPERED, None).unwrap();
+    assert_eq!(seed_list.seeds().len(), 3);
+}
+
+#[test]
+fn it_rejects_unsigned_list() {
+    assert!(matches!(
+        SeedList::parse(url(), UNSIGNED, Some(&signer())),
+        Err(SeedListError::MissingSignature)
+    ));
+
+    let seed_list = SeedList::parse(url(), UNSIGNED, None).unwrap();
+    assert_eq!(seed_list.seeds().len(), 3);
+    assert_eq!(seed_list.skipped(), 1);
+}
+
+#[test]
+fn it_rejects_list_without_valid_seeds() {
+    assert!(matches!(
+        SeedList::parse(url(), "# Empty\nnot a peer uri\n\n", None),
+        Err(SeedListError::NoValidSeeds)
+    ));
+}
+
+#[test]
+fn it_validates_signed_lists() {
+    let seed_list = SeedList::parse(url(), VALID, Some(&signer())).unwrap();
+    assert!(seed_list.validate(&signer()));
+    assert!(!seed_list.validate(&KeyPair::generate_default_csprng().public));
+
+    // The signature is kept even if the signer wasn't checked while parsing.
+    let seed_list = SeedList::parse(url(), VALID, None).unwrap();
+    assert!(seed_list.validate(&signer()));
+}
+
+#[test]
+fn it_does_not_validate_tampered_or_unsigned_lists() {
+    let seed_list = SeedList::parse(url(), TAMPERED, None).unwrap();
+    assert!(!seed_list.validate(&signer()));
+
+    let seed_list = SeedList::parse(url(), UNSIGNED, None).unwrap();
+    assert!(!seed_list.validate(&signer()));
+
+    assert!(!SeedList::new(url(), Some(signer())).validate(&signer()));
+
+    // Seeds added after parsing aren't covered by the signature.
+    let mut seed_list = SeedList::parse(url(), VALID, Some(&signer())).unwrap();
+    seed_list.add_seeds(vec![
+        PeerUri::from_str("wss://evil.example.com:8443").unwrap()
+    ]);
+    assert!(!seed_list.validate(&signer()));
+}
+
+fn seed_list(seeds: &[&str]) -> SeedList {
+    let mut seed_list = SeedList::new(url(), None);
+    seed_list.add_seeds(seeds.iter().map(|seed| PeerUri::from_str(seed).unwrap()));
+    seed_list
+}
+
+#[test]
+fn it_merges_lists_without_duplicates() {
+    let built_in = seed_list(&[
+        "wss://seed1.nimiq.dev:8443",
+        "wss://seed2.nimiq.dev:8443",
+        "wss://seed3.nimiq.dev:8443",
+    ]);
+    let custom = seed_list(&[
+        "wss://seed4.nimiq.dev:8443",
+        "wss://seed2.nimiq.dev:8443",
+        "wss://seed1.nimiq.dev:8443",
+        "wss://seed5.nimiq.dev:8443",
+        "wss://seed4.nimiq.dev:8443",
+    ]);
+
+    let merged = SeedList::from_sources(vec![built_in.clone(), custom.clone()]);
+    let seeds: Vec<String> = merged.seeds().iter().map(ToString::to_string).collect();
+    assert_eq!(
+        seeds,
+        vec![
+            "wss://seed1.nimiq.dev:8443",
+            "wss://seed2.nimiq.dev:8443",
+            "wss://seed3.nimiq.dev:8443",
+            "wss://seed4.nimiq.dev:8443",
+            "wss://seed5.nimiq.dev:8443",
+        ]
+    );
+
+    // The first list takes precedence.
+    let merged = SeedList::from_sources(vec![custom, built_in.clone()]);
+    let seeds: Vec<String> = merged.seeds().iter().map(ToString::to_string).collect();
+    assert_eq!(
+        seeds,
+        vec![
+            "wss://seed4.nimiq.dev:8443",
+            "wss://seed2.nimiq.dev:8443",
+            "wss://seed1.nimiq.dev:8443",
+            "wss://seed5.nimiq.dev:8443",
+            "wss://seed3.nimiq.dev:8443",
+        ]
+    );
+
+    // A single source is deduplicated as well.
+    assert_eq!(SeedList::from_sources(vec![built_in]).seeds().len(), 3);
+}
+
+#[test]
+fn it_merges_seeds_that_are_written_differently() {
+    let peer_id = PeerId::from(&signer()).to_hex();
+
+    let built_in = seed_list(&[
+        "wss://seed1.nimiq.dev:8443",
+        &format!("wss://seed2.nimiq.dev:8443/{}", peer_id),
+        &format!("wss://seed3.nimiq.dev:8443/{}", peer_id),
+    ]);
+
+    let mut custom = seed_list(&[
+        // The hostname differs in case.
+        "/dns/Seed1.Nimiq.dev/tcp/8443/wss",
+        // The peer ID is written in uppercase.
+        &format!("wss://seed2.nimiq.dev:8443/{}", peer_id.to_uppercase()),
+        // The public key is given instead of the peer ID.
+        &format!("wss://seed3.nimiq.dev:8443/{}", signer().to_hex()),
+        // A different peer at a known address is not a duplicate.
+        &format!("wss://seed1.nimiq.dev:8443/{}", peer_id),
+    ]);
+    // The default port is given explicitly.
+    custom.add_seeds(vec![
+        PeerUri::new_wss("seed4.nimiq.dev".to_string(), None, None, None),
+        PeerUri::new_wss("seed4.nimiq.dev".to_string(), Some(443), None, None),
+    ]);
+
+    let merged = SeedList::from_sources(vec![built_in, custom]);
+    let seeds: Vec<String> = merged.seeds().iter().map(ToString::to_string).collect();
+    assert_eq!(
+        seeds,
+        vec![
+            "wss://seed1.nimiq.dev:8443".to_string(),
+            format!("wss://seed2.nimiq.dev:8443/{}", peer_id),
+            format!("wss://seed3.nimiq.dev:8443/{}", peer_id),
+            format!("wss://seed1.nimiq.dev:8443/{}", peer_id),
+            "wss://seed4.nimiq.dev".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn it_keeps_the_signature_if_merging_adds_no_seeds() {
+    let mut seed_list = SeedList::parse(url(), VALID, Some(&signer())).unwrap();
+    let skipped = seed_list.skipped();
+
+    seed_list.merge(SeedList::parse(url(), VALID, None).unwrap());
+    assert_eq!(seed_list.seeds().len(), 3);
+    assert_eq!(seed_list.skipped(), 2 * skipped);
+    assert!(seed_list.validate(&signer()));
+
+    seed_list.merge(self::seed_list(&["wss://evil.example.com:8443"]));
+    assert_eq!(seed_list.seeds().len(), 4);
+    assert!(!seed_list.validate(&signer()));
+}
@@ -58,6 +58,15 @@ impl Account {
         }
     }
 
+    /// Returns the part of the balance that can be spent at the given time. Only vesting contracts
+    /// lock part of their balance, for all other accounts this is the entire balance.
+    pub fn available_balance(&self, time: u64) -> Coin {
+        match *self {
+            Account::Vesting(ref account) => account.available_balance(time),
+            _ => self.balance(),
+        }
+    }
+
     pub fn balance_add(balance: Coin, value: Coin) -> Result<Coin, AccountError> {
         balance
             .checked_add(value)
@@ -67,9 +76,9 @@ impl Account {
     pub fn balance_sub(balance: Coin, value: Coin) -> Result<Coin, AccountError> {
         match balance.checked_sub(value) {
             Some(result) => Ok(result),
-            None => Err(AccountError::InsufficientFunds {
-                balance,
-                needed: value,
+            None => Err(AccountError::InsufficientFundsDetail {
+                available: u64::from(balance),
+                required: u64::from(value),
             }),
         }
     }
@@ -365,6 +374,18 @@ impl AccountTransactionInteraction for Account {
         }
     }
 
+    fn can_pay(&self, transaction: &Transaction, block_time: u64) -> Result<(), AccountError> {
+        match &self {
+            Account::Basic(account) => BasicAccount::can_pay(account, transaction, block_time),
+            Account::Vesting(account) => VestingContract::can_pay(account, transaction, block_time),
+            Account::HTLC(account) => {
+                HashedTimeLockedContract::can_pay(account, transaction, block_time)
+            }
+            Account::Staking(account) => StakingContract::can_pay(account, transaction, block_time),
+            _ => Err(AccountError::InvalidForSender),
+        }
+    }
+
     fn delete(
         accounts_tree: &AccountsTrie,
         db_txn: &mut WriteTransaction,
@@ -2,14 +2,16 @@ use nimiq_database::{
     Environment, ReadTransaction, Transaction as DBTransaction, WriteTransaction,
 };
 use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_keys::Address;
 use nimiq_transaction::{ExecutedTransaction, Transaction, TransactionFlags};
 use nimiq_trie::key_nibbles::KeyNibbles;
 use nimiq_trie::trie::MerkleRadixTrie;
+use nimiq_trie::trie_chunk::TrieChunk;
 
 use crate::{
     logs::{BatchInfo, TransactionLog},
-    Account, AccountError, AccountInherentInteraction, AccountTransactionInteraction, Inherent,
-    Log, Receipt, Receipts, RevertTransactionLogs, TransactionInfo,
+    Account, AccountError, AccountInherentInteraction, AccountTransactionInteraction,
+    AccountsProof, Inherent, Log, Receipt, Receipts, RevertTransactionLogs, TransactionInfo,
 };
 
 /// An alias for the accounts tree.
@@ -61,6 +63,28 @@ impl Accounts {
         }
     }
 
+    /// Returns the accounts at the given addresses, in the same order. All accounts are read
+    /// within the same database transaction.
+    pub fn get_multiple(
+        &self,
+        addresses: &[Address],
+        txn_option: Option<&DBTransaction>,
+    ) -> Vec<Option<Account>> {
+        let get_all = |txn: &DBTransaction| {
+            addresses
+                .iter()
+                .map(|address| self.tree.get(txn, &KeyNibbles::from(address)))
+                .collect()
+        };
+
+        match txn_option {
+            Some(txn) => get_all(txn),
+            None => get_all(&ReadTransaction::new(&self.env)),
+        }
+    }
+
+    /// Returns the root hash of the accounts trie. The hashes of the trie nodes are stored in the
+    /// trie and committing only re-hashes the modified subtrees, so this doesn't traverse the trie.
     pub fn get_root(&self, txn_option: Option<&DBTransaction>) -> Blake2bHash {
         match txn_option {
             Some(txn) => self.tree.root_hash(txn),
@@ -68,6 +92,59 @@ impl Accounts {
         }
     }
 
+    /// Produces a proof of the accounts at the given addresses, including the ones that don't
+    /// exist, that can be verified against the root returned by `get_root`.
+    pub fn get_proof(
+        &self,
+        addresses: &[Address],
+        txn_option: Option<&DBTransaction>,
+    ) -> AccountsProof {
+        let keys: Vec<KeyNibbles> = addresses.iter().map(KeyNibbles::from).collect();
+
+        let proof = match txn_option {
+            Some(txn) => self.tree.get_membership_proof(txn, keys.iter().collect()),
+            None => self
+                .tree
+                .get_membership_proof(&ReadTransaction::new(&self.env), keys.iter().collect()),
+        };
+
+        AccountsProof {
+            addresses: addresses.to_vec(),
+            proof,
+        }
+    }
+
+    /// Returns a chunk of at most `max_nodes` accounts, starting at the key `start_key`, together
+    /// with a proof for it. This is used to sync the accounts trie chunk by chunk.
+    pub fn get_chunk(
+        &self,
+        start_key: &KeyNibbles,
+        max_nodes: usize,
+        txn_option: Option<&DBTransaction>,
+    ) -> TrieChunk<Account> {
+        match txn_option {
+            Some(txn) => self.tree.get_chunk_with_proof(txn, start_key, max_nodes),
+            None => self.tree.get_chunk_with_proof(
+                &ReadTransaction::new(&self.env),
+                start_key,
+                max_nodes,
+            ),
+        }
+    }
+
+    /// Verifies a chunk of accounts against the state root `expected_root` and adds it to the
+    /// accounts trie. The chunk must continue right after the accounts that were already committed.
+    /// Once the last chunk is committed, the root of the accounts trie equals `expected_root`.
+    pub fn commit_chunk(
+        &self,
+        txn: &mut WriteTransaction,
+        chunk: TrieChunk<Account>,
+        expected_root: &Blake2bHash,
+    ) -> Result<(), AccountError> {
+        self.tree.put_chunk(txn, chunk, expected_root)?;
+        Ok(())
+    }
+
     pub fn exercise_transactions(
         &self,
         transactions: &[Transaction],
@@ -217,7 +294,12 @@ impl Accounts {
                             data: sender_info.receipt,
                         });
 
-                        sender_logs.push(TransactionLog::new(transaction.hash(), sender_info.logs));
+                        sender_logs.push(TransactionLog::new(
+                            transaction.hash(),
+                            index as u16,
+                            transaction.fee,
+                            sender_info.logs,
+                        ));
                     }
 
                     // Process recipients logs (if any)
@@ -228,14 +310,23 @@ impl Accounts {
                             data: recipient_info.receipt,
                         });
 
-                        recipient_logs
-                            .push(TransactionLog::new(transaction.hash(), recipient_info.logs));
+                        recipient_logs.push(TransactionLog::new(
+                            transaction.hash(),
+                            index as u16,
+                            transaction.fee,
+                            recipient_info.logs,
+                        ));
                     }
 
                     // Process create logs (if any)
                     if let Some(info) = txn_info.create_info {
                         // Note that create contract transactions do not generate receipts
-                        create_logs.push(TransactionLog::new(transaction.hash(), info.logs));
+                        create_logs.push(TransactionLog::new(
+                            transaction.hash(),
+                            index as u16,
+                            transaction.fee,
+                            info.logs,
+                        ));
                     }
 
                     // Store the sucessful executed transaction
@@ -275,11 +366,16 @@ impl Accounts {
                                 data: None,
                             });
 
-                            sender_logs.push(TransactionLog::new(transaction.hash(), logs));
+                            sender_logs.push(TransactionLog::new(
+                                transaction.hash(),
+                                index as u16,
+                                transaction.fee,
+                                logs,
+                            ));
                         }
                         Err(err) => {
                             // This should not happen, as the mempool should verify that the sender can pay the fee
-                            return Err(err);
+                            return Err(err.at(transaction.sender.clone()));
                         }
                     }
                 }
@@ -489,6 +585,10 @@ impl Accounts {
 
         //Iterate the transactions in the reverse order they were applied
         for (index, transaction) in transactions.iter().rev().enumerate() {
+            // The index of the transaction within the block.
+            let tx_index = ((transactions.len() - 1) - index) as u16;
+            let fee = transaction.get_raw_transaction().fee;
+
             // Sender receipts are always present, however a recipient receipt might or might not be present:
             // it is not present when the transaction is a contract creation transaction, so we need to track
             // recipient receipts using a different index,
@@ -500,7 +600,7 @@ impl Accounts {
                     sender: _,
                     data: _,
                 } => {
-                    assert_eq!((transactions.len() - 1) - index, receipt_index as usize)
+                    assert_eq!(tx_index, receipt_index)
                 }
                 _ => panic!(" Only transaction receipts should be present "),
             }
@@ -516,6 +616,8 @@ impl Accounts {
 
                         contracts_logs.push(TransactionLog::new(
                             transaction.hash(),
+                            tx_index,
+                            fee,
                             vec![Log::RevertContract {
                                 contract_address: transaction.recipient.clone(),
                             }],
@@ -535,7 +637,7 @@ impl Accounts {
                         sender: _,
                         data: _,
                     } => {
-                        assert_eq!((transactions.len() - 1) - index, receipt_index as usize)
+                        assert_eq!(tx_index, receipt_index)
                     }
                     _ => panic!(" Only transaction receipts should be present "),
                 }
@@ -554,12 +656,16 @@ impl Accounts {
             if !logs.recipient_log.is_empty() {
                 recipients_logs.push(TransactionLog::new(
                     transaction.get_raw_transaction().hash(),
+                    tx_index,
+                    fee,
                     logs.recipient_log,
                 ));
             }
 
             senders_logs.push(TransactionLog::new(
                 transaction.get_raw_transaction().hash(),
+                tx_index,
+                fee,
                 logs.sender_log,
             ));
         }
@@ -598,7 +704,8 @@ impl Accounts {
 
         for (index, inherent) in inherents.iter().enumerate() {
             let mut account_info =
-                Account::commit_inherent(&self.tree, txn, inherent, block_height, timestamp)?;
+                Account::commit_inherent(&self.tree, txn, inherent, block_height, timestamp)
+                    .map_err(|err| err.at(inherent.target.clone()))?;
 
             receipts.push(Receipt::Inherent {
                 index: index as u16,
@@ -624,14 +731,19 @@ impl Accounts {
 
         for receipt in receipts {
             inherent_logs.append(&mut match receipt {
-                Receipt::Inherent { index, data, .. } => Account::revert_inherent(
-                    &self.tree,
-                    txn,
-                    &inherents[index as usize],
-                    block_height,
-                    timestamp,
-                    data.as_ref(),
-                )?,
+                Receipt::Inherent { index, data, .. } => {
+                    let inherent = &inherents[index as usize];
+
+                    Account::revert_inherent(
+                        &self.tree,
+                        txn,
+                        inherent,
+                        block_height,
+                        timestamp,
+                        data.as_ref(),
+                    )
+                    .map_err(|err| err.at(inherent.target.clone()))?
+                }
                 _ => {
                     unreachable!()
                 }
@@ -705,7 +817,7 @@ impl Accounts {
 
         for tx_log_sender in senders_logs.iter_mut() {
             if let Some(tx_log) = tx_logs_recipients.peek_mut() {
-                if tx_log.tx_hash == tx_log_sender.tx_hash {
+                if tx_log.index == tx_log_sender.index {
                     tx_log_sender.logs.append(&mut tx_log.logs);
                     tx_logs_recipients.next();
                     // The transactions are wither a create or anything else (mutually exclusive), thus we can avoid comparing to the tx_create.
@@ -714,7 +826,7 @@ impl Accounts {
             }
 
             if let Some(tx_log) = tx_logs_create.peek_mut() {
-                if tx_log.tx_hash == tx_log_sender.tx_hash {
+                if tx_log.index == tx_log_sender.index {
                     tx_log_sender.logs.append(&mut tx_log.logs);
                     tx_logs_create.next();
                 }
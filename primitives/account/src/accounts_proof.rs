@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+
+use beserial::{Deserialize, Serialize};
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_trie::key_nibbles::KeyNibbles;
+use nimiq_trie::trie_proof::TrieProof;
+
+use crate::{Account, AccountsProofError};
+
+/// A proof of the state of a list of accounts. It contains the addresses of the accounts together
+/// with the nodes of the accounts trie that are needed to verify them against the state root of a
+/// block. Accounts that don't exist are proven to be absent.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AccountsProof {
+    #[beserial(len_type(u16))]
+    pub addresses: Vec<Address>,
+    pub proof: TrieProof<Account>,
+}
+
+impl AccountsProof {
+    /// Verifies the proof against the given root hash and returns, for each of the addresses, the
+    /// account at that address or None if there's no such account.
+    pub fn verify(
+        &self,
+        root_hash: &Blake2bHash,
+    ) -> Result<Vec<(Address, Option<Account>)>, AccountsProofError> {
+        if !self.proof.verify(root_hash) {
+            return Err(AccountsProofError::InvalidProof);
+        }
+
+        // The proof is a valid sub-trie, so we can just walk it down to each of the addresses.
+        let nodes: BTreeMap<_, _> = self
+            .proof
+            .nodes
+            .iter()
+            .map(|node| (node.key(), node))
+            .collect();
+
+        self.addresses
+            .iter()
+            .map(|address| {
+                let key = KeyNibbles::from(address);
+                let mut cur_node = nodes[&KeyNibbles::root()];
+
+                while cur_node.key() != &key && cur_node.is_branch() {
+                    match cur_node.get_child_key(&key) {
+                        Ok(child_key) if child_key.is_prefix_of(&key) => {
+                            cur_node = nodes.get(&child_key).copied().ok_or_else(|| {
+                                AccountsProofError::IncompleteProof {
+                                    address: address.clone(),
+                                }
+                            })?;
+                        }
+                        // There's no child on the path to the address, so the account doesn't
+                        // exist.
+                        _ => return Ok((address.clone(), None)),
+                    }
+                }
+
+                if cur_node.key() == &key {
+                    Ok((address.clone(), cur_node.value().ok()))
+                } else {
+                    Ok((address.clone(), None))
+                }
+            })
+            .collect()
+    }
+}
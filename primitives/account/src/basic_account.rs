@@ -18,6 +18,32 @@ pub struct BasicAccount {
     pub balance: Coin,
 }
 
+impl BasicAccount {
+    /// Returns true if the account can be removed from the accounts trie. A basic account is
+    /// fully described by its balance, so an empty account is equivalent to a missing one and can
+    /// be recreated from the balance alone when reverting.
+    pub fn is_prunable(&self) -> bool {
+        self.balance.is_zero()
+    }
+
+    /// Stores a basic account with the given balance, removing it from the accounts trie instead
+    /// if it is prunable.
+    fn put_or_prune(
+        accounts_tree: &AccountsTrie,
+        db_txn: &mut WriteTransaction,
+        key: &KeyNibbles,
+        balance: Coin,
+    ) {
+        let account = BasicAccount { balance };
+
+        if account.is_prunable() {
+            accounts_tree.remove(db_txn, key);
+        } else {
+            accounts_tree.put(db_txn, key, Account::Basic(account));
+        }
+    }
+}
+
 impl AccountTransactionInteraction for BasicAccount {
     fn create(
         _accounts_tree: &AccountsTrie,
@@ -88,17 +114,7 @@ impl AccountTransactionInteraction for BasicAccount {
 
         let new_balance = Account::balance_sub(account.balance(), transaction.value)?;
 
-        if new_balance.is_zero() {
-            accounts_tree.remove(db_txn, &key);
-        } else {
-            accounts_tree.put(
-                db_txn,
-                &key,
-                Account::Basic(BasicAccount {
-                    balance: new_balance,
-                }),
-            );
-        }
+        BasicAccount::put_or_prune(accounts_tree, db_txn, &key, new_balance);
 
         Ok(Vec::new())
     }
@@ -136,17 +152,7 @@ impl AccountTransactionInteraction for BasicAccount {
 
         let new_balance = Account::balance_sub(account.balance(), transaction.total_value())?;
 
-        if new_balance.is_zero() {
-            accounts_tree.remove(db_txn, &key);
-        } else {
-            accounts_tree.put(
-                db_txn,
-                &key,
-                Account::Basic(BasicAccount {
-                    balance: new_balance,
-                }),
-            );
-        }
+        BasicAccount::put_or_prune(accounts_tree, db_txn, &key, new_balance);
 
         let logs = vec![
             Log::PayFee {
@@ -195,18 +201,8 @@ impl AccountTransactionInteraction for BasicAccount {
                 amount: transaction.value,
             },
         ];
-        // If the new balance is zero, it means this account didnt exist before, so we don't need to create it.
-        if new_balance == Coin::ZERO {
-            return Ok(logs);
-        }
-
-        accounts_tree.put(
-            db_txn,
-            &key,
-            Account::Basic(BasicAccount {
-                balance: new_balance,
-            }),
-        );
+        // If the new balance is zero, this account didn't exist before, so it isn't recreated.
+        BasicAccount::put_or_prune(accounts_tree, db_txn, &key, new_balance);
 
         Ok(logs)
     }
@@ -243,17 +239,7 @@ impl AccountTransactionInteraction for BasicAccount {
 
         let new_balance = Account::balance_sub(account.balance(), transaction.fee)?;
 
-        if new_balance.is_zero() {
-            accounts_tree.remove(db_txn, &key);
-        } else {
-            accounts_tree.put(
-                db_txn,
-                &key,
-                Account::Basic(BasicAccount {
-                    balance: new_balance,
-                }),
-            );
-        }
+        BasicAccount::put_or_prune(accounts_tree, db_txn, &key, new_balance);
 
         let logs = vec![Log::PayFee {
             from: transaction.sender.clone(),
@@ -286,18 +272,8 @@ impl AccountTransactionInteraction for BasicAccount {
             from: transaction.sender.clone(),
             fee: transaction.fee,
         }];
-        // If the new balance is zero, it means this account didnt exist before, so we don't need to create it.
-        if new_balance == Coin::ZERO {
-            return Ok(logs);
-        }
-
-        accounts_tree.put(
-            db_txn,
-            &key,
-            Account::Basic(BasicAccount {
-                balance: new_balance,
-            }),
-        );
+        // If the new balance is zero, this account didn't exist before, so it isn't recreated.
+        BasicAccount::put_or_prune(accounts_tree, db_txn, &key, new_balance);
 
         Ok(logs)
     }
@@ -311,6 +287,12 @@ impl AccountTransactionInteraction for BasicAccount {
         Account::balance_sub(self.balance, mempool_balance).is_ok()
     }
 
+    fn can_pay(&self, transaction: &Transaction, _block_time: u64) -> Result<(), AccountError> {
+        let total = Account::balance_add(transaction.value, transaction.fee)?;
+        Account::balance_sub(self.balance, total)?;
+        Ok(())
+    }
+
     fn delete(
         _accounts_tree: &AccountsTrie,
         _db_txn: &mut WriteTransaction,
@@ -384,13 +366,7 @@ impl AccountInherentInteraction for BasicAccount {
 
         let new_balance = Account::balance_sub(account.balance(), inherent.value)?;
 
-        accounts_tree.put(
-            db_txn,
-            &key,
-            Account::Basic(BasicAccount {
-                balance: new_balance,
-            }),
-        );
+        BasicAccount::put_or_prune(accounts_tree, db_txn, &key, new_balance);
 
         Ok(vec![Log::PayoutReward {
             to: inherent.target.clone(),
@@ -0,0 +1,82 @@
+use nimiq_database::Transaction as DBTransaction;
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_trie::error::MerkleRadixTrieError;
+
+use crate::{Account, AccountsTrie};
+
+/// A change of a single entry of the accounts trie between two states of the trie. Entries are
+/// identified by the address of the account they belong to, so all entries of the staking
+/// contract (e.g. its validators and stakers) carry the address of the staking contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccountDiff {
+    Created(Address, Account),
+    Updated {
+        address: Address,
+        before: Account,
+        after: Account,
+    },
+    Deleted(Address, Account),
+}
+
+impl AccountDiff {
+    /// Returns the address of the changed entry.
+    pub fn address(&self) -> &Address {
+        match self {
+            AccountDiff::Created(address, _) => address,
+            AccountDiff::Updated { address, .. } => address,
+            AccountDiff::Deleted(address, _) => address,
+        }
+    }
+}
+
+/// Computes the changes of the accounts trie between two of its states.
+pub trait AccountsTrieDiff {
+    /// Returns the entries that differ between the state with root `before_root` and the state
+    /// with root `after_root`, ordered by their key in the trie. The nodes of the trie are stored
+    /// by key, so each state has to be read through a database transaction that sees it, e.g. a
+    /// read transaction opened before committing a block and one opened afterwards.
+    /// Fails with `RootHashMismatch` if a transaction doesn't see the given root.
+    fn diff(
+        &self,
+        before_root: &Blake2bHash,
+        after_root: &Blake2bHash,
+        before_txn: &DBTransaction,
+        after_txn: &DBTransaction,
+    ) -> Result<Vec<AccountDiff>, MerkleRadixTrieError>;
+}
+
+impl AccountsTrieDiff for AccountsTrie {
+    fn diff(
+        &self,
+        before_root: &Blake2bHash,
+        after_root: &Blake2bHash,
+        before_txn: &DBTransaction,
+        after_txn: &DBTransaction,
+    ) -> Result<Vec<AccountDiff>, MerkleRadixTrieError> {
+        if &self.root_hash(before_txn) != before_root || &self.root_hash(after_txn) != after_root {
+            return Err(MerkleRadixTrieError::RootHashMismatch);
+        }
+
+        let diff = self
+            .diff_leaves(before_txn, after_txn)
+            .into_iter()
+            .filter_map(|(key, before, after)| {
+                // The staking contract keeps its entries below its own address.
+                let address = Address::from(&key.as_bytes()[..Address::SIZE]);
+                match (before, after) {
+                    (None, Some(after)) => Some(AccountDiff::Created(address, after)),
+                    (Some(before), Some(after)) => Some(AccountDiff::Updated {
+                        address,
+                        before,
+                        after,
+                    }),
+                    (Some(before), None) => Some(AccountDiff::Deleted(address, before)),
+                    (None, None) => None,
+                }
+            })
+            .collect();
+
+        Ok(diff)
+    }
+}
@@ -5,11 +5,12 @@ use nimiq_keys::Address;
 use nimiq_primitives::account::AccountType;
 use nimiq_primitives::coin::{Coin, CoinConvertError, CoinParseError};
 use nimiq_transaction::TransactionError;
+use nimiq_trie::error::MerkleRadixTrieError;
 
 #[derive(Debug, Error, PartialEq, Eq)]
 pub enum AccountError {
-    #[error("Insufficient funds: needed {needed}, but has balance {balance}")]
-    InsufficientFunds { needed: Coin, balance: Coin },
+    #[error("Insufficient funds: {required} Luna required, but only {available} Luna available")]
+    InsufficientFundsDetail { available: u64, required: u64 },
     #[error("Type mismatch: expected {expected}, but got {got}")]
     TypeMismatch {
         expected: AccountType,
@@ -25,6 +26,8 @@ pub enum AccountError {
     InvalidForTarget,
     #[error("Invalid receipt")]
     InvalidReceipt,
+    #[error("Receipts incorrectly ordered")]
+    ReceiptsNotOrdered,
     #[error("Invalid serialization")]
     InvalidSerialization(#[from] SerializingError),
     #[error("Invalid transaction")]
@@ -41,4 +44,46 @@ pub enum AccountError {
     NonExistentAddress { address: Address },
     #[error("There is already an account at address {address} in the Accounts Tree.")]
     AlreadyExistentAddress { address: Address },
+    #[error("Invalid accounts chunk: {0}")]
+    InvalidChunk(#[from] MerkleRadixTrieError),
+    #[error("Invalid HTLC proof type")]
+    HTLCInvalidProofType,
+    #[error("HTLC hash algorithm or hash root doesn't match the contract")]
+    HTLCHashMismatch,
+    #[error("Invalid HTLC hash depth: {hash_depth} exceeds the hash count {hash_count}")]
+    HTLCInvalidHashDepth { hash_depth: u8, hash_count: u8 },
+    #[error("HTLC pre-image doesn't hash to the hash root")]
+    HTLCInvalidPreImage,
+    #[error("HTLC has expired: timeout {timeout} < block time {block_time}")]
+    HTLCExpired { timeout: u64, block_time: u64 },
+    #[error("HTLC has not yet expired: timeout {timeout} >= block time {block_time}")]
+    HTLCNotExpired { timeout: u64, block_time: u64 },
+    #[error("{source} at address {address}")]
+    WithContext {
+        address: Address,
+        source: Box<AccountError>,
+    },
+}
+
+impl AccountError {
+    /// Attaches the address of the account that caused the error. If the error already carries
+    /// an address, it is returned unchanged.
+    #[must_use]
+    pub fn at(self, address: Address) -> AccountError {
+        match self {
+            AccountError::WithContext { .. } => self,
+            _ => AccountError::WithContext {
+                address,
+                source: Box::new(self),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AccountsProofError {
+    #[error("The proof is not a valid sub-trie of the given root hash.")]
+    InvalidProof,
+    #[error("The proof doesn't contain the nodes needed for address {address}.")]
+    IncompleteProof { address: Address },
 }
@@ -1,6 +1,6 @@
 use std::convert::TryFrom;
 
-use beserial::{Deserialize, Serialize};
+use beserial::{Deserialize, ReadBytesExt, Serialize, SerializingError};
 use nimiq_database::WriteTransaction;
 use nimiq_keys::Address;
 use nimiq_primitives::account::*;
@@ -16,7 +16,7 @@ use crate::interaction_traits::{AccountInherentInteraction, AccountTransactionIn
 use crate::logs::{AccountInfo, Log};
 use crate::{Account, AccountError, AccountsTrie, BasicAccount};
 
-#[derive(Clone, PartialEq, PartialOrd, Eq, Ord, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, PartialOrd, Eq, Ord, Debug, Serialize)]
 #[cfg_attr(feature = "serde-derive", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde-derive", serde(rename_all = "camelCase"))]
 pub struct HashedTimeLockedContract {
@@ -67,6 +67,33 @@ impl HashedTimeLockedContract {
         }
     }
 
+    /// Returns true if the contract has timed out at the given block time. From then on, the funds
+    /// can only be returned to the sender.
+    pub fn is_expired(&self, block_time: u64) -> bool {
+        self.timeout < block_time
+    }
+
+    /// Returns the time left until the contract times out, or None if it already has.
+    pub fn remaining_time(&self, block_time: u64) -> Option<u64> {
+        if self.is_expired(block_time) {
+            None
+        } else {
+            Some(self.timeout - block_time)
+        }
+    }
+
+    /// Fails with `AccountError::HTLCExpired` if the contract has timed out at the given block
+    /// time.
+    pub fn check_not_expired(&self, block_time: u64) -> Result<(), AccountError> {
+        if self.is_expired(block_time) {
+            return Err(AccountError::HTLCExpired {
+                timeout: self.timeout,
+                block_time,
+            });
+        }
+        Ok(())
+    }
+
     pub fn can_change_balance(
         &self,
         proof: Vec<u8>,
@@ -79,17 +106,14 @@ impl HashedTimeLockedContract {
         match proof_type {
             ProofType::RegularTransfer => {
                 // Check that the contract has not expired yet.
-                if self.timeout < block_time {
-                    warn!("HTLC has expired: {} < {}", self.timeout, block_time);
-                    return Err(AccountError::InvalidForSender);
-                }
+                self.check_not_expired(block_time)?;
 
                 // Check that the provided hash_root is correct.
                 let hash_algorithm: HashAlgorithm = Deserialize::deserialize(proof_buf)?;
 
                 let hash_depth: u8 = Deserialize::deserialize(proof_buf)?;
 
-                let hash_root: AnyHash = Deserialize::deserialize(proof_buf)?;
+                let hash_root = AnyHash::deserialize_for(hash_algorithm, proof_buf)?;
 
                 if hash_algorithm != self.hash_algorithm || hash_root != self.hash_root {
                     warn!("HTLC hash mismatch");
@@ -97,7 +121,7 @@ impl HashedTimeLockedContract {
                 }
 
                 // Ignore pre_image.
-                let _pre_image: AnyHash = Deserialize::deserialize(proof_buf)?;
+                let _pre_image = AnyHash::deserialize_for(hash_algorithm, proof_buf)?;
 
                 // Check that the transaction is signed by the authorized recipient.
                 let signature_proof: SignatureProof = Deserialize::deserialize(proof_buf)?;
@@ -116,9 +140,9 @@ impl HashedTimeLockedContract {
                 )?;
 
                 if new_balance < min_cap {
-                    return Err(AccountError::InsufficientFunds {
-                        balance: new_balance,
-                        needed: min_cap,
+                    return Err(AccountError::InsufficientFundsDetail {
+                        available: u64::from(new_balance),
+                        required: u64::from(min_cap),
                     });
                 }
             }
@@ -137,7 +161,7 @@ impl HashedTimeLockedContract {
             }
             ProofType::TimeoutResolve => {
                 // Check that the contract has expired.
-                if self.timeout >= block_time {
+                if !self.is_expired(block_time) {
                     warn!(
                         "HTLC has not yet expired: {} >= {}",
                         self.timeout, block_time
@@ -156,6 +180,144 @@ impl HashedTimeLockedContract {
 
         Ok(true)
     }
+
+    /// Checks a proof for spending from the contract with a hash pre-image, without applying the
+    /// transaction. The contract must not have expired, the hash algorithm and hash root must
+    /// match the contract, the pre-image must hash to the hash root and the proof must be signed
+    /// by the recipient.
+    /// Note that the signature itself can only be verified against the transaction content, which
+    /// happens when the transaction is verified.
+    pub fn verify_regular_transfer(
+        &self,
+        proof: &[u8],
+        block_time: u64,
+    ) -> Result<(), AccountError> {
+        let proof_buf = &mut &proof[..];
+        Self::read_proof_type(proof_buf, ProofType::RegularTransfer)?;
+
+        self.check_not_expired(block_time)?;
+
+        let hash_algorithm: HashAlgorithm = Deserialize::deserialize(proof_buf)?;
+        let hash_depth: u8 = Deserialize::deserialize(proof_buf)?;
+        let hash_root = AnyHash::deserialize_for(hash_algorithm, proof_buf)?;
+        let pre_image = AnyHash::deserialize_for(hash_algorithm, proof_buf)?;
+        let signature_proof: SignatureProof = Deserialize::deserialize(proof_buf)?;
+        Self::read_end_of_proof(proof_buf)?;
+
+        if hash_algorithm != self.hash_algorithm || hash_root != self.hash_root {
+            return Err(AccountError::HTLCHashMismatch);
+        }
+
+        if hash_depth > self.hash_count {
+            return Err(AccountError::HTLCInvalidHashDepth {
+                hash_depth,
+                hash_count: self.hash_count,
+            });
+        }
+
+        let mut hash = pre_image;
+        for _ in 0..hash_depth {
+            hash = hash_algorithm.digest(hash.as_bytes());
+        }
+
+        if hash != self.hash_root {
+            return Err(AccountError::HTLCInvalidPreImage);
+        }
+
+        if !signature_proof.is_signed_by(&self.recipient) {
+            return Err(AccountError::InvalidSignature);
+        }
+
+        Ok(())
+    }
+
+    /// Checks a proof for resolving the contract before it expires, without applying the
+    /// transaction. The proof must be signed by both the recipient and the sender, in that order.
+    pub fn verify_early_resolve(&self, proof: &[u8]) -> Result<(), AccountError> {
+        let proof_buf = &mut &proof[..];
+        Self::read_proof_type(proof_buf, ProofType::EarlyResolve)?;
+
+        let signature_proof_recipient: SignatureProof = Deserialize::deserialize(proof_buf)?;
+        let signature_proof_sender: SignatureProof = Deserialize::deserialize(proof_buf)?;
+        Self::read_end_of_proof(proof_buf)?;
+
+        if !signature_proof_recipient.is_signed_by(&self.recipient)
+            || !signature_proof_sender.is_signed_by(&self.sender)
+        {
+            return Err(AccountError::InvalidSignature);
+        }
+
+        Ok(())
+    }
+
+    /// Checks a proof for returning the funds to the sender after the contract expired, without
+    /// applying the transaction. The proof must be signed by the sender.
+    pub fn verify_timeout_resolve(
+        &self,
+        proof: &[u8],
+        block_time: u64,
+    ) -> Result<(), AccountError> {
+        let proof_buf = &mut &proof[..];
+        Self::read_proof_type(proof_buf, ProofType::TimeoutResolve)?;
+
+        if !self.is_expired(block_time) {
+            return Err(AccountError::HTLCNotExpired {
+                timeout: self.timeout,
+                block_time,
+            });
+        }
+
+        let signature_proof: SignatureProof = Deserialize::deserialize(proof_buf)?;
+        Self::read_end_of_proof(proof_buf)?;
+
+        if !signature_proof.is_signed_by(&self.sender) {
+            return Err(AccountError::InvalidSignature);
+        }
+
+        Ok(())
+    }
+
+    fn read_proof_type(proof_buf: &mut &[u8], expected: ProofType) -> Result<(), AccountError> {
+        let proof_type: ProofType = Deserialize::deserialize(proof_buf)?;
+
+        if proof_type != expected {
+            return Err(AccountError::HTLCInvalidProofType);
+        }
+
+        Ok(())
+    }
+
+    /// Reads the hash depth and the pre-image from the remainder of a regular transfer proof.
+    fn read_regular_transfer_proof(proof_buf: &mut &[u8]) -> Result<(AnyHash, u8), AccountError> {
+        let hash_algorithm: HashAlgorithm = Deserialize::deserialize(proof_buf)?;
+        let hash_depth: u8 = Deserialize::deserialize(proof_buf)?;
+        let _hash_root = AnyHash::deserialize_for(hash_algorithm, proof_buf)?;
+        let pre_image = AnyHash::deserialize_for(hash_algorithm, proof_buf)?;
+
+        Ok((pre_image, hash_depth))
+    }
+
+    fn read_end_of_proof(proof_buf: &[u8]) -> Result<(), AccountError> {
+        if !proof_buf.is_empty() {
+            return Err(AccountError::InvalidSerialization(
+                SerializingError::InvalidValue,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Deserialize for HashedTimeLockedContract {
+    fn deserialize<R: ReadBytesExt>(reader: &mut R) -> Result<Self, SerializingError> {
+        let balance = Deserialize::deserialize(reader)?;
+        let receipt: HTLCReceipt = Deserialize::deserialize(reader)?;
+
+        Ok(HashedTimeLockedContract {
+            balance,
+            ..HashedTimeLockedContract::from(receipt)
+        })
+    }
 }
 
 impl AccountTransactionInteraction for HashedTimeLockedContract {
@@ -270,9 +432,7 @@ impl AccountTransactionInteraction for HashedTimeLockedContract {
 
         match proof_type {
             ProofType::RegularTransfer => {
-                // Ignore pre_image.
-                let pre_image: AnyHash = Deserialize::deserialize(proof_buf)?;
-                let hash_depth: u8 = Deserialize::deserialize(proof_buf)?;
+                let (pre_image, hash_depth) = Self::read_regular_transfer_proof(proof_buf)?;
 
                 logs.push(Log::HTLCRegularTransfer {
                     contract_address: transaction.sender.clone(),
@@ -371,8 +531,7 @@ impl AccountTransactionInteraction for HashedTimeLockedContract {
 
         logs.push(match proof_type {
             ProofType::RegularTransfer => {
-                let pre_image: AnyHash = Deserialize::deserialize(proof_buf)?;
-                let hash_depth: u8 = Deserialize::deserialize(proof_buf)?;
+                let (pre_image, hash_depth) = Self::read_regular_transfer_proof(proof_buf)?;
                 Log::HTLCRegularTransfer {
                     contract_address: htlc.sender,
                     pre_image,
@@ -506,6 +665,28 @@ impl AccountTransactionInteraction for HashedTimeLockedContract {
             .is_ok()
     }
 
+    fn can_pay(&self, transaction: &Transaction, block_time: u64) -> Result<(), AccountError> {
+        let total = Account::balance_add(transaction.value, transaction.fee)?;
+        let new_balance = Account::balance_sub(self.balance, total)?;
+
+        // Before the timeout the funds can only be claimed by the recipient with a valid hash
+        // pre-image, after it only by the sender.
+        let proof_type: ProofType = Deserialize::deserialize(&mut &transaction.proof[..])?;
+        match proof_type {
+            ProofType::RegularTransfer => {
+                self.verify_regular_transfer(&transaction.proof, block_time)?
+            }
+            ProofType::EarlyResolve => self.verify_early_resolve(&transaction.proof)?,
+            ProofType::TimeoutResolve => {
+                self.verify_timeout_resolve(&transaction.proof, block_time)?
+            }
+        }
+
+        // Regular transfers may only spend up to the min cap given by the hash depth.
+        self.can_change_balance(transaction.proof.clone(), new_balance, block_time)?;
+        Ok(())
+    }
+
     fn delete(
         accounts_tree: &AccountsTrie,
         db_txn: &mut WriteTransaction,
@@ -571,7 +752,7 @@ impl AccountInherentInteraction for HashedTimeLockedContract {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Serialize, Eq, PartialEq)]
 pub struct HTLCReceipt {
     pub sender: Address,
     pub recipient: Address,
@@ -582,6 +763,25 @@ pub struct HTLCReceipt {
     pub total_amount: Coin,
 }
 
+impl Deserialize for HTLCReceipt {
+    fn deserialize<R: ReadBytesExt>(reader: &mut R) -> Result<Self, SerializingError> {
+        let sender = Deserialize::deserialize(reader)?;
+        let recipient = Deserialize::deserialize(reader)?;
+        let hash_algorithm = Deserialize::deserialize(reader)?;
+        let hash_root = AnyHash::deserialize_for(hash_algorithm, reader)?;
+
+        Ok(HTLCReceipt {
+            sender,
+            recipient,
+            hash_algorithm,
+            hash_root,
+            hash_count: Deserialize::deserialize(reader)?,
+            timeout: Deserialize::deserialize(reader)?,
+            total_amount: Deserialize::deserialize(reader)?,
+        })
+    }
+}
+
 impl From<HashedTimeLockedContract> for HTLCReceipt {
     fn from(contract: HashedTimeLockedContract) -> Self {
         HTLCReceipt {
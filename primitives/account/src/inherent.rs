@@ -4,6 +4,8 @@ use beserial::{Deserialize, ReadBytesExt, Serialize, SerializingError, WriteByte
 use nimiq_hash::{Hash, SerializeContent};
 use nimiq_keys::Address;
 use nimiq_primitives::coin::Coin;
+use nimiq_primitives::policy;
+use nimiq_primitives::slots::SlashedSlot;
 
 #[derive(Clone, Debug, Eq, PartialEq, Copy, Serialize, Deserialize)]
 #[repr(u8)]
@@ -35,10 +37,69 @@ pub struct Inherent {
 }
 
 impl Inherent {
+    /// Creates an inherent that pays the given reward to the target address.
+    pub fn reward(target: Address, value: Coin) -> Self {
+        Inherent {
+            ty: InherentType::Reward,
+            target,
+            value,
+            data: vec![],
+        }
+    }
+
+    /// Creates an inherent that slashes the given slot of the validator, for a slashable action
+    /// that occurred at `event_block`.
+    pub fn slash(slot: u16, validator_address: Address, event_block: u32) -> Self {
+        let slot = SlashedSlot {
+            slot,
+            validator_address,
+            event_block,
+        };
+
+        Inherent {
+            ty: InherentType::Slash,
+            target: policy::STAKING_CONTRACT_ADDRESS,
+            value: Coin::ZERO,
+            data: slot.serialize_to_vec(),
+        }
+    }
+
+    /// Creates the inherent that finalizes a batch in the staking contract.
+    pub fn finalize_batch() -> Self {
+        Inherent {
+            ty: InherentType::FinalizeBatch,
+            target: policy::STAKING_CONTRACT_ADDRESS,
+            value: Coin::ZERO,
+            data: vec![],
+        }
+    }
+
+    /// Creates the inherent that finalizes an epoch in the staking contract.
+    pub fn finalize_epoch() -> Self {
+        Inherent {
+            ty: InherentType::FinalizeEpoch,
+            target: policy::STAKING_CONTRACT_ADDRESS,
+            value: Coin::ZERO,
+            data: vec![],
+        }
+    }
+
     #[inline]
     pub fn is_pre_transactions(&self) -> bool {
         self.ty.is_pre_transactions()
     }
+
+    /// Returns true if the inherent may be applied in a macro block (`macro_block` is true) or in
+    /// a micro block (`macro_block` is false). Slashes are only applied in micro blocks, while
+    /// rewards and the finalization of batches and epochs only happen in macro blocks.
+    pub fn is_valid_for(&self, macro_block: bool) -> bool {
+        match self.ty {
+            InherentType::Slash => !macro_block,
+            InherentType::Reward | InherentType::FinalizeBatch | InherentType::FinalizeEpoch => {
+                macro_block
+            }
+        }
+    }
 }
 
 impl Hash for Inherent {}
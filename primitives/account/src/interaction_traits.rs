@@ -12,6 +12,12 @@ pub trait AccountTransactionInteraction: Sized {
         block_time: u64,
     ) -> bool;
 
+    /// Checks, without committing anything, whether the account can pay the value plus fee of the
+    /// given outgoing transaction at the given block time. Time-locks on the funds of the account
+    /// and the conditions encoded in the proof of the transaction are taken into account, the
+    /// validity of the signatures is not.
+    fn can_pay(&self, transaction: &Transaction, block_time: u64) -> Result<(), AccountError>;
+
     fn create(
         accounts_tree: &AccountsTrie,
         db_txn: &mut WriteTransaction,
@@ -4,8 +4,10 @@ extern crate log;
 pub use crate::account::Account;
 pub use crate::accounts::{Accounts, AccountsTrie};
 pub use crate::accounts_list::AccountsList;
+pub use crate::accounts_proof::AccountsProof;
 pub use crate::basic_account::BasicAccount;
-pub use crate::error::AccountError;
+pub use crate::diff::{AccountDiff, AccountsTrieDiff};
+pub use crate::error::{AccountError, AccountsProofError};
 pub use crate::htlc_contract::*;
 pub use crate::inherent::{Inherent, InherentType};
 pub use crate::interaction_traits::*;
@@ -17,7 +19,9 @@ pub use crate::vesting_contract::*;
 mod account;
 mod accounts;
 mod accounts_list;
+mod accounts_proof;
 mod basic_account;
+mod diff;
 mod error;
 mod htlc_contract;
 mod inherent;
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use crate::Receipt;
 use beserial::Serialize as BeSerialize;
 use nimiq_hash::Blake2bHash;
@@ -145,6 +147,36 @@ pub enum Log {
     },
 }
 
+/// The type of a log, without any of its data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde-derive", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-derive", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum LogType {
+    PayFee,
+    Transfer,
+    HtlcCreate,
+    HtlcTimeoutResolve,
+    HtlcRegularTransfer,
+    HtlcEarlyResolve,
+    VestingCreate,
+    CreateValidator,
+    UpdateValidator,
+    InactivateValidator,
+    ReactivateValidator,
+    UnparkValidator,
+    CreateStaker,
+    Stake,
+    UpdateStaker,
+    DeleteValidator,
+    Unstake,
+    PayoutReward,
+    Park,
+    Slash,
+    RevertContract,
+    FailedTransaction,
+}
+
 impl Log {
     pub fn is_related_to_address(&self, address: &Address) -> bool {
         match self {
@@ -248,6 +280,34 @@ impl Log {
             Log::FailedTransaction { from, to, .. } => from == address || to == address,
         }
     }
+
+    /// Returns the type of the log.
+    pub fn ty(&self) -> LogType {
+        match self {
+            Log::PayFee { .. } => LogType::PayFee,
+            Log::Transfer { .. } => LogType::Transfer,
+            Log::HTLCCreate { .. } => LogType::HtlcCreate,
+            Log::HTLCTimeoutResolve { .. } => LogType::HtlcTimeoutResolve,
+            Log::HTLCRegularTransfer { .. } => LogType::HtlcRegularTransfer,
+            Log::HTLCEarlyResolve { .. } => LogType::HtlcEarlyResolve,
+            Log::VestingCreate { .. } => LogType::VestingCreate,
+            Log::CreateValidator { .. } => LogType::CreateValidator,
+            Log::UpdateValidator { .. } => LogType::UpdateValidator,
+            Log::InactivateValidator { .. } => LogType::InactivateValidator,
+            Log::ReactivateValidator { .. } => LogType::ReactivateValidator,
+            Log::UnparkValidator { .. } => LogType::UnparkValidator,
+            Log::CreateStaker { .. } => LogType::CreateStaker,
+            Log::Stake { .. } => LogType::Stake,
+            Log::UpdateStaker { .. } => LogType::UpdateStaker,
+            Log::DeleteValidator { .. } => LogType::DeleteValidator,
+            Log::Unstake { .. } => LogType::Unstake,
+            Log::PayoutReward { .. } => LogType::PayoutReward,
+            Log::Park { .. } => LogType::Park,
+            Log::Slash { .. } => LogType::Slash,
+            Log::RevertContract { .. } => LogType::RevertContract,
+            Log::FailedTransaction { .. } => LogType::FailedTransaction,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -256,12 +316,22 @@ impl Log {
 pub struct TransactionLog {
     #[cfg_attr(feature = "serde-derive", serde(rename = "hash"))]
     pub tx_hash: Blake2bHash,
+    /// The index of the transaction within the block.
+    pub index: u16,
+    /// The fee that was paid by the transaction, regardless of whether it failed or not.
+    pub fee: Coin,
+    /// The logs of the transaction, in the order in which they were generated.
     pub logs: Vec<Log>,
 }
 
 impl TransactionLog {
-    pub fn new(tx_hash: Blake2bHash, logs: Vec<Log>) -> Self {
-        Self { tx_hash, logs }
+    pub fn new(tx_hash: Blake2bHash, index: u16, fee: Coin, logs: Vec<Log>) -> Self {
+        Self {
+            tx_hash,
+            index,
+            fee,
+            logs,
+        }
     }
 }
 
@@ -290,7 +360,151 @@ impl BlockLog {
             BlockLog::RevertedBlock { .. } => true,
         }
     }
+
+    /// Returns true if the block log contains neither inherent logs nor transaction logs.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            BlockLog::AppliedBlock {
+                inherent_logs,
+                tx_logs,
+                ..
+            }
+            | BlockLog::RevertedBlock {
+                inherent_logs,
+                tx_logs,
+                ..
+            } => inherent_logs.is_empty() && tx_logs.is_empty(),
+        }
+    }
+
+    /// Returns a copy of the block log that only contains the logs related to the given address,
+    /// e.g. as sender, recipient, contract, staker or validator.
+    /// Transaction logs without any such log are dropped.
+    pub fn filter_by_address(&self, address: &Address) -> BlockLog {
+        self.filter_logs(|log| log.is_related_to_address(address))
+    }
+
+    /// Returns a copy of the block log that only contains the logs of the given type.
+    /// Transaction logs without any such log are dropped.
+    pub fn filter_by_type(&self, kind: LogType) -> BlockLog {
+        self.filter_logs(|log| log.ty() == kind)
+    }
+
+    fn filter_logs<F: Fn(&Log) -> bool>(&self, predicate: F) -> BlockLog {
+        let filter_inherent_logs = |inherent_logs: &[Log]| -> Vec<Log> {
+            inherent_logs
+                .iter()
+                .filter(|log| predicate(log))
+                .cloned()
+                .collect()
+        };
+
+        let filter_tx_logs = |tx_logs: &[TransactionLog]| -> Vec<TransactionLog> {
+            tx_logs
+                .iter()
+                .filter_map(|tx_log| {
+                    let logs: Vec<Log> = tx_log
+                        .logs
+                        .iter()
+                        .filter(|log| predicate(log))
+                        .cloned()
+                        .collect();
+
+                    if logs.is_empty() {
+                        None
+                    } else {
+                        Some(TransactionLog::new(
+                            tx_log.tx_hash.clone(),
+                            tx_log.index,
+                            tx_log.fee,
+                            logs,
+                        ))
+                    }
+                })
+                .collect()
+        };
+
+        match self {
+            BlockLog::AppliedBlock {
+                inherent_logs,
+                block_hash,
+                block_number,
+                timestamp,
+                tx_logs,
+            } => BlockLog::AppliedBlock {
+                inherent_logs: filter_inherent_logs(inherent_logs),
+                block_hash: block_hash.clone(),
+                block_number: *block_number,
+                timestamp: *timestamp,
+                tx_logs: filter_tx_logs(tx_logs),
+            },
+            BlockLog::RevertedBlock {
+                inherent_logs,
+                block_hash,
+                block_number,
+                tx_logs,
+            } => BlockLog::RevertedBlock {
+                inherent_logs: filter_inherent_logs(inherent_logs),
+                block_hash: block_hash.clone(),
+                block_number: *block_number,
+                tx_logs: filter_tx_logs(tx_logs),
+            },
+        }
+    }
+}
+
+/// A filter for block logs. A log matches the filter if it is related to any of the addresses and
+/// is of any of the log types. An empty set of addresses or of log types doesn't restrict the logs
+/// in that regard.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogFilter {
+    pub addresses: BTreeSet<Address>,
+    pub log_types: BTreeSet<LogType>,
+}
+
+impl LogFilter {
+    pub fn new<A, T>(addresses: A, log_types: T) -> Self
+    where
+        A: IntoIterator<Item = Address>,
+        T: IntoIterator<Item = LogType>,
+    {
+        Self {
+            addresses: addresses.into_iter().collect(),
+            log_types: log_types.into_iter().collect(),
+        }
+    }
+
+    /// Returns true if the filter lets every log through.
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty() && self.log_types.is_empty()
+    }
+
+    pub fn matches(&self, log: &Log) -> bool {
+        (self.log_types.is_empty() || self.log_types.contains(&log.ty()))
+            && (self.addresses.is_empty()
+                || self
+                    .addresses
+                    .iter()
+                    .any(|address| log.is_related_to_address(address)))
+    }
+
+    /// Filters the logs of the given block log. Transaction logs without any matching log are
+    /// dropped. Returns None if no log of the block matches, unless the filter is empty, in which
+    /// case the block log is returned unchanged.
+    pub fn apply(&self, block_log: &BlockLog) -> Option<BlockLog> {
+        if self.is_empty() {
+            return Some(block_log.clone());
+        }
+
+        let block_log = block_log.filter_logs(|log| self.matches(log));
+        if block_log.is_empty() {
+            None
+        } else {
+            Some(block_log)
+        }
+    }
 }
+
 // This structure stores the info/data associated to a sucessful transaction that was commited
 pub struct TransactionInfo {
     pub sender_info: Option<AccountInfo>,
@@ -7,6 +7,8 @@ use beserial::{
 use nimiq_database::{FromDatabaseValue, IntoDatabaseValue};
 use nimiq_hash::{Hash, HashOutput, Hasher, SerializeContent};
 
+use crate::AccountError;
+
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum ReceiptType {
@@ -35,6 +37,35 @@ impl Receipt {
             Receipt::Inherent { .. } => ReceiptType::Inherent,
         }
     }
+
+    /// Returns the key by which receipts are ordered. This is the order in which
+    /// `Accounts::commit_batch` creates them: the pre-transactions inherents, the senders of the
+    /// transactions, the recipients of the transactions and finally the post-transactions
+    /// inherents, each of them ordered by index.
+    pub fn sort_key(&self) -> (u8, u16) {
+        match self {
+            Receipt::Inherent {
+                index,
+                pre_transactions: true,
+                ..
+            } => (0, *index),
+            Receipt::Transaction {
+                index,
+                sender: true,
+                ..
+            } => (1, *index),
+            Receipt::Transaction {
+                index,
+                sender: false,
+                ..
+            } => (2, *index),
+            Receipt::Inherent {
+                index,
+                pre_transactions: false,
+                ..
+            } => (3, *index),
+        }
+    }
 }
 
 impl Serialize for Receipt {
@@ -140,6 +171,25 @@ impl Receipts {
     pub fn is_empty(&self) -> bool {
         self.receipts.is_empty()
     }
+
+    /// Checks that the receipts are in strictly ascending order of their sort key. This also
+    /// rejects duplicate receipts.
+    pub fn verify_sorted(&self) -> Result<(), AccountError> {
+        if self
+            .receipts
+            .windows(2)
+            .all(|pair| pair[0].sort_key() < pair[1].sort_key())
+        {
+            Ok(())
+        } else {
+            Err(AccountError::ReceiptsNotOrdered)
+        }
+    }
+
+    /// Sorts the receipts by their sort key.
+    pub fn sort(&mut self) {
+        self.receipts.sort_by_key(Receipt::sort_key);
+    }
 }
 
 impl From<Vec<Receipt>> for Receipts {
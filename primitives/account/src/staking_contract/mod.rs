@@ -171,6 +171,22 @@ impl StakingContract {
         }
     }
 
+    /// Returns a snapshot of the active validators, together with their total stake (i.e. their
+    /// own deposit plus the coins delegated to them), at the state of the given transaction.
+    /// Validators that were parked during the current epoch are left out.
+    pub fn active_validators(
+        &self,
+        accounts_tree: &AccountsTrie,
+        db_txn: &DBTransaction,
+    ) -> Vec<(Address, Coin)> {
+        self.active_validators
+            .keys()
+            .filter(|address| !self.parked_set.contains(*address))
+            .filter_map(|address| StakingContract::get_validator(accounts_tree, db_txn, address))
+            .map(|validator| (validator.address, validator.balance))
+            .collect()
+    }
+
     /// Get a list containing the addresses of all the stakers that delegating for a given validator.
     pub fn get_validator_stakers(
         accounts_tree: &AccountsTrie,
@@ -204,6 +220,64 @@ impl StakingContract {
         stakers
     }
 
+    /// Get all the stakers delegating for a given validator, ordered by staker address.
+    pub fn get_delegators(
+        accounts_tree: &AccountsTrie,
+        db_txn: &DBTransaction,
+        validator_address: &Address,
+    ) -> Vec<Staker> {
+        match StakingContract::get_validator(accounts_tree, db_txn, validator_address) {
+            Some(validator) => StakingContract::get_stakers_for_validator(
+                accounts_tree,
+                db_txn,
+                validator_address,
+                None,
+                validator.num_stakers as usize,
+            ),
+            None => vec![],
+        }
+    }
+
+    /// Get a page of the stakers delegating for a given validator, ordered by staker address.
+    /// The page starts after the staker with address `start`, if given, and contains at most `max`
+    /// stakers. To fetch the next page, pass the address of the last staker of the current page
+    /// as `start`.
+    pub fn get_stakers_for_validator(
+        accounts_tree: &AccountsTrie,
+        db_txn: &DBTransaction,
+        validator_address: &Address,
+        start: Option<&Address>,
+        max: usize,
+    ) -> Vec<Staker> {
+        // A page never holds more stakers than the validator has.
+        let num_stakers =
+            match StakingContract::get_validator(accounts_tree, db_txn, validator_address) {
+                Some(validator) => validator.num_stakers as usize,
+                None => return vec![],
+            };
+
+        let start_key = StakingContract::get_key_validator_staker(
+            validator_address,
+            start.unwrap_or(&Address::from([0; 20])),
+        );
+
+        // The chunk includes the start key if it exists, so we fetch one more entry. The entries
+        // following the stakers list of this validator aren't stakers of this validator.
+        let chunk =
+            accounts_tree.get_chunk(db_txn, &start_key, max.min(num_stakers).saturating_add(1));
+
+        chunk
+            .into_iter()
+            .map_while(|account| match account {
+                Account::StakingValidatorsStaker(address) => Some(address),
+                _ => None,
+            })
+            .filter(|address| Some(address) != start)
+            .take(max)
+            .filter_map(|address| StakingContract::get_staker(accounts_tree, db_txn, &address))
+            .collect()
+    }
+
     /// Get a staker information given its address, if it exists.
     pub fn get_staker(
         accounts_tree: &AccountsTrie,
@@ -268,6 +342,36 @@ impl StakingContract {
         slots_builder.build()
     }
 
+    /// Estimates the block reward, in Lunas, that the validator with the given address receives for
+    /// its slots in an epoch starting and ending at the given timestamps, excluding transaction
+    /// fees. Slots that lost their reward in the current batch are assumed to keep losing it.
+    /// Returns zero if the validator has no slots.
+    pub fn compute_epoch_reward(
+        &self,
+        validators: &Validators,
+        validator_address: &Address,
+        genesis_supply: Coin,
+        genesis_timestamp: u64,
+        start_timestamp: u64,
+        end_timestamp: u64,
+    ) -> u64 {
+        let validator = match validators.get_validator_by_address(validator_address.clone()) {
+            Some(validator) => validator,
+            None => return 0,
+        };
+
+        // Compute the supply that is minted over the epoch.
+        let genesis_supply = u64::from(genesis_supply);
+        let reward_pot = policy::supply_at(genesis_supply, genesis_timestamp, end_timestamp)
+            - policy::supply_at(genesis_supply, genesis_timestamp, start_timestamp);
+
+        let num_eligible_slots = (validator.slot_range.0..validator.slot_range.1)
+            .filter(|slot| !self.current_lost_rewards.contains(*slot as usize))
+            .count() as u64;
+
+        reward_pot / policy::SLOTS as u64 * num_eligible_slots
+    }
+
     /// Returns a BitSet of slots that lost its rewards in the previous batch.
     pub fn previous_lost_rewards(&self) -> BitSet {
         self.previous_lost_rewards.clone()
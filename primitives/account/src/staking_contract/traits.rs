@@ -666,6 +666,25 @@ impl AccountTransactionInteraction for StakingContract {
         true
     }
 
+    fn can_pay(&self, transaction: &Transaction, _block_time: u64) -> Result<(), AccountError> {
+        let total = Account::balance_add(transaction.value, transaction.fee)?;
+        Account::balance_sub(self.balance, total)?;
+
+        // Note: The balances of stakers and validators are not part of the contract itself, they
+        // are checked via the StakingContract::can_pay_tx interface.
+        match OutgoingStakingTransactionProof::parse(transaction)? {
+            OutgoingStakingTransactionProof::DeleteValidator { proof } => {
+                // Active validators need to be inactivated before they can be deleted.
+                if self.active_validators.contains_key(&proof.compute_signer()) {
+                    return Err(AccountError::InvalidForSender);
+                }
+            }
+            OutgoingStakingTransactionProof::Unstake { .. } => {}
+        }
+
+        Ok(())
+    }
+
     fn delete(
         _accounts_tree: &AccountsTrie,
         _db_txn: &mut WriteTransaction,
@@ -1,9 +1,11 @@
-use beserial::{Deserialize, Serialize};
+use std::io::Read;
+
+use beserial::{Deserialize, ReadBytesExt, Serialize, SerializingError, WriteBytesExt};
 use nimiq_database::WriteTransaction;
 use nimiq_keys::Address;
 use nimiq_primitives::account::AccountType;
 use nimiq_primitives::coin::Coin;
-use nimiq_transaction::account::vesting_contract::CreationTransactionData;
+use nimiq_transaction::account::vesting_contract::{CreationTransactionData, VestingSchedule};
 use nimiq_transaction::{SignatureProof, Transaction};
 use nimiq_trie::key_nibbles::KeyNibbles;
 
@@ -12,7 +14,13 @@ use crate::interaction_traits::{AccountInherentInteraction, AccountTransactionIn
 use crate::logs::{AccountInfo, Log};
 use crate::{Account, AccountError, AccountsTrie, BasicAccount};
 
-#[derive(Clone, PartialEq, PartialOrd, Eq, Ord, Debug, Serialize, Deserialize)]
+/// Marks a vesting contract that is serialized with its schedule. Contracts with a linear schedule
+/// are serialized in the legacy format, which has no schedule, so that their encoding doesn't
+/// change. The legacy format starts with the balance, whose first byte is always zero since coin
+/// values don't exceed 2^53.
+const SCHEDULED_FORMAT: u8 = 1;
+
+#[derive(Clone, PartialEq, PartialOrd, Eq, Ord, Debug)]
 #[cfg_attr(feature = "serde-derive", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde-derive", serde(rename_all = "camelCase"))]
 pub struct VestingContract {
@@ -22,6 +30,7 @@ pub struct VestingContract {
     pub time_step: u64,
     pub step_amount: Coin,
     pub total_amount: Coin,
+    pub schedule: VestingSchedule,
 }
 
 impl VestingContract {
@@ -32,6 +41,7 @@ impl VestingContract {
         time_step: u64,
         step_amount: Coin,
         total_amount: Coin,
+        schedule: VestingSchedule,
     ) -> Self {
         VestingContract {
             balance,
@@ -40,6 +50,7 @@ impl VestingContract {
             time_step,
             step_amount,
             total_amount,
+            schedule,
         }
     }
 
@@ -52,10 +63,21 @@ impl VestingContract {
             time_step: self.time_step,
             step_amount: self.step_amount,
             total_amount: self.total_amount,
+            schedule: self.schedule,
         }
     }
 
+    /// Returns the amount that is still locked at the given time. The whole total amount is locked
+    /// before the first step and, after that, `step_amount` is unlocked with every `time_step`.
+    /// With a cliff, the whole total amount stays locked until the cliff.
+    /// Note that the min cap exceeds the total amount before the start time.
     pub fn min_cap(&self, time: u64) -> Coin {
+        if let VestingSchedule::Cliff { cliff_time } = self.schedule {
+            if time < cliff_time {
+                return self.total_amount;
+            }
+        }
+
         if self.time_step > 0 && self.step_amount > Coin::ZERO {
             let steps = (time as i128 - self.start_time as i128) / self.time_step as i128;
             let min_cap =
@@ -66,6 +88,111 @@ impl VestingContract {
             Coin::ZERO
         }
     }
+
+    /// Returns the part of the total amount that has vested at the given time, regardless of
+    /// whether it was already withdrawn.
+    pub fn vested_amount(&self, time: u64) -> Coin {
+        self.total_amount
+            .checked_sub(self.min_cap(time))
+            .unwrap_or(Coin::ZERO)
+    }
+
+    /// Returns the part of the total amount that is still locked at the given time.
+    pub fn unvested_amount(&self, time: u64) -> Coin {
+        self.min_cap(time).min(self.total_amount)
+    }
+
+    /// Returns the part of the total amount that was already withdrawn from the contract. It is
+    /// derived from the balance, assuming that the contract was funded with its total amount, since
+    /// the withdrawn amount isn't part of the contract's state. It is only meant for display.
+    pub fn withdrawn_amount(&self) -> Coin {
+        self.total_amount
+            .checked_sub(self.balance)
+            .unwrap_or(Coin::ZERO)
+    }
+
+    /// Returns the amount that can be spent at the given time, i.e. the part of the balance that
+    /// exceeds the min cap.
+    pub fn available_balance(&self, time: u64) -> Coin {
+        self.balance
+            .checked_sub(self.min_cap(time))
+            .unwrap_or(Coin::ZERO)
+    }
+}
+
+impl Serialize for VestingContract {
+    fn serialize<W: WriteBytesExt>(&self, writer: &mut W) -> Result<usize, SerializingError> {
+        let mut size = 0;
+        if self.schedule != VestingSchedule::Linear {
+            size += SCHEDULED_FORMAT.serialize(writer)?;
+        }
+
+        size += self.balance.serialize(writer)?;
+        size += self.owner.serialize(writer)?;
+        size += self.start_time.serialize(writer)?;
+        size += self.time_step.serialize(writer)?;
+        size += self.step_amount.serialize(writer)?;
+        size += self.total_amount.serialize(writer)?;
+
+        if self.schedule != VestingSchedule::Linear {
+            size += self.schedule.serialize(writer)?;
+        }
+
+        Ok(size)
+    }
+
+    fn serialized_size(&self) -> usize {
+        let mut size = self.balance.serialized_size()
+            + self.owner.serialized_size()
+            + self.start_time.serialized_size()
+            + self.time_step.serialized_size()
+            + self.step_amount.serialized_size()
+            + self.total_amount.serialized_size();
+
+        if self.schedule != VestingSchedule::Linear {
+            size += SCHEDULED_FORMAT.serialized_size() + self.schedule.serialized_size();
+        }
+
+        size
+    }
+}
+
+impl Deserialize for VestingContract {
+    fn deserialize<R: ReadBytesExt>(reader: &mut R) -> Result<Self, SerializingError> {
+        let format: u8 = Deserialize::deserialize(reader)?;
+
+        let balance = match format {
+            // The first byte of the balance in the legacy format.
+            0 => {
+                let mut bytes = [0u8; 8];
+                reader.read_exact(&mut bytes[1..])?;
+                Deserialize::deserialize(&mut &bytes[..])?
+            }
+            SCHEDULED_FORMAT => Deserialize::deserialize(reader)?,
+            _ => return Err(SerializingError::InvalidValue),
+        };
+
+        let mut contract = VestingContract {
+            balance,
+            owner: Deserialize::deserialize(reader)?,
+            start_time: Deserialize::deserialize(reader)?,
+            time_step: Deserialize::deserialize(reader)?,
+            step_amount: Deserialize::deserialize(reader)?,
+            total_amount: Deserialize::deserialize(reader)?,
+            schedule: VestingSchedule::Linear,
+        };
+
+        if format == SCHEDULED_FORMAT {
+            contract.schedule = Deserialize::deserialize(reader)?;
+            // Linear contracts must use the legacy format, so that every contract has a single
+            // encoding.
+            if contract.schedule == VestingSchedule::Linear {
+                return Err(SerializingError::InvalidValue);
+            }
+        }
+
+        Ok(contract)
+    }
 }
 
 impl AccountTransactionInteraction for VestingContract {
@@ -92,6 +219,7 @@ impl AccountTransactionInteraction for VestingContract {
             data.time_step,
             data.step_amount,
             data.total_amount,
+            data.schedule,
         );
 
         accounts_tree.put(db_txn, &contract_key, Account::Vesting(contract.clone()));
@@ -159,9 +287,9 @@ impl AccountTransactionInteraction for VestingContract {
         let min_cap = vesting.min_cap(block_time);
 
         if new_balance < min_cap {
-            return Err(AccountError::InsufficientFunds {
-                balance: new_balance,
-                needed: min_cap,
+            return Err(AccountError::InsufficientFundsDetail {
+                available: u64::from(new_balance),
+                required: u64::from(min_cap),
             });
         }
 
@@ -382,6 +510,14 @@ impl AccountTransactionInteraction for VestingContract {
         true
     }
 
+    fn can_pay(&self, transaction: &Transaction, block_time: u64) -> Result<(), AccountError> {
+        let total = Account::balance_add(transaction.value, transaction.fee)?;
+
+        // Only the funds above the vesting min cap can be spent.
+        Account::balance_sub(self.available_balance(block_time), total)?;
+        Ok(())
+    }
+
     fn delete(
         accounts_tree: &AccountsTrie,
         db_txn: &mut WriteTransaction,
@@ -447,13 +583,75 @@ impl AccountInherentInteraction for VestingContract {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+/// The receipt of a pruned vesting contract. Receipts of contracts with a linear schedule are
+/// serialized in the legacy format, which ends before the schedule. Since a receipt is always
+/// serialized on its own, the schedule is only appended for other schedules.
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct VestingReceipt {
     pub owner: Address,
     pub start_time: u64,
     pub time_step: u64,
     pub step_amount: Coin,
     pub total_amount: Coin,
+    pub schedule: VestingSchedule,
+}
+
+impl Serialize for VestingReceipt {
+    fn serialize<W: WriteBytesExt>(&self, writer: &mut W) -> Result<usize, SerializingError> {
+        let mut size = 0;
+        size += self.owner.serialize(writer)?;
+        size += self.start_time.serialize(writer)?;
+        size += self.time_step.serialize(writer)?;
+        size += self.step_amount.serialize(writer)?;
+        size += self.total_amount.serialize(writer)?;
+
+        if self.schedule != VestingSchedule::Linear {
+            size += self.schedule.serialize(writer)?;
+        }
+
+        Ok(size)
+    }
+
+    fn serialized_size(&self) -> usize {
+        let mut size = self.owner.serialized_size()
+            + self.start_time.serialized_size()
+            + self.time_step.serialized_size()
+            + self.step_amount.serialized_size()
+            + self.total_amount.serialized_size();
+
+        if self.schedule != VestingSchedule::Linear {
+            size += self.schedule.serialized_size();
+        }
+
+        size
+    }
+}
+
+impl Deserialize for VestingReceipt {
+    fn deserialize<R: ReadBytesExt>(reader: &mut R) -> Result<Self, SerializingError> {
+        let owner = Deserialize::deserialize(reader)?;
+        let start_time = Deserialize::deserialize(reader)?;
+        let time_step = Deserialize::deserialize(reader)?;
+        let step_amount = Deserialize::deserialize(reader)?;
+        let total_amount = Deserialize::deserialize(reader)?;
+
+        // Legacy receipts end here.
+        let mut tag = [0u8; 1];
+        let schedule = if reader.read(&mut tag)? == 0 {
+            VestingSchedule::Linear
+        } else {
+            Deserialize::deserialize(&mut (&tag[..]).chain(reader))?
+        };
+
+        Ok(VestingReceipt {
+            owner,
+            start_time,
+            time_step,
+            step_amount,
+            total_amount,
+            schedule,
+        })
+    }
 }
 
 impl From<VestingContract> for VestingReceipt {
@@ -464,6 +662,7 @@ impl From<VestingContract> for VestingReceipt {
             time_step: contract.time_step,
             step_amount: contract.step_amount,
             total_amount: contract.total_amount,
+            schedule: contract.schedule,
         }
     }
 }
@@ -477,6 +676,7 @@ impl From<VestingReceipt> for VestingContract {
             time_step: receipt.time_step,
             step_amount: receipt.step_amount,
             total_amount: receipt.total_amount,
+            schedule: receipt.schedule,
         }
     }
 }
@@ -1,30 +1,35 @@
 use beserial::{Deserialize, Serialize};
-use nimiq_hash::Hash;
+use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_primitives::account::AccountType;
-use rand::{rngs::StdRng, SeedableRng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::convert::TryFrom;
 use std::time::Instant;
 use tempfile::tempdir;
 
 use nimiq_account::{
-    Account, Accounts, BasicAccount, BatchInfo, Inherent, InherentType, Log, TransactionLog,
+    Account, AccountDiff, AccountError, Accounts, AccountsProof, AccountsProofError,
+    AccountsTrieDiff, BasicAccount, BatchInfo, Inherent, InherentType, Log, TransactionLog,
     VestingContract,
 };
 use nimiq_account::{Receipt, Receipts};
 use nimiq_bls::KeyPair as BLSKeyPair;
-use nimiq_database::WriteTransaction;
 use nimiq_database::{mdbx::MdbxEnvironment, volatile::VolatileEnvironment};
+use nimiq_database::{ReadTransaction, WriteTransaction};
 use nimiq_genesis_builder::GenesisBuilder;
 use nimiq_keys::{Address, KeyPair, PrivateKey, PublicKey, SecureGenerate};
 use nimiq_primitives::coin::Coin;
 use nimiq_primitives::networks::NetworkId;
 use nimiq_primitives::policy;
+use nimiq_primitives::slots::SlashedSlot;
 use nimiq_test_log::test;
 use nimiq_test_utils::test_transaction::{
     generate_accounts, generate_transactions, TestTransaction,
 };
+use nimiq_transaction::account::vesting_contract::VestingSchedule;
 use nimiq_transaction::{ExecutedTransaction, SignatureProof, Transaction};
+use nimiq_trie::error::MerkleRadixTrieError;
 use nimiq_trie::key_nibbles::KeyNibbles;
+use nimiq_trie::trie_node::TrieNode;
 
 const VOLATILE_ENV: bool = true;
 
@@ -117,6 +122,8 @@ fn it_can_commit_and_revert_a_block_body() {
 
     tx_logs.push(TransactionLog::new(
         tx.hash(),
+        0,
+        tx.fee,
         vec![
             Log::PayFee {
                 from: tx.sender.clone(),
@@ -198,6 +205,58 @@ fn it_can_commit_and_revert_a_block_body() {
     assert_eq!(hash1, accounts.get_root(None));
 }
 
+#[test]
+fn it_can_get_multiple_accounts() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts = Accounts::new(env.clone());
+
+    let address_1 = Address::from([1u8; Address::SIZE]);
+    let address_2 = Address::from([2u8; Address::SIZE]);
+    let address_3 = Address::from([3u8; Address::SIZE]);
+
+    let account_1 = Account::Basic(BasicAccount {
+        balance: Coin::from_u64_unchecked(1),
+    });
+    let account_3 = Account::Basic(BasicAccount {
+        balance: Coin::from_u64_unchecked(3),
+    });
+
+    let mut txn = WriteTransaction::new(&env);
+    accounts.init(
+        &mut txn,
+        vec![
+            (KeyNibbles::from(&address_1), account_1.clone()),
+            (KeyNibbles::from(&address_3), account_3.clone()),
+        ],
+    );
+
+    // Uncommitted accounts are visible within the same transaction.
+    assert_eq!(
+        accounts.get_multiple(&[address_1.clone()], Some(&txn)),
+        vec![Some(account_1.clone())]
+    );
+    txn.commit();
+
+    assert_eq!(
+        accounts.get_multiple(
+            &[
+                address_3.clone(),
+                address_2.clone(),
+                address_1.clone(),
+                address_3
+            ],
+            None
+        ),
+        vec![
+            Some(account_3.clone()),
+            None,
+            Some(account_1),
+            Some(account_3)
+        ]
+    );
+    assert!(accounts.get_multiple(&[], None).is_empty());
+}
+
 #[test]
 fn it_correctly_rewards_validators() {
     let env = VolatileEnvironment::new(10).unwrap();
@@ -850,6 +909,7 @@ fn it_commits_valid_and_failing_txns() {
         time_step: 100,
         step_amount: 100.try_into().unwrap(),
         total_amount: 1000.try_into().unwrap(),
+        schedule: VestingSchedule::Linear,
     };
 
     accounts.tree.put(
@@ -936,3 +996,751 @@ fn it_commits_valid_and_failing_txns() {
         Coin::from_u64_unchecked(800)
     );
 }
+
+#[test]
+fn it_associates_logs_with_transactions() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut genesis_builder = GenesisBuilder::default();
+
+    let sender_accounts = generate_accounts(vec![1000; 3], &mut genesis_builder, true);
+    let recipient_accounts = generate_accounts(vec![0; 3], &mut genesis_builder, false);
+
+    let validator_address = Address::from(&KeyPair::generate(&mut rng));
+    genesis_builder.with_genesis_validator(
+        validator_address.clone(),
+        PublicKey::from([0u8; 32]),
+        BLSKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+    let accounts = Accounts::new(env.clone());
+    let mut txn = WriteTransaction::new(&env);
+    accounts.init(&mut txn, genesis_info.accounts);
+    txn.commit();
+
+    // The last transaction moves more funds than available, so it fails but still pays its fee.
+    let mempool_transactions = (0..3)
+        .map(|i| TestTransaction {
+            fee: i as u64 + 1,
+            value: if i == 2 { 5000 } else { 100 },
+            recipient: recipient_accounts[i].clone(),
+            sender: sender_accounts[i].clone(),
+        })
+        .collect();
+    let (transactions, _) = generate_transactions(mempool_transactions, false);
+
+    let inherents = vec![Inherent::slash(0, validator_address.clone(), 1)];
+
+    let mut txn = WriteTransaction::new(&env);
+    let (batch_info, executed_txns) = accounts
+        .commit(&mut txn, &transactions, &inherents, 1, 1)
+        .unwrap();
+    txn.commit();
+
+    assert_eq!(batch_info.tx_logs.len(), transactions.len());
+    for (i, (tx_log, tx)) in batch_info.tx_logs.iter().zip(&transactions).enumerate() {
+        assert_eq!(tx_log.tx_hash, tx.hash());
+        assert_eq!(tx_log.index, i as u16);
+        assert_eq!(tx_log.fee, tx.fee);
+        assert!(tx_log
+            .logs
+            .iter()
+            .all(|log| log.is_related_to_address(&tx.sender)
+                || log.is_related_to_address(&tx.recipient)));
+    }
+    assert!(matches!(
+        batch_info.tx_logs[2].logs[0],
+        Log::FailedTransaction { .. }
+    ));
+
+    // The slash logs are kept apart from the transaction logs.
+    assert!(!batch_info.inherent_logs.is_empty());
+    assert!(batch_info
+        .inherent_logs
+        .iter()
+        .all(|log| log.is_related_to_address(&validator_address)));
+
+    // Reverting yields the same structure, with the transactions in reverse order.
+    let receipts = Receipts::from(batch_info.receipts);
+    let mut txn = WriteTransaction::new(&env);
+    let revert_info = accounts
+        .revert(&mut txn, &executed_txns, &inherents, 1, 1, &receipts)
+        .unwrap();
+    txn.commit();
+
+    assert_eq!(revert_info.tx_logs.len(), transactions.len());
+    for (tx_log, tx) in revert_info.tx_logs.iter().zip(transactions.iter().rev()) {
+        assert_eq!(tx_log.tx_hash, tx.hash());
+        assert_eq!(tx_log.fee, tx.fee);
+    }
+    assert_eq!(
+        revert_info
+            .tx_logs
+            .iter()
+            .map(|tx_log| tx_log.index)
+            .collect::<Vec<_>>(),
+        vec![2, 1, 0]
+    );
+    assert!(!revert_info.inherent_logs.is_empty());
+    assert!(revert_info
+        .inherent_logs
+        .iter()
+        .all(|log| log.is_related_to_address(&validator_address)));
+}
+
+#[test]
+fn it_can_prove_accounts() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts = Accounts::new(env.clone());
+
+    let account = |balance| {
+        Account::Basic(BasicAccount {
+            balance: Coin::from_u64_unchecked(balance),
+        })
+    };
+
+    let mut txn = WriteTransaction::new(&env);
+    accounts.init(
+        &mut txn,
+        vec![
+            (KeyNibbles::from(&[1u8; 20][..]), account(100)),
+            (KeyNibbles::from(&[2u8; 20][..]), account(200)),
+            (KeyNibbles::from(&[3u8; 20][..]), account(300)),
+        ],
+    );
+    txn.commit();
+
+    let root = accounts.get_root(None);
+
+    // Multiple existing accounts.
+    let addresses = vec![Address::from([3u8; 20]), Address::from([1u8; 20])];
+    let proof = accounts.get_proof(&addresses, None);
+    assert_eq!(
+        proof.verify(&root),
+        Ok(vec![
+            (Address::from([3u8; 20]), Some(account(300))),
+            (Address::from([1u8; 20]), Some(account(100))),
+        ])
+    );
+
+    // Existing and missing accounts. The missing accounts either have no child on their path,
+    // or their path ends at a leaf with a different key.
+    let mut almost_existing = [1u8; 20];
+    almost_existing[19] = 2;
+    let addresses = vec![
+        Address::from([2u8; 20]),
+        Address::from([4u8; 20]),
+        Address::from([0x15u8; 20]),
+        Address::from(almost_existing),
+    ];
+    let proof = accounts.get_proof(&addresses, None);
+    assert_eq!(
+        proof.verify(&root),
+        Ok(vec![
+            (Address::from([2u8; 20]), Some(account(200))),
+            (Address::from([4u8; 20]), None),
+            (Address::from([0x15u8; 20]), None),
+            (Address::from(almost_existing), None),
+        ])
+    );
+
+    // The proof can be shipped over the wire.
+    let proof: AccountsProof =
+        Deserialize::deserialize_from_vec(&proof.serialize_to_vec()).unwrap();
+    assert_eq!(proof.verify(&root).unwrap().len(), 4);
+
+    // The proof doesn't match a different root.
+    assert_eq!(
+        proof.verify(&Blake2bHash::default()),
+        Err(AccountsProofError::InvalidProof)
+    );
+
+    // A proof with a tampered account is rejected.
+    let mut tampered = proof.clone();
+    let index = tampered
+        .proof
+        .nodes
+        .iter()
+        .position(|node| node.key() == &KeyNibbles::from(&[2u8; 20][..]))
+        .unwrap();
+    tampered.proof.nodes[index] =
+        TrieNode::new_leaf(KeyNibbles::from(&[2u8; 20][..]), account(2000));
+    assert_eq!(
+        tampered.verify(&root),
+        Err(AccountsProofError::InvalidProof)
+    );
+
+    // A proof can't hide an existing account by omitting its node.
+    let mut tampered = proof.clone();
+    tampered.proof.nodes.remove(index);
+    assert_eq!(
+        tampered.verify(&root),
+        Err(AccountsProofError::IncompleteProof {
+            address: Address::from([2u8; 20])
+        })
+    );
+
+    // A proof can't be extended to addresses it doesn't cover.
+    let mut tampered = proof;
+    tampered.addresses.push(Address::from([3u8; 20]));
+    assert_eq!(
+        tampered.verify(&root),
+        Err(AccountsProofError::IncompleteProof {
+            address: Address::from([3u8; 20])
+        })
+    );
+}
+
+#[test]
+fn it_can_prove_single_accounts() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts = Accounts::new(env.clone());
+
+    // Absence can be proven in an empty trie.
+    let root = accounts.get_root(None);
+    let address = Address::from([1u8; 20]);
+    let proof = accounts.get_proof(&[address.clone()], None);
+    assert_eq!(proof.verify(&root), Ok(vec![(address.clone(), None)]));
+
+    let account = Account::Basic(BasicAccount {
+        balance: Coin::from_u64_unchecked(100),
+    });
+    let mut txn = WriteTransaction::new(&env);
+    accounts.init(
+        &mut txn,
+        vec![(KeyNibbles::from(&address), account.clone())],
+    );
+    txn.commit();
+
+    // The old proof doesn't match the new root.
+    let root = accounts.get_root(None);
+    assert_eq!(proof.verify(&root), Err(AccountsProofError::InvalidProof));
+
+    let proof = accounts.get_proof(&[address.clone()], None);
+    assert_eq!(proof.verify(&root), Ok(vec![(address, Some(account))]));
+}
+
+#[test]
+fn it_can_diff_accounts_between_commits() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts = Accounts::new(env.clone());
+
+    let account = |balance| {
+        Account::Basic(BasicAccount {
+            balance: Coin::from_u64_unchecked(balance),
+        })
+    };
+    let address = |byte| Address::from([byte; 20]);
+    let key = |byte| KeyNibbles::from(&address(byte));
+
+    let mut txn = WriteTransaction::new(&env);
+    accounts.init(
+        &mut txn,
+        vec![
+            (key(1), account(100)),
+            (key(2), account(200)),
+            (key(3), account(300)),
+        ],
+    );
+    txn.commit();
+
+    let before = ReadTransaction::new(&env);
+    let before_root = accounts.tree.root_hash(&before);
+    assert_eq!(
+        accounts
+            .tree
+            .diff(&before_root, &before_root, &before, &before),
+        Ok(vec![])
+    );
+
+    let rewards = vec![
+        Inherent {
+            ty: InherentType::Reward,
+            target: Address::from([1u8; 20]),
+            value: Coin::from_u64_unchecked(10),
+            data: vec![],
+        },
+        Inherent {
+            ty: InherentType::Reward,
+            target: Address::from([4u8; 20]),
+            value: Coin::from_u64_unchecked(40),
+            data: vec![],
+        },
+    ];
+    let mut txn = WriteTransaction::new(&env);
+    accounts.tree.remove(&mut txn, &key(3));
+    accounts.commit(&mut txn, &[], &rewards, 1, 1).unwrap();
+    txn.commit();
+
+    let after = ReadTransaction::new(&env);
+    let after_root = accounts.tree.root_hash(&after);
+    assert_eq!(
+        accounts
+            .tree
+            .diff(&before_root, &after_root, &before, &after),
+        Ok(vec![
+            AccountDiff::Updated {
+                address: address(1),
+                before: account(100),
+                after: account(110),
+            },
+            AccountDiff::Deleted(address(3), account(300)),
+            AccountDiff::Created(address(4), account(40)),
+        ])
+    );
+    assert_eq!(
+        accounts
+            .tree
+            .diff(&after_root, &before_root, &after, &before)
+            .unwrap()
+            .iter()
+            .map(AccountDiff::address)
+            .collect::<Vec<_>>(),
+        vec![&address(1), &address(3), &address(4)]
+    );
+
+    // The transactions must see the given roots.
+    assert_eq!(
+        accounts
+            .tree
+            .diff(&before_root, &after_root, &after, &before),
+        Err(MerkleRadixTrieError::RootHashMismatch)
+    );
+}
+
+#[test]
+fn it_reports_the_address_of_a_failing_inherent() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts = Accounts::new(env.clone());
+    let mut txn = WriteTransaction::new(&env);
+
+    let address = Address::from([1u8; 20]);
+
+    // Basic accounts only accept reward inherents.
+    let inherent = Inherent {
+        ty: InherentType::Slash,
+        target: address.clone(),
+        value: Coin::ZERO,
+        data: vec![],
+    };
+
+    let err = accounts
+        .commit(&mut txn, &[], &[inherent], 1, 1)
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        AccountError::WithContext {
+            address: address.clone(),
+            source: Box::new(AccountError::InvalidInherent),
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        format!("Invalid inherent at address {}", address)
+    );
+
+    // The address isn't replaced once it is attached.
+    match err.at(Address::from([2u8; 20])) {
+        AccountError::WithContext {
+            address: err_address,
+            ..
+        } => assert_eq!(err_address, address),
+        err => panic!("Unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn it_can_sync_accounts_in_chunks() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let genesis_accounts: Vec<(KeyNibbles, Account)> = (0..3000u64)
+        .map(|i| {
+            let mut address = [0u8; Address::SIZE];
+            rng.fill(&mut address);
+            (
+                KeyNibbles::from(&address[..]),
+                Account::Basic(BasicAccount {
+                    balance: Coin::from_u64_unchecked(i + 1),
+                }),
+            )
+        })
+        .collect();
+
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts = Accounts::new(env.clone());
+    let mut txn = WriteTransaction::new(&env);
+    accounts.init(&mut txn, genesis_accounts.clone());
+    txn.commit();
+    let root = accounts.get_root(None);
+
+    // A node that inserted the same accounts in a different order serves identical chunks.
+    let other_env = VolatileEnvironment::new(10).unwrap();
+    let other_accounts = Accounts::new(other_env.clone());
+    let mut txn = WriteTransaction::new(&other_env);
+    other_accounts.init(&mut txn, genesis_accounts.into_iter().rev().collect());
+    txn.commit();
+    assert_eq!(other_accounts.get_root(None), root);
+
+    let sync_env = VolatileEnvironment::new(10).unwrap();
+    let sync_accounts = Accounts::new(sync_env.clone());
+
+    let mut start_key = KeyNibbles::root();
+    let mut num_chunks = 0;
+    while sync_accounts.get_root(None) != root {
+        let chunk = accounts.get_chunk(&start_key, 100, None);
+        assert_eq!(
+            chunk.serialize_to_vec(),
+            other_accounts
+                .get_chunk(&start_key, 100, None)
+                .serialize_to_vec()
+        );
+
+        let mut txn = WriteTransaction::new(&sync_env);
+        sync_accounts.commit_chunk(&mut txn, chunk, &root).unwrap();
+        txn.commit();
+
+        start_key = sync_accounts
+            .tree
+            .last_leaf_key(&ReadTransaction::new(&sync_env))
+            .unwrap();
+
+        num_chunks += 1;
+        assert!(num_chunks <= 31);
+    }
+
+    assert_eq!(sync_accounts.size(None), 3000);
+}
+
+#[test]
+fn it_keeps_the_cached_root_consistent_across_commits() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let genesis_accounts: Vec<(KeyNibbles, Account)> = (0..200u64)
+        .map(|i| {
+            let mut address = [0u8; Address::SIZE];
+            rng.fill(&mut address);
+            (
+                KeyNibbles::from(&address[..]),
+                Account::Basic(BasicAccount {
+                    balance: Coin::from_u64_unchecked(i + 1),
+                }),
+            )
+        })
+        .collect();
+
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts = Accounts::new(env.clone());
+    let mut txn = WriteTransaction::new(&env);
+    accounts.init(&mut txn, genesis_accounts);
+    txn.commit();
+
+    // Rebuilds the trie from its leaves in a fresh environment, which hashes every node.
+    let recompute_root = |accounts: &Accounts| {
+        let chunk = accounts.get_chunk(&KeyNibbles::root(), 1000, None);
+        let fresh_env = VolatileEnvironment::new(10).unwrap();
+        let fresh_accounts = Accounts::new(fresh_env.clone());
+        let mut txn = WriteTransaction::new(&fresh_env);
+        fresh_accounts.init(
+            &mut txn,
+            chunk
+                .nodes
+                .iter()
+                .map(|node| (node.key().clone(), node.value().unwrap()))
+                .collect(),
+        );
+        txn.commit();
+        fresh_accounts.get_root(None)
+    };
+
+    assert_eq!(accounts.get_root(None), recompute_root(&accounts));
+
+    let mut roots = vec![accounts.get_root(None)];
+    let mut receipts = vec![];
+    for i in 1..=5u8 {
+        let rewards: Vec<Inherent> = (0..i)
+            .map(|j| Inherent {
+                ty: InherentType::Reward,
+                target: Address::from([i * 10 + j; Address::SIZE]),
+                value: Coin::from_u64_unchecked(u64::from(j) + 1),
+                data: vec![],
+            })
+            .collect();
+
+        let mut txn = WriteTransaction::new(&env);
+        let (batch_info, _) = accounts
+            .commit(&mut txn, &[], &rewards, u32::from(i), u64::from(i))
+            .unwrap();
+        txn.commit();
+
+        let root = accounts.get_root(None);
+        assert_ne!(&root, roots.last().unwrap());
+        assert_eq!(accounts.get_root(None), root);
+        assert_eq!(root, recompute_root(&accounts));
+
+        roots.push(root);
+        receipts.push((i, rewards, Receipts::from(batch_info.receipts)));
+    }
+
+    // Committing a batch without changes doesn't change the root.
+    let mut txn = WriteTransaction::new(&env);
+    accounts.finalize_batch(&mut txn);
+    txn.commit();
+    assert_eq!(&accounts.get_root(None), roots.last().unwrap());
+
+    while let Some((i, rewards, receipts)) = receipts.pop() {
+        roots.pop();
+
+        let mut txn = WriteTransaction::new(&env);
+        accounts
+            .revert(
+                &mut txn,
+                &[],
+                &rewards,
+                u32::from(i),
+                u64::from(i),
+                &receipts,
+            )
+            .unwrap();
+        txn.commit();
+
+        assert_eq!(&accounts.get_root(None), roots.last().unwrap());
+        assert_eq!(accounts.get_root(None), recompute_root(&accounts));
+    }
+}
+
+#[test]
+fn it_rejects_invalid_account_chunks() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts = Accounts::new(env.clone());
+    let mut txn = WriteTransaction::new(&env);
+    accounts.init(
+        &mut txn,
+        (1..=20u8)
+            .map(|i| {
+                (
+                    KeyNibbles::from(&[i; Address::SIZE][..]),
+                    Account::Basic(BasicAccount {
+                        balance: Coin::from_u64_unchecked(i as u64),
+                    }),
+                )
+            })
+            .collect(),
+    );
+    txn.commit();
+    let root = accounts.get_root(None);
+
+    let sync_env = VolatileEnvironment::new(10).unwrap();
+    let sync_accounts = Accounts::new(sync_env.clone());
+    let mut txn = WriteTransaction::new(&sync_env);
+
+    // The first chunk must start at the root key.
+    let chunk = accounts.get_chunk(&KeyNibbles::from(&[5u8; Address::SIZE][..]), 5, None);
+    assert_eq!(
+        sync_accounts.commit_chunk(&mut txn, chunk, &root),
+        Err(AccountError::InvalidChunk(
+            MerkleRadixTrieError::ChunkNotContiguous
+        ))
+    );
+
+    // A chunk with a wrong root.
+    let chunk = accounts.get_chunk(&KeyNibbles::root(), 5, None);
+    assert_eq!(
+        sync_accounts.commit_chunk(&mut txn, chunk.clone(), &Blake2bHash::default()),
+        Err(AccountError::InvalidChunk(
+            MerkleRadixTrieError::InvalidChunkProof
+        ))
+    );
+
+    // A chunk that doesn't match its proof.
+    let mut tampered = chunk.clone();
+    tampered.nodes.remove(2);
+    assert_eq!(
+        sync_accounts.commit_chunk(&mut txn, tampered, &root),
+        Err(AccountError::InvalidChunk(
+            MerkleRadixTrieError::InvalidChunkProof
+        ))
+    );
+
+    // A chunk that leaves out an account, together with its proof.
+    let mut tampered = chunk.clone();
+    let removed = tampered.nodes.remove(2);
+    tampered
+        .proof
+        .nodes
+        .retain(|node| node.key() != removed.key());
+    assert_eq!(
+        sync_accounts.commit_chunk(&mut txn, tampered, &root),
+        Err(AccountError::InvalidChunk(
+            MerkleRadixTrieError::IncompleteChunk
+        ))
+    );
+
+    // The untampered chunk is accepted.
+    assert_eq!(sync_accounts.commit_chunk(&mut txn, chunk, &root), Ok(()));
+}
+
+#[test]
+fn it_can_verify_and_sort_receipts() {
+    let sorted = vec![
+        Receipt::Inherent {
+            index: 0,
+            pre_transactions: true,
+            data: None,
+        },
+        Receipt::Inherent {
+            index: 2,
+            pre_transactions: true,
+            data: None,
+        },
+        Receipt::Transaction {
+            index: 0,
+            sender: true,
+            data: None,
+        },
+        Receipt::Transaction {
+            index: 1,
+            sender: true,
+            data: Some(vec![1, 2, 3]),
+        },
+        Receipt::Transaction {
+            index: 0,
+            sender: false,
+            data: None,
+        },
+        Receipt::Inherent {
+            index: 1,
+            pre_transactions: false,
+            data: None,
+        },
+    ];
+    assert_eq!(Receipts::from(sorted.clone()).verify_sorted(), Ok(()));
+
+    let mut shuffled = sorted.clone();
+    shuffled.reverse();
+    shuffled.rotate_left(2);
+    let mut receipts = Receipts::from(shuffled);
+    assert_eq!(
+        receipts.verify_sorted(),
+        Err(AccountError::ReceiptsNotOrdered)
+    );
+
+    receipts.sort();
+    assert_eq!(receipts.verify_sorted(), Ok(()));
+    assert_eq!(receipts.receipts, sorted);
+
+    // Duplicates are rejected.
+    let mut duplicated = sorted;
+    duplicated.push(duplicated[5].clone());
+    assert_eq!(
+        Receipts::from(duplicated).verify_sorted(),
+        Err(AccountError::ReceiptsNotOrdered)
+    );
+}
+
+#[test]
+fn it_can_create_typed_inherents() {
+    let address = Address::from([1u8; Address::SIZE]);
+
+    let reward = Inherent::reward(address.clone(), Coin::from_u64_unchecked(100));
+    assert_eq!(reward.ty, InherentType::Reward);
+    assert_eq!(reward.target, address);
+    assert_eq!(reward.value, Coin::from_u64_unchecked(100));
+    assert!(reward.data.is_empty());
+    assert!(reward.is_valid_for(true));
+    assert!(!reward.is_valid_for(false));
+
+    let slash = Inherent::slash(5, address.clone(), 42);
+    assert_eq!(slash.ty, InherentType::Slash);
+    assert_eq!(slash.target, policy::STAKING_CONTRACT_ADDRESS);
+    assert_eq!(slash.value, Coin::ZERO);
+    let slot: SlashedSlot = Deserialize::deserialize_from_vec(&slash.data).unwrap();
+    assert_eq!(slot.slot, 5);
+    assert_eq!(slot.validator_address, address);
+    assert_eq!(slot.event_block, 42);
+    assert!(slash.is_pre_transactions());
+    assert!(slash.is_valid_for(false));
+    assert!(!slash.is_valid_for(true));
+
+    for inherent in [Inherent::finalize_batch(), Inherent::finalize_epoch()] {
+        assert_eq!(inherent.target, policy::STAKING_CONTRACT_ADDRESS);
+        assert_eq!(inherent.value, Coin::ZERO);
+        assert!(inherent.data.is_empty());
+        assert!(inherent.is_valid_for(true));
+        assert!(!inherent.is_valid_for(false));
+    }
+    assert_eq!(Inherent::finalize_batch().ty, InherentType::FinalizeBatch);
+    assert_eq!(Inherent::finalize_epoch().ty, InherentType::FinalizeEpoch);
+}
+
+#[test]
+fn it_orders_receipts_like_commit() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut genesis_builder = GenesisBuilder::default();
+
+    let sender_accounts = generate_accounts(vec![1000; 2], &mut genesis_builder, true);
+    let recipient_accounts = generate_accounts(vec![0; 2], &mut genesis_builder, false);
+
+    let validator_address = Address::from(&KeyPair::generate(&mut rng));
+    genesis_builder.with_genesis_validator(
+        validator_address.clone(),
+        PublicKey::from([0u8; 32]),
+        BLSKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+    let accounts = Accounts::new(env.clone());
+    let mut txn = WriteTransaction::new(&env);
+    accounts.init(&mut txn, genesis_info.accounts);
+    txn.commit();
+
+    let mempool_transactions = (0..2)
+        .map(|i| TestTransaction {
+            fee: 1,
+            value: 100,
+            recipient: recipient_accounts[i].clone(),
+            sender: sender_accounts[i].clone(),
+        })
+        .collect();
+    let (transactions, _) = generate_transactions(mempool_transactions, false);
+
+    // The reward is applied after the transactions, the slash before them.
+    let inherents = vec![
+        Inherent::reward(
+            recipient_accounts[0].address.clone(),
+            Coin::from_u64_unchecked(10),
+        ),
+        Inherent::slash(0, validator_address, 1),
+    ];
+
+    let mut txn = WriteTransaction::new(&env);
+    let (batch_info, _) = accounts
+        .commit(&mut txn, &transactions, &inherents, 1, 1)
+        .unwrap();
+    txn.commit();
+
+    let receipts = Receipts::from(batch_info.receipts);
+    assert_eq!(receipts.verify_sorted(), Ok(()));
+    assert!(matches!(
+        receipts.receipts.first(),
+        Some(Receipt::Inherent {
+            pre_transactions: true,
+            ..
+        })
+    ));
+    assert!(matches!(
+        receipts.receipts.last(),
+        Some(Receipt::Inherent {
+            pre_transactions: false,
+            ..
+        })
+    ));
+
+    // Sorting restores the order in which the receipts were created.
+    let mut shuffled = receipts.clone();
+    shuffled.receipts.reverse();
+    shuffled.sort();
+    assert_eq!(shuffled, receipts);
+}
@@ -2,7 +2,8 @@ use std::convert::TryInto;
 
 use beserial::{Deserialize, Serialize};
 use nimiq_account::{
-    Account, AccountError, AccountTransactionInteraction, AccountsTrie, BasicAccount, Log,
+    Account, AccountError, AccountInherentInteraction, AccountTransactionInteraction, AccountsTrie,
+    BasicAccount, Inherent, InherentType, Log,
 };
 use nimiq_database::volatile::VolatileEnvironment;
 use nimiq_database::WriteTransaction;
@@ -99,9 +100,9 @@ fn basic_transfer_works() {
 
     assert_eq!(
         BasicAccount::commit_outgoing_transaction(&accounts_tree, &mut db_txn, &tx, 1, 2),
-        Err(AccountError::InsufficientFunds {
-            needed: Coin::from_u64_unchecked(1001),
-            balance: Coin::from_u64_unchecked(899)
+        Err(AccountError::InsufficientFundsDetail {
+            available: 899,
+            required: 1001
         })
     );
 
@@ -110,9 +111,9 @@ fn basic_transfer_works() {
 
     assert_eq!(
         BasicAccount::commit_outgoing_transaction(&accounts_tree, &mut db_txn, &tx, 1, 2),
-        Err(AccountError::InsufficientFunds {
-            needed: Coin::from_u64_unchecked(900),
-            balance: Coin::from_u64_unchecked(899)
+        Err(AccountError::InsufficientFundsDetail {
+            available: 899,
+            required: 900
         })
     );
 
@@ -234,6 +235,59 @@ fn create_and_prune_works() {
     assert_eq!(accounts_tree.get(&db_txn, &key_recipient), None);
 }
 
+#[test]
+fn reverting_prunes_empty_accounts() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts_tree = AccountsTrie::new(env.clone(), "AccountsTree");
+    let mut db_txn = WriteTransaction::new(&env);
+
+    init_tree(&accounts_tree, &mut db_txn);
+
+    assert!(BasicAccount {
+        balance: Coin::ZERO
+    }
+    .is_prunable());
+    assert!(!BasicAccount {
+        balance: Coin::from_u64_unchecked(1)
+    }
+    .is_prunable());
+
+    let key_sender = KeyNibbles::from(&Address::from_any_str(ADDRESS_1).unwrap());
+    let sender_before = accounts_tree.get(&db_txn, &key_sender);
+
+    // A failed transaction that spends the entire balance on the fee prunes the sender, reverting
+    // it recreates the sender identically.
+    let mut tx = make_signed_transaction(1, Address::from([0; 20]));
+    tx.fee = Coin::from_u64_unchecked(1000);
+
+    BasicAccount::commit_failed_transaction(&accounts_tree, &mut db_txn, &tx, 1).unwrap();
+    assert_eq!(accounts_tree.get(&db_txn, &key_sender), None);
+
+    BasicAccount::revert_failed_transaction(&accounts_tree, &mut db_txn, &tx, None).unwrap();
+    assert_eq!(accounts_tree.get(&db_txn, &key_sender), sender_before);
+
+    // Reverting a reward to a new account doesn't leave an empty account behind.
+    let address = Address::from([1; 20]);
+    let key = KeyNibbles::from(&address);
+    let inherent = Inherent {
+        ty: InherentType::Reward,
+        target: address,
+        value: Coin::from_u64_unchecked(10),
+        data: vec![],
+    };
+
+    BasicAccount::commit_inherent(&accounts_tree, &mut db_txn, &inherent, 1, 2).unwrap();
+    assert_eq!(
+        accounts_tree.get(&db_txn, &key),
+        Some(Account::Basic(BasicAccount {
+            balance: Coin::from_u64_unchecked(10)
+        }))
+    );
+
+    BasicAccount::revert_inherent(&accounts_tree, &mut db_txn, &inherent, 1, 2, None).unwrap();
+    assert_eq!(accounts_tree.get(&db_txn, &key), None);
+}
+
 fn init_tree(accounts_tree: &AccountsTrie, db_txn: &mut WriteTransaction) {
     let key_1 = KeyNibbles::from(&Address::from_any_str(ADDRESS_1).unwrap());
     let key_2 = KeyNibbles::from(&Address::from_any_str(ADDRESS_2).unwrap());
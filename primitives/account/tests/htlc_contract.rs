@@ -3,16 +3,17 @@ use std::convert::TryInto;
 use beserial::{Deserialize, Serialize, SerializingError};
 use nimiq_account::{
     Account, AccountError, AccountTransactionInteraction, AccountsTrie, HashedTimeLockedContract,
+    Log,
 };
 use nimiq_database::volatile::VolatileEnvironment;
 use nimiq_database::WriteTransaction;
-use nimiq_hash::{Blake2bHasher, HashOutput, Hasher, Sha256Hasher};
+use nimiq_hash::{Blake2bHasher, HashOutput, Hasher, Sha256Hasher, Sha512Hasher};
 use nimiq_keys::{Address, KeyPair, PrivateKey};
 use nimiq_primitives::account::AccountType;
 use nimiq_primitives::coin::Coin;
 use nimiq_primitives::networks::NetworkId;
 use nimiq_test_log::test;
-use nimiq_transaction::account::htlc_contract::{AnyHash, HashAlgorithm, ProofType};
+use nimiq_transaction::account::htlc_contract::{AnyHash, AnyHash32, HashAlgorithm, ProofType};
 use nimiq_transaction::account::AccountTransactionVerification;
 use nimiq_transaction::{SignatureProof, Transaction, TransactionError, TransactionFlags};
 use nimiq_trie::key_nibbles::KeyNibbles;
@@ -72,10 +73,44 @@ fn it_can_serialize_a_htlc() {
     assert_eq!(hex::encode(bytes2), HTLC);
 }
 
+#[test]
+fn it_still_deserializes_htlcs_with_32_byte_hash_roots() {
+    let bytes: Vec<u8> = hex::decode(HTLC).unwrap();
+    let htlc: HashedTimeLockedContract = Deserialize::deserialize(&mut &bytes[..]).unwrap();
+    assert!(matches!(htlc.hash_root, AnyHash::Hash32(_)));
+
+    let account: Account =
+        Deserialize::deserialize_from_vec(&Account::HTLC(htlc.clone()).serialize_to_vec()).unwrap();
+    assert_eq!(account, Account::HTLC(htlc));
+}
+
+#[test]
+fn it_can_serialize_a_sha512_htlc() {
+    let contract = HashedTimeLockedContract {
+        balance: Coin::from_u64_unchecked(1000),
+        sender: "1b215589344cf570d36bec770825eae30b732139".parse().unwrap(),
+        recipient: "24786862babbdb05e7c4430612135eb2a8368123".parse().unwrap(),
+        hash_algorithm: HashAlgorithm::Sha512,
+        hash_root: AnyHash::from(Sha512Hasher::default().digest(&[1u8; 64])),
+        hash_count: 1,
+        timeout: 169525,
+        total_amount: Coin::from_u64_unchecked(1000),
+    };
+
+    let bytes = contract.serialize_to_vec();
+    assert_eq!(bytes.len(), 8 + 20 + 20 + 1 + 64 + 1 + 8 + 8);
+    assert_eq!(contract.serialized_size(), bytes.len());
+    assert_eq!(bytes[48], HashAlgorithm::Sha512 as u8);
+
+    let htlc: HashedTimeLockedContract = Deserialize::deserialize_from_vec(&bytes).unwrap();
+    assert!(matches!(htlc.hash_root, AnyHash::Hash64(_)));
+    assert_eq!(htlc, contract);
+}
+
 #[test]
 #[allow(unused_must_use)]
 fn it_can_verify_creation_transaction() {
-    let mut data: Vec<u8> = Vec::with_capacity(Address::SIZE * 2 + AnyHash::SIZE + 10);
+    let mut data: Vec<u8> = Vec::with_capacity(Address::SIZE * 2 + AnyHash32::SIZE + 10);
     let sender = Address::from([0u8; 20]);
     let recipient = Address::from([0u8; 20]);
     sender.serialize(&mut data);
@@ -152,7 +187,7 @@ fn it_can_create_contract_from_transaction() {
     let accounts_tree = AccountsTrie::new(env.clone(), "AccountsTree");
     let mut db_txn = WriteTransaction::new(&env);
 
-    let mut data: Vec<u8> = Vec::with_capacity(Address::SIZE * 2 + AnyHash::SIZE + 10);
+    let mut data: Vec<u8> = Vec::with_capacity(Address::SIZE * 2 + AnyHash32::SIZE + 10);
     let sender = Address::from([0u8; 20]);
     let recipient = Address::from([0u8; 20]);
     sender.serialize(&mut data);
@@ -302,7 +337,7 @@ fn it_can_verify_regular_transfer() {
 
     // regular: valid Blake-2b
     let mut proof =
-        Vec::with_capacity(3 + 2 * AnyHash::SIZE + recipient_signature_proof.serialized_size());
+        Vec::with_capacity(3 + 2 * AnyHash32::SIZE + recipient_signature_proof.serialized_size());
     Serialize::serialize(&ProofType::RegularTransfer, &mut proof);
     Serialize::serialize(&HashAlgorithm::Blake2b, &mut proof);
     Serialize::serialize(&1u8, &mut proof);
@@ -318,7 +353,8 @@ fn it_can_verify_regular_transfer() {
     assert_eq!(AccountType::verify_outgoing_transaction(&tx), Ok(()));
 
     // regular: valid SHA-256
-    proof = Vec::with_capacity(3 + 2 * AnyHash::SIZE + recipient_signature_proof.serialized_size());
+    proof =
+        Vec::with_capacity(3 + 2 * AnyHash32::SIZE + recipient_signature_proof.serialized_size());
     Serialize::serialize(&ProofType::RegularTransfer, &mut proof);
     Serialize::serialize(&HashAlgorithm::Sha256, &mut proof);
     Serialize::serialize(&1u8, &mut proof);
@@ -366,7 +402,8 @@ fn it_can_verify_regular_transfer() {
     );
 
     // regular: invalid over-long
-    proof = Vec::with_capacity(4 + 2 * AnyHash::SIZE + recipient_signature_proof.serialized_size());
+    proof =
+        Vec::with_capacity(4 + 2 * AnyHash32::SIZE + recipient_signature_proof.serialized_size());
     Serialize::serialize(&ProofType::RegularTransfer, &mut proof);
     Serialize::serialize(&HashAlgorithm::Blake2b, &mut proof);
     Serialize::serialize(&1u8, &mut proof);
@@ -466,6 +503,81 @@ fn it_can_verify_timeout_resolve() {
     );
 }
 
+#[test]
+#[allow(unused_must_use)]
+fn it_checks_the_hash_lock_and_timeout_before_paying() {
+    let (contract, mut tx, pre_image, sender_signature_proof, recipient_signature_proof) =
+        prepare_outgoing_transaction();
+
+    let regular_transfer_proof = |hash_depth: u8, pre_image: &AnyHash| {
+        let mut proof = Vec::new();
+        Serialize::serialize(&ProofType::RegularTransfer, &mut proof);
+        Serialize::serialize(&HashAlgorithm::Blake2b, &mut proof);
+        Serialize::serialize(&hash_depth, &mut proof);
+        Serialize::serialize(&contract.hash_root, &mut proof);
+        Serialize::serialize(pre_image, &mut proof);
+        Serialize::serialize(&recipient_signature_proof, &mut proof);
+        proof
+    };
+
+    // The recipient can claim the funds with the pre-image before the timeout.
+    tx.proof = regular_transfer_proof(2, &pre_image);
+    assert_eq!(contract.can_pay(&tx, 50), Ok(()));
+
+    // But not after it.
+    assert_eq!(
+        contract.can_pay(&tx, 101),
+        Err(AccountError::HTLCExpired {
+            timeout: 100,
+            block_time: 101,
+        })
+    );
+
+    // Nor with a wrong pre-image.
+    tx.proof = regular_transfer_proof(2, &AnyHash::from([2u8; 32]));
+    assert_eq!(
+        contract.can_pay(&tx, 50),
+        Err(AccountError::HTLCInvalidPreImage)
+    );
+
+    // A partial pre-image only unlocks part of the funds.
+    let partial_pre_image = AnyHash::from(<[u8; 32]>::from(
+        Blake2bHasher::default().digest(pre_image.as_bytes()),
+    ));
+    tx.proof = regular_transfer_proof(1, &partial_pre_image);
+    assert_eq!(
+        contract.can_pay(&tx, 50),
+        Err(AccountError::InsufficientFundsDetail {
+            available: 0,
+            required: 500,
+        })
+    );
+
+    // The sender can only get the funds back after the timeout.
+    let mut proof = Vec::new();
+    Serialize::serialize(&ProofType::TimeoutResolve, &mut proof);
+    Serialize::serialize(&sender_signature_proof, &mut proof);
+    tx.proof = proof;
+    assert_eq!(
+        contract.can_pay(&tx, 100),
+        Err(AccountError::HTLCNotExpired {
+            timeout: 100,
+            block_time: 100,
+        })
+    );
+    assert_eq!(contract.can_pay(&tx, 101), Ok(()));
+
+    // A timeout resolve signed by the recipient is rejected.
+    let mut proof = Vec::new();
+    Serialize::serialize(&ProofType::TimeoutResolve, &mut proof);
+    Serialize::serialize(&recipient_signature_proof, &mut proof);
+    tx.proof = proof;
+    assert_eq!(
+        contract.can_pay(&tx, 101),
+        Err(AccountError::InvalidSignature)
+    );
+}
+
 #[test]
 #[allow(unused_must_use)]
 fn it_can_apply_and_revert_valid_transaction() {
@@ -478,7 +590,7 @@ fn it_can_apply_and_revert_valid_transaction() {
 
     // regular transfer
     let mut proof =
-        Vec::with_capacity(3 + 2 * AnyHash::SIZE + recipient_signature_proof.serialized_size());
+        Vec::with_capacity(3 + 2 * AnyHash32::SIZE + recipient_signature_proof.serialized_size());
     Serialize::serialize(&ProofType::RegularTransfer, &mut proof);
     Serialize::serialize(&HashAlgorithm::Blake2b, &mut proof);
     Serialize::serialize(&2u8, &mut proof);
@@ -632,7 +744,7 @@ fn it_refuses_invalid_transaction() {
 
     // regular transfer: timeout passed
     let mut proof =
-        Vec::with_capacity(3 + 2 * AnyHash::SIZE + recipient_signature_proof.serialized_size());
+        Vec::with_capacity(3 + 2 * AnyHash32::SIZE + recipient_signature_proof.serialized_size());
     Serialize::serialize(&ProofType::RegularTransfer, &mut proof);
     Serialize::serialize(&HashAlgorithm::Blake2b, &mut proof);
     Serialize::serialize(&2u8, &mut proof);
@@ -649,12 +761,15 @@ fn it_refuses_invalid_transaction() {
             1,
             101
         ),
-        Err(AccountError::InvalidForSender)
+        Err(AccountError::HTLCExpired {
+            timeout: 100,
+            block_time: 101
+        })
     );
 
     // regular transfer: hash mismatch
     let mut proof =
-        Vec::with_capacity(3 + 2 * AnyHash::SIZE + recipient_signature_proof.serialized_size());
+        Vec::with_capacity(3 + 2 * AnyHash32::SIZE + recipient_signature_proof.serialized_size());
     Serialize::serialize(&ProofType::RegularTransfer, &mut proof);
     Serialize::serialize(&HashAlgorithm::Blake2b, &mut proof);
     Serialize::serialize(&2u8, &mut proof);
@@ -676,7 +791,7 @@ fn it_refuses_invalid_transaction() {
 
     // regular transfer: invalid signature
     let mut proof =
-        Vec::with_capacity(3 + 2 * AnyHash::SIZE + recipient_signature_proof.serialized_size());
+        Vec::with_capacity(3 + 2 * AnyHash32::SIZE + recipient_signature_proof.serialized_size());
     Serialize::serialize(&ProofType::RegularTransfer, &mut proof);
     Serialize::serialize(&HashAlgorithm::Blake2b, &mut proof);
     Serialize::serialize(&2u8, &mut proof);
@@ -698,7 +813,7 @@ fn it_refuses_invalid_transaction() {
 
     // regular transfer: underflow
     let mut proof =
-        Vec::with_capacity(3 + 2 * AnyHash::SIZE + recipient_signature_proof.serialized_size());
+        Vec::with_capacity(3 + 2 * AnyHash32::SIZE + recipient_signature_proof.serialized_size());
     Serialize::serialize(&ProofType::RegularTransfer, &mut proof);
     Serialize::serialize(&HashAlgorithm::Blake2b, &mut proof);
     Serialize::serialize(&1u8, &mut proof);
@@ -720,9 +835,9 @@ fn it_refuses_invalid_transaction() {
             1,
             1
         ),
-        Err(AccountError::InsufficientFunds {
-            needed: 500.try_into().unwrap(),
-            balance: 0.try_into().unwrap()
+        Err(AccountError::InsufficientFundsDetail {
+            available: 0,
+            required: 500
         })
     );
 
@@ -780,3 +895,330 @@ fn it_refuses_invalid_transaction() {
         Err(AccountError::InvalidSignature)
     );
 }
+
+#[test]
+fn it_reports_the_remaining_time_until_the_timeout() {
+    let (contract, _, _, _, _) = prepare_outgoing_transaction();
+
+    assert!(!contract.is_expired(0));
+    assert_eq!(contract.remaining_time(0), Some(100));
+    assert!(!contract.is_expired(100));
+    assert_eq!(contract.remaining_time(100), Some(0));
+    assert_eq!(contract.check_not_expired(100), Ok(()));
+
+    assert!(contract.is_expired(101));
+    assert_eq!(contract.remaining_time(101), None);
+    assert_eq!(
+        contract.check_not_expired(101),
+        Err(AccountError::HTLCExpired {
+            timeout: 100,
+            block_time: 101
+        })
+    );
+}
+
+#[test]
+#[allow(unused_must_use)]
+fn it_can_verify_spend_proofs_offline() {
+    let (contract, _, pre_image, sender_signature_proof, recipient_signature_proof) =
+        prepare_outgoing_transaction();
+
+    let regular_transfer_proof = |hash_depth: u8,
+                                  pre_image: &AnyHash,
+                                  signature: &SignatureProof| {
+        let mut proof = Vec::with_capacity(3 + 2 * AnyHash32::SIZE + signature.serialized_size());
+        Serialize::serialize(&ProofType::RegularTransfer, &mut proof);
+        Serialize::serialize(&HashAlgorithm::Blake2b, &mut proof);
+        Serialize::serialize(&hash_depth, &mut proof);
+        Serialize::serialize(&contract.hash_root, &mut proof);
+        Serialize::serialize(pre_image, &mut proof);
+        Serialize::serialize(signature, &mut proof);
+        proof
+    };
+
+    // regular transfer: valid
+    let proof = regular_transfer_proof(2, &pre_image, &recipient_signature_proof);
+    assert_eq!(contract.verify_regular_transfer(&proof, 100), Ok(()));
+
+    // regular transfer: valid with an intermediate pre-image
+    let intermediate_pre_image = AnyHash::from(<[u8; 32]>::from(
+        Blake2bHasher::default().digest(pre_image.as_bytes()),
+    ));
+    let proof = regular_transfer_proof(1, &intermediate_pre_image, &recipient_signature_proof);
+    assert_eq!(contract.verify_regular_transfer(&proof, 100), Ok(()));
+
+    // regular transfer: wrong hash count
+    let proof = regular_transfer_proof(3, &pre_image, &recipient_signature_proof);
+    assert_eq!(
+        contract.verify_regular_transfer(&proof, 100),
+        Err(AccountError::HTLCInvalidHashDepth {
+            hash_depth: 3,
+            hash_count: 2
+        })
+    );
+
+    // regular transfer: wrong pre-image
+    let proof = regular_transfer_proof(1, &pre_image, &recipient_signature_proof);
+    assert_eq!(
+        contract.verify_regular_transfer(&proof, 100),
+        Err(AccountError::HTLCInvalidPreImage)
+    );
+
+    // regular transfer: timeout passed
+    let proof = regular_transfer_proof(2, &pre_image, &recipient_signature_proof);
+    assert_eq!(
+        contract.verify_regular_transfer(&proof, 101),
+        Err(AccountError::HTLCExpired {
+            timeout: 100,
+            block_time: 101
+        })
+    );
+
+    // regular transfer: signed by the sender
+    let proof = regular_transfer_proof(2, &pre_image, &sender_signature_proof);
+    assert_eq!(
+        contract.verify_regular_transfer(&proof, 100),
+        Err(AccountError::InvalidSignature)
+    );
+
+    // early resolve: valid
+    let mut proof = Vec::new();
+    Serialize::serialize(&ProofType::EarlyResolve, &mut proof);
+    Serialize::serialize(&recipient_signature_proof, &mut proof);
+    Serialize::serialize(&sender_signature_proof, &mut proof);
+    assert_eq!(contract.verify_early_resolve(&proof), Ok(()));
+    assert_eq!(
+        contract.verify_regular_transfer(&proof, 100),
+        Err(AccountError::HTLCInvalidProofType)
+    );
+
+    // early resolve: signatures in the wrong order
+    let mut proof = Vec::new();
+    Serialize::serialize(&ProofType::EarlyResolve, &mut proof);
+    Serialize::serialize(&sender_signature_proof, &mut proof);
+    Serialize::serialize(&recipient_signature_proof, &mut proof);
+    assert_eq!(
+        contract.verify_early_resolve(&proof),
+        Err(AccountError::InvalidSignature)
+    );
+
+    // timeout resolve: valid
+    let mut proof = Vec::new();
+    Serialize::serialize(&ProofType::TimeoutResolve, &mut proof);
+    Serialize::serialize(&sender_signature_proof, &mut proof);
+    assert_eq!(contract.verify_timeout_resolve(&proof, 101), Ok(()));
+
+    // timeout resolve: premature timeout
+    assert_eq!(
+        contract.verify_timeout_resolve(&proof, 100),
+        Err(AccountError::HTLCNotExpired {
+            timeout: 100,
+            block_time: 100
+        })
+    );
+
+    // timeout resolve: invalid over-long
+    Serialize::serialize(&0u8, &mut proof);
+    assert_eq!(
+        contract.verify_timeout_resolve(&proof, 101),
+        Err(AccountError::InvalidSerialization(
+            SerializingError::InvalidValue
+        ))
+    );
+}
+
+#[test]
+#[allow(unused_must_use)]
+fn it_can_create_sha512_contract_from_transaction() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts_tree = AccountsTrie::new(env.clone(), "AccountsTree");
+    let mut db_txn = WriteTransaction::new(&env);
+
+    let hash_root = AnyHash::from(Sha512Hasher::default().digest(&[1u8; 64]));
+    let mut data: Vec<u8> = Vec::with_capacity(Address::SIZE * 2 + 64 + 10);
+    let sender = Address::from([0u8; 20]);
+    let recipient = Address::from([1u8; 20]);
+    sender.serialize(&mut data);
+    recipient.serialize(&mut data);
+    HashAlgorithm::Sha512.serialize(&mut data);
+    hash_root.serialize(&mut data);
+    Serialize::serialize(&2u8, &mut data);
+    Serialize::serialize(&1000u64, &mut data);
+
+    let mut transaction = Transaction::new_contract_creation(
+        data,
+        sender.clone(),
+        AccountType::Basic,
+        AccountType::HTLC,
+        100.try_into().unwrap(),
+        0.try_into().unwrap(),
+        0,
+        NetworkId::Dummy,
+    );
+    assert_eq!(
+        AccountType::verify_incoming_transaction(&transaction),
+        Ok(())
+    );
+
+    // A 32 byte hash root is too short for SHA-512.
+    let mut short_data = transaction.data.clone();
+    short_data.drain(41..73);
+    let mut short_transaction = transaction.clone();
+    short_transaction.data = short_data;
+    short_transaction.recipient = short_transaction.contract_creation_address();
+    assert_eq!(
+        AccountType::verify_incoming_transaction(&short_transaction),
+        Err(TransactionError::InvalidData)
+    );
+
+    HashedTimeLockedContract::create(&accounts_tree, &mut db_txn, &transaction, 0, 0);
+
+    match accounts_tree.get(
+        &db_txn,
+        &KeyNibbles::from(&transaction.contract_creation_address()),
+    ) {
+        Some(Account::HTLC(htlc)) => {
+            assert_eq!(htlc.balance, 100.try_into().unwrap());
+            assert_eq!(htlc.sender, sender);
+            assert_eq!(htlc.recipient, recipient);
+            assert_eq!(htlc.hash_algorithm, HashAlgorithm::Sha512);
+            assert_eq!(htlc.hash_root, hash_root);
+            assert_eq!(htlc.hash_count, 2);
+            assert_eq!(htlc.timeout, 1000);
+        }
+        _ => panic!(),
+    }
+
+    transaction.recipient = Address::from([2u8; 20]);
+    assert_eq!(
+        AccountType::verify_incoming_transaction(&transaction),
+        Err(TransactionError::InvalidForRecipient)
+    );
+}
+
+#[test]
+#[allow(unused_must_use)]
+fn it_can_redeem_and_refund_sha512_contract() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts_tree = AccountsTrie::new(env.clone(), "AccountsTree");
+    let mut db_txn = WriteTransaction::new(&env);
+
+    let (mut start_contract, mut tx, _, sender_signature_proof, recipient_signature_proof) =
+        prepare_outgoing_transaction();
+
+    let pre_image = AnyHash::from([1u8; 64]);
+    start_contract.hash_algorithm = HashAlgorithm::Sha512;
+    start_contract.hash_root = HashAlgorithm::Sha512.digest(
+        HashAlgorithm::Sha512
+            .digest(pre_image.as_bytes())
+            .as_bytes(),
+    );
+
+    // redeem
+    let mut proof = Vec::with_capacity(3 + 2 * 64 + recipient_signature_proof.serialized_size());
+    Serialize::serialize(&ProofType::RegularTransfer, &mut proof);
+    Serialize::serialize(&HashAlgorithm::Sha512, &mut proof);
+    Serialize::serialize(&2u8, &mut proof);
+    Serialize::serialize(&start_contract.hash_root, &mut proof);
+    Serialize::serialize(&pre_image, &mut proof);
+    Serialize::serialize(&recipient_signature_proof, &mut proof);
+    tx.proof = proof;
+
+    assert_eq!(AccountType::verify_outgoing_transaction(&tx), Ok(()));
+    assert_eq!(start_contract.verify_regular_transfer(&tx.proof, 1), Ok(()));
+
+    accounts_tree.put(
+        &mut db_txn,
+        &KeyNibbles::from(&[0u8; 20][..]),
+        Account::HTLC(start_contract.clone()),
+    );
+
+    let account_info = HashedTimeLockedContract::commit_outgoing_transaction(
+        &accounts_tree,
+        &mut db_txn,
+        &tx,
+        1,
+        1,
+    )
+    .unwrap();
+
+    assert_eq!(
+        account_info.logs.last(),
+        Some(&Log::HTLCRegularTransfer {
+            contract_address: tx.sender.clone(),
+            pre_image: pre_image.clone(),
+            hash_depth: 2,
+        })
+    );
+    assert_eq!(
+        accounts_tree.get(&db_txn, &KeyNibbles::from(&[0u8; 20][..])),
+        None
+    );
+
+    HashedTimeLockedContract::revert_outgoing_transaction(
+        &accounts_tree,
+        &mut db_txn,
+        &tx,
+        1,
+        1,
+        account_info.receipt.as_ref(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        accounts_tree
+            .get(&db_txn, &KeyNibbles::from(&[0u8; 20][..]))
+            .unwrap(),
+        Account::HTLC(start_contract.clone())
+    );
+
+    // redeem: wrong pre-image
+    let mut proof = Vec::with_capacity(3 + 2 * 64 + recipient_signature_proof.serialized_size());
+    Serialize::serialize(&ProofType::RegularTransfer, &mut proof);
+    Serialize::serialize(&HashAlgorithm::Sha512, &mut proof);
+    Serialize::serialize(&1u8, &mut proof);
+    Serialize::serialize(&start_contract.hash_root, &mut proof);
+    Serialize::serialize(&pre_image, &mut proof);
+    Serialize::serialize(&recipient_signature_proof, &mut proof);
+    assert_eq!(
+        start_contract.verify_regular_transfer(&proof, 1),
+        Err(AccountError::HTLCInvalidPreImage)
+    );
+
+    // refund
+    let mut proof = Vec::with_capacity(1 + sender_signature_proof.serialized_size());
+    Serialize::serialize(&ProofType::TimeoutResolve, &mut proof);
+    Serialize::serialize(&sender_signature_proof, &mut proof);
+    tx.proof = proof;
+
+    let account_info = HashedTimeLockedContract::commit_outgoing_transaction(
+        &accounts_tree,
+        &mut db_txn,
+        &tx,
+        1,
+        101,
+    )
+    .unwrap();
+
+    assert_eq!(
+        accounts_tree.get(&db_txn, &KeyNibbles::from(&[0u8; 20][..])),
+        None
+    );
+
+    HashedTimeLockedContract::revert_outgoing_transaction(
+        &accounts_tree,
+        &mut db_txn,
+        &tx,
+        1,
+        101,
+        account_info.receipt.as_ref(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        accounts_tree
+            .get(&db_txn, &KeyNibbles::from(&[0u8; 20][..]))
+            .unwrap(),
+        Account::HTLC(start_contract)
+    );
+}
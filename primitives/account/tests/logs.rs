@@ -0,0 +1,166 @@
+use nimiq_account::{BlockLog, Log, LogType, TransactionLog};
+use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_keys::Address;
+use nimiq_primitives::coin::Coin;
+use nimiq_test_log::test;
+
+fn address(byte: u8) -> Address {
+    Address::from([byte; 20])
+}
+
+fn block_log() -> BlockLog {
+    let transfer_1_2 = Log::Transfer {
+        from: address(1),
+        to: address(2),
+        amount: Coin::from_u64_unchecked(100),
+    };
+    let stake_3_4 = Log::Stake {
+        staker_address: address(3),
+        validator_address: Some(address(4)),
+        value: Coin::from_u64_unchecked(200),
+    };
+
+    BlockLog::AppliedBlock {
+        inherent_logs: vec![
+            Log::PayoutReward {
+                to: address(2),
+                value: Coin::from_u64_unchecked(10),
+            },
+            Log::Park {
+                validator_address: address(4),
+                event_block: 1,
+            },
+        ],
+        block_hash: "block".hash::<Blake2bHash>(),
+        block_number: 2,
+        timestamp: 3,
+        tx_logs: vec![
+            TransactionLog::new(
+                "tx0".hash::<Blake2bHash>(),
+                0,
+                Coin::from_u64_unchecked(1),
+                vec![
+                    Log::PayFee {
+                        from: address(1),
+                        fee: Coin::from_u64_unchecked(1),
+                    },
+                    transfer_1_2,
+                ],
+            ),
+            TransactionLog::new(
+                "tx1".hash::<Blake2bHash>(),
+                1,
+                Coin::from_u64_unchecked(2),
+                vec![
+                    Log::PayFee {
+                        from: address(3),
+                        fee: Coin::from_u64_unchecked(2),
+                    },
+                    Log::Transfer {
+                        from: address(3),
+                        to: address(5),
+                        amount: Coin::from_u64_unchecked(200),
+                    },
+                    stake_3_4,
+                ],
+            ),
+        ],
+    }
+}
+
+fn logs_of(block_log: &BlockLog) -> (&Vec<Log>, &Vec<TransactionLog>) {
+    match block_log {
+        BlockLog::AppliedBlock {
+            inherent_logs,
+            tx_logs,
+            ..
+        } => (inherent_logs, tx_logs),
+        BlockLog::RevertedBlock { .. } => panic!("Expected an applied block log"),
+    }
+}
+
+#[test]
+fn it_can_filter_block_logs_by_address() {
+    let block_log = block_log();
+
+    // Address 2 is only the recipient of the first transaction and of a reward.
+    let filtered = block_log.filter_by_address(&address(2));
+    let (inherent_logs, tx_logs) = logs_of(&filtered);
+    assert_eq!(inherent_logs.len(), 1);
+    assert_eq!(inherent_logs[0].ty(), LogType::PayoutReward);
+    assert_eq!(tx_logs.len(), 1);
+    assert_eq!(tx_logs[0].tx_hash, "tx0".hash::<Blake2bHash>());
+    assert_eq!(tx_logs[0].index, 0);
+    assert_eq!(tx_logs[0].fee, Coin::from_u64_unchecked(1));
+    assert_eq!(
+        tx_logs[0].logs.iter().map(Log::ty).collect::<Vec<_>>(),
+        vec![LogType::Transfer]
+    );
+
+    // Address 3 sent and staked in the second transaction, so all of its logs are kept.
+    let filtered = block_log.filter_by_address(&address(3));
+    let (inherent_logs, tx_logs) = logs_of(&filtered);
+    assert!(inherent_logs.is_empty());
+    assert_eq!(tx_logs.len(), 1);
+    assert_eq!(tx_logs[0].index, 1);
+    assert_eq!(tx_logs[0].logs.len(), 3);
+
+    // Address 4 only appears as validator.
+    let filtered = block_log.filter_by_address(&address(4));
+    let (inherent_logs, tx_logs) = logs_of(&filtered);
+    assert_eq!(inherent_logs.len(), 1);
+    assert_eq!(inherent_logs[0].ty(), LogType::Park);
+    assert_eq!(tx_logs.len(), 1);
+    assert_eq!(
+        tx_logs[0].logs.iter().map(Log::ty).collect::<Vec<_>>(),
+        vec![LogType::Stake]
+    );
+
+    // The block metadata is preserved, even if nothing matches.
+    let filtered = block_log.filter_by_address(&address(6));
+    assert!(filtered.is_empty());
+    match filtered {
+        BlockLog::AppliedBlock {
+            block_hash,
+            block_number,
+            timestamp,
+            ..
+        } => {
+            assert_eq!(block_hash, "block".hash::<Blake2bHash>());
+            assert_eq!(block_number, 2);
+            assert_eq!(timestamp, 3);
+        }
+        BlockLog::RevertedBlock { .. } => panic!("Expected an applied block log"),
+    }
+}
+
+#[test]
+fn it_can_filter_block_logs_by_type() {
+    let block_log = block_log();
+
+    let filtered = block_log.filter_by_type(LogType::PayFee);
+    let (inherent_logs, tx_logs) = logs_of(&filtered);
+    assert!(inherent_logs.is_empty());
+    assert_eq!(tx_logs.len(), 2);
+    assert!(tx_logs
+        .iter()
+        .all(|tx_log| tx_log.logs.len() == 1 && tx_log.logs[0].ty() == LogType::PayFee));
+
+    let filtered = block_log.filter_by_type(LogType::Stake);
+    let (inherent_logs, tx_logs) = logs_of(&filtered);
+    assert!(inherent_logs.is_empty());
+    assert_eq!(tx_logs.len(), 1);
+    assert_eq!(tx_logs[0].index, 1);
+
+    let filtered = block_log.filter_by_type(LogType::Slash);
+    assert!(filtered.is_empty());
+
+    // Filtering is idempotent and composes with the address filter.
+    let filtered = block_log
+        .filter_by_type(LogType::Transfer)
+        .filter_by_address(&address(5));
+    assert_eq!(filtered, filtered.filter_by_address(&address(5)));
+    let (_, tx_logs) = logs_of(&filtered);
+    assert_eq!(tx_logs.len(), 1);
+    assert_eq!(tx_logs[0].logs.len(), 1);
+}
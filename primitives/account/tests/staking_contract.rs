@@ -611,6 +611,82 @@ fn inactivate_validator_works() {
     assert!(account_info.logs.is_empty());
 }
 
+#[test]
+fn active_validators_snapshot_works() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts_tree = AccountsTrie::new(env.clone(), "AccountsTrie");
+    let mut db_txn = WriteTransaction::new(&env);
+
+    make_sample_contract(&accounts_tree, &mut db_txn, true);
+
+    let validator_address = Address::from_any_str(VALIDATOR_ADDRESS).unwrap();
+
+    let signing_key =
+        PublicKey::deserialize_from_vec(&hex::decode(VALIDATOR_SIGNING_KEY).unwrap()).unwrap();
+
+    let signing_keypair = ed25519_key_pair(VALIDATOR_SIGNING_SECRET_KEY);
+
+    let voting_key =
+        BlsPublicKey::deserialize_from_vec(&hex::decode(VALIDATOR_VOTING_KEY).unwrap()).unwrap();
+
+    // Create a second validator and retire it.
+    let retired_address = Address::from([3u8; 20]);
+
+    StakingContract::create_validator(
+        &accounts_tree,
+        &mut db_txn,
+        &retired_address,
+        signing_key,
+        voting_key,
+        retired_address.clone(),
+        None,
+        Coin::from_u64_unchecked(policy::VALIDATOR_DEPOSIT),
+    )
+    .unwrap();
+
+    let staking_contract = StakingContract::get_staking_contract(&accounts_tree, &db_txn);
+    assert_eq!(
+        staking_contract.active_validators(&accounts_tree, &db_txn),
+        vec![
+            (
+                retired_address.clone(),
+                Coin::from_u64_unchecked(policy::VALIDATOR_DEPOSIT)
+            ),
+            (
+                validator_address.clone(),
+                Coin::from_u64_unchecked(policy::VALIDATOR_DEPOSIT + 150_000_000)
+            ),
+        ]
+    );
+
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::InactivateValidator {
+            validator_address: retired_address,
+            proof: SignatureProof::default(),
+        },
+        0,
+        &signing_keypair,
+    );
+
+    StakingContract::commit_incoming_transaction(&accounts_tree, &mut db_txn, &tx, 2, 0).unwrap();
+
+    // Only the active validator remains, with its deposit and the delegated stake.
+    let mut staking_contract = StakingContract::get_staking_contract(&accounts_tree, &db_txn);
+    assert_eq!(
+        staking_contract.active_validators(&accounts_tree, &db_txn),
+        vec![(
+            validator_address.clone(),
+            Coin::from_u64_unchecked(policy::VALIDATOR_DEPOSIT + 150_000_000)
+        )]
+    );
+
+    // Parked validators are left out as well.
+    staking_contract.parked_set.insert(validator_address);
+    assert!(staking_contract
+        .active_validators(&accounts_tree, &db_txn)
+        .is_empty());
+}
+
 #[test]
 fn reactivate_validator_works() {
     let env = VolatileEnvironment::new(10).unwrap();
@@ -1133,6 +1209,62 @@ fn delete_validator_works() {
     );
 }
 
+#[test]
+fn can_pay_checks_outgoing_transactions() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts_tree = AccountsTrie::new(env.clone(), "AccountsTrie");
+    let mut db_txn = WriteTransaction::new(&env);
+
+    make_sample_contract(&accounts_tree, &mut db_txn, true);
+
+    let staking_contract = StakingContract::get_staking_contract(&accounts_tree, &db_txn);
+    let balance = staking_contract.balance;
+
+    // Active validators can't be deleted.
+    let delete_tx = make_delete_validator_transaction();
+    assert_eq!(
+        staking_contract.can_pay(&delete_tx, 0),
+        Err(AccountError::InvalidForSender)
+    );
+
+    // Unstaking is limited by the balance of the contract.
+    assert_eq!(
+        staking_contract.can_pay(&make_unstake_transaction(150_000_000), 0),
+        Ok(())
+    );
+    assert_eq!(
+        staking_contract.can_pay(&make_unstake_transaction(u64::from(balance) + 1), 0),
+        Err(AccountError::InsufficientFundsDetail {
+            available: u64::from(balance),
+            required: u64::from(balance) + 1,
+        })
+    );
+
+    // The proof must be a valid outgoing staking proof.
+    let mut invalid_tx = make_unstake_transaction(150_000_000);
+    invalid_tx.proof = vec![];
+    assert!(matches!(
+        staking_contract.can_pay(&invalid_tx, 0),
+        Err(AccountError::InvalidTransaction(_))
+    ));
+
+    // Once the validator is inactive, it can be deleted.
+    let retire_tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::InactivateValidator {
+            validator_address: Address::from_any_str(VALIDATOR_ADDRESS).unwrap(),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &ed25519_key_pair(VALIDATOR_SIGNING_SECRET_KEY),
+    );
+
+    StakingContract::commit_incoming_transaction(&accounts_tree, &mut db_txn, &retire_tx, 2, 0)
+        .unwrap();
+
+    let staking_contract = StakingContract::get_staking_contract(&accounts_tree, &db_txn);
+    assert_eq!(staking_contract.can_pay(&delete_tx, 0), Ok(()));
+}
+
 #[test]
 fn create_staker_works() {
     let env = VolatileEnvironment::new(10).unwrap();
@@ -1611,6 +1743,239 @@ fn update_staker_works() {
     assert!(account_info.logs.is_empty());
 }
 
+#[test]
+fn get_stakers_for_validator_works() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts_tree = AccountsTrie::new(env.clone(), "AccountsTrie");
+    let mut db_txn = WriteTransaction::new(&env);
+
+    make_sample_contract(&accounts_tree, &mut db_txn, true);
+
+    let staker_keypair = ed25519_key_pair(STAKER_PRIVATE_KEY);
+
+    let staker_address = Address::from_any_str(STAKER_ADDRESS).unwrap();
+
+    let validator_address = Address::from_any_str(VALIDATOR_ADDRESS).unwrap();
+
+    let other_validator_address = Address::from([69u8; 20]);
+
+    let signing_key =
+        PublicKey::deserialize_from_vec(&hex::decode(VALIDATOR_SIGNING_KEY).unwrap()).unwrap();
+    let voting_key =
+        BlsPublicKey::deserialize_from_vec(&hex::decode(VALIDATOR_VOTING_KEY).unwrap()).unwrap();
+
+    StakingContract::create_validator(
+        &accounts_tree,
+        &mut db_txn,
+        &other_validator_address,
+        signing_key,
+        voting_key,
+        other_validator_address.clone(),
+        None,
+        Coin::from_u64_unchecked(policy::VALIDATOR_DEPOSIT),
+    )
+    .unwrap();
+
+    // Add some more stakers. Their addresses are ordered before the one of the sample staker.
+    for i in 1..4u8 {
+        StakingContract::create_staker(
+            &accounts_tree,
+            &mut db_txn,
+            &Address::from([i; 20]),
+            Coin::from_u64_unchecked(100_000_000),
+            Some(validator_address.clone()),
+        )
+        .unwrap();
+    }
+
+    let get_addresses = |db_txn: &WriteTransaction,
+                         validator_address: &Address,
+                         start: Option<&Address>,
+                         max: usize| {
+        StakingContract::get_stakers_for_validator(
+            &accounts_tree,
+            db_txn,
+            validator_address,
+            start,
+            max,
+        )
+        .into_iter()
+        .map(|staker| staker.address)
+        .collect::<Vec<_>>()
+    };
+
+    // Pages through the stakers.
+    assert_eq!(
+        get_addresses(&db_txn, &validator_address, None, 2),
+        vec![Address::from([1u8; 20]), Address::from([2u8; 20])]
+    );
+    assert_eq!(
+        get_addresses(
+            &db_txn,
+            &validator_address,
+            Some(&Address::from([2u8; 20])),
+            2
+        ),
+        vec![Address::from([3u8; 20]), staker_address.clone()]
+    );
+    assert_eq!(
+        get_addresses(&db_txn, &validator_address, Some(&staker_address), 2),
+        vec![]
+    );
+    assert_eq!(
+        get_addresses(&db_txn, &validator_address, None, 10).len(),
+        4
+    );
+    // The page size is capped by the number of stakers of the validator.
+    assert_eq!(
+        get_addresses(&db_txn, &validator_address, None, usize::MAX).len(),
+        4
+    );
+    assert_eq!(get_addresses(&db_txn, &validator_address, None, 0), vec![]);
+    assert_eq!(
+        get_addresses(&db_txn, &Address::from([70u8; 20]), None, 10),
+        vec![]
+    );
+    assert_eq!(
+        get_addresses(&db_txn, &other_validator_address, None, 10),
+        vec![]
+    );
+
+    let staker = StakingContract::get_stakers_for_validator(
+        &accounts_tree,
+        &db_txn,
+        &validator_address,
+        Some(&Address::from([3u8; 20])),
+        1,
+    );
+    assert_eq!(
+        staker,
+        vec![StakingContract::get_staker(&accounts_tree, &db_txn, &staker_address).unwrap()]
+    );
+
+    // Changing the delegation moves the staker to the other validator.
+    let tx = make_signed_incoming_transaction(
+        IncomingStakingTransactionData::UpdateStaker {
+            new_delegation: Some(other_validator_address.clone()),
+            proof: SignatureProof::default(),
+        },
+        0,
+        &staker_keypair,
+    );
+
+    let account_info =
+        StakingContract::commit_incoming_transaction(&accounts_tree, &mut db_txn, &tx, 2, 0)
+            .unwrap();
+
+    assert_eq!(
+        get_addresses(&db_txn, &validator_address, None, 10),
+        vec![
+            Address::from([1u8; 20]),
+            Address::from([2u8; 20]),
+            Address::from([3u8; 20])
+        ]
+    );
+    assert_eq!(
+        get_addresses(&db_txn, &other_validator_address, None, 10),
+        vec![staker_address.clone()]
+    );
+
+    // Reverting the delegation change restores the stakers of both validators.
+    StakingContract::revert_incoming_transaction(
+        &accounts_tree,
+        &mut db_txn,
+        &tx,
+        2,
+        0,
+        account_info.receipt.as_ref(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        get_addresses(&db_txn, &validator_address, None, 10),
+        vec![
+            Address::from([1u8; 20]),
+            Address::from([2u8; 20]),
+            Address::from([3u8; 20]),
+            staker_address.clone()
+        ]
+    );
+    assert_eq!(
+        get_addresses(&db_txn, &other_validator_address, None, 10),
+        vec![]
+    );
+}
+
+#[test]
+fn get_delegators_works() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts_tree = AccountsTrie::new(env.clone(), "AccountsTrie");
+    let mut db_txn = WriteTransaction::new(&env);
+
+    make_sample_contract(&accounts_tree, &mut db_txn, true);
+
+    let staker_address = Address::from_any_str(STAKER_ADDRESS).unwrap();
+
+    let validator_address = Address::from_any_str(VALIDATOR_ADDRESS).unwrap();
+
+    // Add stakers with and without delegation.
+    for i in 1..4u8 {
+        StakingContract::create_staker(
+            &accounts_tree,
+            &mut db_txn,
+            &Address::from([i; 20]),
+            Coin::from_u64_unchecked(i as u64 * 100_000_000),
+            Some(validator_address.clone()),
+        )
+        .unwrap();
+    }
+
+    StakingContract::create_staker(
+        &accounts_tree,
+        &mut db_txn,
+        &Address::from([4u8; 20]),
+        Coin::from_u64_unchecked(100_000_000),
+        None,
+    )
+    .unwrap();
+
+    let staker_balance = StakingContract::get_staker(&accounts_tree, &db_txn, &staker_address)
+        .unwrap()
+        .balance;
+
+    let delegators = StakingContract::get_delegators(&accounts_tree, &db_txn, &validator_address);
+    assert!(delegators
+        .iter()
+        .all(|staker| staker.delegation == Some(validator_address.clone())));
+    assert_eq!(
+        delegators
+            .into_iter()
+            .map(|staker| (staker.address, staker.balance))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                Address::from([1u8; 20]),
+                Coin::from_u64_unchecked(100_000_000)
+            ),
+            (
+                Address::from([2u8; 20]),
+                Coin::from_u64_unchecked(200_000_000)
+            ),
+            (
+                Address::from([3u8; 20]),
+                Coin::from_u64_unchecked(300_000_000)
+            ),
+            (staker_address, staker_balance),
+        ]
+    );
+
+    // Unknown validators have no delegators.
+    assert!(
+        StakingContract::get_delegators(&accounts_tree, &db_txn, &Address::from([69u8; 20]))
+            .is_empty()
+    );
+}
+
 #[test]
 fn unstake_works() {
     let env = VolatileEnvironment::new(10).unwrap();
@@ -1624,9 +1989,9 @@ fn unstake_works() {
 
     assert_eq!(
         StakingContract::commit_outgoing_transaction(&accounts_tree, &mut db_txn, &tx, 1, 0),
-        Err(AccountError::InsufficientFunds {
-            needed: Coin::from_u64_unchecked(200_000_000),
-            balance: Coin::from_u64_unchecked(150_000_000)
+        Err(AccountError::InsufficientFundsDetail {
+            available: 150_000_000,
+            required: 200_000_000
         })
     );
 
@@ -1830,6 +2195,197 @@ fn unstake_works() {
     );
 }
 
+#[test]
+fn partial_unstake_can_be_reverted() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts_tree = AccountsTrie::new(env.clone(), "AccountsTrie");
+    let mut db_txn = WriteTransaction::new(&env);
+
+    make_sample_contract(&accounts_tree, &mut db_txn, true);
+
+    let staker_address = Address::from_any_str(STAKER_ADDRESS).unwrap();
+
+    let validator_address = Address::from_any_str(VALIDATOR_ADDRESS).unwrap();
+
+    // Unstake part of the balance twice in the same block.
+    let tx_1 = make_unstake_transaction(30_000_000);
+    let tx_2 = make_unstake_transaction(20_000_000);
+
+    let account_info_1 =
+        StakingContract::commit_outgoing_transaction(&accounts_tree, &mut db_txn, &tx_1, 1, 0)
+            .unwrap();
+    let account_info_2 =
+        StakingContract::commit_outgoing_transaction(&accounts_tree, &mut db_txn, &tx_2, 1, 0)
+            .unwrap();
+
+    // A partial unstake keeps the staker entry and thus needs no receipt.
+    assert_eq!(account_info_1.receipt, None);
+    assert_eq!(account_info_2.receipt, None);
+
+    let staker = StakingContract::get_staker(&accounts_tree, &db_txn, &staker_address).unwrap();
+
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(100_000_000));
+    assert_eq!(staker.delegation, Some(validator_address.clone()));
+
+    let validator =
+        StakingContract::get_validator(&accounts_tree, &db_txn, &validator_address).unwrap();
+
+    assert_eq!(
+        validator.balance,
+        Coin::from_u64_unchecked(VALIDATOR_DEPOSIT + 100_000_000)
+    );
+    assert_eq!(validator.num_stakers, 1);
+
+    // Revert in reverse order.
+    let logs = StakingContract::revert_outgoing_transaction(
+        &accounts_tree,
+        &mut db_txn,
+        &tx_2,
+        1,
+        0,
+        account_info_2.receipt.as_ref(),
+    )
+    .unwrap();
+    assert_eq!(
+        logs[2],
+        Log::Unstake {
+            staker_address: staker_address.clone(),
+            validator_address: Some(validator_address.clone()),
+            value: Coin::from_u64_unchecked(20_000_000),
+        }
+    );
+
+    StakingContract::revert_outgoing_transaction(
+        &accounts_tree,
+        &mut db_txn,
+        &tx_1,
+        1,
+        0,
+        account_info_1.receipt.as_ref(),
+    )
+    .unwrap();
+
+    let staker = StakingContract::get_staker(&accounts_tree, &db_txn, &staker_address).unwrap();
+
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(150_000_000));
+    assert_eq!(staker.delegation, Some(validator_address.clone()));
+
+    let validator =
+        StakingContract::get_validator(&accounts_tree, &db_txn, &validator_address).unwrap();
+
+    assert_eq!(
+        validator.balance,
+        Coin::from_u64_unchecked(VALIDATOR_DEPOSIT + 150_000_000)
+    );
+    assert_eq!(validator.num_stakers, 1);
+
+    let staking_contract = StakingContract::get_staking_contract(&accounts_tree, &db_txn);
+
+    assert_eq!(
+        staking_contract.balance,
+        Coin::from_u64_unchecked(VALIDATOR_DEPOSIT + 150_000_000)
+    );
+    assert_eq!(
+        staking_contract.active_validators.get(&validator_address),
+        Some(&Coin::from_u64_unchecked(VALIDATOR_DEPOSIT + 150_000_000))
+    );
+}
+
+#[test]
+fn unstake_works_for_slashed_validator() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts_tree = AccountsTrie::new(env.clone(), "AccountsTrie");
+    let mut db_txn = WriteTransaction::new(&env);
+
+    make_sample_contract(&accounts_tree, &mut db_txn, true);
+
+    let staker_address = Address::from_any_str(STAKER_ADDRESS).unwrap();
+
+    let validator_address = Address::from_any_str(VALIDATOR_ADDRESS).unwrap();
+
+    // Slash the validator and unstake from it in the same block.
+    let slot = SlashedSlot {
+        slot: 0,
+        validator_address: validator_address.clone(),
+        event_block: 1,
+    };
+
+    let inherent = Inherent {
+        ty: InherentType::Slash,
+        target: Default::default(),
+        value: Coin::ZERO,
+        data: slot.serialize_to_vec(),
+    };
+
+    let slash_info =
+        StakingContract::commit_inherent(&accounts_tree, &mut db_txn, &inherent, 1, 0).unwrap();
+
+    let tx = make_unstake_transaction(100_000_000);
+
+    let unstake_info =
+        StakingContract::commit_outgoing_transaction(&accounts_tree, &mut db_txn, &tx, 1, 0)
+            .unwrap();
+    assert_eq!(unstake_info.receipt, None);
+
+    // The stake is withdrawn, while the validator stays parked.
+    let staker = StakingContract::get_staker(&accounts_tree, &db_txn, &staker_address).unwrap();
+
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(50_000_000));
+
+    let validator =
+        StakingContract::get_validator(&accounts_tree, &db_txn, &validator_address).unwrap();
+
+    assert_eq!(
+        validator.balance,
+        Coin::from_u64_unchecked(VALIDATOR_DEPOSIT + 50_000_000)
+    );
+
+    let staking_contract = StakingContract::get_staking_contract(&accounts_tree, &db_txn);
+
+    assert!(staking_contract.parked_set.contains(&validator_address));
+    assert_eq!(
+        staking_contract.active_validators.get(&validator_address),
+        Some(&Coin::from_u64_unchecked(VALIDATOR_DEPOSIT + 50_000_000))
+    );
+
+    // Revert the block in reverse order.
+    StakingContract::revert_outgoing_transaction(
+        &accounts_tree,
+        &mut db_txn,
+        &tx,
+        1,
+        0,
+        unstake_info.receipt.as_ref(),
+    )
+    .unwrap();
+
+    revert_slash_inherent(
+        &accounts_tree,
+        &mut db_txn,
+        &inherent,
+        1,
+        slash_info.receipt.as_ref(),
+        &validator_address,
+        slot.slot,
+        slot.event_block,
+    );
+
+    let staker = StakingContract::get_staker(&accounts_tree, &db_txn, &staker_address).unwrap();
+
+    assert_eq!(staker.balance, Coin::from_u64_unchecked(150_000_000));
+
+    let staking_contract = StakingContract::get_staking_contract(&accounts_tree, &db_txn);
+
+    assert_eq!(
+        staking_contract.balance,
+        Coin::from_u64_unchecked(VALIDATOR_DEPOSIT + 150_000_000)
+    );
+    assert_eq!(
+        staking_contract.active_validators.get(&validator_address),
+        Some(&Coin::from_u64_unchecked(VALIDATOR_DEPOSIT + 150_000_000))
+    );
+}
+
 #[test]
 fn zero_value_inherents_not_allowed() {
     let env = VolatileEnvironment::new(10).unwrap();
@@ -2,7 +2,8 @@ use std::convert::{TryFrom, TryInto};
 
 use beserial::{Deserialize, Serialize, SerializingError};
 use nimiq_account::{
-    Account, AccountError, AccountTransactionInteraction, AccountsTrie, VestingContract,
+    Account, AccountError, AccountTransactionInteraction, AccountsTrie, BasicAccount,
+    VestingContract, VestingReceipt,
 };
 use nimiq_database::volatile::VolatileEnvironment;
 use nimiq_database::WriteTransaction;
@@ -11,6 +12,7 @@ use nimiq_primitives::account::AccountType;
 use nimiq_primitives::coin::Coin;
 use nimiq_primitives::networks::NetworkId;
 use nimiq_test_log::test;
+use nimiq_transaction::account::vesting_contract::{CreationTransactionData, VestingSchedule};
 use nimiq_transaction::account::AccountTransactionVerification;
 use nimiq_transaction::{SignatureProof, Transaction, TransactionError, TransactionFlags};
 use nimiq_trie::key_nibbles::KeyNibbles;
@@ -27,12 +29,31 @@ fn create_serialized_contract() {
         time_step: 259200,
         step_amount: Coin::from_u64_unchecked(2625000000000),
         total_amount: Coin::from_u64_unchecked(52500000000000),
+        schedule: VestingSchedule::Linear,
     };
     let mut bytes: Vec<u8> = Vec::with_capacity(contract.serialized_size());
     contract.serialize(&mut bytes).unwrap();
     assert_eq!(CONTRACT, hex::encode(bytes));
 }
 
+#[test]
+fn it_keeps_the_legacy_encoding_regardless_of_the_funding() {
+    // Contracts funded with more or less than their total amount are encoded like any other
+    // linear contract.
+    let bytes: Vec<u8> = hex::decode(CONTRACT).unwrap();
+    let contract: VestingContract = Deserialize::deserialize(&mut &bytes[..]).unwrap();
+    for balance in [1u64, 52500000000001] {
+        let funded = contract.change_balance(Coin::from_u64_unchecked(balance));
+        let bytes2 = funded.serialize_to_vec();
+        assert_eq!(bytes2.len(), bytes.len());
+        assert_eq!(&bytes2[8..], &bytes[8..]);
+        assert_eq!(
+            VestingContract::deserialize_from_vec(&bytes2).unwrap(),
+            funded
+        );
+    }
+}
+
 #[test]
 fn it_can_deserialize_a_vesting_contract() {
     let bytes: Vec<u8> = hex::decode(CONTRACT).unwrap();
@@ -46,6 +67,7 @@ fn it_can_deserialize_a_vesting_contract() {
     assert_eq!(contract.step_amount, 2625000000000.try_into().unwrap());
     assert_eq!(contract.time_step, 259200);
     assert_eq!(contract.total_amount, 52500000000000.try_into().unwrap());
+    assert_eq!(contract.schedule, VestingSchedule::Linear);
 }
 
 #[test]
@@ -58,6 +80,58 @@ fn it_can_serialize_a_vesting_contract() {
     assert_eq!(hex::encode(bytes2), CONTRACT);
 }
 
+#[test]
+fn it_can_serialize_a_vesting_contract_with_a_cliff() {
+    let bytes: Vec<u8> = hex::decode(CONTRACT).unwrap();
+    let mut contract: VestingContract = Deserialize::deserialize(&mut &bytes[..]).unwrap();
+    contract.schedule = VestingSchedule::Cliff { cliff_time: 1000 };
+
+    // The contract is followed by another account to make sure that it is properly delimited.
+    let account1 = Account::Vesting(contract.clone());
+    let account2 = Account::Basic(BasicAccount {
+        balance: 1000.try_into().unwrap(),
+    });
+    let mut bytes2 = account1.serialize_to_vec();
+    assert_eq!(bytes2.len(), account1.serialized_size());
+    account2.serialize(&mut bytes2).unwrap();
+
+    let reader = &mut &bytes2[..];
+    assert_eq!(Account::deserialize(reader).unwrap(), account1);
+    assert_eq!(Account::deserialize(reader).unwrap(), account2);
+    assert!(reader.is_empty());
+
+    // A linear schedule must use the legacy format.
+    let mut bytes3 = contract.serialize_to_vec();
+    assert_eq!(bytes3.len(), bytes.len() + 10);
+    bytes3.truncate(bytes3.len() - 8);
+    *bytes3.last_mut().unwrap() = 0;
+    assert_eq!(
+        VestingContract::deserialize_from_vec(&bytes3),
+        Err(SerializingError::InvalidValue)
+    );
+}
+
+#[test]
+fn it_can_deserialize_legacy_receipts() {
+    // A legacy receipt holds the contract without its balance.
+    let bytes: Vec<u8> = hex::decode(CONTRACT).unwrap();
+    let contract: VestingContract = Deserialize::deserialize(&mut &bytes[..]).unwrap();
+    let receipt: VestingReceipt = Deserialize::deserialize_from_vec(&bytes[8..]).unwrap();
+    assert_eq!(receipt, VestingReceipt::from(contract.clone()));
+    assert_eq!(receipt.serialize_to_vec(), &bytes[8..]);
+
+    let receipt = VestingReceipt::from(VestingContract {
+        schedule: VestingSchedule::Cliff { cliff_time: 1000 },
+        ..contract
+    });
+    let bytes2 = receipt.serialize_to_vec();
+    assert_eq!(bytes2.len(), receipt.serialized_size());
+    assert_eq!(
+        VestingReceipt::deserialize_from_vec(&bytes2).unwrap(),
+        receipt
+    );
+}
+
 #[test]
 #[allow(unused_must_use)]
 fn it_can_verify_creation_transaction() {
@@ -332,6 +406,7 @@ fn it_can_apply_and_revert_valid_transaction() {
         time_step: 100,
         step_amount: 100.try_into().unwrap(),
         total_amount: 1000.try_into().unwrap(),
+        schedule: VestingSchedule::Linear,
     };
 
     accounts_tree.put(
@@ -378,6 +453,7 @@ fn it_can_apply_and_revert_valid_transaction() {
         time_step: 0,
         step_amount: 100.try_into().unwrap(),
         total_amount: 1000.try_into().unwrap(),
+        schedule: VestingSchedule::Linear,
     };
 
     accounts_tree.put(
@@ -429,6 +505,7 @@ fn it_refuses_invalid_transaction() {
         time_step: 100,
         step_amount: 100.try_into().unwrap(),
         total_amount: 1000.try_into().unwrap(),
+        schedule: VestingSchedule::Linear,
     };
 
     accounts_tree.put(
@@ -464,9 +541,315 @@ fn it_refuses_invalid_transaction() {
 
     assert_eq!(
         VestingContract::commit_outgoing_transaction(&accounts_tree, &mut db_txn, &tx, 1, 100),
-        Err(AccountError::InsufficientFunds {
-            needed: 900.try_into().unwrap(),
-            balance: 800.try_into().unwrap()
+        Err(AccountError::InsufficientFundsDetail {
+            available: 800,
+            required: 900
+        })
+    );
+}
+
+#[test]
+fn it_can_check_payments_against_the_vesting_schedule() {
+    let contract = VestingContract {
+        balance: 1000.try_into().unwrap(),
+        owner: Address::from([1u8; 20]),
+        start_time: 0,
+        time_step: 100,
+        step_amount: 100.try_into().unwrap(),
+        total_amount: 1000.try_into().unwrap(),
+        schedule: VestingSchedule::Linear,
+    };
+
+    // Nothing is vested yet.
+    assert_eq!(
+        contract.can_pay(&make_payment(Coin::ZERO, 1.try_into().unwrap()), 50),
+        Err(AccountError::InsufficientFundsDetail {
+            available: 0,
+            required: 1,
+        })
+    );
+
+    // After two steps, 200 are vested.
+    assert_eq!(
+        contract.can_pay(
+            &make_payment(190.try_into().unwrap(), 10.try_into().unwrap()),
+            200
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        contract.can_pay(
+            &make_payment(191.try_into().unwrap(), 10.try_into().unwrap()),
+            200
+        ),
+        Err(AccountError::InsufficientFundsDetail {
+            available: 200,
+            required: 201,
+        })
+    );
+
+    // Everything is vested eventually.
+    assert_eq!(
+        contract.can_pay(
+            &make_payment(990.try_into().unwrap(), 10.try_into().unwrap()),
+            1000
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn it_can_compute_the_available_balance() {
+    let contract = VestingContract {
+        balance: 1000.try_into().unwrap(),
+        owner: Address::from([1u8; 20]),
+        start_time: 1000,
+        time_step: 100,
+        step_amount: 250.try_into().unwrap(),
+        total_amount: 1000.try_into().unwrap(),
+        schedule: VestingSchedule::Linear,
+    };
+
+    // Everything is locked before the first step.
+    for time in [999, 1099] {
+        assert_eq!(contract.min_cap(time), 1000.try_into().unwrap());
+        assert_eq!(contract.available_balance(time), Coin::ZERO);
+    }
+
+    // Before the start, the min cap exceeds the total amount.
+    assert_eq!(contract.min_cap(0), 3500.try_into().unwrap());
+    assert_eq!(contract.available_balance(0), Coin::ZERO);
+
+    // Half of the funds are unlocked after two steps.
+    assert_eq!(contract.min_cap(1250), 500.try_into().unwrap());
+    assert_eq!(contract.available_balance(1250), 500.try_into().unwrap());
+
+    // Everything is unlocked after the last step.
+    for time in [1400, 10000] {
+        assert_eq!(contract.min_cap(time), Coin::ZERO);
+        assert_eq!(contract.available_balance(time), 1000.try_into().unwrap());
+    }
+
+    // The available balance never exceeds the balance.
+    let contract = contract.change_balance(300.try_into().unwrap());
+    assert_eq!(contract.available_balance(1250), Coin::ZERO);
+    assert_eq!(contract.available_balance(1300), 50.try_into().unwrap());
+}
+
+#[test]
+fn it_can_compute_the_vested_amount() {
+    let contract = VestingContract {
+        balance: 1000.try_into().unwrap(),
+        owner: Address::from([1u8; 20]),
+        start_time: 1000,
+        time_step: 100,
+        step_amount: 250.try_into().unwrap(),
+        total_amount: 1000.try_into().unwrap(),
+        schedule: VestingSchedule::Linear,
+    };
+
+    assert_eq!(contract.vested_amount(0), Coin::ZERO);
+    assert_eq!(contract.unvested_amount(0), 1000.try_into().unwrap());
+    assert_eq!(contract.vested_amount(999), Coin::ZERO);
+    assert_eq!(contract.unvested_amount(999), 1000.try_into().unwrap());
+    assert_eq!(contract.vested_amount(1250), 500.try_into().unwrap());
+    assert_eq!(contract.unvested_amount(1250), 500.try_into().unwrap());
+    assert_eq!(contract.vested_amount(1400), 1000.try_into().unwrap());
+    assert_eq!(contract.unvested_amount(1400), Coin::ZERO);
+    assert_eq!(contract.withdrawn_amount(), Coin::ZERO);
+
+    // Withdrawing doesn't change what has vested, only what is available.
+    let contract = contract.change_balance(700.try_into().unwrap());
+    assert_eq!(contract.withdrawn_amount(), 300.try_into().unwrap());
+    assert_eq!(contract.vested_amount(1250), 500.try_into().unwrap());
+    assert_eq!(contract.available_balance(1250), 200.try_into().unwrap());
+
+    let account = Account::Vesting(contract);
+    assert_eq!(account.available_balance(1250), 200.try_into().unwrap());
+    assert_eq!(account.balance(), 700.try_into().unwrap());
+
+    let account = Account::Basic(BasicAccount {
+        balance: 700.try_into().unwrap(),
+    });
+    assert_eq!(account.available_balance(1250), 700.try_into().unwrap());
+}
+
+#[test]
+fn it_keeps_the_legacy_creation_data_format() {
+    let owner = Address::from([1u8; 20]);
+    let mut transaction = Transaction::new_contract_creation(
+        vec![],
+        owner.clone(),
+        AccountType::Basic,
+        AccountType::Vesting,
+        1000.try_into().unwrap(),
+        0.try_into().unwrap(),
+        0,
+        NetworkId::Dummy,
+    );
+
+    // All legacy formats are parsed as linear schedules and serialized as before.
+    let mut legacy_data = vec![];
+    Serialize::serialize(&owner, &mut legacy_data);
+    Serialize::serialize(&100u64, &mut legacy_data);
+    Serialize::serialize(&10u64, &mut legacy_data);
+    Serialize::serialize(&Coin::try_from(1000).unwrap(), &mut legacy_data);
+    Serialize::serialize(&Coin::try_from(500).unwrap(), &mut legacy_data);
+    for len in [Address::SIZE + 8, Address::SIZE + 24, Address::SIZE + 32] {
+        transaction.data = legacy_data[..len].to_vec();
+        let data = CreationTransactionData::parse(&transaction).unwrap();
+        assert_eq!(data.schedule, VestingSchedule::Linear);
+        assert_eq!(data.serialize_to_vec(), transaction.data);
+    }
+
+    // A schedule with a cliff appends the cliff time to the full format.
+    let data = CreationTransactionData {
+        owner,
+        start_time: 100,
+        time_step: 10,
+        step_amount: Coin::try_from(100).unwrap(),
+        total_amount: Coin::try_from(1000).unwrap(),
+        schedule: VestingSchedule::Cliff { cliff_time: 150 },
+    };
+    transaction.data = data.serialize_to_vec();
+    assert_eq!(transaction.data.len(), Address::SIZE + 40);
+    transaction.recipient = transaction.contract_creation_address();
+    assert_eq!(
+        AccountType::verify_incoming_transaction(&transaction),
+        Ok(())
+    );
+
+    let parsed = CreationTransactionData::parse(&transaction).unwrap();
+    assert_eq!(parsed.schedule, VestingSchedule::Cliff { cliff_time: 150 });
+    assert_eq!(parsed.total_amount, data.total_amount);
+}
+
+#[test]
+fn it_locks_all_funds_until_the_cliff() {
+    let key_pair = KeyPair::from(
+        PrivateKey::deserialize_from_vec(
+            &hex::decode("9d5bd02379e7e45cf515c788048f5cf3c454ffabd3e83bd1d7667716c325c3c0")
+                .unwrap(),
+        )
+        .unwrap(),
+    );
+
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts_tree = AccountsTrie::new(env.clone(), "AccountsTree");
+    let mut db_txn = WriteTransaction::new(&env);
+
+    // Without the cliff, 100 would be released every 100 starting at 100.
+    let start_contract = VestingContract {
+        balance: 1000.try_into().unwrap(),
+        owner: Address::from(&key_pair.public),
+        start_time: 0,
+        time_step: 100,
+        step_amount: 100.try_into().unwrap(),
+        total_amount: 1000.try_into().unwrap(),
+        schedule: VestingSchedule::Cliff { cliff_time: 350 },
+    };
+    assert_eq!(start_contract.min_cap(349), 1000.try_into().unwrap());
+    assert_eq!(start_contract.min_cap(350), 700.try_into().unwrap());
+    assert_eq!(start_contract.min_cap(1000), Coin::ZERO);
+
+    accounts_tree.put(
+        &mut db_txn,
+        &KeyNibbles::from(&[1u8; 20][..]),
+        Account::Vesting(start_contract.clone()),
+    );
+
+    let mut tx = Transaction::new_basic(
+        Address::from([1u8; 20]),
+        Address::from([2u8; 20]),
+        300.try_into().unwrap(),
+        0.try_into().unwrap(),
+        1,
+        NetworkId::Dummy,
+    );
+    tx.sender_type = AccountType::Vesting;
+    let signature = key_pair.sign(&tx.serialize_content()[..]);
+    tx.proof = SignatureProof::from(key_pair.public, signature).serialize_to_vec();
+
+    // Before the cliff, nothing can be withdrawn, even though three steps have passed.
+    assert_eq!(
+        VestingContract::commit_outgoing_transaction(&accounts_tree, &mut db_txn, &tx, 1, 349),
+        Err(AccountError::InsufficientFundsDetail {
+            available: 700,
+            required: 1000
         })
     );
+
+    // At the cliff, all steps that have passed are released at once.
+    let account_info =
+        VestingContract::commit_outgoing_transaction(&accounts_tree, &mut db_txn, &tx, 1, 350)
+            .unwrap();
+    assert_eq!(
+        accounts_tree
+            .get(&db_txn, &KeyNibbles::from(&[1u8; 20][..]))
+            .unwrap()
+            .balance(),
+        700.try_into().unwrap()
+    );
+    VestingContract::revert_outgoing_transaction(
+        &accounts_tree,
+        &mut db_txn,
+        &tx,
+        1,
+        350,
+        account_info.receipt.as_ref(),
+    )
+    .unwrap();
+    assert_eq!(
+        accounts_tree
+            .get(&db_txn, &KeyNibbles::from(&[1u8; 20][..]))
+            .unwrap(),
+        Account::Vesting(start_contract.clone())
+    );
+
+    // After full vesting, everything can be withdrawn and the receipt restores the cliff.
+    let mut tx = Transaction::new_basic(
+        Address::from([1u8; 20]),
+        Address::from([2u8; 20]),
+        1000.try_into().unwrap(),
+        0.try_into().unwrap(),
+        1,
+        NetworkId::Dummy,
+    );
+    tx.sender_type = AccountType::Vesting;
+    let signature = key_pair.sign(&tx.serialize_content()[..]);
+    tx.proof = SignatureProof::from(key_pair.public, signature).serialize_to_vec();
+
+    let account_info =
+        VestingContract::commit_outgoing_transaction(&accounts_tree, &mut db_txn, &tx, 1, 1000)
+            .unwrap();
+    assert_eq!(
+        accounts_tree.get(&db_txn, &KeyNibbles::from(&[1u8; 20][..])),
+        None
+    );
+    VestingContract::revert_outgoing_transaction(
+        &accounts_tree,
+        &mut db_txn,
+        &tx,
+        1,
+        1000,
+        account_info.receipt.as_ref(),
+    )
+    .unwrap();
+    assert_eq!(
+        accounts_tree
+            .get(&db_txn, &KeyNibbles::from(&[1u8; 20][..]))
+            .unwrap(),
+        Account::Vesting(start_contract)
+    );
+}
+
+fn make_payment(value: Coin, fee: Coin) -> Transaction {
+    Transaction::new_basic(
+        Address::from([1u8; 20]),
+        Address::from([2u8; 20]),
+        value,
+        fee,
+        1,
+        NetworkId::Dummy,
+    )
 }
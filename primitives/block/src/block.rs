@@ -207,19 +207,21 @@ impl Block {
     pub fn sum_transaction_fees(&self) -> Coin {
         match self {
             Block::Macro(_) => Coin::ZERO,
-            Block::Micro(ref block) => block
-                .body
-                .as_ref()
-                .map(|ex| {
-                    ex.transactions
-                        .iter()
-                        .map(|tx| tx.get_raw_transaction().fee)
-                        .sum()
-                })
-                .unwrap_or(Coin::ZERO),
+            Block::Micro(ref block) => Coin::from_u64_unchecked(block.total_fee()),
         }
     }
 
+    /// Returns the size, in bytes, of the serialized block without serializing it into a buffer.
+    /// This can be used to check a block against the size limits before validating it.
+    pub fn estimated_serialized_size(&self) -> usize {
+        let mut size = self.ty().serialized_size();
+        size += match self {
+            Block::Macro(ref block) => block.serialized_size(),
+            Block::Micro(ref block) => block.estimated_serialized_size(),
+        };
+        size
+    }
+
     /// Unwraps the block and returns a reference to the underlying Macro block.
     pub fn unwrap_macro_ref(&self) -> &MacroBlock {
         if let Block::Macro(ref block) = self {
@@ -286,6 +288,12 @@ impl Block {
             Block::Micro(_) => false,
         }
     }
+
+    /// Returns true if the block is an election block, i.e. the macro block that ends an epoch,
+    /// false otherwise.
+    pub fn is_election_block(&self) -> bool {
+        self.is_macro() && policy::is_election_block_at(self.block_number())
+    }
 }
 
 impl Serialize for Block {
@@ -391,6 +399,18 @@ impl BlockHeader {
         }
     }
 
+    /// Returns how far, in milliseconds, the timestamp of the block lies ahead of the given time.
+    /// Blocks with a timestamp at or before the given time have a drift of 0.
+    pub fn timestamp_drift(&self, now_ms: u64) -> u64 {
+        self.timestamp().saturating_sub(now_ms)
+    }
+
+    /// Checks if the timestamp of the block lies further ahead of the given time than the given
+    /// tolerance. Both times are Unix timestamps in milliseconds.
+    pub fn is_from_future(&self, now_ms: u64, tolerance_ms: u64) -> bool {
+        self.timestamp_drift(now_ms) > tolerance_ms
+    }
+
     /// Returns the parent hash of the block. The parent hash is the hash of the header of the
     /// immediately preceding block.
     pub fn parent_hash(&self) -> &Blake2bHash {
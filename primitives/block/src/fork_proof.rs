@@ -6,6 +6,7 @@ use nimiq_hash::{Blake2bHash, Hash, HashOutput, SerializeContent};
 use nimiq_keys::{PublicKey as SchnorrPublicKey, Signature as SchnorrSignature};
 use nimiq_primitives::policy;
 use nimiq_vrf::VrfSeed;
+use thiserror::Error;
 
 use crate::MicroHeader;
 
@@ -39,10 +40,13 @@ impl ForkProof {
             return Err(ForkProofError::SameHeader);
         }
 
-        // Check that the headers have equal block numbers and seeds.
-        if self.header1.block_number != self.header2.block_number
-            || self.header1.seed.entropy() != self.header2.seed.entropy()
-        {
+        // Check that the headers have equal block numbers.
+        if self.header1.block_number != self.header2.block_number {
+            return Err(ForkProofError::BlockNumberMismatch);
+        }
+
+        // Check that the headers have equal seeds, i.e. they were produced for the same slot.
+        if self.header1.seed.entropy() != self.header2.seed.entropy() {
             return Err(ForkProofError::SlotMismatch);
         }
 
@@ -131,9 +135,14 @@ impl std::hash::Hash for ForkProof {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
 pub enum ForkProofError {
+    #[error("Headers have different block numbers")]
+    BlockNumberMismatch,
+    #[error("Headers were produced for different slots")]
     SlotMismatch,
+    #[error("Invalid justification")]
     InvalidJustification,
+    #[error("Headers are equal")]
     SameHeader,
 }
@@ -9,6 +9,7 @@ pub use macro_block::*;
 pub use micro_block::*;
 pub use multisig::*;
 use nimiq_transaction::TransactionError;
+use nimiq_vrf::VrfError;
 pub use signed::*;
 pub use skip_block::*;
 pub use tendermint::*;
@@ -33,6 +34,8 @@ pub enum BlockError {
     FromTheFuture,
     #[error("Block size exceeded")]
     SizeExceeded,
+    #[error("Block size exceeded: {actual} > {max}")]
+    SizeExceededDetail { actual: usize, max: usize },
 
     #[error("Body hash mismatch")]
     BodyHashMismatch,
@@ -73,8 +76,10 @@ pub enum BlockError {
     InvalidJustification,
     #[error("Skip block proof is invalid")]
     InvalidSkipBlockProof,
-    #[error("Contains an invalid seed")]
-    InvalidSeed,
+    #[error("Contains an invalid seed: {0}")]
+    InvalidSeed(VrfError),
+    #[error("Skip block doesn't carry over the previous seed")]
+    SkipBlockSeedMismatch,
     #[error("Invalid history root")]
     InvalidHistoryRoot,
     #[error("Incorrect validators")]
@@ -107,10 +107,7 @@ impl MacroBlock {
     pub fn nano_zkp_hash(&self) -> Blake2sHash {
         let mut message = self.hash().serialize_to_vec();
 
-        if let Some(validators) = self.get_validators() {
-            // Create the tree.
-            let mut pk_tree_root = MacroBlock::pk_tree_root(&validators);
-
+        if let Some(mut pk_tree_root) = self.compute_pk_tree_root() {
             // Add it to the message.
             message.append(&mut pk_tree_root);
         }
@@ -132,16 +129,38 @@ impl MacroBlock {
         pk_tree_construct(public_keys)
     }
 
+    /// Recomputes the PKTree root from the validators contained in this block, the same way the
+    /// block verification does. Only returns Some if it is an election block. This is useful to
+    /// diagnose a mismatch with the `pk_tree_root` stored in the body.
+    pub fn compute_pk_tree_root(&self) -> Option<Vec<u8>> {
+        let validators = self.body.as_ref()?.validators.as_ref()?;
+        Some(MacroBlock::pk_tree_root(validators))
+    }
+
     /// Returns whether or not this macro block is an election block.
     pub fn is_election_block(&self) -> bool {
         policy::is_election_block_at(self.header.block_number)
     }
 
-    /// Returns a copy of the validator slots. Only returns Some if it is an election block.
+    /// Returns a copy of the validator slots contained in the body of this block, if any.
+    ///
+    /// Deprecated: the validators contained in an election block are the ones for the *next*
+    /// epoch, not for the epoch the block belongs to. Use `get_next_validators` instead, which makes
+    /// this explicit.
     pub fn get_validators(&self) -> Option<Validators> {
         self.body.as_ref()?.validators.clone()
     }
 
+    /// Returns a copy of the validator slots for the epoch following this block. Only election
+    /// blocks contain them, so this returns None for any other macro block or if the body is
+    /// missing.
+    pub fn get_next_validators(&self) -> Option<Validators> {
+        if !self.is_election_block() {
+            return None;
+        }
+        self.body.as_ref()?.validators.clone()
+    }
+
     /// Returns the block number of this macro block.
     pub fn block_number(&self) -> u32 {
         self.header.block_number
@@ -1,15 +1,16 @@
 use std::fmt::Debug;
 use std::{fmt, io};
 
-use beserial::{Deserialize, Serialize};
+use beserial::{Deserialize, DeserializeWithLength, ReadBytesExt, Serialize};
 use nimiq_database::{FromDatabaseValue, IntoDatabaseValue};
 use nimiq_hash::{Blake2bHash, Hash, SerializeContent};
 use nimiq_hash_derive::SerializeContent;
 use nimiq_keys::Signature;
 use nimiq_primitives::policy;
 use nimiq_transaction::ExecutedTransaction;
-use nimiq_transaction::Transaction;
+use nimiq_transaction::{Transaction, TransactionError};
 use nimiq_vrf::VrfSeed;
+use thiserror::Error;
 
 use crate::fork_proof::ForkProof;
 use crate::skip_block::SkipBlockProof;
@@ -87,6 +88,76 @@ impl MicroBody {
             .map(|txn| txn.get_raw_transaction().clone())
             .collect()
     }
+
+    /// Deserializes a micro block body transaction by transaction, stopping at the first
+    /// transaction that fails to deserialize. On failure, the index of the offending transaction
+    /// and its byte position relative to the start of the body are returned alongside the error.
+    /// Errors in the fork proofs or the transaction count precede all transactions and are
+    /// reported with index 0 and position 0.
+    pub fn deserialize_checked<R: ReadBytesExt>(
+        reader: &mut R,
+    ) -> Result<Self, MicroBodyTransactionError> {
+        let mut reader = PositionReader::new(reader);
+        let invalid_prefix = |error| MicroBodyTransactionError {
+            index: 0,
+            position: 0,
+            error: TransactionError::InvalidSerialization(error),
+        };
+
+        let fork_proofs: Vec<ForkProof> =
+            DeserializeWithLength::deserialize::<u16, _>(&mut reader).map_err(invalid_prefix)?;
+        let num_transactions: u16 =
+            Deserialize::deserialize(&mut reader).map_err(invalid_prefix)?;
+
+        let mut transactions = Vec::with_capacity(num_transactions as usize);
+        for index in 0..num_transactions as usize {
+            let position = reader.position;
+            let transaction = Deserialize::deserialize(&mut reader).map_err(|error| {
+                MicroBodyTransactionError {
+                    index,
+                    position,
+                    error: TransactionError::from(error),
+                }
+            })?;
+            transactions.push(transaction);
+        }
+
+        Ok(MicroBody {
+            fork_proofs,
+            transactions,
+        })
+    }
+}
+
+/// Error returned by [`MicroBody::deserialize_checked`] for the first invalid transaction.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Invalid transaction {index} at byte {position} of the micro body: {error}")]
+pub struct MicroBodyTransactionError {
+    /// The index of the offending transaction within the body.
+    pub index: usize,
+    /// The byte position of the offending transaction relative to the start of the body.
+    pub position: usize,
+    pub error: TransactionError,
+}
+
+/// A reader that keeps track of the number of bytes read from the underlying reader.
+struct PositionReader<'a, R> {
+    inner: &'a mut R,
+    position: usize,
+}
+
+impl<'a, R> PositionReader<'a, R> {
+    fn new(inner: &'a mut R) -> Self {
+        PositionReader { inner, position: 0 }
+    }
+}
+
+impl<'a, R: io::Read> io::Read for PositionReader<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let num_bytes = self.inner.read(buf)?;
+        self.position += num_bytes;
+        Ok(num_bytes)
+    }
 }
 
 impl MicroBlock {
@@ -103,6 +174,43 @@ impl MicroBlock {
         false
     }
 
+    /// Returns the number of transactions in the micro block. Skip blocks and blocks without a body
+    /// have no transactions.
+    pub fn transaction_count(&self) -> usize {
+        self.body.as_ref().map_or(0, |body| body.transactions.len())
+    }
+
+    /// Returns the sum of the fees, in Lunas, of all of the transactions in the micro block. Skip
+    /// blocks and blocks without a body have no fees.
+    pub fn total_fee(&self) -> u64 {
+        self.body.as_ref().map_or(0, |body| {
+            body.transactions
+                .iter()
+                .map(|tx| u64::from(tx.get_raw_transaction().fee))
+                .sum()
+        })
+    }
+
+    /// Returns the size, in bytes, of the serialized micro block. The size is computed by summing
+    /// up the sizes of the header, justification and body, without serializing the block into a
+    /// buffer.
+    pub fn estimated_serialized_size(&self) -> usize {
+        let mut size = self.header.serialized_size();
+        size += /*justification option*/ 1;
+        size += self
+            .justification
+            .as_ref()
+            .map_or(0, |justification| justification.serialized_size());
+        size += /*body option*/ 1;
+        size += self.body.as_ref().map_or(0, |body| body.serialized_size());
+        size
+    }
+
+    /// Returns the maximum size, in bytes, of a serialized micro block. This is a loose upper bound,
+    /// since the header and the justification together are much smaller than twice the maximum
+    /// header size. It is only meant to reject clearly oversized blocks before verifying them.
+    pub const MAX_SIZE: usize = policy::MAX_SIZE_MICRO_BODY + 2 * MicroHeader::MAX_SIZE;
+
     // Returns the available size, in bytes, in a micro block body for transactions.
     pub fn get_available_bytes(num_fork_proofs: usize) -> usize {
         policy::MAX_SIZE_MICRO_BODY
@@ -1,7 +1,11 @@
+use thiserror::Error;
+
 use beserial::{BigEndian, Deserialize, ReadBytesExt, Serialize, SerializingError, WriteBytesExt};
-use nimiq_bls::{AggregateSignature, Signature};
+use nimiq_bls::{AggregatePublicKey, AggregateSignature, SigHash, Signature};
 use nimiq_collections::bitset::BitSet;
 use nimiq_handel::contribution::{AggregatableContribution, ContributionError};
+use nimiq_primitives::policy::TWO_F_PLUS_ONE;
+use nimiq_primitives::slots::Validators;
 
 /*
 This does not really belong here, but as there would otherwise be a cyclic dependency it needs to be here for now.
@@ -51,6 +55,19 @@ impl IndividualSignature {
     }
 }
 
+/// Enum containing the reasons why a multisignature can fail to verify against a validator set.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum MultisigError {
+    #[error("Signer bitmap contains slot {slot}, but there are only {num_slots} slots")]
+    SignerOutOfRange { slot: usize, num_slots: u16 },
+    #[error("Not enough slots signed: {signers} < {required}")]
+    InsufficientSlots { signers: u16, required: u16 },
+    #[error("Voting key of the validator owning slot {0} is invalid")]
+    InvalidVotingKey(u16),
+    #[error("Aggregate signature is invalid")]
+    InvalidSignature,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[cfg_attr(feature = "serde-derive", derive(serde::Serialize, serde::Deserialize))]
 pub struct MultiSignature {
@@ -62,6 +79,112 @@ impl MultiSignature {
     pub fn new(signature: AggregateSignature, signers: BitSet) -> Self {
         Self { signature, signers }
     }
+
+    /// Incorporates the partial signature of a single signer into the aggregate. Fails without
+    /// modifying the aggregate if the signer already contributed, since aggregating the same
+    /// signature twice would corrupt the aggregate.
+    pub fn merge_partial(
+        &mut self,
+        signer_id: usize,
+        partial: &Signature,
+    ) -> Result<(), ContributionError> {
+        if self.signers.contains(signer_id) {
+            let mut overlap = BitSet::new();
+            overlap.insert(signer_id);
+            return Err(ContributionError::Overlapping(overlap));
+        }
+
+        self.signature.aggregate(partial);
+        self.signers.insert(signer_id);
+        Ok(())
+    }
+
+    /// Returns the set of signers that contributed to the aggregate.
+    pub fn signers_bitmap(&self) -> &BitSet {
+        &self.signers
+    }
+
+    /// Verifies the aggregate signature over the given message hash against the voting keys of
+    /// the signing slots. Returns the number of signing slots if the signers are slots of the
+    /// given validators, if they reach the 2f+1 threshold and if the signature is valid.
+    pub fn verify_against_validators(
+        &self,
+        validators: &Validators,
+        message_hash: &SigHash,
+    ) -> Result<u16, MultisigError> {
+        let agg_pk = self.aggregate_public_key(validators)?;
+
+        self.verify_aggregate(&agg_pk, message_hash)
+    }
+
+    /// Aggregates the voting keys of the signing slots. Fails if the signers are not slots of the
+    /// given validators. The result only depends on the validators and the signer bitmap, so it
+    /// can be reused for other signatures by the same signers.
+    pub fn aggregate_public_key(
+        &self,
+        validators: &Validators,
+    ) -> Result<AggregatePublicKey, MultisigError> {
+        let num_slots: u16 = validators
+            .iter()
+            .map(|validator| validator.num_slots())
+            .sum();
+
+        // All signers must be slots of the validators.
+        if let Some(slot) = self.signers.iter().find(|slot| *slot >= num_slots as usize) {
+            return Err(MultisigError::SignerOutOfRange { slot, num_slots });
+        }
+
+        // Get the public key for each signing slot and add them together to get the aggregated
+        // public key.
+        let mut agg_pk = AggregatePublicKey::new();
+
+        for validator in validators.iter() {
+            let (first_slot, last_slot) = validator.slot_range;
+            let num_signed = (first_slot..last_slot)
+                .filter(|slot| self.signers.contains(*slot as usize))
+                .count();
+
+            if num_signed == 0 {
+                continue;
+            }
+
+            let pk = validator
+                .voting_key
+                .uncompress()
+                .ok_or(MultisigError::InvalidVotingKey(first_slot))?;
+
+            for _ in 0..num_signed {
+                agg_pk.aggregate(&pk);
+            }
+        }
+
+        Ok(agg_pk)
+    }
+
+    /// Verifies the aggregate signature over the given message hash against the aggregated voting
+    /// keys of the signers, as returned by `aggregate_public_key`. Returns the number of signing
+    /// slots if they reach the 2f+1 threshold and if the signature is valid.
+    pub fn verify_aggregate(
+        &self,
+        agg_pk: &AggregatePublicKey,
+        message_hash: &SigHash,
+    ) -> Result<u16, MultisigError> {
+        // Check if there are enough votes.
+        let signers = self.signers.len() as u16;
+        if signers < TWO_F_PLUS_ONE {
+            return Err(MultisigError::InsufficientSlots {
+                signers,
+                required: TWO_F_PLUS_ONE,
+            });
+        }
+
+        // Verify the aggregated signature against our aggregated public key.
+        if !agg_pk.verify_hash(message_hash.clone(), &self.signature) {
+            return Err(MultisigError::InvalidSignature);
+        }
+
+        Ok(signers)
+    }
 }
 
 impl AggregatableContribution for MultiSignature {
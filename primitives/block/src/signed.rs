@@ -65,6 +65,8 @@ pub trait Message:
     + PartialEq
     + 'static
 {
+    /// The prefix that is mixed into the hash before signing. Each kind of signed message must use
+    /// a distinct prefix, so that a signature can't be replayed as one for another kind of message.
     const PREFIX: u8;
 
     fn hash_with_prefix(&self) -> SigHash {
@@ -1,14 +1,17 @@
 use std::fmt::Debug;
 
 use beserial::{Deserialize, Serialize};
-use nimiq_bls::AggregatePublicKey;
+use nimiq_bls::AggregateSignature;
+use nimiq_collections::bitset::BitSet;
 use nimiq_hash::{Hash, SerializeContent};
 use nimiq_hash_derive::SerializeContent;
 use nimiq_primitives::policy::TWO_F_PLUS_ONE;
 use nimiq_primitives::slots::Validators;
 use nimiq_vrf::VrfEntropy;
 
-use crate::{Message, MultiSignature, SignedMessage, PREFIX_SKIP_BLOCK_INFO};
+use crate::{
+    BlockError, Message, MultiSignature, MultisigError, SignedMessage, PREFIX_SKIP_BLOCK_INFO,
+};
 
 pub type SignedSkipBlockInfo = SignedMessage<SkipBlockInfo>;
 
@@ -41,34 +44,85 @@ pub struct SkipBlockProof {
 }
 
 impl SkipBlockProof {
+    /// Aggregates the individual signed skip block infos into a proof. Each signature is given
+    /// together with the slot band of the validator that produced it and counts for all slots of
+    /// that validator. Fails if any signature is invalid, if the signatures are not all over the
+    /// same skip block info, or if the signers don't own enough slots to reach the threshold.
+    pub fn aggregate(
+        signatures: impl Iterator<Item = (usize, SignedSkipBlockInfo)>,
+        validators: &Validators,
+    ) -> Result<SkipBlockProof, BlockError> {
+        let mut skip_block_info: Option<SkipBlockInfo> = None;
+        let mut signature = AggregateSignature::new();
+        let mut signers = BitSet::new();
+
+        for (validator_idx, signed_info) in signatures {
+            // All signatures must be over the same skip block info.
+            match skip_block_info {
+                Some(ref info) if info != &signed_info.message => {
+                    return Err(BlockError::InvalidSkipBlockProof);
+                }
+                Some(_) => {}
+                None => skip_block_info = Some(signed_info.message.clone()),
+            }
+
+            let validator = validators
+                .validators
+                .get(validator_idx)
+                .ok_or(BlockError::InvalidSkipBlockProof)?;
+
+            // Each validator must only be counted once.
+            let (first_slot, last_slot) = validator.slot_range;
+            if signers.contains(first_slot as usize) {
+                return Err(BlockError::InvalidSkipBlockProof);
+            }
+
+            let valid = validator
+                .voting_key
+                .uncompress()
+                .map_or(false, |pk| signed_info.verify(&pk));
+            if !valid {
+                return Err(BlockError::InvalidSkipBlockProof);
+            }
+
+            // The signature of a validator counts for each of its slots.
+            signature.aggregate(&signed_info.signature.multiply(validator.num_slots()));
+            for slot in first_slot..last_slot {
+                signers.insert(slot as usize);
+            }
+        }
+
+        // Check if there are enough votes.
+        if signers.len() < TWO_F_PLUS_ONE as usize {
+            return Err(BlockError::InvalidSkipBlockProof);
+        }
+
+        Ok(SkipBlockProof {
+            sig: MultiSignature::new(signature, signers),
+        })
+    }
+
     /// Verifies the proof. This only checks that the proof is valid for this skip block, not that
     /// the skip block itself is valid.
     pub fn verify(&self, skip_block: &SkipBlockInfo, validators: &Validators) -> bool {
-        // Check if there are enough votes.
-        if self.sig.signers.len() < TWO_F_PLUS_ONE as usize {
-            error!(
-                "SkipBlockProof verification failed: Not enough slots signed the skip block message."
-            );
-            return false;
+        match self.verify_signature(skip_block, validators) {
+            Ok(_) => true,
+            Err(e) => {
+                error!("SkipBlockProof verification failed: {}", e);
+                false
+            }
         }
+    }
 
-        // Get the public key for each SLOT present in the signature and add them together to get
-        // the aggregated public key.
-        let agg_pk =
-            self.sig
-                .signers
-                .iter()
-                .fold(AggregatePublicKey::new(), |mut aggregate, slot| {
-                    let pk = validators
-                        .get_validator_by_slot_number(slot as u16)
-                        .voting_key
-                        .uncompress()
-                        .expect("Failed to uncompress CompressedPublicKey");
-                    aggregate.aggregate(&pk);
-                    aggregate
-                });
-
-        // Verify the aggregated signature against our aggregated public key.
-        agg_pk.verify_hash(skip_block.hash_with_prefix(), &self.sig.signature)
+    /// Verifies the aggregate signature of this proof for the given skip block against the
+    /// validators. Returns the number of signing slots, or the reason why the signature is
+    /// invalid.
+    pub fn verify_signature(
+        &self,
+        skip_block: &SkipBlockInfo,
+        validators: &Validators,
+    ) -> Result<u16, MultisigError> {
+        self.sig
+            .verify_against_validators(validators, &skip_block.hash_with_prefix())
     }
 }
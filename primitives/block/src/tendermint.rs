@@ -1,8 +1,10 @@
 use log::error;
+use std::collections::BTreeMap;
 use std::io;
 
 use beserial::{Deserialize, Serialize};
-use nimiq_bls::AggregatePublicKey;
+use nimiq_bls::{AggregateSignature, SigHash, Signature};
+use nimiq_collections::bitset::BitSet;
 use nimiq_hash::{Blake2sHash, Hash, SerializeContent};
 use nimiq_hash_derive::SerializeContent;
 use nimiq_primitives::policy::TWO_F_PLUS_ONE;
@@ -12,7 +14,7 @@ use crate::signed::{
     Message, SignedMessage, PREFIX_TENDERMINT_COMMIT, PREFIX_TENDERMINT_PREPARE,
     PREFIX_TENDERMINT_PROPOSAL,
 };
-use crate::{MacroBlock, MacroHeader, MultiSignature};
+use crate::{MacroBlock, MacroHeader, MultiSignature, MultisigError};
 
 /// The proposal message sent by the Tendermint leader.
 #[derive(Clone, Debug, Serialize, Deserialize, SerializeContent, PartialEq, Eq)]
@@ -47,6 +49,83 @@ impl TendermintProof {
         self.sig.signers.len() as u16
     }
 
+    /// Removes redundant signers from the proof. The validators that signed are kept in order of
+    /// descending number of signed slots (ties are broken by slot band) until their slots reach
+    /// the threshold, the signatures of all other validators are dropped.
+    ///
+    /// As signatures can't be removed from an aggregate, the aggregate signature is rebuilt from
+    /// the individual precommits of the kept validators, given by their slot bands. The rebuilt
+    /// proof is verified for the given block and only replaces the original one if it is valid.
+    ///
+    /// Returns whether the proof was compacted. This is not the case if the proof is already
+    /// minimal, if it doesn't reach the threshold, if the precommit of any of the kept validators
+    /// is missing or if the rebuilt proof doesn't verify.
+    pub fn compact(
+        &mut self,
+        block: &MacroBlock,
+        validators: &Validators,
+        precommits: &BTreeMap<usize, Signature>,
+    ) -> bool {
+        // Collect the validators that signed, together with the slots they signed for.
+        let mut signers: Vec<(usize, Vec<u16>)> = validators
+            .iter()
+            .enumerate()
+            .map(|(validator_idx, validator)| {
+                let (first_slot, last_slot) = validator.slot_range;
+                let slots: Vec<u16> = (first_slot..last_slot)
+                    .filter(|slot| self.sig.signers.contains(*slot as usize))
+                    .collect();
+                (validator_idx, slots)
+            })
+            .filter(|(_, slots)| !slots.is_empty())
+            .collect();
+
+        // Keep the validators with the most slots first.
+        signers.sort_by(|(_, a), (_, b)| b.len().cmp(&a.len()));
+
+        let mut num_votes = 0;
+        let mut num_kept = 0;
+        for (_, slots) in &signers {
+            if num_votes >= TWO_F_PLUS_ONE as usize {
+                break;
+            }
+            num_votes += slots.len();
+            num_kept += 1;
+        }
+
+        if num_votes < TWO_F_PLUS_ONE as usize || num_kept == signers.len() {
+            return false;
+        }
+
+        let mut signature = AggregateSignature::new();
+        let mut signer_slots = BitSet::new();
+
+        for (validator_idx, slots) in &signers[..num_kept] {
+            let precommit = match precommits.get(validator_idx) {
+                Some(precommit) => precommit,
+                None => return false,
+            };
+
+            // The precommit of a validator counts for each of its signed slots.
+            signature.aggregate(&precommit.multiply(slots.len() as u16));
+            for slot in slots {
+                signer_slots.insert(*slot as usize);
+            }
+        }
+
+        let compacted = TendermintProof {
+            round: self.round,
+            sig: MultiSignature::new(signature, signer_slots),
+        };
+        if let Err(e) = compacted.verify_signature(block, validators) {
+            error!("Compacted justification is invalid - {}", e);
+            return false;
+        }
+
+        *self = compacted;
+        true
+    }
+
     /// Verifies the proof. This only checks that the proof is valid for this block, not that the
     /// block itself is valid.
     pub fn verify(block: &MacroBlock, current_validators: &Validators) -> bool {
@@ -59,12 +138,29 @@ impl TendermintProof {
             Some(x) => x,
         };
 
-        // Check if there are enough votes.
-        if justification.votes() < TWO_F_PLUS_ONE {
-            error!("Invalid justification - not enough votes!");
-            return false;
+        match justification.verify_signature(block, current_validators) {
+            Ok(_) => true,
+            Err(e) => {
+                error!("Invalid justification - {}", e);
+                false
+            }
         }
+    }
 
+    /// Verifies the aggregate signature of this proof for the given block against the current
+    /// validators. Returns the number of signing slots, or the reason why the signature is
+    /// invalid.
+    pub fn verify_signature(
+        &self,
+        block: &MacroBlock,
+        current_validators: &Validators,
+    ) -> Result<u16, MultisigError> {
+        self.sig
+            .verify_against_validators(current_validators, &self.message_hash(block))
+    }
+
+    /// Returns the hash of the precommit message that the validators signed for the given block.
+    pub fn message_hash(&self, block: &MacroBlock) -> SigHash {
         // Calculate the `nano_zkp_hash`. This a special hash that is calculated using the `validators`
         // field of the block body. It is necessary for the ZKP proofs used in the nano sync.
         let block_hash = block.nano_zkp_hash();
@@ -74,23 +170,12 @@ impl TendermintProof {
             proposal_hash: Some(block_hash),
             id: TendermintIdentifier {
                 block_number: block.block_number(),
-                round_number: justification.round,
+                round_number: self.round,
                 step: TendermintStep::PreCommit,
             },
         };
 
-        // Get the public key for each SLOT and add them together to get the aggregated public key
-        // (if they are part of the Multisignature Bitset).
-        let mut agg_pk = AggregatePublicKey::new();
-
-        for (i, pk) in current_validators.voting_keys().iter().enumerate() {
-            if justification.sig.signers.contains(i as usize) {
-                agg_pk.aggregate(pk);
-            }
-        }
-
-        // Verify the aggregated signature against our aggregated public key.
-        agg_pk.verify(&message, &justification.sig.signature)
+        message.hash()
     }
 }
 
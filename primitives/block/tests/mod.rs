@@ -1,14 +1,29 @@
+use std::collections::BTreeMap;
+use std::io::Write;
 use std::str::FromStr;
 
 use beserial::{Deserialize, Serialize};
-use nimiq_block::{IndividualSignature, MacroBlock, MacroBody, MacroHeader, MultiSignature};
-use nimiq_bls::{CompressedPublicKey, KeyPair};
+use nimiq_block::{
+    Block, BlockError, ForkProof, ForkProofError, IndividualSignature, MacroBlock, MacroBody,
+    MacroHeader, Message, MicroBlock, MicroBody, MicroBodyTransactionError, MicroHeader,
+    MicroJustification, MultiSignature, MultisigError, SignedSkipBlockInfo, SkipBlockInfo,
+    SkipBlockProof, TendermintIdentifier, TendermintProof, TendermintStep, TendermintVote,
+    PREFIX_POKOSK, PREFIX_TENDERMINT_COMMIT, PREFIX_TENDERMINT_PREPARE, PREFIX_TENDERMINT_PROPOSAL,
+    PREFIX_VALIDATOR_INFO,
+};
+use nimiq_bls::{AggregatePublicKey, AggregateSignature, CompressedPublicKey, KeyPair};
 use nimiq_collections::bitset::BitSet;
+use nimiq_handel::contribution::ContributionError;
 use nimiq_handel::update::LevelUpdate;
-use nimiq_hash::{Blake2bHasher, Hasher};
-use nimiq_keys::{Address, PublicKey};
-use nimiq_primitives::slots::ValidatorsBuilder;
+use nimiq_hash::{Blake2bHash, Blake2bHasher, Blake2sHasher, Hash, Hasher, SerializeContent};
+use nimiq_keys::{Address, KeyPair as SchnorrKeyPair, PrivateKey, PublicKey, Signature};
+use nimiq_primitives::coin::Coin;
+use nimiq_primitives::networks::NetworkId;
+use nimiq_primitives::policy;
+use nimiq_primitives::slots::{Validators, ValidatorsBuilder};
 use nimiq_test_log::test;
+use nimiq_transaction::{ExecutedTransaction, Transaction, TransactionError};
+use nimiq_utils::key_rng::SecureGenerate;
 use nimiq_vrf::VrfSeed;
 
 #[test]
@@ -109,16 +124,27 @@ fn it_can_convert_macro_block_into_slots() {
     let validators_from_macro = macro_block.get_validators().unwrap();
 
     assert_eq!(validator_slots, validators_from_macro);
+
+    // Only election blocks carry the validators of the next epoch.
+    assert_eq!(macro_block.get_next_validators(), None);
+
+    let mut election_block = macro_block;
+    election_block.header.block_number = policy::BLOCKS_PER_EPOCH;
+    assert_eq!(election_block.get_next_validators(), Some(validator_slots));
 }
 
-fn create_multisig() -> MultiSignature {
+fn create_key_pair() -> KeyPair {
     let raw_key = hex::decode(
         "1b9e470e0deb06fe55774bb2cf499b411f55265c10d8d78742078381803451e058c88\
         391431799462edde4c7872649964137d8e03cd618dd4a25690c56ffd7f42fb7ae8049d29f38d569598b38d4\
         39f69107cc0b6f4ecd00a250c74409510100",
     )
     .unwrap();
-    let key_pair = KeyPair::deserialize_from_vec(&raw_key).unwrap();
+    KeyPair::deserialize_from_vec(&raw_key).unwrap()
+}
+
+fn create_multisig() -> MultiSignature {
+    let key_pair = create_key_pair();
     let signature = key_pair.sign(&"foobar");
     IndividualSignature::new(signature, 1).as_multisig()
 }
@@ -141,3 +167,698 @@ fn test_serialize_deserialize_with_message() {
     let update = LevelUpdate::new(create_multisig(), None, 2, 3).with_tag(42u64);
     assert_eq!(update.serialized_size(), 108 + 8);
 }
+
+fn empty_multisig() -> MultiSignature {
+    MultiSignature::new(AggregateSignature::new(), BitSet::new())
+}
+
+#[test]
+fn it_can_merge_partial_signatures_in_order() {
+    let key_pair = create_key_pair();
+
+    let mut multisig = empty_multisig();
+    for signer in 0..3 {
+        multisig
+            .merge_partial(signer, &key_pair.sign(&"foobar"))
+            .unwrap();
+    }
+
+    assert_eq!(
+        multisig.signers_bitmap().iter().collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+    let public_key = AggregatePublicKey::from_public_keys(&[key_pair.public_key; 3]);
+    assert!(public_key.verify(&"foobar", &multisig.signature));
+}
+
+#[test]
+fn it_can_merge_partial_signatures_out_of_order() {
+    let key_pair = create_key_pair();
+    let signatures: Vec<_> = (0..4)
+        .map(|signer| key_pair.sign(&format!("foobar{}", signer)))
+        .collect();
+
+    let mut in_order = empty_multisig();
+    for (signer, signature) in signatures.iter().enumerate() {
+        in_order.merge_partial(signer, signature).unwrap();
+    }
+
+    let mut out_of_order = empty_multisig();
+    for signer in [2, 0, 3, 1] {
+        out_of_order
+            .merge_partial(signer, &signatures[signer])
+            .unwrap();
+    }
+
+    assert_eq!(in_order, out_of_order);
+}
+
+#[test]
+fn it_rejects_duplicate_partial_signatures() {
+    let key_pair = create_key_pair();
+    let signature = key_pair.sign(&"foobar");
+
+    let mut multisig = empty_multisig();
+    multisig.merge_partial(1, &signature).unwrap();
+    multisig.merge_partial(4, &signature).unwrap();
+    let expected = multisig.clone();
+
+    match multisig.merge_partial(4, &signature) {
+        Err(ContributionError::Overlapping(overlap)) => {
+            assert_eq!(overlap.iter().collect::<Vec<_>>(), vec![4])
+        }
+        result => panic!("Unexpected merge result: {:?}", result),
+    }
+
+    // The aggregate must not have been modified.
+    assert_eq!(multisig, expected);
+}
+
+fn create_micro_header(block_number: u32, extra_data: Vec<u8>) -> MicroHeader {
+    MicroHeader {
+        version: 1,
+        block_number,
+        timestamp: 1000 * block_number as u64,
+        parent_hash: Blake2bHash::default(),
+        seed: VrfSeed::default(),
+        extra_data,
+        state_root: Blake2bHash::default(),
+        body_root: Blake2bHash::default(),
+        history_root: Blake2bHash::default(),
+    }
+}
+
+fn create_micro_block(
+    fork_proofs: Vec<ForkProof>,
+    transactions: Vec<ExecutedTransaction>,
+) -> Block {
+    Block::Micro(MicroBlock {
+        header: create_micro_header(42, vec![0xff; 32]),
+        justification: Some(MicroJustification::Micro(Signature::default())),
+        body: Some(MicroBody {
+            fork_proofs,
+            transactions,
+        }),
+    })
+}
+
+fn assert_estimated_size(block: &Block) {
+    let serialized = block.serialize_to_vec();
+    assert_eq!(block.estimated_serialized_size(), serialized.len());
+    assert_eq!(block.estimated_serialized_size(), block.serialized_size());
+    if let Block::Micro(ref micro_block) = block {
+        assert_eq!(
+            micro_block.estimated_serialized_size(),
+            micro_block.serialized_size()
+        );
+    }
+}
+
+#[test]
+fn it_detects_blocks_from_the_future() {
+    // The micro header created by the helper has a timestamp of 42000.
+    let header = create_micro_block(vec![], vec![]).header();
+    let tolerance = 10_000;
+
+    assert_eq!(header.timestamp_drift(50_000), 0);
+    assert!(!header.is_from_future(50_000, tolerance));
+
+    // Exactly at the tolerance the block is still accepted.
+    let now = 42_000 - tolerance;
+    assert_eq!(header.timestamp_drift(now), tolerance);
+    assert!(!header.is_from_future(now, tolerance));
+    assert!(header.is_from_future(now - 1, tolerance));
+    assert_eq!(header.timestamp_drift(now - 1), tolerance + 1);
+
+    // Without tolerance any timestamp ahead of the current time is from the future.
+    assert!(!header.is_from_future(42_000, 0));
+    assert!(header.is_from_future(41_999, 0));
+}
+
+#[test]
+fn it_estimates_serialized_size_of_empty_blocks() {
+    assert_estimated_size(&create_micro_block(vec![], vec![]));
+
+    assert_estimated_size(&Block::Micro(MicroBlock {
+        header: create_micro_header(1, vec![]),
+        justification: None,
+        body: None,
+    }));
+
+    assert_estimated_size(&Block::Macro(MacroBlock::default()));
+}
+
+#[test]
+fn it_estimates_serialized_size_of_transaction_heavy_blocks() {
+    let transactions = (0..500u64)
+        .map(|i| {
+            let mut tx = Transaction::new_basic(
+                Address::from([1u8; 20]),
+                Address::from([2u8; 20]),
+                Coin::from_u64_unchecked(1000 + i),
+                Coin::from_u64_unchecked(i),
+                1,
+                NetworkId::UnitAlbatross,
+            );
+            tx.data = vec![i as u8; (i % 64) as usize];
+            if i % 3 == 0 {
+                ExecutedTransaction::Err(tx)
+            } else {
+                ExecutedTransaction::Ok(tx)
+            }
+        })
+        .collect();
+
+    assert_estimated_size(&create_micro_block(vec![], transactions));
+}
+
+fn create_executed_transactions(count: u64) -> Vec<ExecutedTransaction> {
+    (0..count)
+        .map(|i| {
+            ExecutedTransaction::Ok(Transaction::new_basic(
+                Address::from([1u8; 20]),
+                Address::from([2u8; 20]),
+                Coin::from_u64_unchecked(1000 + i),
+                Coin::from_u64_unchecked(i),
+                1,
+                NetworkId::UnitAlbatross,
+            ))
+        })
+        .collect()
+}
+
+#[test]
+fn it_can_deserialize_checked_micro_bodies() {
+    let body = MicroBody {
+        fork_proofs: vec![],
+        transactions: create_executed_transactions(3),
+    };
+    let serialized = body.serialize_to_vec();
+
+    let deserialized = MicroBody::deserialize_checked(&mut &serialized[..]).unwrap();
+    assert_eq!(deserialized, body);
+}
+
+#[test]
+fn it_can_count_transactions_and_sum_fees_of_micro_blocks() {
+    let mut transactions = create_executed_transactions(4);
+    if let ExecutedTransaction::Ok(tx) = transactions.remove(3) {
+        transactions.push(ExecutedTransaction::Err(tx));
+    }
+
+    let block = create_micro_block(vec![], transactions);
+    let micro_block = block.unwrap_micro_ref();
+    assert_eq!(micro_block.transaction_count(), 4);
+    // Failed transactions pay their fee as well.
+    assert_eq!(micro_block.total_fee(), 1 + 2 + 3);
+    assert_eq!(
+        block.sum_transaction_fees(),
+        Coin::from_u64_unchecked(1 + 2 + 3)
+    );
+
+    let no_body = MicroBlock {
+        header: create_micro_header(1, vec![]),
+        justification: None,
+        body: None,
+    };
+    assert_eq!(no_body.transaction_count(), 0);
+    assert_eq!(no_body.total_fee(), 0);
+    assert_eq!(Block::Micro(no_body).sum_transaction_fees(), Coin::ZERO);
+}
+
+#[test]
+fn it_reports_the_first_invalid_transaction_in_micro_bodies() {
+    let body = MicroBody {
+        fork_proofs: vec![],
+        transactions: create_executed_transactions(3),
+    };
+    let mut serialized = body.serialize_to_vec();
+
+    // Corrupt the execution result tag of the second transaction.
+    let offset = /*fork proofs length*/ 2 + /*transactions length*/ 2
+        + body.transactions[0].serialized_size();
+    serialized[offset] = 0xff;
+
+    match MicroBody::deserialize_checked(&mut &serialized[..]) {
+        Err(MicroBodyTransactionError {
+            index,
+            position,
+            error: TransactionError::InvalidSerialization(_),
+        }) => {
+            assert_eq!(index, 1);
+            assert_eq!(position, offset);
+        }
+        result => panic!("Unexpected result: {:?}", result),
+    }
+
+    // A truncated body fails at the last transaction.
+    let serialized = body.serialize_to_vec();
+    let last_offset = offset + body.transactions[1].serialized_size();
+    match MicroBody::deserialize_checked(&mut &serialized[..serialized.len() - 1]) {
+        Err(MicroBodyTransactionError {
+            index,
+            position,
+            error: TransactionError::InvalidSerialization(_),
+        }) => {
+            assert_eq!(index, 2);
+            assert_eq!(position, last_offset);
+        }
+        result => panic!("Unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn it_estimates_serialized_size_of_fork_proof_heavy_blocks() {
+    let fork_proofs = (0..20u32)
+        .map(|i| ForkProof {
+            header1: create_micro_header(i, vec![1; (i % 33) as usize]),
+            header2: create_micro_header(i, vec![2; 32]),
+            justification1: Signature::default(),
+            justification2: Signature::default(),
+            prev_vrf_seed: VrfSeed::default(),
+        })
+        .collect();
+
+    assert_estimated_size(&create_micro_block(fork_proofs, vec![]));
+}
+
+fn create_fork_proof(
+    key_pair: &SchnorrKeyPair,
+    header1: MicroHeader,
+    header2: MicroHeader,
+) -> ForkProof {
+    let justification1 = key_pair.sign(header1.hash::<Blake2bHash>().as_slice());
+    let justification2 = key_pair.sign(header2.hash::<Blake2bHash>().as_slice());
+    ForkProof {
+        header1,
+        header2,
+        justification1,
+        justification2,
+        prev_vrf_seed: VrfSeed::default(),
+    }
+}
+
+#[test]
+fn it_can_verify_fork_proofs() {
+    let key_pair = SchnorrKeyPair::from(
+        PrivateKey::from_str("041580cc67e66e9e08b68fd9e4c9deb68737168fbe7488de2638c2e906c2f5ad")
+            .unwrap(),
+    );
+    let other_key_pair = SchnorrKeyPair::from(
+        PrivateKey::from_str("b410a7a583cbc13ef4f1cbddace30928bcb4f9c13722501f5ea5d1b1ba32e7cd")
+            .unwrap(),
+    );
+
+    let seed = VrfSeed::default().sign_next(&key_pair);
+    let mut header1 = create_micro_header(1, vec![1]);
+    header1.seed = seed.clone();
+    let mut header2 = create_micro_header(1, vec![2]);
+    header2.seed = seed;
+
+    // Valid fork proof.
+    let proof = create_fork_proof(&key_pair, header1.clone(), header2.clone());
+    assert_eq!(proof.verify(&key_pair.public), Ok(()));
+
+    // Signed by another validator.
+    assert_eq!(
+        proof.verify(&other_key_pair.public),
+        Err(ForkProofError::InvalidJustification)
+    );
+
+    // Garbage justification.
+    let mut bad_proof = proof.clone();
+    bad_proof.justification2 = Signature::default();
+    assert_eq!(
+        bad_proof.verify(&key_pair.public),
+        Err(ForkProofError::InvalidJustification)
+    );
+
+    // Same header twice.
+    let same_proof = create_fork_proof(&key_pair, header1.clone(), header1.clone());
+    assert_eq!(
+        same_proof.verify(&key_pair.public),
+        Err(ForkProofError::SameHeader)
+    );
+
+    // Headers at different heights.
+    let mut header3 = header2.clone();
+    header3.block_number = 2;
+    let height_proof = create_fork_proof(&key_pair, header1.clone(), header3);
+    assert_eq!(
+        height_proof.verify(&key_pair.public),
+        Err(ForkProofError::BlockNumberMismatch)
+    );
+
+    // Headers produced for different slots.
+    let mut header4 = header2;
+    header4.seed = header1.seed.sign_next(&key_pair);
+    let slot_proof = create_fork_proof(&key_pair, header1, header4);
+    assert_eq!(
+        slot_proof.verify(&key_pair.public),
+        Err(ForkProofError::SlotMismatch)
+    );
+}
+
+fn create_skip_block_validators(key_pair: &KeyPair, slots: &[u16]) -> Validators {
+    let mut builder = ValidatorsBuilder::new();
+    for (i, num_slots) in slots.iter().enumerate() {
+        for _ in 0..*num_slots {
+            builder.push(
+                Address::from([i as u8; 20]),
+                key_pair.public_key,
+                PublicKey::default(),
+            );
+        }
+    }
+    builder.build()
+}
+
+#[test]
+fn it_can_aggregate_skip_block_proofs() {
+    let key_pair = create_key_pair();
+    let validators = create_skip_block_validators(&key_pair, &[400, 112]);
+
+    let skip_block_info = SkipBlockInfo {
+        block_number: 42,
+        vrf_entropy: VrfSeed::default().entropy(),
+    };
+    let signed_info =
+        SignedSkipBlockInfo::from_message(skip_block_info.clone(), &key_pair.secret_key, 0);
+
+    // Both validators together own all slots.
+    let proof = SkipBlockProof::aggregate(
+        vec![(0, signed_info.clone()), (1, signed_info.clone())].into_iter(),
+        &validators,
+    )
+    .unwrap();
+    assert_eq!(proof.sig.signers.len(), policy::SLOTS as usize);
+    assert!(proof.verify(&skip_block_info, &validators));
+
+    // The first validator alone owns enough slots.
+    let proof =
+        SkipBlockProof::aggregate(vec![(0, signed_info.clone())].into_iter(), &validators).unwrap();
+    assert_eq!(proof.sig.signers.len(), 400);
+    assert!(proof.verify(&skip_block_info, &validators));
+}
+
+#[test]
+fn it_rejects_underweight_skip_block_proofs() {
+    let key_pair = create_key_pair();
+    let validators = create_skip_block_validators(&key_pair, &[400, 112]);
+
+    let signed_info = SignedSkipBlockInfo::from_message(
+        SkipBlockInfo {
+            block_number: 42,
+            vrf_entropy: VrfSeed::default().entropy(),
+        },
+        &key_pair.secret_key,
+        1,
+    );
+
+    // The second validator only owns 112 slots, which is below the threshold.
+    assert_eq!(
+        SkipBlockProof::aggregate(vec![(1, signed_info.clone())].into_iter(), &validators),
+        Err(BlockError::InvalidSkipBlockProof)
+    );
+
+    // Counting the same validator multiple times must not help either.
+    assert_eq!(
+        SkipBlockProof::aggregate(
+            vec![
+                (1, signed_info.clone()),
+                (1, signed_info.clone()),
+                (1, signed_info)
+            ]
+            .into_iter(),
+            &validators
+        ),
+        Err(BlockError::InvalidSkipBlockProof)
+    );
+}
+
+fn create_skip_block_multisig(
+    key_pair: &KeyPair,
+    slots: impl Iterator<Item = u16>,
+) -> MultiSignature {
+    let signed_info = SignedSkipBlockInfo::from_message(
+        SkipBlockInfo {
+            block_number: 42,
+            vrf_entropy: VrfSeed::default().entropy(),
+        },
+        &key_pair.secret_key,
+        0,
+    );
+
+    let mut signers = BitSet::new();
+    for slot in slots {
+        signers.insert(slot as usize);
+    }
+
+    MultiSignature::new(
+        AggregateSignature::from_signatures(&[signed_info
+            .signature
+            .multiply(signers.len() as u16)]),
+        signers,
+    )
+}
+
+#[test]
+fn it_can_verify_multisigs_against_validators() {
+    let key_pair = create_key_pair();
+    let validators = create_skip_block_validators(&key_pair, &[400, 112]);
+
+    let message_hash = SkipBlockInfo {
+        block_number: 42,
+        vrf_entropy: VrfSeed::default().entropy(),
+    }
+    .hash_with_prefix();
+
+    // Exactly 2f+1 slots signed.
+    let multisig = create_skip_block_multisig(&key_pair, 0..policy::TWO_F_PLUS_ONE);
+    assert_eq!(
+        multisig.verify_against_validators(&validators, &message_hash),
+        Ok(policy::TWO_F_PLUS_ONE)
+    );
+
+    // All slots signed, across both validators.
+    let multisig = create_skip_block_multisig(&key_pair, 0..policy::SLOTS);
+    assert_eq!(
+        multisig.verify_against_validators(&validators, &message_hash),
+        Ok(policy::SLOTS)
+    );
+
+    // A different message.
+    let other_hash = SkipBlockInfo {
+        block_number: 43,
+        vrf_entropy: VrfSeed::default().entropy(),
+    }
+    .hash_with_prefix();
+    assert_eq!(
+        multisig.verify_against_validators(&validators, &other_hash),
+        Err(MultisigError::InvalidSignature)
+    );
+}
+
+#[test]
+fn it_rejects_multisigs_below_the_threshold() {
+    let key_pair = create_key_pair();
+    let validators = create_skip_block_validators(&key_pair, &[400, 112]);
+
+    let message_hash = SkipBlockInfo {
+        block_number: 42,
+        vrf_entropy: VrfSeed::default().entropy(),
+    }
+    .hash_with_prefix();
+
+    let multisig = create_skip_block_multisig(&key_pair, 0..policy::TWO_F_PLUS_ONE - 1);
+    assert_eq!(
+        multisig.verify_against_validators(&validators, &message_hash),
+        Err(MultisigError::InsufficientSlots {
+            signers: policy::TWO_F_PLUS_ONE - 1,
+            required: policy::TWO_F_PLUS_ONE,
+        })
+    );
+
+    // The signature doesn't match the signers.
+    let mut multisig = create_skip_block_multisig(&key_pair, 0..policy::TWO_F_PLUS_ONE);
+    multisig.signers.insert(policy::TWO_F_PLUS_ONE as usize);
+    assert_eq!(
+        multisig.verify_against_validators(&validators, &message_hash),
+        Err(MultisigError::InvalidSignature)
+    );
+}
+
+#[test]
+fn it_rejects_multisigs_with_signers_beyond_the_validators() {
+    let key_pair = create_key_pair();
+    let validators = create_skip_block_validators(&key_pair, &[400, 112]);
+
+    let message_hash = SkipBlockInfo {
+        block_number: 42,
+        vrf_entropy: VrfSeed::default().entropy(),
+    }
+    .hash_with_prefix();
+
+    // The bitmap is one slot longer than the validator set.
+    let multisig = create_skip_block_multisig(&key_pair, 0..policy::SLOTS + 1);
+    assert_eq!(
+        multisig.verify_against_validators(&validators, &message_hash),
+        Err(MultisigError::SignerOutOfRange {
+            slot: policy::SLOTS as usize,
+            num_slots: policy::SLOTS,
+        })
+    );
+
+    // The skip block proof reports it as invalid instead of panicking.
+    let proof = SkipBlockProof { sig: multisig };
+    assert!(!proof.verify(
+        &SkipBlockInfo {
+            block_number: 42,
+            vrf_entropy: VrfSeed::default().entropy(),
+        },
+        &validators
+    ));
+}
+
+#[test]
+fn it_binds_signatures_to_the_message_kind() {
+    let key_pair = create_key_pair();
+    let skip_block_info = SkipBlockInfo {
+        block_number: 42,
+        vrf_entropy: VrfSeed::default().entropy(),
+    };
+
+    let signed_info =
+        SignedSkipBlockInfo::from_message(skip_block_info.clone(), &key_pair.secret_key, 0);
+    assert!(signed_info.verify(&key_pair.public_key));
+
+    // The same payload must not verify when interpreted as any other kind of message.
+    for prefix in [
+        PREFIX_TENDERMINT_PROPOSAL,
+        PREFIX_TENDERMINT_PREPARE,
+        PREFIX_TENDERMINT_COMMIT,
+        PREFIX_POKOSK,
+        PREFIX_VALIDATOR_INFO,
+    ] {
+        let mut hasher = Blake2sHasher::new();
+        hasher.write_all(&[prefix]).unwrap();
+        skip_block_info.serialize_content(&mut hasher).unwrap();
+
+        assert!(!key_pair
+            .public_key
+            .verify_hash(hasher.finish(), &signed_info.signature));
+    }
+}
+
+#[test]
+fn it_can_compute_pk_tree_root() {
+    let key_pair = create_key_pair();
+    let validators = create_skip_block_validators(&key_pair, &[400, 112]);
+
+    let mut macro_block = MacroBlock::default();
+    assert_eq!(macro_block.compute_pk_tree_root(), None);
+
+    macro_block.body = Some(MacroBody {
+        validators: Some(validators.clone()),
+        pk_tree_root: None,
+        lost_reward_set: BitSet::new(),
+        disabled_set: BitSet::new(),
+    });
+    assert_eq!(
+        macro_block.compute_pk_tree_root(),
+        Some(MacroBlock::pk_tree_root(&validators))
+    );
+}
+
+#[test]
+fn it_can_compact_tendermint_proofs() {
+    let key_pair1 = KeyPair::generate_default_csprng();
+    let key_pair2 = KeyPair::generate_default_csprng();
+
+    let mut builder = ValidatorsBuilder::new();
+    for (i, (key_pair, num_slots)) in [(&key_pair1, 400), (&key_pair2, 112)].iter().enumerate() {
+        for _ in 0..*num_slots {
+            builder.push(
+                Address::from([i as u8; 20]),
+                key_pair.public_key,
+                PublicKey::default(),
+            );
+        }
+    }
+    let validators = builder.build();
+
+    let mut block = MacroBlock::default();
+    let vote = TendermintVote {
+        proposal_hash: Some(block.nano_zkp_hash()),
+        id: TendermintIdentifier {
+            block_number: block.block_number(),
+            round_number: 0,
+            step: TendermintStep::PreCommit,
+        },
+    };
+    let precommit1 = key_pair1.sign(&vote);
+    let precommit2 = key_pair2.sign(&vote);
+
+    // Both validators precommitted, although the first one alone reaches the threshold.
+    let mut signers = BitSet::new();
+    for slot in 0..policy::SLOTS {
+        signers.insert(slot as usize);
+    }
+    let mut proof = TendermintProof {
+        round: 0,
+        sig: MultiSignature::new(
+            AggregateSignature::from_signatures(&[
+                precommit1.multiply(400),
+                precommit2.multiply(112),
+            ]),
+            signers,
+        ),
+    };
+    block.justification = Some(proof.clone());
+    assert!(TendermintProof::verify(&block, &validators));
+
+    // Precommits that don't belong to the validators leave the proof untouched.
+    let original = proof.clone();
+    let swapped: BTreeMap<usize, _> = vec![(0, precommit2.clone()), (1, precommit1.clone())]
+        .into_iter()
+        .collect();
+    assert!(!proof.compact(&block, &validators, &swapped));
+    assert_eq!(proof, original);
+
+    let precommits: BTreeMap<usize, _> =
+        vec![(0, precommit1), (1, precommit2)].into_iter().collect();
+    let size = proof.serialized_size();
+
+    assert!(proof.compact(&block, &validators, &precommits));
+    assert_eq!(proof.votes(), 400);
+    assert!(proof.serialized_size() < size);
+
+    block.justification = Some(proof.clone());
+    assert!(TendermintProof::verify(&block, &validators));
+
+    // Compacting a minimal proof doesn't change it.
+    let compacted = proof.clone();
+    assert!(!proof.compact(&block, &validators, &precommits));
+    assert_eq!(proof, compacted);
+}
+
+#[test]
+fn it_can_identify_election_blocks() {
+    let macro_block_at = |block_number| {
+        let mut block = MacroBlock::default();
+        block.header.block_number = block_number;
+        Block::Macro(block)
+    };
+
+    let election_block = macro_block_at(policy::BLOCKS_PER_EPOCH);
+    assert!(election_block.is_election_block());
+
+    let checkpoint_block = macro_block_at(policy::BLOCKS_PER_BATCH);
+    assert!(!checkpoint_block.is_election_block());
+
+    let mut micro_block = create_micro_block(vec![], vec![]);
+    if let Block::Micro(ref mut block) = micro_block {
+        block.header.block_number = policy::BLOCKS_PER_EPOCH;
+    }
+    assert!(!micro_block.is_election_block());
+}
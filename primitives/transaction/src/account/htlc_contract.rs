@@ -1,8 +1,8 @@
 use log::error;
 use strum_macros::Display;
 
-use beserial::{Deserialize, Serialize};
-use nimiq_hash::{Blake2bHasher, Hasher, Sha256Hasher};
+use beserial::{Deserialize, ReadBytesExt, Serialize, SerializingError, WriteBytesExt};
+use nimiq_hash::{Blake2bHasher, Hasher, Sha256Hasher, Sha512Hash, Sha512Hasher};
 use nimiq_keys::Address;
 use nimiq_macros::{add_hex_io_fns_typed_arr, create_typed_array};
 use nimiq_primitives::account::AccountType;
@@ -51,7 +51,16 @@ impl AccountTransactionVerification for HashedTimeLockedContractVerifier {
             return Err(TransactionError::InvalidForRecipient);
         }
 
-        if transaction.data.len() != (20 * 2 + 1 + 32 + 1 + 8) {
+        // The size of the hash root depends on the hash algorithm, which follows the two addresses.
+        let hash_size = transaction
+            .data
+            .get(Address::SIZE * 2..)
+            .and_then(|buf| HashAlgorithm::deserialize(&mut &buf[..]).ok())
+            .map_or(AnyHash32::SIZE, |hash_algorithm| {
+                hash_algorithm.digest_size()
+            });
+
+        if transaction.data.len() != (20 * 2 + 1 + hash_size + 1 + 8) {
             warn!(
                 "Invalid data length. For the following transaction:\n{:?}",
                 transaction
@@ -75,8 +84,8 @@ impl AccountTransactionVerification for HashedTimeLockedContractVerifier {
             ProofType::RegularTransfer => {
                 let hash_algorithm: HashAlgorithm = Deserialize::deserialize(proof_buf)?;
                 let hash_depth: u8 = Deserialize::deserialize(proof_buf)?;
-                let hash_root: [u8; 32] = AnyHash::deserialize(proof_buf)?.into();
-                let mut pre_image: [u8; 32] = AnyHash::deserialize(proof_buf)?.into();
+                let hash_root = AnyHash::deserialize_for(hash_algorithm, proof_buf)?;
+                let mut pre_image = AnyHash::deserialize_for(hash_algorithm, proof_buf)?;
                 let signature_proof: SignatureProof = Deserialize::deserialize(proof_buf)?;
 
                 if !proof_buf.is_empty() {
@@ -88,14 +97,7 @@ impl AccountTransactionVerification for HashedTimeLockedContractVerifier {
                 }
 
                 for _ in 0..hash_depth {
-                    match hash_algorithm {
-                        HashAlgorithm::Blake2b => {
-                            pre_image = Blake2bHasher::default().digest(&pre_image[..]).into();
-                        }
-                        HashAlgorithm::Sha256 => {
-                            pre_image = Sha256Hasher::default().digest(&pre_image[..]).into();
-                        }
-                    }
+                    pre_image = hash_algorithm.digest(pre_image.as_bytes());
                 }
 
                 if hash_root != pre_image {
@@ -167,6 +169,30 @@ impl AccountTransactionVerification for HashedTimeLockedContractVerifier {
 pub enum HashAlgorithm {
     Blake2b = 1,
     Sha256 = 3,
+    Sha512 = 4,
+}
+
+impl HashAlgorithm {
+    /// Returns the size in bytes of the digests produced by this hash algorithm.
+    pub fn digest_size(&self) -> usize {
+        match self {
+            HashAlgorithm::Blake2b | HashAlgorithm::Sha256 => AnyHash32::SIZE,
+            HashAlgorithm::Sha512 => AnyHash64::SIZE,
+        }
+    }
+
+    /// Hashes `data` with this hash algorithm.
+    pub fn digest(&self, data: &[u8]) -> AnyHash {
+        match self {
+            HashAlgorithm::Blake2b => {
+                AnyHash::from(<[u8; 32]>::from(Blake2bHasher::default().digest(data)))
+            }
+            HashAlgorithm::Sha256 => {
+                AnyHash::from(<[u8; 32]>::from(Sha256Hasher::default().digest(data)))
+            }
+            HashAlgorithm::Sha512 => AnyHash::from(Sha512Hasher::default().digest(data)),
+        }
+    }
 }
 
 impl Default for HashAlgorithm {
@@ -183,16 +209,123 @@ pub enum ProofType {
     TimeoutResolve = 3,
 }
 
-create_typed_array!(AnyHash, u8, 32);
-add_hex_io_fns_typed_arr!(AnyHash, AnyHash::SIZE);
+create_typed_array!(AnyHash32, u8, 32);
+add_hex_io_fns_typed_arr!(AnyHash32, AnyHash32::SIZE);
 
-impl AnyHash {
+impl AnyHash32 {
     pub fn as_bytes(&self) -> &[u8] {
         &self.0
     }
 }
 
-#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub type AnyHash64 = Sha512Hash;
+
+/// A hash root or pre-image of a HTLC. Its size depends on the hash algorithm of the contract,
+/// so it is serialized without a length prefix and has to be deserialized with
+/// [`AnyHash::deserialize_for`].
+#[derive(Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub enum AnyHash {
+    Hash32(AnyHash32),
+    Hash64(AnyHash64),
+}
+
+impl AnyHash {
+    pub fn deserialize_for<R: ReadBytesExt>(
+        hash_algorithm: HashAlgorithm,
+        reader: &mut R,
+    ) -> Result<Self, SerializingError> {
+        match hash_algorithm.digest_size() {
+            AnyHash32::SIZE => Ok(AnyHash::Hash32(Deserialize::deserialize(reader)?)),
+            _ => Ok(AnyHash::Hash64(Deserialize::deserialize(reader)?)),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            AnyHash::Hash32(hash) => hash.as_bytes(),
+            AnyHash::Hash64(hash) => hash.as_bytes(),
+        }
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.as_bytes())
+    }
+}
+
+impl Default for AnyHash {
+    fn default() -> Self {
+        AnyHash::Hash32(AnyHash32::default())
+    }
+}
+
+impl Serialize for AnyHash {
+    fn serialize<W: WriteBytesExt>(&self, writer: &mut W) -> Result<usize, SerializingError> {
+        match self {
+            AnyHash::Hash32(hash) => hash.serialize(writer),
+            AnyHash::Hash64(hash) => hash.serialize(writer),
+        }
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.as_bytes().len()
+    }
+}
+
+impl From<[u8; 32]> for AnyHash {
+    fn from(arr: [u8; 32]) -> Self {
+        AnyHash::Hash32(AnyHash32::from(arr))
+    }
+}
+
+impl From<AnyHash32> for AnyHash {
+    fn from(hash: AnyHash32) -> Self {
+        AnyHash::Hash32(hash)
+    }
+}
+
+impl From<[u8; 64]> for AnyHash {
+    fn from(arr: [u8; 64]) -> Self {
+        AnyHash::Hash64(AnyHash64::from(arr))
+    }
+}
+
+impl From<AnyHash64> for AnyHash {
+    fn from(hash: AnyHash64) -> Self {
+        AnyHash::Hash64(hash)
+    }
+}
+
+impl std::str::FromStr for AnyHash {
+    type Err = hex::FromHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.len() / 2 {
+            AnyHash32::SIZE => Ok(AnyHash::Hash32(s.parse()?)),
+            AnyHash64::SIZE => Ok(AnyHash::Hash64(s.parse()?)),
+            _ => Err(hex::FromHexError::InvalidStringLength),
+        }
+    }
+}
+
+impl From<&'static str> for AnyHash {
+    fn from(s: &'static str) -> Self {
+        s.parse().unwrap()
+    }
+}
+
+impl std::fmt::Display for AnyHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl std::fmt::Debug for AnyHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+#[derive(Default, Clone, Debug, Serialize)]
 #[cfg_attr(feature = "serde-derive", derive(serde::Serialize, serde::Deserialize))]
 pub struct CreationTransactionData {
     pub sender: Address,
@@ -203,6 +336,23 @@ pub struct CreationTransactionData {
     pub timeout: u64,
 }
 
+impl Deserialize for CreationTransactionData {
+    fn deserialize<R: ReadBytesExt>(reader: &mut R) -> Result<Self, SerializingError> {
+        let sender = Deserialize::deserialize(reader)?;
+        let recipient = Deserialize::deserialize(reader)?;
+        let hash_algorithm = Deserialize::deserialize(reader)?;
+        let hash_root = AnyHash::deserialize_for(hash_algorithm, reader)?;
+        Ok(CreationTransactionData {
+            sender,
+            recipient,
+            hash_algorithm,
+            hash_root,
+            hash_count: Deserialize::deserialize(reader)?,
+            timeout: Deserialize::deserialize(reader)?,
+        })
+    }
+}
+
 impl CreationTransactionData {
     pub fn parse(transaction: &Transaction) -> Result<Self, TransactionError> {
         Ok(Deserialize::deserialize(&mut &transaction.data[..])?)
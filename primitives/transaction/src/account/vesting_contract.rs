@@ -49,7 +49,12 @@ impl AccountTransactionVerification for VestingContractVerifier {
             return Err(TransactionError::InvalidForRecipient);
         }
 
-        let allowed_sizes = [Address::SIZE + 8, Address::SIZE + 24, Address::SIZE + 32];
+        let allowed_sizes = [
+            Address::SIZE + 8,
+            Address::SIZE + 24,
+            Address::SIZE + 32,
+            Address::SIZE + 40,
+        ];
         if !allowed_sizes.contains(&transaction.data.len()) {
             warn!(
                 "Invalid data length for this transaction:\n{:?}",
@@ -77,6 +82,29 @@ impl AccountTransactionVerification for VestingContractVerifier {
     }
 }
 
+/// The schedule by which the funds of a vesting contract are released on top of the steps.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Serialize, Deserialize)]
+#[repr(u8)]
+#[cfg_attr(feature = "serde-derive", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde-derive",
+    serde(rename_all = "kebab-case", tag = "type")
+)]
+pub enum VestingSchedule {
+    /// `step_amount` is released with every `time_step` after `start_time`.
+    Linear,
+    /// Nothing is released before `cliff_time`. At that time, all steps that have passed are
+    /// released at once and the remaining steps are released as in the linear schedule.
+    #[cfg_attr(feature = "serde-derive", serde(rename_all = "camelCase"))]
+    Cliff { cliff_time: u64 },
+}
+
+impl Default for VestingSchedule {
+    fn default() -> Self {
+        VestingSchedule::Linear
+    }
+}
+
 #[derive(Default, Clone, Debug)]
 #[cfg_attr(feature = "serde-derive", derive(serde::Serialize, serde::Deserialize))]
 pub struct CreationTransactionData {
@@ -85,6 +113,7 @@ pub struct CreationTransactionData {
     pub time_step: u64,
     pub step_amount: Coin,
     pub total_amount: Coin,
+    pub schedule: VestingSchedule,
 }
 
 impl CreationTransactionData {
@@ -101,6 +130,7 @@ impl CreationTransactionData {
                 time_step,
                 step_amount: transaction.value,
                 total_amount: transaction.value,
+                schedule: VestingSchedule::Linear,
             })
         } else if transaction.data.len() == Address::SIZE + 24 {
             let start_time = Deserialize::deserialize(reader)?;
@@ -112,6 +142,7 @@ impl CreationTransactionData {
                 time_step,
                 step_amount,
                 total_amount: transaction.value,
+                schedule: VestingSchedule::Linear,
             })
         } else if transaction.data.len() == Address::SIZE + 32 {
             // Create a vesting account with some instantly vested funds or additional funds considered.
@@ -125,6 +156,22 @@ impl CreationTransactionData {
                 time_step,
                 step_amount,
                 total_amount,
+                schedule: VestingSchedule::Linear,
+            })
+        } else if transaction.data.len() == Address::SIZE + 40 {
+            // Create a vesting account that doesn't release any funds before the cliff.
+            let start_time = Deserialize::deserialize(reader)?;
+            let time_step = Deserialize::deserialize(reader)?;
+            let step_amount = Deserialize::deserialize(reader)?;
+            let total_amount = Deserialize::deserialize(reader)?;
+            let cliff_time = Deserialize::deserialize(reader)?;
+            Ok(CreationTransactionData {
+                owner,
+                start_time,
+                time_step,
+                step_amount,
+                total_amount,
+                schedule: VestingSchedule::Cliff { cliff_time },
             })
         } else {
             Err(TransactionError::InvalidData)
@@ -137,7 +184,13 @@ impl Serialize for CreationTransactionData {
         let mut size = 0;
         size += self.owner.serialize(writer)?;
 
-        if self.step_amount == self.total_amount {
+        if let VestingSchedule::Cliff { cliff_time } = self.schedule {
+            size += self.start_time.serialize(writer)?;
+            size += self.time_step.serialize(writer)?;
+            size += self.step_amount.serialize(writer)?;
+            size += self.total_amount.serialize(writer)?;
+            size += cliff_time.serialize(writer)?;
+        } else if self.step_amount == self.total_amount {
             if self.start_time == 0 {
                 size += self.time_step.serialize(writer)?;
             } else {
@@ -156,7 +209,9 @@ impl Serialize for CreationTransactionData {
     }
 
     fn serialized_size(&self) -> usize {
-        if self.step_amount == self.total_amount {
+        if let VestingSchedule::Cliff { .. } = self.schedule {
+            Address::SIZE + 40
+        } else if self.step_amount == self.total_amount {
             if self.start_time == 0 {
                 Address::SIZE + 8
             } else {
@@ -11,4 +11,12 @@ pub enum MerkleRadixTrieError {
     BranchesHaveNoValue,
     #[error("Tried to query a child that does not exist.")]
     ChildDoesNotExist,
+    #[error("The proof of the chunk is invalid.")]
+    InvalidChunkProof,
+    #[error("The chunk is missing leaf nodes in its key range.")]
+    IncompleteChunk,
+    #[error("The chunk doesn't start where the trie ends.")]
+    ChunkNotContiguous,
+    #[error("The root hash of the trie doesn't match the expected root hash.")]
+    RootHashMismatch,
 }
@@ -40,6 +40,12 @@ impl KeyNibbles {
         self.length == 0
     }
 
+    /// Returns the bytes of the key. If the key has an odd number of nibbles, only the high nibble
+    /// of the last byte is part of the key.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.bytes_length as usize]
+    }
+
     /// Returns the nibble at the given index as an usize. The usize represents a hexadecimal
     /// character.
     pub fn get(&self, index: usize) -> Option<usize> {
@@ -1,5 +1,6 @@
 pub mod error;
 pub mod key_nibbles;
 pub mod trie;
+pub mod trie_chunk;
 pub mod trie_node;
 pub mod trie_proof;
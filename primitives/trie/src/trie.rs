@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::marker::PhantomData;
 
 use log::error;
@@ -6,7 +7,9 @@ use beserial::{Deserialize, Serialize};
 use nimiq_database::{Database, Environment, Transaction, WriteTransaction};
 use nimiq_hash::{Blake2bHash, Hash};
 
+use crate::error::MerkleRadixTrieError;
 use crate::key_nibbles::KeyNibbles;
+use crate::trie_chunk::TrieChunk;
 use crate::trie_node::TrieNode;
 use crate::trie_proof::TrieProof;
 
@@ -48,7 +51,9 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
         tree
     }
 
-    /// Returns the root hash of the Merkle Radix Trie.
+    /// Returns the root hash of the Merkle Radix Trie. Every node stores the hashes of its
+    /// children, so this only hashes the root node. The result is only up-to-date if
+    /// `update_root` was called after the last modification.
     pub fn root_hash(&self, txn: &Transaction) -> Blake2bHash {
         self.get_root(txn).unwrap().hash()
     }
@@ -401,6 +406,45 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
         Some(TrieProof::new(proof_nodes))
     }
 
+    /// Produces a Merkle proof of the inclusion or exclusion of the given keys in the Merkle Radix
+    /// Trie. In contrast to `get_proof`, the keys don't need to be part of the trie. For each key
+    /// the proof contains the path from the root down to the node with that key or, if the key
+    /// doesn't exist, down to the deepest node that proves its absence. That is either a branch
+    /// node without a matching child, or a branch node whose matching child isn't a prefix of the
+    /// key.
+    /// Just like in `get_proof`, the nodes are returned in post-order.
+    pub fn get_membership_proof(&self, txn: &Transaction, keys: Vec<&KeyNibbles>) -> TrieProof<A> {
+        let root = self
+            .get_root(txn)
+            .expect("Merkle Radix Trie must have a root node!");
+
+        // Collect the nodes along the paths of all keys. A BTreeMap removes the duplicates and
+        // keeps the nodes sorted by key.
+        let mut nodes = BTreeMap::new();
+        nodes.insert(root.key().clone(), root.clone());
+
+        for key in keys {
+            let mut cur_node = root.clone();
+
+            // Go down the trie until we find a node with our key or we can't go any further.
+            while cur_node.key() != key && cur_node.is_branch() {
+                match cur_node.get_child_key(key) {
+                    Ok(child_key) if child_key.is_prefix_of(key) => {
+                        cur_node = txn.get(&self.db, &child_key).unwrap();
+                        nodes.insert(child_key, cur_node.clone());
+                    }
+                    // There's no child on the path to our key, so the current node proves that
+                    // the key doesn't exist.
+                    _ => break,
+                }
+            }
+        }
+
+        // A key always sorts before all the keys in its subtree. So, in descending order, each
+        // subtree is contiguous and directly followed by its root, which is a valid post-order.
+        TrieProof::new(nodes.into_values().rev().collect())
+    }
+
     /// Creates a proof for the chunk of the Merkle Radix Trie that starts at the key `start` (which
     /// might or not be a part of the trie, if it is then it will be part of the chunk) and contains
     /// at most `size` leaf nodes.
@@ -417,6 +461,104 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
         self.get_proof(txn, chunk_keys)
     }
 
+    /// Returns the chunk of the Merkle Radix Trie that contains at most `size` leaf nodes, starting
+    /// at the key `start`, together with a proof for it. The chunks are deterministic, i.e. two
+    /// tries with the same root hash produce identical chunks for the same start key.
+    pub fn get_chunk_with_proof(
+        &self,
+        txn: &Transaction,
+        start: &KeyNibbles,
+        size: usize,
+    ) -> TrieChunk<A> {
+        let chunk = self.get_trie_chunk(txn, start, size);
+
+        let mut keys: Vec<&KeyNibbles> = chunk.iter().map(|node| node.key()).collect();
+        keys.push(start);
+
+        let proof = self.get_membership_proof(txn, keys);
+
+        TrieChunk::new(start.clone(), chunk, proof)
+    }
+
+    /// Verifies the given chunk against `expected_root` and adds its leaf nodes to the trie. The
+    /// chunk must start at the last leaf node of the trie (or at the root key, if the trie is
+    /// empty), so that a trie can be rebuilt from consecutive chunks.
+    pub fn put_chunk(
+        &self,
+        txn: &mut WriteTransaction,
+        chunk: TrieChunk<A>,
+        expected_root: &Blake2bHash,
+    ) -> Result<(), MerkleRadixTrieError> {
+        let last_key = self.last_leaf_key(txn).unwrap_or_else(KeyNibbles::root);
+        if chunk.start_key != last_key {
+            error!(
+                "The chunk starts at key {}, but the last key of the trie is {}.",
+                chunk.start_key, last_key
+            );
+            return Err(MerkleRadixTrieError::ChunkNotContiguous);
+        }
+
+        chunk.verify(expected_root)?;
+
+        for node in chunk.nodes {
+            let value = node.value()?;
+            self.put(txn, node.key(), value);
+        }
+
+        self.update_root(txn);
+
+        Ok(())
+    }
+
+    /// Returns the key of the leaf node with the largest key in the trie, if there is one.
+    pub fn last_leaf_key(&self, txn: &Transaction) -> Option<KeyNibbles> {
+        let mut node = self.get_root(txn)?;
+
+        loop {
+            match node {
+                TrieNode::BranchNode { key, children } => {
+                    // Only the root node can have no children.
+                    let child = children.iter().flatten().last()?;
+                    node = txn.get(&self.db, &(&key + &child.suffix))
+                        .expect("Failed to find the child of a Merkle Radix Trie node. The database must be corrupt!");
+                }
+                TrieNode::LeafNode { key, .. } => return Some(key),
+            }
+        }
+    }
+
+    /// Returns the leaves that differ between the state of the trie seen by the transaction
+    /// `before` and the state seen by the transaction `after`, ordered by key. Each entry contains
+    /// the value before and after the change, where None means that there was no leaf at the key.
+    /// Subtrees that have the same hash in both states are skipped, so the hashes of both states
+    /// must be up-to-date.
+    pub fn diff_leaves(
+        &self,
+        before: &Transaction,
+        after: &Transaction,
+    ) -> Vec<(KeyNibbles, Option<A>, Option<A>)>
+    where
+        A: PartialEq,
+    {
+        let mut changes = BTreeMap::new();
+
+        self.diff_subtrees(
+            before,
+            after,
+            self.get_root(before),
+            self.get_root(after),
+            &mut changes,
+        );
+
+        changes
+            .into_iter()
+            .filter(|(_, (value_before, value_after))| value_before != value_after)
+            .map(|(key, (value_before, value_after))| (key, value_before, value_after))
+            .collect()
+    }
+
+    /// Re-hashes the subtrees that were modified since the last call. Unmodified subtrees keep
+    /// their stored hashes and are not visited.
     pub fn update_root(&self, txn: &mut WriteTransaction) {
         self.update_hashes(txn, &KeyNibbles::root());
     }
@@ -454,16 +596,112 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
 
         // Compute sub hashes if necessary.
         let default_hash = Blake2bHash::default();
+        let mut is_dirty = false;
         for mut child in node.iter_children_mut() {
             if child.hash == default_hash {
                 // TODO This could be parallelized.
                 child.hash = self.update_hashes(txn, &(key + &child.suffix));
+                is_dirty = true;
             }
         }
-        txn.put_reserve(&self.db, key, &node);
+
+        // Only write the node back if one of its children changed.
+        if is_dirty {
+            txn.put_reserve(&self.db, key, &node);
+        }
         node.hash()
     }
 
+    /// Collects the leaves that might differ between two subtrees at the same position in both
+    /// states. Children with the same suffix are compared recursively and skipped if their hashes
+    /// match. If the structure of the subtrees differs, all of their leaves are collected.
+    fn diff_subtrees(
+        &self,
+        before: &Transaction,
+        after: &Transaction,
+        node_before: Option<TrieNode<A>>,
+        node_after: Option<TrieNode<A>>,
+        changes: &mut BTreeMap<KeyNibbles, (Option<A>, Option<A>)>,
+    ) {
+        match (node_before, node_after) {
+            (
+                Some(TrieNode::BranchNode {
+                    key,
+                    children: children_before,
+                }),
+                Some(TrieNode::BranchNode {
+                    key: key_after,
+                    children: children_after,
+                }),
+            ) if key == key_after => {
+                for (child_before, child_after) in children_before.iter().zip(children_after.iter())
+                {
+                    match (child_before, child_after) {
+                        (Some(child_before), Some(child_after))
+                            if child_before.suffix == child_after.suffix =>
+                        {
+                            if child_before.hash != child_after.hash {
+                                let child_key = &key + &child_before.suffix;
+                                self.diff_subtrees(
+                                    before,
+                                    after,
+                                    before.get(&self.db, &child_key),
+                                    after.get(&self.db, &child_key),
+                                    changes,
+                                );
+                            }
+                        }
+                        _ => {
+                            let child_before = child_before
+                                .as_ref()
+                                .and_then(|child| before.get(&self.db, &(&key + &child.suffix)));
+                            let child_after = child_after
+                                .as_ref()
+                                .and_then(|child| after.get(&self.db, &(&key + &child.suffix)));
+                            self.diff_subtrees(before, after, child_before, child_after, changes);
+                        }
+                    }
+                }
+            }
+            (node_before, node_after) => {
+                if let Some(node) = node_before {
+                    for (key, value) in self.get_leaves(before, node) {
+                        changes.entry(key).or_insert((None, None)).0 = Some(value);
+                    }
+                }
+                if let Some(node) = node_after {
+                    for (key, value) in self.get_leaves(after, node) {
+                        changes.entry(key).or_insert((None, None)).1 = Some(value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns all leaves in the subtree of the given node, together with their keys.
+    fn get_leaves(&self, txn: &Transaction, node: TrieNode<A>) -> Vec<(KeyNibbles, A)> {
+        let mut leaves = Vec::new();
+        let mut stack = vec![node];
+
+        while let Some(item) = stack.pop() {
+            match item {
+                TrieNode::BranchNode { children, key } => {
+                    for child in children.iter().flatten().rev() {
+                        let combined = &key + &child.suffix;
+
+                        stack.push(txn.get(&self.db, &combined)
+                                .expect("Failed to find the child of a Merkle Radix Trie node. The database must be corrupt!"));
+                    }
+                }
+                TrieNode::LeafNode { key, value } => {
+                    leaves.push((key, value));
+                }
+            }
+        }
+
+        leaves
+    }
+
     /// Returns the nodes of the chunk of the Merkle Radix Trie that starts at the key `start` and
     /// has size `size`. This is used by the `get_chunk` and `get_chunk_proof` functions.
     fn get_trie_chunk(
@@ -562,6 +800,53 @@ mod tests {
         assert_eq!(trie.get(&txn, &key_3), None);
     }
 
+    #[test]
+    fn diff_works() {
+        let key_1: KeyNibbles = "413f22b3e".parse().unwrap();
+        let key_2: KeyNibbles = "413b39931".parse().unwrap();
+        let key_3: KeyNibbles = "413b397fa".parse().unwrap();
+        let key_4: KeyNibbles = "cfb986f5a".parse().unwrap();
+
+        let env = nimiq_database::volatile::VolatileEnvironment::new(10).unwrap();
+        let trie = MerkleRadixTrie::new(env.clone(), "database");
+
+        let mut txn = WriteTransaction::new(&env);
+        trie.put(&mut txn, &key_1, 1);
+        trie.put(&mut txn, &key_2, 2);
+        trie.put(&mut txn, &key_4, 4);
+        trie.update_root(&mut txn);
+        txn.commit();
+
+        let before = nimiq_database::ReadTransaction::new(&env);
+        assert_eq!(trie.diff_leaves(&before, &before), vec![]);
+
+        // Adding key_3 changes the structure of the trie around key_2.
+        let mut txn = WriteTransaction::new(&env);
+        trie.put(&mut txn, &key_1, 10);
+        trie.put(&mut txn, &key_3, 3);
+        trie.remove(&mut txn, &key_4);
+        trie.update_root(&mut txn);
+        txn.commit();
+
+        let after = nimiq_database::ReadTransaction::new(&env);
+        assert_eq!(
+            trie.diff_leaves(&before, &after),
+            vec![
+                (key_3.clone(), None, Some(3)),
+                (key_1.clone(), Some(1), Some(10)),
+                (key_4.clone(), Some(4), None),
+            ]
+        );
+        assert_eq!(
+            trie.diff_leaves(&after, &before),
+            vec![
+                (key_3, Some(3), None),
+                (key_1, Some(10), Some(1)),
+                (key_4, None, Some(4)),
+            ]
+        );
+    }
+
     #[test]
     fn get_proof_works() {
         let key_1 = "cfb986f5a".parse().unwrap();
@@ -0,0 +1,92 @@
+use std::collections::BTreeSet;
+
+use beserial::{Deserialize, Serialize};
+use nimiq_hash::{Blake2bHash, Hash};
+
+use crate::error::MerkleRadixTrieError;
+use crate::key_nibbles::KeyNibbles;
+use crate::trie_node::TrieNode;
+use crate::trie_proof::TrieProof;
+
+/// A chunk of the Merkle Radix Trie. It contains the leaf nodes with keys greater or equal to
+/// `start_key`, in ascending order, together with a proof for those leaves and for the start key.
+/// The proof allows to verify that the chunk contains all the leaves of the trie in the key range
+/// it covers, so that a trie can be rebuilt from consecutive chunks.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrieChunk<A: Serialize + Deserialize + Clone> {
+    pub start_key: KeyNibbles,
+    #[beserial(len_type(u16))]
+    pub nodes: Vec<TrieNode<A>>,
+    pub proof: TrieProof<A>,
+}
+
+impl<A: Serialize + Deserialize + Clone> TrieChunk<A> {
+    pub fn new(start_key: KeyNibbles, nodes: Vec<TrieNode<A>>, proof: TrieProof<A>) -> Self {
+        TrieChunk {
+            start_key,
+            nodes,
+            proof,
+        }
+    }
+
+    /// Returns the key of the last leaf node in the chunk, if there is one.
+    pub fn end_key(&self) -> Option<&KeyNibbles> {
+        self.nodes.last().map(|node| node.key())
+    }
+
+    /// Verifies the chunk against the given root hash. Besides checking the proof, this makes sure
+    /// that the chunk contains every leaf of the trie from the start key up to its last leaf. If
+    /// the chunk is empty, there must be no leaves after the start key at all.
+    pub fn verify(&self, root_hash: &Blake2bHash) -> Result<(), MerkleRadixTrieError> {
+        if !self.proof.verify(root_hash) {
+            return Err(MerkleRadixTrieError::InvalidChunkProof);
+        }
+
+        // The proven leaves within the range of the chunk must be exactly the leaves of the chunk.
+        // The proof nodes are in post-order, so the leaves are in descending order.
+        let mut proven_leaves = self
+            .proof
+            .nodes
+            .iter()
+            .filter(|node| node.is_leaf() && node.key() >= &self.start_key)
+            .rev();
+
+        for node in &self.nodes {
+            match proven_leaves.next() {
+                Some(leaf)
+                    if leaf.key() == node.key()
+                        && leaf.hash::<Blake2bHash>() == node.hash::<Blake2bHash>() => {}
+                _ => return Err(MerkleRadixTrieError::InvalidChunkProof),
+            }
+        }
+
+        if proven_leaves.next().is_some() {
+            return Err(MerkleRadixTrieError::InvalidChunkProof);
+        }
+
+        // Every child that is left out of the proof must lie entirely outside of the range of the
+        // chunk. Otherwise the chunk could be missing leaves.
+        let proven_keys: BTreeSet<_> = self.proof.nodes.iter().map(|node| node.key()).collect();
+        let end_key = self.end_key();
+
+        for node in self.proof.nodes.iter().filter(|node| node.is_branch()) {
+            for child in node.iter_children() {
+                let child_key = node.key() + &child.suffix;
+
+                if proven_keys.contains(&child_key) {
+                    continue;
+                }
+
+                let before_start =
+                    child_key < self.start_key && !child_key.is_prefix_of(&self.start_key);
+                let after_end = end_key.map_or(false, |end_key| &child_key > end_key);
+
+                if !before_start && !after_end {
+                    return Err(MerkleRadixTrieError::IncompleteChunk);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
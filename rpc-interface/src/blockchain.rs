@@ -6,7 +6,7 @@ use nimiq_keys::Address;
 
 use crate::types::{
     Account, Block, BlockLog, BlockchainState, ExecutedTransaction, Inherent, LogType, ParkedSet,
-    RPCData, RPCResult, SlashedSlots, Slot, Staker, Validator,
+    RPCData, RPCResult, Slash, SlashedSlots, Slot, Staker, Validator,
 };
 
 #[nimiq_jsonrpc_derive::proxy(name = "BlockchainProxy", rename_all = "camelCase")]
@@ -107,6 +107,23 @@ pub trait BlockchainInterface {
         include_stakers: Option<bool>,
     ) -> RPCResult<Validator, BlockchainState, Self::Error>;
 
+    async fn get_validator_slashes(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<Vec<Slash>, BlockchainState, Self::Error>;
+
+    async fn get_validator_stakers(
+        &mut self,
+        address: Address,
+        start: Option<Address>,
+        max: Option<u16>,
+    ) -> RPCResult<Vec<Staker>, BlockchainState, Self::Error>;
+
+    async fn get_validator_delegators(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<Vec<Staker>, BlockchainState, Self::Error>;
+
     async fn get_staker_by_address(
         &mut self,
         address: Address,
@@ -1,4 +1,4 @@
-use crate::types::RPCResult;
+use crate::types::{PeerInfo, PeerListEntry, RPCResult};
 use async_trait::async_trait;
 
 #[nimiq_jsonrpc_derive::proxy(name = "NetworkProxy", rename_all = "camelCase")]
@@ -10,5 +10,19 @@ pub trait NetworkInterface {
 
     async fn get_peer_count(&mut self) -> RPCResult<usize, (), Self::Error>;
 
-    async fn get_peer_list(&mut self) -> RPCResult<Vec<String>, (), Self::Error>;
+    async fn get_peer_list(&mut self) -> RPCResult<Vec<PeerListEntry>, (), Self::Error>;
+
+    async fn get_peer_info(&mut self, peer_id: String) -> RPCResult<PeerInfo, (), Self::Error>;
+
+    async fn get_peer_list_detailed(&mut self) -> RPCResult<Vec<PeerInfo>, (), Self::Error>;
+
+    async fn connect_peer(&mut self, address: String) -> RPCResult<(), (), Self::Error>;
+
+    async fn disconnect_peer(&mut self, peer_id: String) -> RPCResult<(), (), Self::Error>;
+
+    async fn ban_peer(
+        &mut self,
+        peer_id: String,
+        duration_secs: Option<u64>,
+    ) -> RPCResult<(), (), Self::Error>;
 }
@@ -11,9 +11,10 @@ use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DeserializeFromStr, DisplayFromStr, SerializeDisplay};
 
 use beserial::Serialize as BeSerialize;
+pub use nimiq_account::LogType;
 use nimiq_account::{BlockLog as BBlockLog, Log, TransactionLog};
 use nimiq_block::{MicroJustification, MultiSignature};
-use nimiq_blockchain::{AbstractBlockchain, Blockchain};
+use nimiq_blockchain::{AbstractBlockchain, Blockchain, SlashReason as BSlashReason, SlashRecord};
 use nimiq_bls::CompressedPublicKey;
 use nimiq_collections::BitSet;
 use nimiq_hash::{Blake2bHash, Hash};
@@ -135,6 +136,7 @@ impl FromStr for ValidityStartHeight {
 pub enum HashAlgorithm {
     Blake2b = 1,
     Sha256 = 3,
+    Sha512 = 4,
 }
 
 #[allow(clippy::from_over_into)]
@@ -143,6 +145,7 @@ impl Into<HTLCContractHashAlgorithm> for HashAlgorithm {
         match self {
             HashAlgorithm::Blake2b => HTLCContractHashAlgorithm::Blake2b,
             HashAlgorithm::Sha256 => HTLCContractHashAlgorithm::Sha256,
+            HashAlgorithm::Sha512 => HTLCContractHashAlgorithm::Sha512,
         }
     }
 }
@@ -435,6 +438,44 @@ pub struct ParkedSet {
     pub validators: Vec<Address>,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SlashReason {
+    ForkProof,
+    SkipBlock,
+}
+
+impl From<BSlashReason> for SlashReason {
+    fn from(reason: BSlashReason) -> Self {
+        match reason {
+            BSlashReason::ForkProof => SlashReason::ForkProof,
+            BSlashReason::SkipBlock => SlashReason::SkipBlock,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Slash {
+    pub block_number: u32,
+    pub event_block: u32,
+    pub slot: u16,
+    pub validator: Address,
+    pub reason: SlashReason,
+}
+
+impl From<SlashRecord> for Slash {
+    fn from(slash: SlashRecord) -> Self {
+        Self {
+            block_number: slash.block_number,
+            event_block: slash.event_block,
+            slot: slash.slot,
+            validator: slash.validator_address,
+            reason: slash.reason.into(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ForkProof {
@@ -739,12 +780,14 @@ pub struct Validator {
     pub inactivity_flag: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stakers: Option<Vec<Staker>>,
+    pub projected_epoch_reward: Coin,
 }
 
 impl Validator {
     pub fn from_validator(
         validator: &nimiq_account::Validator,
         stakers: Option<Vec<Staker>>,
+        projected_epoch_reward: Coin,
     ) -> Self {
         Validator {
             address: validator.address.clone(),
@@ -756,6 +799,7 @@ impl Validator {
             num_stakers: validator.num_stakers,
             inactivity_flag: validator.inactivity_flag,
             stakers,
+            projected_epoch_reward,
         }
     }
 }
@@ -850,33 +894,6 @@ impl BlockchainState {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, ValueEnum)]
-#[serde(rename_all = "kebab-case")]
-pub enum LogType {
-    PayFee,
-    Transfer,
-    HtlcCreate,
-    HtlcTimeoutResolve,
-    HtlcRegularTransfer,
-    HtlcEarlyResolve,
-    VestingCreate,
-    CreateValidator,
-    UpdateValidator,
-    InactivateValidator,
-    ReactivateValidator,
-    UnparkValidator,
-    CreateStaker,
-    Stake,
-    UpdateStaker,
-    DeleteValidator,
-    Unstake,
-    PayoutReward,
-    Park,
-    Slash,
-    RevertContract,
-    FailedTransaction,
-}
-
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case", tag = "type")]
 pub enum BlockLog {
@@ -898,71 +915,6 @@ pub enum BlockLog {
     },
 }
 
-impl LogType {
-    pub fn with_log(log: &Log) -> Self {
-        match log {
-            Log::PayFee { .. } => Self::PayFee,
-            Log::Transfer { .. } => Self::Transfer,
-            Log::HTLCCreate { .. } => Self::HtlcCreate,
-            Log::HTLCTimeoutResolve { .. } => Self::HtlcTimeoutResolve,
-            Log::HTLCRegularTransfer { .. } => Self::HtlcRegularTransfer,
-            Log::HTLCEarlyResolve { .. } => Self::HtlcEarlyResolve,
-            Log::VestingCreate { .. } => Self::VestingCreate,
-            Log::CreateValidator { .. } => Self::CreateValidator,
-            Log::UpdateValidator { .. } => Self::UpdateValidator,
-            Log::InactivateValidator { .. } => Self::InactivateValidator,
-            Log::ReactivateValidator { .. } => Self::ReactivateValidator,
-            Log::UnparkValidator { .. } => Self::UnparkValidator,
-            Log::CreateStaker { .. } => Self::CreateStaker,
-            Log::Stake { .. } => Self::Stake,
-            Log::UpdateStaker { .. } => Self::UpdateStaker,
-            Log::DeleteValidator { .. } => Self::DeleteValidator,
-            Log::Unstake { .. } => Self::Unstake,
-            Log::PayoutReward { .. } => Self::PayoutReward,
-            Log::Park { .. } => Self::Park,
-            Log::Slash { .. } => Self::Slash,
-            Log::RevertContract { .. } => Self::RevertContract,
-            Log::FailedTransaction { .. } => Self::FailedTransaction,
-        }
-    }
-}
-
-/// Checks if a given log is related to any of the addresses provided and if it is of any of the log types provided.
-/// If no addresses and log_types are provided it will return false.
-/// If the vec of addresses is empty, compares only to the log_types (meaning it will not care about the addresses
-/// the log is related to), and vice_versa.
-pub fn is_of_log_type_and_related_to_addresses(
-    log: &Log,
-    addresses: &Vec<Address>,
-    log_types: &Vec<LogType>,
-) -> bool {
-    // If addresses are empty, it tries to find a matchinf log_type and early return. Otherwise, it will finish and return false.
-    if addresses.is_empty() {
-        for log_type in log_types {
-            if log_type.eq(&LogType::with_log(log)) {
-                return true;
-            }
-        }
-    } else {
-        // Iterates over the addresses (and the log_types if those exist).
-        for address in addresses {
-            if log.is_related_to_address(address) {
-                if log_types.is_empty() {
-                    return true;
-                }
-                // If there are log_types to compare with, it tries to find a match and early return.
-                for log_type in log_types {
-                    if log_type.eq(&LogType::with_log(log)) {
-                        return true;
-                    }
-                }
-            }
-        }
-    }
-    // It iterated over all the existing vecs and found no match.
-    false
-}
-
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MempoolInfo {
@@ -1141,3 +1093,34 @@ impl MempoolInfo {
         info
     }
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionDirection {
+    Inbound,
+    Outbound,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerListEntry {
+    pub peer_id: String,
+    /// The last measured round-trip time to the peer, in milliseconds.
+    pub rtt: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerInfo {
+    pub peer_id: String,
+    /// The addresses we know for the peer.
+    pub addresses: Vec<String>,
+    /// Whether the peer dialed us (inbound) or we dialed the peer (outbound).
+    pub direction: ConnectionDirection,
+    /// The bitfield of services the peer provides, if known.
+    pub services: Option<u32>,
+    /// The time since the connection to the peer was established, in seconds.
+    pub connection_age: u64,
+    /// The last measured round-trip time to the peer, in milliseconds.
+    pub rtt: Option<u64>,
+}
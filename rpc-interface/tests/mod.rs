@@ -1,5 +1,5 @@
 use nimiq_hash::Blake2bHash;
-use nimiq_rpc_interface::types::Block;
+use nimiq_rpc_interface::types::{Block, ConnectionDirection, PeerInfo};
 
 #[test]
 fn it_can_deserialize_result_blocks() {
@@ -7,3 +7,38 @@ fn it_can_deserialize_result_blocks() {
     let value = serde_json::from_str(data).unwrap();
     let _result: Result<Block, Blake2bHash> = serde_json::from_value(value).unwrap();
 }
+
+#[test]
+fn it_can_round_trip_peer_info() {
+    let peer_info = PeerInfo {
+        peer_id: "12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN".to_string(),
+        addresses: vec![
+            "/ip4/127.0.0.1/tcp/8443/ws".to_string(),
+            "/dns4/seed1.nimiq.dev/tcp/8443/wss".to_string(),
+        ],
+        direction: ConnectionDirection::Outbound,
+        services: Some(0x3),
+        connection_age: 42,
+        rtt: Some(120),
+    };
+
+    let value = serde_json::to_value(&peer_info).unwrap();
+    assert_eq!(value["peerId"], peer_info.peer_id.as_str());
+    assert_eq!(value["direction"], "outbound");
+    assert_eq!(value["connectionAge"], 42);
+    assert_eq!(value["rtt"], 120);
+
+    let deserialized: PeerInfo = serde_json::from_value(value).unwrap();
+    assert_eq!(deserialized, peer_info);
+
+    // Peers without a known peer contact don't have services.
+    let peer_info = PeerInfo {
+        direction: ConnectionDirection::Inbound,
+        services: None,
+        rtt: None,
+        ..peer_info
+    };
+    let data = serde_json::to_string(&peer_info).unwrap();
+    let deserialized: PeerInfo = serde_json::from_str(&data).unwrap();
+    assert_eq!(deserialized, peer_info);
+}
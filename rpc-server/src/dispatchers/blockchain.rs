@@ -4,14 +4,13 @@ use async_trait::async_trait;
 use futures::{future, stream::BoxStream, StreamExt};
 use parking_lot::RwLock;
 
-use nimiq_account::{BlockLog as BBlockLog, StakingContract, TransactionLog};
+use nimiq_account::{LogFilter, StakingContract};
 use nimiq_blockchain::{AbstractBlockchain, Blockchain, BlockchainEvent};
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
-use nimiq_primitives::policy;
+use nimiq_primitives::{coin::Coin, policy};
 use nimiq_rpc_interface::types::{
-    is_of_log_type_and_related_to_addresses, BlockLog, BlockNumberOrHash, BlockchainState,
-    ParkedSet, RPCData, RPCResult, Validator,
+    BlockLog, BlockNumberOrHash, BlockchainState, ParkedSet, RPCData, RPCResult, Slash, Validator,
 };
 use nimiq_rpc_interface::{
     blockchain::BlockchainInterface,
@@ -53,6 +52,7 @@ fn get_block_by_hash(
 
 /// Tries to fetch a validator information given its address. It has an option to include a collection
 /// containing the addresses and stakes of all the stakers that are delegating to the validator.
+/// If the validator has slots in the current epoch, it also includes its projected reward for them.
 /// This function requeires the read lock acquisition prior to its execution
 fn get_validator_by_address(
     blockchain: &Blockchain,
@@ -85,8 +85,12 @@ fn get_validator_by_address(
         stakers = Some(stakers_list);
     }
 
+    let projected_epoch_reward = Coin::from_u64_unchecked(
+        blockchain.compute_epoch_reward(address, policy::epoch_at(blockchain.block_number() + 1)),
+    );
+
     Ok(RPCData::with_blockchain(
-        Validator::from_validator(&validator.unwrap(), stakers),
+        Validator::from_validator(&validator.unwrap(), stakers, projected_epoch_reward),
         blockchain,
     ))
 }
@@ -582,6 +586,78 @@ impl BlockchainInterface for BlockchainDispatcher {
         get_validator_by_address(blockchain.deref(), &address, include_stakers)
     }
 
+    /// Returns the slashes of the validator with the given address that are still stored,
+    /// ordered by the block number of the block that applied them.
+    async fn get_validator_slashes(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<Vec<Slash>, BlockchainState, Self::Error> {
+        let blockchain = self.blockchain.read();
+
+        let slashes = blockchain
+            .get_validator_slashes(&address)
+            .into_iter()
+            .map(Slash::from)
+            .collect();
+
+        Ok(RPCData::with_blockchain(slashes, &blockchain))
+    }
+
+    /// Returns a page of the stakers delegating to the validator with the given address, ordered
+    /// by staker address. The page starts after the staker address `start`, if given, and contains
+    /// at most `max` stakers, which defaults to 500.
+    async fn get_validator_stakers(
+        &mut self,
+        address: Address,
+        start: Option<Address>,
+        max: Option<u16>,
+    ) -> RPCResult<Vec<Staker>, BlockchainState, Self::Error> {
+        let blockchain = self.blockchain.read();
+
+        let accounts_tree = &blockchain.state().accounts.tree;
+        let db_txn = blockchain.read_transaction();
+
+        if StakingContract::get_validator(accounts_tree, &db_txn, &address).is_none() {
+            return Err(Error::ValidatorNotFound(address));
+        }
+
+        let stakers = StakingContract::get_stakers_for_validator(
+            accounts_tree,
+            &db_txn,
+            &address,
+            start.as_ref(),
+            max.unwrap_or(500) as usize,
+        );
+
+        Ok(RPCData::with_blockchain(
+            stakers.iter().map(Staker::from_staker).collect(),
+            &blockchain,
+        ))
+    }
+
+    /// Returns all the stakers delegating to the validator with the given address, ordered by
+    /// staker address.
+    async fn get_validator_delegators(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<Vec<Staker>, BlockchainState, Self::Error> {
+        let blockchain = self.blockchain.read();
+
+        let accounts_tree = &blockchain.state().accounts.tree;
+        let db_txn = blockchain.read_transaction();
+
+        if StakingContract::get_validator(accounts_tree, &db_txn, &address).is_none() {
+            return Err(Error::ValidatorNotFound(address));
+        }
+
+        let delegators = StakingContract::get_delegators(accounts_tree, &db_txn, &address);
+
+        Ok(RPCData::with_blockchain(
+            delegators.iter().map(Staker::from_staker).collect(),
+            &blockchain,
+        ))
+    }
+
     /// Tries to fetch a staker information given its address.
     async fn get_staker_by_address(
         &mut self,
@@ -634,14 +710,20 @@ impl BlockchainInterface for BlockchainDispatcher {
     ) -> Result<BoxStream<'static, RPCData<Blake2bHash, ()>>, Self::Error> {
         let stream = self.blockchain.write().notifier.as_stream();
         Ok(stream
-            .map(|event| match event {
-                BlockchainEvent::Extended(hash) => hash.into(),
-                BlockchainEvent::HistoryAdopted(hash) => hash.into(),
-                BlockchainEvent::Finalized(hash) => hash.into(),
-                BlockchainEvent::EpochFinalized(hash) => hash.into(),
-                BlockchainEvent::Rebranched(_, new_branch) => {
-                    new_branch.into_iter().last().unwrap().0.into()
-                }
+            .filter_map(|event| {
+                let result = match event {
+                    BlockchainEvent::Extended(hash) => Some(hash.into()),
+                    BlockchainEvent::HistoryAdopted(hash) => Some(hash.into()),
+                    BlockchainEvent::Finalized(hash) => Some(hash.into()),
+                    BlockchainEvent::EpochFinalized(hash) => Some(hash.into()),
+                    BlockchainEvent::Rebranched(_, new_branch) => {
+                        Some(new_branch.into_iter().last().unwrap().0.into())
+                    }
+                    BlockchainEvent::RevertedTo(hash, _) => Some(hash.into()),
+                    // Transaction confirmations don't change the head.
+                    BlockchainEvent::TransactionConfirmed { .. } => None,
+                };
+                future::ready(result)
             })
             .boxed())
     }
@@ -679,106 +761,9 @@ impl BlockchainInterface for BlockchainDispatcher {
         addresses: Vec<Address>,
         log_types: Vec<LogType>,
     ) -> Result<BoxStream<'static, RPCData<BlockLog, BlockchainState>>, Self::Error> {
-        let stream = self.blockchain.write().log_notifier.as_stream();
+        let filter = LogFilter::new(addresses, log_types);
+        let stream = self.blockchain.write().subscribe_logs(filter);
 
-        if addresses.is_empty() && log_types.is_empty() {
-            Ok(Box::pin(stream.boxed().map(RPCData::with_block_log)))
-        } else {
-            Ok(stream
-                .filter_map(move |event| {
-                    let result = match event {
-                        BBlockLog::AppliedBlock {
-                            mut inherent_logs,
-                            block_hash,
-                            block_number,
-                            timestamp,
-                            tx_logs,
-                        } => {
-                            // Collects the inherents that are related to any of the addresses specified and of any of the log types provided.
-                            inherent_logs.retain(|log| {
-                                is_of_log_type_and_related_to_addresses(log, &addresses, &log_types)
-                            });
-                            // Since each TransactionLog has its own vec of logs, we iterate over each tx_logs and filter their logs,
-                            // if a tx_log has no logs after filtering, it will be filtered out completly.
-                            let tx_logs: Vec<TransactionLog> = tx_logs
-                                .into_iter()
-                                .filter_map(|mut tx_log| {
-                                    tx_log.logs.retain(|log| {
-                                        is_of_log_type_and_related_to_addresses(
-                                            log, &addresses, &log_types,
-                                        )
-                                    });
-                                    if tx_log.logs.is_empty() {
-                                        None
-                                    } else {
-                                        Some(tx_log)
-                                    }
-                                })
-                                .collect();
-
-                            // If this block has no transaction logs or inherent logs of interest, we return None. Otherwise, we return the filtered BlockLog.
-                            // This way the stream only emmits an event if a block has at least one log fulfilling the specified criteria.
-                            if !inherent_logs.is_empty() || !tx_logs.is_empty() {
-                                Some(RPCData::new(
-                                    BlockLog::AppliedBlock {
-                                        inherent_logs,
-                                        timestamp,
-                                        tx_logs,
-                                    },
-                                    BlockchainState {
-                                        block_number,
-                                        block_hash,
-                                    },
-                                ))
-                            } else {
-                                None
-                            }
-                        }
-                        BBlockLog::RevertedBlock {
-                            mut inherent_logs,
-                            block_hash,
-                            block_number,
-                            tx_logs,
-                        } => {
-                            // Filters the inherents and tx_logs the same way as the AppliedBlock
-                            inherent_logs.retain(|log| {
-                                is_of_log_type_and_related_to_addresses(log, &addresses, &log_types)
-                            });
-                            let tx_logs: Vec<TransactionLog> = tx_logs
-                                .into_iter()
-                                .filter_map(|mut tx_log| {
-                                    tx_log.logs.retain(|log| {
-                                        is_of_log_type_and_related_to_addresses(
-                                            log, &addresses, &log_types,
-                                        )
-                                    });
-                                    if tx_log.logs.is_empty() {
-                                        None
-                                    } else {
-                                        Some(tx_log)
-                                    }
-                                })
-                                .collect();
-
-                            if !inherent_logs.is_empty() || !tx_logs.is_empty() {
-                                Some(RPCData::new(
-                                    BlockLog::RevertedBlock {
-                                        inherent_logs,
-                                        tx_logs,
-                                    },
-                                    BlockchainState {
-                                        block_number,
-                                        block_hash,
-                                    },
-                                ))
-                            } else {
-                                None
-                            }
-                        }
-                    };
-                    future::ready(result)
-                })
-                .boxed())
-        }
+        Ok(stream.map(RPCData::with_block_log).boxed())
     }
 }
@@ -1,11 +1,14 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 
-use nimiq_network_interface::network::Network as InterfaceNetwork;
-use nimiq_network_libp2p::Network;
+use nimiq_network_interface::{network::Network as InterfaceNetwork, peer::CloseReason};
+use nimiq_network_libp2p::{ConnectionDirection, Multiaddr, Network, NetworkError, PeerId};
 use nimiq_rpc_interface::network::NetworkInterface;
-use nimiq_rpc_interface::types::RPCResult;
+use nimiq_rpc_interface::types::{
+    ConnectionDirection as RPCConnectionDirection, PeerInfo, PeerListEntry, RPCResult,
+};
 
 use crate::error::Error;
 
@@ -17,6 +20,26 @@ impl NetworkDispatcher {
     pub fn new(network: Arc<Network>) -> Self {
         NetworkDispatcher { network }
     }
+
+    fn peer_info(&self, peer_id: &PeerId) -> Option<PeerInfo> {
+        let info = self.network.get_peer_info(peer_id)?;
+
+        Some(PeerInfo {
+            peer_id: info.peer_id.to_string(),
+            addresses: info
+                .addresses
+                .iter()
+                .map(|address| address.to_string())
+                .collect(),
+            direction: match info.direction {
+                ConnectionDirection::Inbound => RPCConnectionDirection::Inbound,
+                ConnectionDirection::Outbound => RPCConnectionDirection::Outbound,
+            },
+            services: info.services.map(|services| services.bits()),
+            connection_age: info.connection_age.as_secs(),
+            rtt: info.rtt.map(|rtt| rtt.as_millis() as u64),
+        })
+    }
 }
 
 #[nimiq_jsonrpc_derive::service(rename_all = "camelCase")]
@@ -31,17 +54,83 @@ impl NetworkInterface for NetworkDispatcher {
 
     /// Returns the number of peers.
     async fn get_peer_count(&mut self) -> RPCResult<usize, (), Self::Error> {
-        Ok(self.network.get_peers().len().into())
+        Ok(self.network.peer_count().into())
     }
 
-    /// Returns a list with the IDs of all our peers.
-    async fn get_peer_list(&mut self) -> RPCResult<Vec<String>, (), Self::Error> {
+    /// Returns a list with the base58 encoded IDs of all our peers, as displayed by libp2p tools,
+    /// together with the last measured round-trip time to each of them.
+    async fn get_peer_list(&mut self) -> RPCResult<Vec<PeerListEntry>, (), Self::Error> {
         Ok(self
             .network
             .get_peers()
             .into_iter()
-            .map(|peer_id| peer_id.to_string())
+            .map(|peer_id| PeerListEntry {
+                peer_id: peer_id.to_base58(),
+                rtt: self
+                    .network
+                    .get_peer_info(&peer_id)
+                    .and_then(|info| info.rtt)
+                    .map(|rtt| rtt.as_millis() as u64),
+            })
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    /// Returns detailed information about the connected peer with the given ID.
+    async fn get_peer_info(&mut self, peer_id: String) -> RPCResult<PeerInfo, (), Self::Error> {
+        let peer_id = peer_id
+            .parse::<PeerId>()
+            .map_err(|_| Error::InvalidPeerId(peer_id))?;
+        let info = self
+            .peer_info(&peer_id)
+            .ok_or(NetworkError::UnknownPeer(peer_id))?;
+        Ok(info.into())
+    }
+
+    /// Returns a list with detailed information about all our peers.
+    async fn get_peer_list_detailed(&mut self) -> RPCResult<Vec<PeerInfo>, (), Self::Error> {
+        Ok(self
+            .network
+            .get_peers()
+            .iter()
+            .filter_map(|peer_id| self.peer_info(peer_id))
             .collect::<Vec<_>>()
             .into())
     }
+
+    /// Dials the peer at the given address.
+    async fn connect_peer(&mut self, address: String) -> RPCResult<(), (), Self::Error> {
+        let address = address
+            .parse::<Multiaddr>()
+            .map_err(|_| Error::InvalidPeerAddress(address))?;
+        self.network.dial_address(address).await?;
+        Ok(().into())
+    }
+
+    /// Closes the connection to the peer with the given ID.
+    async fn disconnect_peer(&mut self, peer_id: String) -> RPCResult<(), (), Self::Error> {
+        let peer_id = peer_id
+            .parse::<PeerId>()
+            .map_err(|_| Error::InvalidPeerId(peer_id))?;
+        self.network
+            .disconnect_peer(peer_id, CloseReason::Other)
+            .await?;
+        Ok(().into())
+    }
+
+    /// Bans the peer with the given ID for the given duration in seconds. Without a duration, the
+    /// peer stays banned until the node is restarted.
+    async fn ban_peer(
+        &mut self,
+        peer_id: String,
+        duration_secs: Option<u64>,
+    ) -> RPCResult<(), (), Self::Error> {
+        let peer_id = peer_id
+            .parse::<PeerId>()
+            .map_err(|_| Error::InvalidPeerId(peer_id))?;
+        self.network
+            .ban_peer(peer_id, duration_secs.map(Duration::from_secs))
+            .await?;
+        Ok(().into())
+    }
 }
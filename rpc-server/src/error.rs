@@ -14,6 +14,12 @@ pub enum Error {
     #[error("{0}")]
     NetworkError(#[from] nimiq_network_libp2p::NetworkError),
 
+    #[error("Invalid peer ID: {0}")]
+    InvalidPeerId(String),
+
+    #[error("Invalid peer address: {0}")]
+    InvalidPeerAddress(String),
+
     #[error("Mempool rejected transaction: {0}")]
     MempoolError(VerifyErr),
 
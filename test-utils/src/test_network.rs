@@ -70,7 +70,7 @@ impl TestNetwork for Network {
             genesis_hash.clone(),
             true,
         );
-        let network = Arc::new(Network::new(clock, config).await);
+        let network = Arc::new(Network::new(clock, config).await.unwrap());
         network.listen_on(vec![peer_address]).await;
         network
     }
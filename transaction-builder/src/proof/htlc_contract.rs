@@ -1,5 +1,5 @@
 use beserial::{Serialize, SerializingError, WriteBytesExt};
-use nimiq_hash::{Blake2bHash, Sha256Hash};
+use nimiq_hash::{Blake2bHash, Sha256Hash, Sha512Hash};
 use nimiq_keys::KeyPair;
 use nimiq_transaction::account::htlc_contract::{AnyHash, HashAlgorithm, ProofType};
 use nimiq_transaction::{SignatureProof, Transaction};
@@ -464,6 +464,28 @@ impl HtlcProofBuilder {
         )
     }
 
+    /// This method creates a proof for the `RegularTransfer` case using Sha512 hashes.
+    ///
+    /// Works like [`regular_transfer_sha256`], but for contracts whose `hash_root` is a
+    /// 64 byte Sha512 digest.
+    ///
+    /// [`regular_transfer_sha256`]: struct.HtlcProofBuilder.html#method.regular_transfer_sha256
+    pub fn regular_transfer_sha512(
+        &mut self,
+        pre_image: Sha512Hash,
+        hash_count: u8,
+        hash_root: Sha512Hash,
+        recipient_signature: SignatureProof,
+    ) -> &mut Self {
+        self.regular_transfer(
+            HashAlgorithm::Sha512,
+            pre_image.into(),
+            hash_count,
+            hash_root.into(),
+            recipient_signature,
+        )
+    }
+
     /// This method generates the final transaction if the signature has been set correctly.
     /// Otherwise, it returns `None`.
     pub fn generate(self) -> Option<Transaction> {
@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use nimiq_hash::{Blake2bHash, Sha256Hash};
+use nimiq_hash::{Blake2bHash, Sha256Hash, Sha512Hash};
 use nimiq_keys::Address;
 use nimiq_transaction::account::htlc_contract::CreationTransactionData as HtlcCreationData;
 use nimiq_transaction::account::htlc_contract::{AnyHash, HashAlgorithm};
@@ -181,6 +181,15 @@ impl HtlcRecipientBuilder {
         self
     }
 
+    /// Sets the hash data for the HTLC using Sha512 hashes.
+    /// The `hash_root` is the result of hashing the pre-image hash `hash_count` times.
+    pub fn with_sha512_hash(&mut self, hash_root: Sha512Hash, hash_count: u8) -> &mut Self {
+        self.hash_root = Some(AnyHash::from(hash_root));
+        self.hash_count = hash_count;
+        self.hash_algorithm = Some(HashAlgorithm::Sha512);
+        self
+    }
+
     /// Sets the blockchain height at which the `sender` automatically gains control over the funds.
     pub fn with_timeout(&mut self, timeout: u64) -> &mut Self {
         self.timeout = Some(timeout);
@@ -5,6 +5,7 @@ use thiserror::Error;
 use nimiq_keys::Address;
 use nimiq_primitives::coin::Coin;
 use nimiq_transaction::account::vesting_contract::CreationTransactionData as VestingCreationData;
+use nimiq_transaction::account::vesting_contract::VestingSchedule;
 
 use crate::recipient::Recipient;
 
@@ -66,6 +67,7 @@ pub struct VestingRecipientBuilder {
     time_step: Option<u64>,
     step_amount: Option<Coin>,
     total_amount: Option<Coin>,
+    cliff_time: Option<u64>,
 }
 
 impl VestingRecipientBuilder {
@@ -138,6 +140,12 @@ impl VestingRecipientBuilder {
         self
     }
 
+    /// Sets a cliff for the release schedule. No funds are released before `cliff_time`.
+    pub fn with_cliff_time(&mut self, cliff_time: u64) -> &mut Self {
+        self.cliff_time = Some(cliff_time);
+        self
+    }
+
     /// This method tries putting together the contract creation,
     /// returning a [`Recipient`] in case of success.
     /// In case of a failure, it returns a [`VestingRecipientBuilderError`].
@@ -179,6 +187,11 @@ impl VestingRecipientBuilder {
                 total_amount: self
                     .total_amount
                     .ok_or(VestingRecipientBuilderError::NoTotalAmount)?,
+                schedule: self
+                    .cliff_time
+                    .map_or(VestingSchedule::Linear, |cliff_time| {
+                        VestingSchedule::Cliff { cliff_time }
+                    }),
             },
         })
     }
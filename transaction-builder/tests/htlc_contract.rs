@@ -6,14 +6,14 @@ use nimiq_keys::{Address, KeyPair, PrivateKey};
 use nimiq_primitives::account::AccountType;
 use nimiq_primitives::networks::NetworkId;
 use nimiq_test_log::test;
-use nimiq_transaction::account::htlc_contract::{AnyHash, HashAlgorithm, ProofType};
+use nimiq_transaction::account::htlc_contract::{AnyHash, AnyHash32, HashAlgorithm, ProofType};
 use nimiq_transaction::{SignatureProof, Transaction};
 use nimiq_transaction_builder::{Recipient, TransactionBuilder};
 
 #[test]
 #[allow(unused_must_use)]
 fn it_can_create_creation_transaction() {
-    let mut data: Vec<u8> = Vec::with_capacity(Address::SIZE * 2 + AnyHash::SIZE + 10);
+    let mut data: Vec<u8> = Vec::with_capacity(Address::SIZE * 2 + AnyHash32::SIZE + 10);
     let sender = Address::from([0u8; 20]);
     let recipient = Address::from([0u8; 20]);
     sender.serialize(&mut data);
@@ -122,7 +122,7 @@ fn it_can_create_regular_transfer() {
 
     // regular: valid Blake-2b
     let mut proof =
-        Vec::with_capacity(3 + 2 * AnyHash::SIZE + recipient_signature_proof.serialized_size());
+        Vec::with_capacity(3 + 2 * AnyHash32::SIZE + recipient_signature_proof.serialized_size());
     Serialize::serialize(&ProofType::RegularTransfer, &mut proof);
     Serialize::serialize(&HashAlgorithm::Blake2b, &mut proof);
     Serialize::serialize(&1u8, &mut proof);
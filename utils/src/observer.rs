@@ -130,10 +130,21 @@ impl<E> Notifier<E> {
 
 impl<E: Clone + Send + 'static> Notifier<E> {
     pub fn as_stream(&mut self) -> NotifierStream<E> {
+        self.as_filtered_stream(|event: &E| Some(event.clone()))
+    }
+
+    /// Returns a stream of the events for which `filter` returns Some. The filter is applied when
+    /// the event is notified, so events that are filtered out are never sent to the stream.
+    pub fn as_filtered_stream<F>(&mut self, filter: F) -> NotifierStream<E>
+    where
+        F: Fn(&E) -> Option<E> + Send + Sync + 'static,
+    {
         let (tx, rx) = mpsc::unbounded_channel();
         let handle = self.register(move |event: &E| {
-            if let Err(e) = tx.send(event.clone()) {
-                log::error!("Failed to send event to channel: {}", e);
+            if let Some(event) = filter(event) {
+                if let Err(e) = tx.send(event) {
+                    log::error!("Failed to send event to channel: {}", e);
+                }
             }
         });
 
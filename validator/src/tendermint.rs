@@ -347,6 +347,7 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> TendermintOutsideDeps
                 None,
                 true,
                 false,
+                blockchain.max_future_drift_ms,
             )
             .is_err()
             {
@@ -412,6 +412,10 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
             BlockchainEvent::Rebranched(ref old_chain, ref new_chain) => {
                 self.on_blockchain_rebranched(old_chain, new_chain)
             }
+            BlockchainEvent::RevertedTo(_, ref old_chain) => {
+                self.on_blockchain_rebranched(old_chain, &[])
+            }
+            BlockchainEvent::TransactionConfirmed { .. } => {}
         }
     }
 
@@ -451,7 +455,7 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
 
     fn on_fork_event(&mut self, event: ForkEvent) {
         match event {
-            ForkEvent::Detected(fork_proof) => self.blockchain_state.fork_proofs.insert(fork_proof),
+            ForkEvent::Detected { proof, .. } => self.blockchain_state.fork_proofs.insert(proof),
         };
     }
 
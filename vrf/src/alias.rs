@@ -4,11 +4,24 @@ use std::cmp::{Ord, Ordering, PartialOrd};
 use std::fmt::Debug;
 use std::ops::{Add, Mul, Sub};
 
+use beserial::{Deserialize, ReadBytesExt, Serialize, SerializingError, WriteBytesExt};
 use num_traits::sign::Unsigned;
 use num_traits::{FromPrimitive, ToPrimitive};
+use thiserror::Error;
 
 use crate::rng::Rng;
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Error)]
+pub enum AliasError {
+    #[error("No probabilities were given")]
+    Empty,
+    #[error("All probabilities are zero")]
+    ZeroTotal,
+    #[error("The probabilities are too large to build the tables")]
+    Overflow,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct AliasMethod<P>
 where
     P: Copy
@@ -29,6 +42,9 @@ where
     /// Number of entries
     n: usize,
 
+    /// The probabilities the tables were built from
+    p: Vec<P>,
+
     /// Alias table
     K: Vec<usize>,
 
@@ -49,7 +65,94 @@ where
         + PartialOrd<P>
         + Ord,
 {
+    /// Builds the tables for the given probabilities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the probabilities are invalid, see `try_new`.
+    ///
     pub fn new<V: AsRef<[P]>>(p: V) -> Self {
+        Self::try_new(p).expect("Invalid probabilities for alias method")
+    }
+
+    /// Builds the tables for the given probabilities. Fails if there are no probabilities, if they
+    /// are all zero, or if their sum or any probability scaled by the number of entries doesn't
+    /// fit into `P`.
+    pub fn try_new<V: AsRef<[P]>>(p: V) -> Result<Self, AliasError> {
+        let p = p.as_ref().to_vec();
+        Self::check_probabilities(&p)?;
+
+        let (T, K, U) = Self::build_tables(&p);
+
+        Ok(Self {
+            T,
+            n: p.len(),
+            p,
+            K,
+            U,
+        })
+    }
+
+    /// Checks that the tables can be built from the given probabilities without overflowing.
+    fn check_probabilities(p: &[P]) -> Result<(), AliasError> {
+        if p.is_empty() {
+            return Err(AliasError::Empty);
+        }
+
+        let mut total: u128 = 0;
+        let mut max: u128 = 0;
+        for p_i in p {
+            let p_i = p_i.to_u128().ok_or(AliasError::Overflow)?;
+            total = total.checked_add(p_i).ok_or(AliasError::Overflow)?;
+            max = max.max(p_i);
+        }
+
+        if total == 0 {
+            return Err(AliasError::ZeroTotal);
+        }
+
+        // The tables contain the probabilities scaled by the number of entries.
+        let max_scaled = max
+            .checked_mul(p.len() as u128)
+            .ok_or(AliasError::Overflow)?;
+        if P::from_u128(total).is_none() || P::from_u128(max_scaled).is_none() {
+            return Err(AliasError::Overflow);
+        }
+
+        // Sampling draws a u64 below the total.
+        if total > u64::MAX as u128 {
+            return Err(AliasError::Overflow);
+        }
+
+        Ok(())
+    }
+
+    /// Changes the probabilities of some entries, given as pairs of index and new probability,
+    /// and updates the tables accordingly. The tables are rebuilt from all probabilities, since
+    /// the alias table can't be updated locally.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is out of range, if a probability can't be converted to `P` or if the
+    /// new probabilities are invalid, see `try_new`.
+    ///
+    pub fn update_weights(&mut self, changes: &[(usize, u64)]) {
+        for (i, p_i) in changes {
+            self.p[*i] = P::from_u64(*p_i).expect("Can't convert probability to P");
+        }
+
+        Self::check_probabilities(&self.p).expect("Invalid probabilities for alias method");
+
+        let (T, K, U) = Self::build_tables(&self.p);
+
+        self.T = T;
+        self.K = K;
+        self.U = U;
+    }
+
+    /// Builds the total probability, the alias table and the probabilities table for the given
+    /// probabilities.
+    fn build_tables(p: &[P]) -> (P, Vec<usize>, Vec<P>) {
         // The algorithm was roughly taken from
         //
         // * https://en.wikipedia.org/wiki/Alias_method#Table_generation
@@ -59,7 +162,6 @@ where
         // T - total probability
         // n - number of probabilities
 
-        let p = p.as_ref();
         let n = p.len();
 
         // Construct scaled probabilities and total probability.
@@ -93,7 +195,7 @@ where
             K[i_u] = i_o;
 
             // Remove allocated space from U: U_o -= (T - U_u)
-            U[i_o] = U[i_o] + U[i_u] - T;
+            U[i_o] = U[i_o] - (T - U[i_u]);
 
             // Assign entry i_o to the appropriate category base on the new value.
             match U[i_o].cmp(&T) {
@@ -112,7 +214,7 @@ where
             (U[i] < T) == (K[i] != i)
         }));
 
-        Self { T, n, K, U }
+        (T, K, U)
     }
 
     pub fn len(&self) -> usize {
@@ -127,6 +229,29 @@ where
         self.T
     }
 
+    /// Returns the probability with which `sample` returns the given index. This is computed from
+    /// the tables, so it reflects how entries are actually selected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of range.
+    ///
+    pub fn probability(&self, i: usize) -> f64 {
+        assert!(i < self.n, "Index out of range");
+
+        // An index is selected if it is drawn as column and its probability is below U, or if
+        // another column that aliases to it is drawn and its probability is at least U.
+        let T = self.T.to_f64().unwrap();
+        let mut probability = self.U[i].to_f64().unwrap() / T;
+        for (x, K_x) in self.K.iter().enumerate() {
+            if *K_x == i && x != i {
+                probability += (T - self.U[x].to_f64().unwrap()) / T;
+            }
+        }
+
+        probability / self.n as f64
+    }
+
     /// Sample from the discrete random distribution
     ///
     /// # Arguments
@@ -139,9 +264,9 @@ where
     /// Returns the index corresponding to the probability in the input `p`.
     ///
     pub fn sample<R: Rng>(&self, rng: &mut R) -> usize {
-        let x = rng.next_u64_max(self.n as u64) as usize;
+        let x = rng.next_u64_below(self.n as u64) as usize;
 
-        let y = P::from_u64(rng.next_u64_max(self.T.to_u64().unwrap())).unwrap();
+        let y = P::from_u64(rng.next_u64_below(self.T.to_u64().unwrap())).unwrap();
 
         if y < self.U[x] {
             x
@@ -149,4 +274,360 @@ where
             self.K[x]
         }
     }
+
+    /// Samples `k` distinct indices from the discrete random distribution without replacement.
+    /// Indices are drawn one after another from the probabilities of the entries that haven't
+    /// been drawn yet, so every draw takes a single pass over the entries. The indices are
+    /// returned in the order they were drawn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is larger than the number of entries with non-zero probability.
+    ///
+    pub fn sample_multiple<R: Rng>(&self, k: usize, rng: &mut R) -> Vec<usize> {
+        assert!(k <= self.n, "Can't sample more entries than there are");
+        assert!(
+            k <= self.p.iter().filter(|p_i| !p_i.is_zero()).count(),
+            "Can't sample more entries than have a non-zero probability"
+        );
+
+        // The remaining probabilities. Drawn entries are set to zero. The total fits into a u64,
+        // see `check_probabilities`.
+        let mut p: Vec<u64> = self.p.iter().map(|p_i| p_i.to_u64().unwrap()).collect();
+        let mut total: u64 = p.iter().sum();
+        let mut indices = Vec::with_capacity(k);
+
+        while indices.len() < k {
+            let mut y = rng.next_u64_below(total);
+
+            // Find the entry whose cumulative probability range contains y.
+            let i = p
+                .iter()
+                .position(|p_i| {
+                    if y < *p_i {
+                        true
+                    } else {
+                        y -= *p_i;
+                        false
+                    }
+                })
+                .expect("The sampled value is below the total probability");
+
+            total -= p[i];
+            p[i] = 0;
+            indices.push(i);
+        }
+
+        indices
+    }
+}
+
+impl<P> Serialize for AliasMethod<P>
+where
+    P: Copy
+        + Debug
+        + Unsigned
+        + Add<P>
+        + Sub<P>
+        + Mul<P>
+        + FromPrimitive
+        + ToPrimitive
+        + PartialOrd<P>
+        + Ord
+        + Serialize,
+{
+    fn serialize<W: WriteBytesExt>(&self, writer: &mut W) -> Result<usize, SerializingError> {
+        let mut size = 0;
+        size += Serialize::serialize(&self.T, writer)?;
+        size += Serialize::serialize(
+            &u32::try_from(self.n).map_err(|_| SerializingError::Overflow)?,
+            writer,
+        )?;
+        for ((p_i, U_i), K_i) in self.p.iter().zip(&self.U).zip(&self.K) {
+            size += Serialize::serialize(p_i, writer)?;
+            size += Serialize::serialize(U_i, writer)?;
+            // The alias is an index, so it fits into an u32 if the number of entries does.
+            size += Serialize::serialize(&(*K_i as u32), writer)?;
+        }
+        Ok(size)
+    }
+
+    fn serialized_size(&self) -> usize {
+        let mut size = 0;
+        size += Serialize::serialized_size(&self.T);
+        size += Serialize::serialized_size(&0u32);
+        for (p_i, U_i) in self.p.iter().zip(&self.U) {
+            size += Serialize::serialized_size(p_i);
+            size += Serialize::serialized_size(U_i);
+            size += Serialize::serialized_size(&0u32);
+        }
+        size
+    }
+}
+
+impl<P> Deserialize for AliasMethod<P>
+where
+    P: Copy
+        + Debug
+        + Unsigned
+        + Add<P>
+        + Sub<P>
+        + Mul<P>
+        + FromPrimitive
+        + ToPrimitive
+        + PartialOrd<P>
+        + Ord
+        + Deserialize,
+{
+    fn deserialize<R: ReadBytesExt>(reader: &mut R) -> Result<Self, SerializingError> {
+        let T: P = Deserialize::deserialize(reader)?;
+        let n: u32 = Deserialize::deserialize(reader)?;
+        let n = n as usize;
+
+        let mut p = Vec::new();
+        let mut K = Vec::new();
+        let mut U = Vec::new();
+
+        for _ in 0..n {
+            p.push(Deserialize::deserialize(reader)?);
+            U.push(Deserialize::deserialize(reader)?);
+            let K_i: u32 = Deserialize::deserialize(reader)?;
+            K.push(K_i as usize);
+        }
+
+        // Check that the tables are consistent, so that sampling from them can't fail.
+        let valid = (0..n).all(|i| K[i] < n && U[i] <= T && (U[i] < T) == (K[i] != i));
+        if !valid
+            || Self::check_probabilities(&p).is_err()
+            || p.iter().fold(P::zero(), |sum, p_i| sum + *p_i) != T
+        {
+            return Err(SerializingError::InvalidValue);
+        }
+
+        Ok(Self { T, n, p, K, U })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{RngCore, SeedableRng};
+
+    use nimiq_test_log::test;
+
+    use super::*;
+
+    struct TestRng(StdRng);
+
+    impl Rng for TestRng {
+        fn next_u64(&mut self) -> u64 {
+            self.0.next_u64()
+        }
+    }
+
+    #[test]
+    fn it_samples_according_to_the_probabilities() {
+        let p: Vec<u64> = vec![0, 1, 2, 3, 4, 10, 0, 20];
+        let lookup = AliasMethod::new(&p);
+        let mut rng = TestRng(StdRng::seed_from_u64(42));
+
+        let num_samples = 100_000;
+        let mut counts = vec![0u64; p.len()];
+        for _ in 0..num_samples {
+            counts[lookup.sample(&mut rng)] += 1;
+        }
+
+        let total = p.iter().sum::<u64>() as f64;
+        for (p_i, count) in p.iter().zip(counts) {
+            let probability = *p_i as f64 / total;
+            let expected = probability * num_samples as f64;
+            let std_dev = (num_samples as f64 * probability * (1.0 - probability)).sqrt();
+
+            // Allow a deviation of 5 standard deviations. Entries with probability zero are
+            // never sampled.
+            assert!(
+                (count as f64 - expected).abs() <= 5.0 * std_dev,
+                "Sampled {} times, expected {}",
+                count,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn it_samples_multiple_distinct_entries() {
+        let mut rng = TestRng(StdRng::seed_from_u64(7));
+
+        for _ in 0..200 {
+            // Random probabilities, some of them zero.
+            let n = rng.next_u64_range(1, 64) as usize;
+            let p: Vec<u64> = (0..n)
+                .map(|_| rng.next_u64_below(4).saturating_sub(1) * rng.next_u64_below(1000))
+                .collect();
+            let non_zero = p.iter().filter(|p_i| **p_i > 0).count();
+            if non_zero == 0 {
+                continue;
+            }
+
+            let lookup = AliasMethod::new(&p);
+
+            let k = rng.next_u64_range(0, non_zero as u64 + 1) as usize;
+            let indices = lookup.sample_multiple(k, &mut rng);
+
+            assert_eq!(indices.len(), k);
+            let mut distinct = indices.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            assert_eq!(distinct.len(), k);
+            assert!(indices.iter().all(|i| p[*i] > 0));
+        }
+    }
+
+    #[test]
+    fn it_samples_multiple_entries_with_skewed_probabilities() {
+        // Rejecting duplicates would almost never draw the small entries here.
+        let p: Vec<u64> = vec![1 << 60, 1, 0, 1];
+        let lookup = AliasMethod::new(&p);
+        let mut rng = TestRng(StdRng::seed_from_u64(3));
+
+        let mut indices = lookup.sample_multiple(3, &mut rng);
+        assert_eq!(indices[0], 0);
+        indices.sort_unstable();
+        assert_eq!(indices, vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn it_samples_multiple_according_to_the_probabilities() {
+        let p: Vec<u64> = vec![1, 2, 3, 4];
+        let lookup = AliasMethod::new(&p);
+        let mut rng = TestRng(StdRng::seed_from_u64(42));
+
+        let num_samples = 50_000;
+        let mut counts = vec![0u64; p.len()];
+        for _ in 0..num_samples {
+            for i in lookup.sample_multiple(2, &mut rng) {
+                counts[i] += 1;
+            }
+        }
+
+        // The probability that an entry is included is the probability of drawing it first,
+        // plus the probability of drawing it second after any other entry.
+        let total = p.iter().sum::<u64>() as f64;
+        for (i, count) in counts.into_iter().enumerate() {
+            let p_i = p[i] as f64 / total;
+            let probability = p_i
+                + p.iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, p_j)| {
+                        let p_j = *p_j as f64 / total;
+                        p_j * p_i / (1.0 - p_j)
+                    })
+                    .sum::<f64>();
+            let expected = probability * num_samples as f64;
+            let std_dev = (num_samples as f64 * probability * (1.0 - probability)).sqrt();
+
+            assert!(
+                (count as f64 - expected).abs() <= 5.0 * std_dev,
+                "Sampled {} times, expected {}",
+                count,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't sample more entries than there are")]
+    fn it_panics_when_sampling_too_many_entries() {
+        let lookup = AliasMethod::new(vec![1u64, 2, 3]);
+        let mut rng = TestRng(StdRng::seed_from_u64(0));
+        lookup.sample_multiple(4, &mut rng);
+    }
+
+    #[test]
+    fn it_rejects_invalid_probabilities() {
+        assert_eq!(AliasMethod::<u64>::try_new(vec![]), Err(AliasError::Empty));
+        assert_eq!(
+            AliasMethod::try_new(vec![0u64, 0, 0]),
+            Err(AliasError::ZeroTotal)
+        );
+        assert_eq!(
+            AliasMethod::try_new(vec![u64::MAX, 1]),
+            Err(AliasError::Overflow)
+        );
+
+        // The sum fits, but not the probabilities scaled by the number of entries.
+        assert_eq!(
+            AliasMethod::try_new(vec![u64::MAX / 2, 0, 0]),
+            Err(AliasError::Overflow)
+        );
+        assert!(AliasMethod::try_new(vec![u64::MAX / 3, 0, 0]).is_ok());
+
+        // Zeros are fine as long as some probability isn't zero.
+        assert!(AliasMethod::try_new(vec![0u64, 3, 0]).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid probabilities for alias method")]
+    fn it_panics_on_invalid_probabilities() {
+        AliasMethod::new(vec![0u64, 0]);
+    }
+
+    #[test]
+    fn it_computes_selection_probabilities() {
+        let p: Vec<u64> = vec![0, 1, 2, 3, 4, 10, 0, 20];
+        let lookup = AliasMethod::new(&p);
+
+        let total = p.iter().sum::<u64>() as f64;
+        for (i, p_i) in p.iter().enumerate() {
+            assert!((lookup.probability(i) - *p_i as f64 / total).abs() < 1e-9);
+        }
+
+        for p in [
+            vec![1u64],
+            vec![7, 7, 7],
+            (0..512).map(|i| (i * 7919) % 1000).collect(),
+        ] {
+            let lookup = AliasMethod::new(&p);
+            let sum: f64 = (0..lookup.len()).map(|i| lookup.probability(i)).sum();
+            assert!((sum - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn it_can_update_weights() {
+        let mut lookup = AliasMethod::new(vec![5u64, 1, 7, 3]);
+        lookup.update_weights(&[(1, 4), (3, 0)]);
+
+        assert_eq!(lookup, AliasMethod::new(vec![5u64, 4, 7, 0]));
+        assert_eq!(lookup.total(), 16);
+    }
+
+    #[test]
+    fn it_can_serialize_and_deserialize() {
+        let p: Vec<u64> = (0..512).map(|i| (i * 7919) % 1000).collect();
+        let lookup = AliasMethod::new(p);
+
+        let bytes = lookup.serialize_to_vec();
+        assert_eq!(bytes.len(), lookup.serialized_size());
+
+        let deserialized: AliasMethod<u64> = Deserialize::deserialize_from_vec(&bytes).unwrap();
+        assert_eq!(deserialized, lookup);
+
+        // Both sample the same entries from the same randomness.
+        let mut rng1 = TestRng(StdRng::seed_from_u64(1));
+        let mut rng2 = TestRng(StdRng::seed_from_u64(1));
+        for _ in 0..1000 {
+            assert_eq!(lookup.sample(&mut rng1), deserialized.sample(&mut rng2));
+        }
+
+        // Tables with an out of range alias are rejected.
+        let mut invalid = lookup;
+        invalid.K[0] = 512;
+        invalid.U[0] = 0;
+        assert_eq!(
+            AliasMethod::<u64>::deserialize_from_vec(&invalid.serialize_to_vec()),
+            Err(SerializingError::InvalidValue)
+        );
+    }
 }
@@ -1,4 +1,4 @@
-pub use alias::AliasMethod;
+pub use alias::{AliasError, AliasMethod};
 pub use rng::Rng;
 pub use vrf::{VrfEntropy, VrfRng, VrfSeed, VrfUseCase};
 
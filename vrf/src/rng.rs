@@ -2,9 +2,25 @@ pub trait Rng {
     /// Returns a random 64 bit integer
     fn next_u64(&mut self) -> u64;
 
-    /// Returns a random integer in range [0, max)
+    /// Returns a random integer in range [0, max). This is the same as `next_u64_below`.
     fn next_u64_max(&mut self, max: u64) -> u64 {
-        let bitmask = max.next_power_of_two() - 1;
+        self.next_u64_below(max)
+    }
+
+    /// Returns a random integer in range [0, max). Integers are drawn from the smallest range of
+    /// the form [0, 2^k) that contains [0, max) and rejected until one is below `max`, so the
+    /// result is unbiased.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero, since the range is empty.
+    fn next_u64_below(&mut self, max: u64) -> u64 {
+        assert!(max > 0, "Can't sample from an empty range");
+
+        let bitmask = match max.checked_next_power_of_two() {
+            Some(power) => power - 1,
+            None => u64::MAX,
+        };
 
         loop {
             // Get a integer in the range [0, n] where n is the next power of 2
@@ -16,4 +32,51 @@ pub trait Rng {
             }
         }
     }
+
+    /// Returns a random integer in range [0, max) using Lemire's multiply-shift method. The
+    /// integer is the upper half of the 128 bit product of a random 64 bit integer and `max`. The
+    /// few products that would bias the result are rejected, which almost never happens for small
+    /// `max`, so this usually needs exactly one call to `next_u64`.
+    ///
+    /// This draws different integers than `next_u64_below` from the same randomness.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero, since the range is empty.
+    fn next_u64_below_lemire(&mut self, max: u64) -> u64 {
+        assert!(max > 0, "Can't sample from an empty range");
+
+        let mut product = u128::from(self.next_u64()) * u128::from(max);
+
+        // The lower half of the product is below `max` only in the rare case that the result
+        // might be biased. Only then do we need the threshold, which costs a division.
+        if (product as u64) < max {
+            let threshold = max.wrapping_neg() % max;
+            while (product as u64) < threshold {
+                product = u128::from(self.next_u64()) * u128::from(max);
+            }
+        }
+
+        (product >> 64) as u64
+    }
+
+    /// Returns a random integer in range [min, max), without bias.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min` is not smaller than `max`, since the range is empty.
+    fn next_u64_range(&mut self, min: u64, max: u64) -> u64 {
+        assert!(min < max, "Can't sample from an empty range");
+
+        min + self.next_u64_below(max - min)
+    }
+
+    /// Shuffles the slice in place using the Fisher–Yates shuffle. The permutation only depends on
+    /// the sequence of integers returned by `next_u64`.
+    fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j = self.next_u64_below((i + 1) as u64) as usize;
+            slice.swap(i, j);
+        }
+    }
 }
@@ -1,8 +1,9 @@
 #![allow(non_snake_case)]
 
 use std::fmt;
-use std::hash::Hash;
+use std::hash::{Hash, Hasher as StdHasher};
 use std::io::Write;
+use std::ops::RangeInclusive;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use curve25519_dalek::constants;
@@ -14,39 +15,199 @@ use rand::RngCore;
 #[cfg(feature = "serde-derive")]
 use serde_big_array::BigArray;
 use sha2::{Digest, Sha256, Sha512};
+use subtle::{Choice, ConstantTimeEq};
+use thiserror::Error;
+use zeroize::Zeroize;
 
 use beserial::{Deserialize, Serialize, SerializingError};
 use nimiq_hash::{Blake2bHash, Blake2bHasher, HashOutput, Hasher};
 use nimiq_keys::{KeyPair, PublicKey};
-use nimiq_macros::create_typed_array;
 
 use crate::rng::Rng;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
 pub enum VrfError {
+    /// The seed doesn't verify against the previous seed and the public key. Either it was signed
+    /// by a different key or it was tampered with.
+    #[error("VRF seed was not produced by the given signer")]
     Forged,
+    /// The seed is well-formed, but its point has small order.
+    #[error("VRF seed has an invalid signature")]
     InvalidSignature,
+    /// The seed can't be decoded into a point and two scalars.
+    #[error("VRF seed is malformed")]
+    Malformed,
+    /// The signer's public key isn't a valid point or has small order.
+    #[error("VRF signer public key is invalid")]
+    InvalidPublicKey,
+    /// A seed in a chain of seeds doesn't verify against its predecessor.
+    #[error("VRF seed at index {index} of the chain is invalid: {error}")]
+    BrokenChain { index: usize, error: Box<VrfError> },
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-#[repr(u8)]
 pub enum VrfUseCase {
     /// Used to produce the next seed in the VRF seed chain.
-    Seed = 1,
+    Seed,
     /// Used to select the validator slots at the end of each epoch.
-    ValidatorSlotSelection = 2,
+    ValidatorSlotSelection,
     /// Used to determine the view slots at each block height.
-    ViewSlotSelection = 3,
+    ViewSlotSelection,
     /// Used to randomly distribute the rewards.
-    RewardDistribution = 4,
+    RewardDistribution,
+    /// Used by applications built on top of Albatross to derive their own randomness. The tag
+    /// distinguishes the use cases of an application, it is not shared with the consensus tags.
+    Application(u8),
 }
 
-create_typed_array!(VrfEntropy, u8, 32);
+impl VrfUseCase {
+    /// The tags reserved for consensus use cases. New consensus use cases must use a tag in this
+    /// range.
+    pub const CONSENSUS_TAGS: RangeInclusive<u8> = 0..=0x7f;
+
+    /// The tag of all application use cases. It is followed by the application's own tag, so
+    /// application use cases can never produce the same stream as a consensus use case.
+    pub const APPLICATION_TAG: u8 = 0x80;
+
+    /// Returns the tag that is prefixed to the VRF message or RNG input for this use case.
+    pub fn tag(&self) -> u8 {
+        match self {
+            VrfUseCase::Seed => 1,
+            VrfUseCase::ValidatorSlotSelection => 2,
+            VrfUseCase::ViewSlotSelection => 3,
+            VrfUseCase::RewardDistribution => 4,
+            VrfUseCase::Application(_) => VrfUseCase::APPLICATION_TAG,
+        }
+    }
+
+    /// Returns true if this use case is used by the consensus.
+    pub fn is_consensus(&self) -> bool {
+        !matches!(self, VrfUseCase::Application(_))
+    }
+
+    /// Writes the domain of this use case, i.e. its tag followed by the application tag (if any).
+    fn write_domain<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_u8(self.tag())?;
+        if let VrfUseCase::Application(tag) = self {
+            writer.write_u8(*tag)?;
+        }
+        Ok(())
+    }
+}
+
+/// The entropy of a VRF Seed. Since it feeds the randomness of the consensus, it is compared in
+/// constant time and wiped from memory when dropped.
+#[derive(Default, Clone, PartialOrd, Ord)]
+pub struct VrfEntropy(pub [u8; 32]);
 
 impl VrfEntropy {
+    pub const SIZE: usize = 32;
+
+    #[inline]
+    pub fn len() -> usize {
+        Self::SIZE
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
     pub fn rng(self, use_case: VrfUseCase) -> VrfRng {
         VrfRng::new(self, use_case)
     }
+
+    /// Compares two entropies in constant time.
+    pub fn ct_eq(&self, other: &Self) -> Choice {
+        ConstantTimeEq::ct_eq(self, other)
+    }
+}
+
+impl ConstantTimeEq for VrfEntropy {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0[..].ct_eq(&other.0[..])
+    }
+}
+
+impl PartialEq for VrfEntropy {
+    fn eq(&self, other: &Self) -> bool {
+        ConstantTimeEq::ct_eq(self, other).into()
+    }
+}
+
+impl Eq for VrfEntropy {}
+
+impl Hash for VrfEntropy {
+    fn hash<H: StdHasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl Zeroize for VrfEntropy {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for VrfEntropy {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<'a> From<&'a [u8]> for VrfEntropy {
+    fn from(slice: &'a [u8]) -> Self {
+        assert_eq!(
+            slice.len(),
+            VrfEntropy::SIZE,
+            "Tried to create instance with slice of wrong length"
+        );
+        let mut entropy = VrfEntropy::default();
+        entropy.0.copy_from_slice(slice);
+        entropy
+    }
+}
+
+impl From<[u8; 32]> for VrfEntropy {
+    fn from(bytes: [u8; 32]) -> Self {
+        VrfEntropy(bytes)
+    }
+}
+
+impl From<VrfEntropy> for [u8; 32] {
+    fn from(entropy: VrfEntropy) -> [u8; 32] {
+        entropy.0
+    }
+}
+
+impl AsRef<[u8]> for VrfEntropy {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for VrfEntropy {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl Serialize for VrfEntropy {
+    fn serialize<W: WriteBytesExt>(&self, writer: &mut W) -> Result<usize, SerializingError> {
+        writer.write_all(&self.0)?;
+        Ok(VrfEntropy::SIZE)
+    }
+
+    fn serialized_size(&self) -> usize {
+        VrfEntropy::SIZE
+    }
+}
+
+impl Deserialize for VrfEntropy {
+    fn deserialize<R: ReadBytesExt>(reader: &mut R) -> Result<Self, SerializingError> {
+        let mut entropy = VrfEntropy::default();
+        reader.read_exact(&mut entropy.0)?;
+        Ok(entropy)
+    }
 }
 
 impl std::fmt::Debug for VrfEntropy {
@@ -82,30 +243,34 @@ impl VrfSeed {
         // Deserialize signature.
         let V = CompressedEdwardsY::from_slice(&self.signature[..32])
             .decompress()
-            .ok_or(VrfError::InvalidSignature)?;
+            .ok_or(VrfError::Malformed)?;
 
         let h = Scalar::from_canonical_bytes(self.signature[32..64].try_into().unwrap())
-            .ok_or(VrfError::InvalidSignature)?;
+            .ok_or(VrfError::Malformed)?;
 
         let s = Scalar::from_canonical_bytes(self.signature[64..].try_into().unwrap())
-            .ok_or(VrfError::InvalidSignature)?;
+            .ok_or(VrfError::Malformed)?;
 
         // Deserialize public key.
         let A_bytes = public_key.as_bytes();
 
         let A = CompressedEdwardsY::from_slice(A_bytes)
             .decompress()
-            .ok_or(VrfError::InvalidSignature)?;
+            .ok_or(VrfError::InvalidPublicKey)?;
+
+        if A.is_small_order() {
+            return Err(VrfError::InvalidPublicKey);
+        }
 
         // Concatenate use case prefix and previous entropy to form message. Note that we use the
         // entropy here and not the signature, that's because we need the message to be unique.
-        let mut message = vec![VrfUseCase::Seed as u8];
+        let mut message = vec![VrfUseCase::Seed.tag()];
         message.extend_from_slice(prev_seed.entropy().as_slice());
 
         // Follow the verification algorithm for VXEdDSA.
         // https://www.signal.org/docs/specifications/xeddsa/#vxeddsa
         let B_v = EdwardsPoint::hash_from_bytes::<Sha512>(&[A_bytes, &message[..]].concat());
-        if A.is_small_order() || V.is_small_order() || B_v.is_identity() {
+        if V.is_small_order() || B_v.is_identity() {
             return Err(VrfError::InvalidSignature);
         }
         let R = &s * &constants::ED25519_BASEPOINT_TABLE - h * A;
@@ -148,7 +313,7 @@ impl VrfSeed {
 
         // Concatenate use case prefix and entropy to form message. Note that we use the entropy
         // here and not the signature, that's because we need the message to be unique.
-        let mut message = vec![VrfUseCase::Seed as u8];
+        let mut message = vec![VrfUseCase::Seed.tag()];
         message.extend_from_slice(self.entropy().as_slice());
 
         // Follow the signing algorithm for VXEdDSA.
@@ -179,6 +344,37 @@ impl VrfSeed {
         }
     }
 
+    /// Produces the VRF Seed that is `n` steps after the current one, by calling `sign_next` `n`
+    /// times. Since signing is randomized, the resulting seed differs between calls, but its
+    /// entropy doesn't.
+    #[must_use]
+    pub fn advance_n(&self, n: u32, keypair: &KeyPair) -> Self {
+        let mut seed = self.clone();
+        for _ in 0..n {
+            seed = seed.sign_next(keypair);
+        }
+        seed
+    }
+
+    /// Verifies a chain of VRF Seeds following the current one, each given with the public key of
+    /// its signer. The first seed of the chain is verified against the current seed and every
+    /// other seed against its predecessor. All intermediate seeds are needed, because each step
+    /// can only be verified using the entropy of the previous seed, which can't be derived without
+    /// the private key. If a seed is invalid, the index of the first invalid seed is returned in a
+    /// `VrfError::BrokenChain`.
+    pub fn verify_chain(&self, seeds: &[(VrfSeed, PublicKey)]) -> Result<(), VrfError> {
+        let mut prev_seed = self;
+        for (index, (seed, public_key)) in seeds.iter().enumerate() {
+            seed.verify(prev_seed, public_key)
+                .map_err(|error| VrfError::BrokenChain {
+                    index,
+                    error: Box::new(error),
+                })?;
+            prev_seed = seed;
+        }
+        Ok(())
+    }
+
     // Extracts the entropy, which is 256 verifiably random bits, from the current VRF Seed. This
     // entropy can then be used for any purpose for which we need randomness. Note that this entropy
     // is what is unique for a given message and public key, not the signature (which can be
@@ -262,6 +458,7 @@ impl Deserialize for VrfSeed {
 pub struct VrfRng {
     entropy: VrfEntropy,
     use_case: VrfUseCase,
+    round: Option<u32>,
     counter: u64,
 }
 
@@ -270,14 +467,27 @@ impl VrfRng {
         Self {
             entropy,
             use_case,
+            round: None,
             counter: 0,
         }
     }
 
+    /// Initializes a VRF RNG, for a given use case and Tendermint round, from the given VRF Seed.
+    /// The round is mixed into every output, so different rounds with the same seed produce
+    /// independent randomness. Like `VrfSeed::rng`, this might panic if the seed is invalid.
+    pub fn from_seed_and_round(seed: &VrfSeed, use_case: VrfUseCase, round: u32) -> VrfRng {
+        let mut rng = seed.rng(use_case);
+        rng.round = Some(round);
+        rng
+    }
+
     pub fn next_hash(&mut self) -> Blake2bHash {
-        // Hash use-case prefix, counter and entropy.
+        // Hash use-case prefix, round (if any), counter and entropy.
         let mut hasher = Blake2bHasher::new();
-        hasher.write_u8(self.use_case as u8).unwrap();
+        self.use_case.write_domain(&mut hasher).unwrap();
+        if let Some(round) = self.round {
+            hasher.write_u32::<BigEndian>(round).unwrap();
+        }
         hasher.write_u64::<BigEndian>(self.counter).unwrap();
         hasher.write_all(self.entropy.as_slice()).unwrap();
 
@@ -370,4 +580,365 @@ mod tests {
             assert!(fake_seed.verify(&prev_seed, &key_pair.public).is_err());
         }
     }
+
+    #[test]
+    fn it_reports_why_a_seed_is_invalid() {
+        let mut rng = rand::thread_rng();
+        let key_pair = KeyPair::generate(&mut rng);
+        let prev_seed = VrfSeed::default();
+
+        let seed = prev_seed.sign_next(&key_pair);
+        assert_eq!(seed.verify(&prev_seed, &key_pair.public), Ok(()));
+
+        // A non-canonical scalar can't be decoded.
+        let mut bytes = seed.signature;
+        bytes[32..64].copy_from_slice(&[0xff; 32]);
+        assert_eq!(
+            VrfSeed { signature: bytes }.verify(&prev_seed, &key_pair.public),
+            Err(VrfError::Malformed)
+        );
+
+        // The all-zero seed decodes to a point of small order.
+        assert_eq!(
+            VrfSeed::default().verify(&prev_seed, &key_pair.public),
+            Err(VrfError::InvalidSignature)
+        );
+
+        // The identity point is not a valid public key.
+        let mut identity = [0u8; PublicKey::SIZE];
+        identity[0] = 1;
+        assert_eq!(
+            seed.verify(&prev_seed, &PublicKey::from(&identity)),
+            Err(VrfError::InvalidPublicKey)
+        );
+
+        // A tampered signature or a different signer don't verify.
+        let mut bytes = seed.signature;
+        bytes[64] ^= 1;
+        assert_eq!(
+            VrfSeed { signature: bytes }.verify(&prev_seed, &key_pair.public),
+            Err(VrfError::Forged)
+        );
+        assert_eq!(
+            seed.verify(&prev_seed, &KeyPair::generate(&mut rng).public),
+            Err(VrfError::Forged)
+        );
+    }
+
+    #[test]
+    fn it_can_advance_multiple_steps() {
+        let mut rng = rand::thread_rng();
+        let key_pair = KeyPair::generate(&mut rng);
+        let seed = VrfSeed::default().sign_next(&key_pair);
+
+        assert_eq!(seed.advance_n(0, &key_pair), seed);
+
+        // The entropy only depends on the number of steps.
+        for k in 0..4 {
+            for m in 0..4 {
+                assert_eq!(
+                    seed.advance_n(k, &key_pair)
+                        .advance_n(m, &key_pair)
+                        .entropy(),
+                    seed.advance_n(k + m, &key_pair).entropy()
+                );
+            }
+        }
+
+        // The chain of intermediate seeds verifies.
+        let mut chain = vec![];
+        let mut prev_seed = seed.clone();
+        for _ in 0..5 {
+            prev_seed = prev_seed.sign_next(&key_pair);
+            chain.push((prev_seed.clone(), key_pair.public));
+        }
+        assert_eq!(chain[4].0.entropy(), seed.advance_n(5, &key_pair).entropy());
+        assert_eq!(seed.verify_chain(&chain), Ok(()));
+        assert_eq!(seed.verify_chain(&[]), Ok(()));
+
+        // Leaving out a seed breaks the chain.
+        chain.remove(2);
+        assert_eq!(
+            seed.verify_chain(&chain),
+            Err(VrfError::BrokenChain {
+                index: 2,
+                error: Box::new(VrfError::Forged)
+            })
+        );
+    }
+
+    #[test]
+    fn it_can_verify_chains_with_different_signers() {
+        let mut rng = rand::thread_rng();
+        let key_pairs: Vec<KeyPair> = (0..6).map(|_| KeyPair::generate(&mut rng)).collect();
+        let seed = VrfSeed::default();
+
+        let mut chain = vec![];
+        let mut prev_seed = seed.clone();
+        for key_pair in &key_pairs {
+            prev_seed = prev_seed.sign_next(key_pair);
+            chain.push((prev_seed.clone(), key_pair.public));
+        }
+        assert_eq!(seed.verify_chain(&chain), Ok(()));
+
+        // Attributing a seed to the wrong signer breaks the chain at that seed.
+        let mut wrong_signer = chain.clone();
+        wrong_signer[4].1 = key_pairs[0].public;
+        assert_eq!(
+            seed.verify_chain(&wrong_signer),
+            Err(VrfError::BrokenChain {
+                index: 4,
+                error: Box::new(VrfError::Forged)
+            })
+        );
+
+        // Tampering with a seed in the middle breaks the chain at that seed, even though the
+        // following seeds are valid successors of the original one.
+        let mut tampered = chain;
+        tampered[2].0.signature[40] ^= 1;
+        assert!(matches!(
+            seed.verify_chain(&tampered),
+            Err(VrfError::BrokenChain { index: 2, .. })
+        ));
+    }
+
+    fn test_entropy() -> VrfEntropy {
+        let mut bytes = [0u8; VrfEntropy::SIZE];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        VrfEntropy::from(bytes)
+    }
+
+    #[test]
+    fn vrf_rng_test_vectors() {
+        let mut rng = test_entropy().rng(VrfUseCase::ValidatorSlotSelection);
+        let values: Vec<u64> = (0..3).map(|_| rng.next_u64()).collect();
+        assert_eq!(
+            values,
+            vec![
+                16255561767606916288,
+                696204401634299403,
+                4204000090748776832
+            ]
+        );
+
+        let mut rng = test_entropy().rng(VrfUseCase::ValidatorSlotSelection);
+        let values: Vec<u64> = (0..8).map(|_| rng.next_u64_below(10)).collect();
+        assert_eq!(values, vec![0, 0, 8, 4, 3, 9, 4, 5]);
+
+        let mut rng = test_entropy().rng(VrfUseCase::ValidatorSlotSelection);
+        let values: Vec<u64> = (0..4).map(|_| rng.next_u64_range(100, 200)).collect();
+        assert_eq!(values, vec![164, 111, 100, 129]);
+
+        let mut rng = test_entropy().rng(VrfUseCase::ValidatorSlotSelection);
+        let mut values: Vec<u64> = (0..10).collect();
+        rng.shuffle(&mut values);
+        assert_eq!(values, vec![6, 7, 1, 3, 4, 8, 2, 5, 9, 0]);
+
+        // A different use case yields a different permutation.
+        let mut rng = test_entropy().rng(VrfUseCase::ViewSlotSelection);
+        let mut values: Vec<u64> = (0..10).collect();
+        rng.shuffle(&mut values);
+        assert_eq!(values, vec![7, 5, 8, 1, 2, 3, 0, 6, 4, 9]);
+    }
+
+    #[test]
+    fn vrf_rng_lemire_test_vectors() {
+        let mut rng = test_entropy().rng(VrfUseCase::ValidatorSlotSelection);
+        let values: Vec<u64> = (0..8).map(|_| rng.next_u64_below_lemire(10)).collect();
+        assert_eq!(values, vec![8, 0, 2, 6, 3, 7, 1, 2]);
+
+        // Large ranges need to reject some draws.
+        let mut rng = test_entropy().rng(VrfUseCase::ValidatorSlotSelection);
+        let values: Vec<u64> = (0..4)
+            .map(|_| rng.next_u64_below_lemire((1 << 63) + 1))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                8127780883803458144,
+                348102200817149701,
+                6466202878844399268,
+                1137041946800691814
+            ]
+        );
+
+        let mut rng = test_entropy().rng(VrfUseCase::ViewSlotSelection);
+        let values: Vec<u64> = (0..6).map(|_| rng.next_u64_below_lemire(512)).collect();
+        assert_eq!(values, vec![510, 458, 296, 495, 309, 456]);
+    }
+
+    #[test]
+    fn vrf_rng_lemire_is_uniform() {
+        let num_buckets = 7;
+        let num_seeds = 70_000;
+        let mut counts = vec![0u64; num_buckets];
+
+        // Draw one integer from each of many different entropies.
+        for i in 0..num_seeds {
+            let mut bytes = [0u8; VrfEntropy::SIZE];
+            bytes[..8].copy_from_slice(&(i as u64).to_be_bytes());
+            let mut rng = VrfEntropy::from(bytes).rng(VrfUseCase::ViewSlotSelection);
+
+            let value = rng.next_u64_below_lemire(num_buckets as u64);
+            counts[value as usize] += 1;
+        }
+
+        let probability = 1.0 / num_buckets as f64;
+        let expected = num_seeds as f64 * probability;
+        let std_dev = (num_seeds as f64 * probability * (1.0 - probability)).sqrt();
+        for count in counts {
+            assert!(
+                (count as f64 - expected).abs() <= 5.0 * std_dev,
+                "Sampled {} times, expected {}",
+                count,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't sample from an empty range")]
+    fn vrf_rng_lemire_panics_on_empty_range() {
+        let mut rng = test_entropy().rng(VrfUseCase::RewardDistribution);
+        rng.next_u64_below_lemire(0);
+    }
+
+    #[test]
+    fn vrf_rng_differs_per_round() {
+        let seed = VrfSeed::default().sign_next(&KeyPair::generate(&mut rand::thread_rng()));
+
+        let next = |mut rng: VrfRng| -> Vec<u64> { (0..4).map(|_| rng.next_u64()).collect() };
+
+        let round_0 = next(VrfRng::from_seed_and_round(
+            &seed,
+            VrfUseCase::ViewSlotSelection,
+            0,
+        ));
+        let round_1 = next(VrfRng::from_seed_and_round(
+            &seed,
+            VrfUseCase::ViewSlotSelection,
+            1,
+        ));
+
+        // The same round always yields the same output.
+        assert_eq!(
+            round_0,
+            next(VrfRng::from_seed_and_round(
+                &seed,
+                VrfUseCase::ViewSlotSelection,
+                0
+            ))
+        );
+
+        // Different rounds, and the round-less RNG, yield different outputs.
+        assert_ne!(round_0, round_1);
+        assert_ne!(round_0, next(seed.rng(VrfUseCase::ViewSlotSelection)));
+    }
+
+    #[test]
+    fn vrf_rng_samples_full_range() {
+        let mut rng = test_entropy().rng(VrfUseCase::RewardDistribution);
+
+        assert_eq!(rng.next_u64_below(1), 0);
+        assert_eq!(rng.next_u64_range(7, 8), 7);
+
+        // Ranges above 2^63 don't overflow when computing the bitmask.
+        let max = u64::MAX - 1;
+        for _ in 0..100 {
+            assert!(rng.next_u64_below(max) < max);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't sample from an empty range")]
+    fn vrf_rng_panics_on_empty_range() {
+        let mut rng = test_entropy().rng(VrfUseCase::RewardDistribution);
+        rng.next_u64_below(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't sample from an empty range")]
+    fn vrf_rng_panics_on_empty_interval() {
+        let mut rng = test_entropy().rng(VrfUseCase::RewardDistribution);
+        rng.next_u64_range(5, 5);
+    }
+
+    #[test]
+    fn consensus_use_cases_are_in_reserved_range() {
+        for use_case in [
+            VrfUseCase::Seed,
+            VrfUseCase::ValidatorSlotSelection,
+            VrfUseCase::ViewSlotSelection,
+            VrfUseCase::RewardDistribution,
+        ] {
+            assert!(use_case.is_consensus());
+            assert!(VrfUseCase::CONSENSUS_TAGS.contains(&use_case.tag()));
+        }
+
+        for tag in [0, 1, 0x7f, 0x80, 0xff] {
+            let use_case = VrfUseCase::Application(tag);
+            assert!(!use_case.is_consensus());
+            assert!(!VrfUseCase::CONSENSUS_TAGS.contains(&use_case.tag()));
+        }
+    }
+
+    #[test]
+    fn vrf_rng_differs_per_use_case() {
+        let use_cases = [
+            VrfUseCase::Seed,
+            VrfUseCase::ValidatorSlotSelection,
+            VrfUseCase::ViewSlotSelection,
+            VrfUseCase::RewardDistribution,
+            VrfUseCase::Application(1),
+            VrfUseCase::Application(2),
+        ];
+
+        let streams: Vec<Vec<u64>> = use_cases
+            .iter()
+            .map(|use_case| {
+                let mut rng = test_entropy().rng(*use_case);
+                (0..4).map(|_| rng.next_u64()).collect()
+            })
+            .collect();
+
+        for (i, stream) in streams.iter().enumerate() {
+            for (j, other) in streams.iter().enumerate().skip(i + 1) {
+                assert_ne!(stream, other, "{:?} and {:?}", use_cases[i], use_cases[j]);
+            }
+        }
+
+        // An application tag never collides with the consensus use case of the same number.
+        let mut consensus = test_entropy().rng(VrfUseCase::ViewSlotSelection);
+        let mut application = test_entropy().rng(VrfUseCase::Application(3));
+        assert_ne!(consensus.next_hash(), application.next_hash());
+    }
+
+    #[test]
+    fn vrf_entropy_ct_eq_matches_eq() {
+        let entropy = test_entropy();
+
+        for i in 0..VrfEntropy::SIZE {
+            let mut other = entropy.clone();
+            assert!(bool::from(entropy.ct_eq(&other)));
+            assert_eq!(entropy, other);
+
+            other.0[i] ^= 1;
+            assert!(!bool::from(entropy.ct_eq(&other)));
+            assert_ne!(entropy, other);
+        }
+    }
+
+    #[test]
+    fn vrf_entropy_is_zeroed_on_drop() {
+        let mut entropy = std::mem::ManuallyDrop::new(test_entropy());
+        let ptr = &entropy.0 as *const [u8; VrfEntropy::SIZE];
+
+        // Run the destructor without freeing the memory, so it can still be inspected.
+        unsafe {
+            std::mem::ManuallyDrop::drop(&mut entropy);
+            assert_eq!(std::ptr::read_volatile(ptr), [0u8; VrfEntropy::SIZE]);
+        }
+    }
 }